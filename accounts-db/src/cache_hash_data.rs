@@ -4,13 +4,13 @@ use crate::pubkey_bins::PubkeyBinCalculator24;
 use {
     crate::{accounts_hash::CalculateHashIntermediate, cache_hash_data_stats::CacheHashDataStats},
     bytemuck::{Pod, Zeroable},
+    lru::LruCache,
     memmap2::MmapMut,
     solana_measure::measure::Measure,
-    std::io::BufWriter,
     std::{
         collections::HashSet,
         fs::{self, remove_file, File, OpenOptions},
-        io::Write,
+        io::{IoSlice, Write},
         path::{Path, PathBuf},
         sync::{atomic::Ordering, Arc, Mutex},
     },
@@ -20,10 +20,81 @@ pub type EntryType = CalculateHashIntermediate;
 pub type SavedType = Vec<Vec<EntryType>>;
 pub type SavedTypeSlice = [Vec<EntryType>];
 
+/// On-disk compression codec for a cache hash data file's entry payload.
+/// The header itself is always stored uncompressed, so a reader can
+/// detect the codec (and entry count) before deciding how to map the
+/// rest of the file.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default)]
+#[repr(u8)]
+pub enum Compression {
+    #[default]
+    None = 0,
+    Zstd = 1,
+}
+
+impl Compression {
+    fn from_byte(byte: u8) -> Self {
+        match byte {
+            1 => Self::Zstd,
+            _ => Self::None,
+        }
+    }
+}
+
+/// Current on-disk `Header` version. `map()` treats an unset (`0`)
+/// `checksum` as a pre-checksum file rather than rejecting it, so old
+/// files stay readable without needing to branch on `version` directly.
+const CURRENT_HEADER_VERSION: u8 = 1;
+
 #[derive(Pod, Zeroable, Copy, Clone)]
 #[repr(C)]
 pub struct Header {
     count: usize,
+    version: u8,
+    /// `Compression` as a raw byte; `0` (`Compression::None`) for the
+    /// original uncompressed format.
+    compression: u8,
+    _reserved: [u8; 6],
+    /// FNV-1a hash of the on-disk entry payload (the compressed bytes,
+    /// when `compression != None`), used to detect bit flips and torn
+    /// writes. `0` means unverified: either a pre-checksum file, or
+    /// (vanishingly unlikely) a payload that genuinely hashes to zero.
+    checksum: u64,
+    /// The bin index `data[0]` started at when this file was saved; see
+    /// `CacheHashData::save`.
+    start_bin_index: u32,
+    /// Number of bins written; the per-bin offset table immediately
+    /// following this header holds `bin_count + 1` entries (one per bin,
+    /// plus a final sentinel). `0` for a file saved without one (old
+    /// format), in which case there is no table and `get_bin_slice`
+    /// always returns `None`.
+    bin_count: u32,
+}
+
+/// Checksum-mismatch counter for cache hash data files.
+///
+/// `CacheHashDataStats` (used below for the existing read/save counters)
+/// is defined outside this tree, so this new counter is tracked here
+/// instead of being added to that external struct.
+#[derive(Default)]
+pub(crate) struct CacheHashDataIntegrityStats {
+    pub(crate) checksum_mismatches: std::sync::atomic::AtomicUsize,
+}
+
+/// Dependency-free 64-bit FNV-1a hash. No crc/xxhash crate is available
+/// in this tree, and the checksum only needs to catch accidental
+/// corruption (bit flips, torn writes), not resist tampering.
+fn fnv1a64(chunks: &[&[u8]]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+    let mut hash = OFFSET_BASIS;
+    for chunk in chunks {
+        for &byte in *chunk {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    }
+    hash
 }
 
 /// cache hash data file to be mmapped later
@@ -32,13 +103,44 @@ pub(crate) struct CacheHashDataFileReference {
     file_len: u64,
     path: PathBuf,
     stats: Arc<CacheHashDataStats>,
+    integrity_stats: Arc<CacheHashDataIntegrityStats>,
+}
+
+/// Backing storage for a mapped cache hash data file's entry payload
+/// (the header is always read directly off the initial mmap, before
+/// either variant is chosen). Compressed data can't be safely
+/// reinterpreted as `&[EntryType]` straight out of the mmap, so it's
+/// decompressed into an aligned heap buffer instead.
+enum Backing {
+    Mmap(MmapMut),
+    Decoded(Vec<u8>),
+}
+
+impl Backing {
+    /// Entry-payload bytes, with everything preceding the entries
+    /// stripped for both variants: `Mmap` skips over `prefix_len` (the
+    /// header, plus the per-bin offset table when present), `Decoded`
+    /// never had a prefix in the first place.
+    fn entry_bytes(&self, prefix_len: usize) -> &[u8] {
+        match self {
+            Backing::Mmap(mmap) => &mmap[prefix_len..],
+            Backing::Decoded(buf) => buf,
+        }
+    }
 }
 
 /// mmapped cache hash data file
 pub(crate) struct CacheHashDataFile {
     cell_size: u64,
-    mmap: MmapMut,
+    backing: Backing,
     capacity: u64,
+    /// Byte offset of the first entry, past the header and (if present)
+    /// the per-bin offset table.
+    entries_offset: u64,
+    /// Cumulative entry-count offsets, one per bin plus a final
+    /// sentinel, as written by `CacheHashData::save`; empty if this file
+    /// predates the per-bin index (old format).
+    bin_offsets: Vec<u64>,
 }
 
 impl CacheHashDataFileReference {
@@ -64,29 +166,84 @@ impl CacheHashDataFileReference {
         }
         assert_eq!((cell_size as usize) % std::mem::size_of::<u64>(), 0);
         let mut cache_file = CacheHashDataFile {
-            mmap,
+            backing: Backing::Mmap(mmap),
             cell_size,
             capacity: 0,
+            entries_offset: header_size,
+            bin_offsets: Vec::new(),
         };
-        let header = cache_file.get_header_mut();
+        let header = *cache_file.get_header_mut();
         let entries = header.count;
-
-        let capacity = cell_size * (entries as u64) + header_size;
-        if file_len < capacity {
+        let compression = Compression::from_byte(header.compression);
+
+        // The table holds one cumulative-offset entry per bin plus a
+        // final sentinel, so a file with `bin_count` bins written has
+        // `bin_count + 1` table entries; `bin_count == 0` means no table
+        // was written at all (old format).
+        let index_bytes_len = if header.bin_count > 0 {
+            (header.bin_count as u64 + 1) * std::mem::size_of::<u64>() as u64
+        } else {
+            0
+        };
+        let entries_offset = header_size + index_bytes_len;
+        if file_len < entries_offset {
             return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
         }
-        cache_file.capacity = capacity;
-        assert_eq!(
-            capacity, file_len,
-            "expected: {capacity}, len on disk: {file_len} {}, entries: {entries}, cell_size: {cell_size}", self.path.display(),
-        );
+        if header.bin_count > 0 {
+            let table_bytes = match &cache_file.backing {
+                Backing::Mmap(mmap) => &mmap[header_size as usize..entries_offset as usize],
+                Backing::Decoded(_) => unreachable!("backing is always Mmap at this point"),
+            };
+            cache_file.bin_offsets = table_bytes
+                .chunks_exact(std::mem::size_of::<u64>())
+                .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+                .collect();
+        }
+        cache_file.entries_offset = entries_offset;
+
+        if header.checksum != 0 {
+            let payload = match &cache_file.backing {
+                Backing::Mmap(mmap) => &mmap[entries_offset as usize..],
+                Backing::Decoded(_) => unreachable!("backing is always Mmap at this point"),
+            };
+            if fnv1a64(&[payload]) != header.checksum {
+                self.integrity_stats
+                    .checksum_mismatches
+                    .fetch_add(1, Ordering::Relaxed);
+                return Err(std::io::Error::from(std::io::ErrorKind::InvalidData));
+            }
+        }
+
+        match compression {
+            Compression::None => {
+                let capacity = cell_size * (entries as u64) + entries_offset;
+                if file_len < capacity {
+                    return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof));
+                }
+                cache_file.capacity = capacity;
+                assert_eq!(
+                    capacity, file_len,
+                    "expected: {capacity}, len on disk: {file_len} {}, entries: {entries}, cell_size: {cell_size}", self.path.display(),
+                );
+            }
+            Compression::Zstd => {
+                let compressed = match &cache_file.backing {
+                    Backing::Mmap(mmap) => &mmap[entries_offset as usize..],
+                    Backing::Decoded(_) => unreachable!("backing is always Mmap at this point"),
+                };
+                let expected_len = (cell_size * entries as u64) as usize;
+                let decoded = decode_zstd_aligned(compressed, expected_len)?;
+                cache_file.capacity = entries_offset + decoded.len() as u64;
+                cache_file.backing = Backing::Decoded(decoded);
+            }
+        }
 
         self.stats
             .total_entries
             .fetch_add(entries, Ordering::Relaxed);
         self.stats
             .cache_file_size
-            .fetch_add(capacity as usize, Ordering::Relaxed);
+            .fetch_add(cache_file.capacity as usize, Ordering::Relaxed);
 
         self.stats.loaded_from_cache.fetch_add(1, Ordering::Relaxed);
         self.stats
@@ -100,6 +257,56 @@ impl CacheHashDataFileReference {
     }
 }
 
+/// Decompresses `compressed` and copies the result into a freshly
+/// allocated, `u64`-backed buffer (so it satisfies the same alignment
+/// the mmapped path already relies on), verifying the decoded length
+/// matches `expected_len` exactly.
+fn decode_zstd_aligned(compressed: &[u8], expected_len: usize) -> std::io::Result<Vec<u8>> {
+    let raw = zstd::decode_all(compressed)
+        .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidData))?;
+    if raw.len() != expected_len {
+        return Err(std::io::Error::from(std::io::ErrorKind::InvalidData));
+    }
+    debug_assert_eq!(expected_len % std::mem::size_of::<u64>(), 0);
+    let mut aligned: Vec<u64> = vec![0u64; expected_len / std::mem::size_of::<u64>()];
+    unsafe {
+        std::ptr::copy_nonoverlapping(raw.as_ptr(), aligned.as_mut_ptr() as *mut u8, expected_len);
+    }
+    let decoded = unsafe {
+        let ptr = aligned.as_mut_ptr();
+        let cap_bytes = aligned.capacity() * std::mem::size_of::<u64>();
+        std::mem::forget(aligned);
+        Vec::from_raw_parts(ptr as *mut u8, expected_len, cap_bytes)
+    };
+    unsafe {
+        assert_eq!(
+            decoded.align_to::<EntryType>().0.len(),
+            0,
+            "decoded buffer is not aligned"
+        );
+    }
+    Ok(decoded)
+}
+
+/// Serializes `data`'s bins' cumulative entry-count offsets as
+/// little-endian `u64`s: one entry per bin plus a trailing sentinel, so
+/// `get_bin_slice` can read off each bin's `[start, end)` element range.
+/// Empty for `data.is_empty()`, matching `Header::bin_count == 0` (no
+/// table at all) rather than writing a table with only the sentinel.
+fn bin_offsets_table(data: &SavedTypeSlice) -> Vec<u8> {
+    if data.is_empty() {
+        return Vec::new();
+    }
+    let mut bytes = Vec::with_capacity((data.len() + 1) * std::mem::size_of::<u64>());
+    let mut cumulative = 0u64;
+    bytes.extend_from_slice(&cumulative.to_le_bytes());
+    for bin in data {
+        cumulative += bin.len() as u64;
+        bytes.extend_from_slice(&cumulative.to_le_bytes());
+    }
+    bytes
+}
+
 impl CacheHashDataFile {
     /// return a slice of a reference to all the cache hash data from the mmapped file
     pub fn get_cache_hash_data(&self) -> &[EntryType] {
@@ -131,8 +338,9 @@ impl CacheHashDataFile {
 
     /// get '&[EntryType]' from cache file [ix..]
     fn get_slice(&self, ix: u64) -> &[EntryType] {
-        let start = self.get_element_offset_byte(ix);
-        let item_slice: &[u8] = &self.mmap[start..];
+        let start = (ix * self.cell_size) as usize;
+        debug_assert_eq!(start % std::mem::align_of::<EntryType>(), 0);
+        let item_slice = &self.backing.entry_bytes(self.entries_offset as usize)[start..];
         let remaining_elements = item_slice.len() / std::mem::size_of::<EntryType>();
         unsafe {
             let item = item_slice.as_ptr() as *const EntryType;
@@ -140,17 +348,27 @@ impl CacheHashDataFile {
         }
     }
 
-    /// return byte offset of entry 'ix' into a slice which contains a header and at least ix elements
-    fn get_element_offset_byte(&self, ix: u64) -> usize {
-        let start = (ix * self.cell_size) as usize + std::mem::size_of::<Header>();
-        debug_assert_eq!(start % std::mem::align_of::<EntryType>(), 0);
-        start
+    /// Returns just bin `relative_bin`'s entries, using the header's
+    /// per-bin offset index to jump directly to the right element range
+    /// instead of rebinning (or touching) the rest of the file.
+    ///
+    /// `relative_bin` is relative to the `start_bin_index` this file was
+    /// saved with. Returns `None` if this file predates the index (old
+    /// format) or `relative_bin` is out of range for it; callers should
+    /// fall back to a full scan via `get_cache_hash_data`/`load_all` in
+    /// that case.
+    pub fn get_bin_slice(&self, relative_bin: usize) -> Option<&[EntryType]> {
+        let start = *self.bin_offsets.get(relative_bin)?;
+        let end = *self.bin_offsets.get(relative_bin + 1)?;
+        Some(&self.get_slice(start)[..(end - start) as usize])
     }
 
     fn get_header_mut(&mut self) -> &mut Header {
-        let start = 0_usize;
-        let end = start + std::mem::size_of::<Header>();
-        let item_slice: &[u8] = &self.mmap[start..end];
+        let header_size = std::mem::size_of::<Header>();
+        let item_slice: &[u8] = match &self.backing {
+            Backing::Mmap(mmap) => &mmap[..header_size],
+            Backing::Decoded(_) => unreachable!("header is only read before decompression"),
+        };
         unsafe {
             let item = item_slice.as_ptr() as *mut Header;
             &mut *item
@@ -158,10 +376,79 @@ impl CacheHashDataFile {
     }
 }
 
+/// Hit/miss/eviction counters for `CacheHashDataFilePool`.
+///
+/// `CacheHashDataStats` (used above for the existing read/save counters)
+/// is defined outside this tree, so these new pool counters are tracked
+/// here instead of being added to that external struct.
+#[derive(Default)]
+pub(crate) struct CacheHashDataFilePoolStats {
+    pub(crate) hits: usize,
+    pub(crate) misses: usize,
+    pub(crate) evictions: usize,
+}
+
+/// Bounds how many `CacheHashDataFile` mmaps can be resident at once by a
+/// byte budget rather than an unbounded count, evicting the
+/// least-recently-used entries (dropping their `MmapMut`) once the budget
+/// would otherwise be exceeded. `get_file_reference_to_map_later` exists
+/// specifically so callers can know a file exists without mapping it yet;
+/// this is the component that actually bounds how many of those
+/// references get turned into live mmaps at once.
+pub(crate) struct CacheHashDataFilePool {
+    max_resident_bytes: u64,
+    resident_bytes: u64,
+    entries: LruCache<PathBuf, Arc<CacheHashDataFile>>,
+    pub(crate) stats: CacheHashDataFilePoolStats,
+}
+
+impl CacheHashDataFilePool {
+    pub(crate) fn new(max_resident_bytes: u64) -> Self {
+        Self {
+            max_resident_bytes,
+            resident_bytes: 0,
+            // Capacity is unbounded by count; eviction is driven entirely
+            // by `max_resident_bytes` below.
+            entries: LruCache::new(usize::MAX),
+            stats: CacheHashDataFilePoolStats::default(),
+        }
+    }
+
+    /// Returns a shared handle to `path`'s mapped cache file. On a hit,
+    /// just touches the LRU order; on a miss, maps `reference` and evicts
+    /// least-recently-used entries until `max_resident_bytes` is honored.
+    pub(crate) fn map(
+        &mut self,
+        path: &Path,
+        reference: &CacheHashDataFileReference,
+    ) -> Result<Arc<CacheHashDataFile>, std::io::Error> {
+        if let Some(existing) = self.entries.get(path) {
+            self.stats.hits += 1;
+            return Ok(Arc::clone(existing));
+        }
+        self.stats.misses += 1;
+
+        let mapped = Arc::new(reference.map()?);
+        let capacity = mapped.capacity;
+        while self.resident_bytes + capacity > self.max_resident_bytes {
+            let Some((_, evicted)) = self.entries.pop_lru() else {
+                break;
+            };
+            self.resident_bytes -= evicted.capacity;
+            self.stats.evictions += 1;
+        }
+        self.resident_bytes += capacity;
+        self.entries.put(path.to_path_buf(), Arc::clone(&mapped));
+        Ok(mapped)
+    }
+}
+
 pub type PreExistingCacheFiles = HashSet<PathBuf>;
 pub struct CacheHashData {
     cache_dir: PathBuf,
     pre_existing_cache_files: Arc<Mutex<PreExistingCacheFiles>>,
+    compression: Compression,
+    integrity_stats: Arc<CacheHashDataIntegrityStats>,
     pub stats: Arc<CacheHashDataStats>,
 }
 
@@ -174,6 +461,14 @@ impl Drop for CacheHashData {
 
 impl CacheHashData {
     pub fn new(cache_dir: PathBuf) -> CacheHashData {
+        Self::new_with_compression(cache_dir, Compression::None)
+    }
+
+    /// Like `new`, but saves entries compressed with `compression` instead
+    /// of always writing the raw, directly-mmappable layout. Useful for
+    /// large account sets where on-disk cache size matters more than
+    /// avoiding the decompress-on-load cost.
+    pub fn new_with_compression(cache_dir: PathBuf, compression: Compression) -> CacheHashData {
         std::fs::create_dir_all(&cache_dir).unwrap_or_else(|err| {
             panic!("error creating cache dir {}: {err}", cache_dir.display())
         });
@@ -181,6 +476,8 @@ impl CacheHashData {
         let result = CacheHashData {
             cache_dir,
             pre_existing_cache_files: Arc::new(Mutex::new(PreExistingCacheFiles::default())),
+            compression,
+            integrity_stats: Arc::default(),
             stats: Arc::default(),
         };
 
@@ -257,6 +554,7 @@ impl CacheHashData {
             file_len,
             path,
             stats: Arc::clone(&self.stats),
+            integrity_stats: Arc::clone(&self.integrity_stats),
         })
     }
 
@@ -274,19 +572,23 @@ impl CacheHashData {
             .remove(file_name.as_ref());
     }
 
-    /// save 'data' to 'file_name'
+    /// save 'data' to 'file_name'. 'start_bin_index' is `data[0]`'s bin
+    /// index, recorded in the header; `get_bin_slice` takes bin indices
+    /// relative to it and does not read it back.
     pub fn save(
         &self,
         file_name: impl AsRef<Path>,
         data: &SavedTypeSlice,
+        start_bin_index: usize,
     ) -> Result<(), std::io::Error> {
-        self.save_internal(file_name, data)
+        self.save_internal(file_name, data, start_bin_index)
     }
 
     fn save_internal(
         &self,
         file_name: impl AsRef<Path>,
         data: &SavedTypeSlice,
+        start_bin_index: usize,
     ) -> Result<(), std::io::Error> {
         let mut m = Measure::start("save");
         let cache_path = self.cache_dir.join(file_name);
@@ -299,43 +601,107 @@ impl CacheHashData {
             .map(|x: &Vec<EntryType>| x.len())
             .collect::<Vec<_>>();
         let entries = entries.iter().sum::<usize>();
-        let capacity = cell_size * (entries as u64) + std::mem::size_of::<Header>() as u64;
+        // One cumulative offset per bin plus a trailing sentinel, so
+        // `get_bin_slice` can read off each bin's `[start, end)` element
+        // range without rebinning or touching other bins.
+        let bin_offsets_table = bin_offsets_table(data);
 
-        let file = OpenOptions::new()
+        let mut file = OpenOptions::new()
             .create_new(true)
             .write(true)
             .open(cache_path)?;
-        let mut fw = BufWriter::new(file);
 
-        let header = Header { count: entries };
-        let header_slice = bytemuck::bytes_of(&header);
-
-        fw.write_all(header_slice)?;
         m1.stop();
         self.stats
             .create_save_us
             .fetch_add(m1.as_us(), Ordering::Relaxed);
 
+        let mut m2 = Measure::start("write_to_mmap");
+        let uncompressed_len = cell_size as usize * entries;
+        let file_len = match self.compression {
+            Compression::None => {
+                let mut i = 0;
+                let mut entry_slices: Vec<&[u8]> = Vec::with_capacity(data.len());
+                for x in data {
+                    if x.is_empty() {
+                        continue;
+                    }
+                    let size = x.len() * std::mem::size_of::<EntryType>();
+                    let slice = unsafe { std::slice::from_raw_parts(x.as_ptr() as *const u8, size) };
+                    entry_slices.push(slice);
+                    i += x.len();
+                }
+                assert_eq!(i, entries);
+
+                let header = Header {
+                    count: entries,
+                    version: CURRENT_HEADER_VERSION,
+                    compression: self.compression as u8,
+                    _reserved: [0; 6],
+                    checksum: fnv1a64(&entry_slices),
+                    start_bin_index: start_bin_index as u32,
+                    bin_count: if data.is_empty() { 0 } else { data.len() as u32 },
+                };
+                let header_slice = bytemuck::bytes_of(&header);
+
+                // One IoSlice per non-empty bin, plus the header and the
+                // offset table, so the whole file goes out in (typically)
+                // a single `writev` instead of one `write_all` per bin
+                // through an intermediate `BufWriter` copy.
+                let mut slices = Vec::with_capacity(entry_slices.len() + 2);
+                slices.push(IoSlice::new(header_slice));
+                slices.push(IoSlice::new(&bin_offsets_table));
+                slices.extend(entry_slices.into_iter().map(IoSlice::new));
+                // `write_all_vectored` already loops over `write_vectored`,
+                // advancing past fully-written slices, since the OS may
+                // write fewer iovecs than supplied.
+                file.write_all_vectored(&mut slices)?;
+                header_slice.len() + bin_offsets_table.len() + uncompressed_len
+            }
+            Compression::Zstd => {
+                // The codec needs the whole payload contiguous to
+                // compress it, so there's no vectored-write benefit here
+                // the way there is for the uncompressed path above.
+                let mut payload = Vec::with_capacity(uncompressed_len);
+                for x in data {
+                    let size = x.len() * std::mem::size_of::<EntryType>();
+                    let slice = unsafe { std::slice::from_raw_parts(x.as_ptr() as *const u8, size) };
+                    payload.extend_from_slice(slice);
+                }
+                let compressed = zstd::encode_all(&payload[..], 0)?;
+
+                let header = Header {
+                    count: entries,
+                    version: CURRENT_HEADER_VERSION,
+                    compression: self.compression as u8,
+                    _reserved: [0; 6],
+                    checksum: fnv1a64(&[&compressed]),
+                    start_bin_index: start_bin_index as u32,
+                    bin_count: if data.is_empty() { 0 } else { data.len() as u32 },
+                };
+                let header_slice = bytemuck::bytes_of(&header);
+
+                let mut slices = [
+                    IoSlice::new(header_slice),
+                    IoSlice::new(&bin_offsets_table),
+                    IoSlice::new(compressed.as_slice()),
+                ];
+                file.write_all_vectored(&mut slices)?;
+                header_slice.len() + bin_offsets_table.len() + compressed.len()
+            }
+        };
+        m2.stop();
+        self.stats
+            .write_to_mmap_us
+            .fetch_add(m2.as_us(), Ordering::Relaxed);
+
         self.stats
             .cache_file_size
-            .fetch_add(capacity as usize, Ordering::Relaxed);
+            .fetch_add(file_len, Ordering::Relaxed);
         self.stats
             .total_entries
             .fetch_add(entries, Ordering::Relaxed);
 
-        let mut m2 = Measure::start("write_to_mmap");
-        let mut i = 0;
-        for x in data {
-            let size = x.len() * std::mem::size_of::<EntryType>();
-            let slice = unsafe { std::slice::from_raw_parts(x.as_ptr() as *const u8, size) };
-            fw.write_all(slice)?;
-            i += x.len();
-        }
-        assert_eq!(i, entries);
-        m2.stop();
-        self.stats
-            .write_to_mmap_us
-            .fetch_add(m2.as_us(), Ordering::Relaxed);
         m.stop();
         self.stats.save_us.fetch_add(m.as_us(), Ordering::Relaxed);
         self.stats.saved_to_cache.fetch_add(1, Ordering::Relaxed);
@@ -385,7 +751,9 @@ pub mod tests {
                         }
                         let cache = CacheHashData::new(cache_dir.clone());
                         let file_name = PathBuf::from("test");
-                        cache.save(&file_name, &data_this_pass).unwrap();
+                        cache
+                            .save(&file_name, &data_this_pass, start_bin_this_pass)
+                            .unwrap();
                         cache.get_cache_files();
                         assert_eq!(
                             cache
@@ -418,6 +786,179 @@ pub mod tests {
         }
     }
 
+    #[test]
+    fn test_cache_hash_data_file_pool_evicts_lru_when_over_budget() {
+        use tempfile::TempDir;
+        let tmpdir = TempDir::new().unwrap();
+        let cache_dir = tmpdir.path().to_path_buf();
+        let cache = CacheHashData::new(cache_dir.clone());
+
+        let entry = CalculateHashIntermediate::new(
+            solana_sdk::hash::Hash::new_unique(),
+            1,
+            solana_sdk::pubkey::new_rand(),
+        );
+        let data = vec![vec![entry]];
+
+        let file_a = PathBuf::from("a");
+        let file_b = PathBuf::from("b");
+        cache.save(&file_a, &data, 0).unwrap();
+        cache.save(&file_b, &data, 0).unwrap();
+
+        let reference_a = cache.get_file_reference_to_map_later(&file_a).unwrap();
+        let reference_b = cache.get_file_reference_to_map_later(&file_b).unwrap();
+
+        // data has 1 bin, so the offset table is 2 u64s (start + sentinel).
+        let capacity = std::mem::size_of::<EntryType>() as u64
+            + std::mem::size_of::<Header>() as u64
+            + 2 * std::mem::size_of::<u64>() as u64;
+        // Budget for exactly one resident file at a time, so mapping a
+        // second file must evict the first.
+        let mut pool = CacheHashDataFilePool::new(capacity);
+
+        let mapped_a = pool.map(&cache_dir.join(&file_a), &reference_a).unwrap();
+        assert_eq!(pool.stats.misses, 1);
+        assert_eq!(pool.stats.hits, 0);
+        assert_eq!(pool.stats.evictions, 0);
+
+        // Re-mapping the same file is a hit, not a miss or eviction.
+        let mapped_a_again = pool.map(&cache_dir.join(&file_a), &reference_a).unwrap();
+        assert!(Arc::ptr_eq(&mapped_a, &mapped_a_again));
+        assert_eq!(pool.stats.hits, 1);
+
+        // Mapping a second file exceeds the budget, evicting the first.
+        let _mapped_b = pool.map(&cache_dir.join(&file_b), &reference_b).unwrap();
+        assert_eq!(pool.stats.misses, 2);
+        assert_eq!(pool.stats.evictions, 1);
+        assert_eq!(pool.resident_bytes, capacity);
+    }
+
+    #[test]
+    fn test_read_write_compressed() {
+        use tempfile::TempDir;
+        let tmpdir = TempDir::new().unwrap();
+        let cache_dir = tmpdir.path().to_path_buf();
+        let cache = CacheHashData::new_with_compression(cache_dir, Compression::Zstd);
+
+        let bin_calculator = PubkeyBinCalculator24::new(1);
+        let (data, _total_points) = generate_test_data(5, 1, &bin_calculator);
+
+        let file_name = PathBuf::from("test_compressed");
+        cache.save(&file_name, &data, 0).unwrap();
+
+        let mut accum = vec![vec![]];
+        cache
+            .load(&file_name, &mut accum, 0, &bin_calculator)
+            .unwrap();
+        assert_eq!(accum, data);
+    }
+
+    #[test]
+    fn test_checksum_mismatch_is_detected() {
+        use {
+            std::io::{Seek, Write},
+            tempfile::TempDir,
+        };
+        let tmpdir = TempDir::new().unwrap();
+        let cache_dir = tmpdir.path().to_path_buf();
+        let cache = CacheHashData::new(cache_dir.clone());
+
+        let entry = CalculateHashIntermediate::new(
+            solana_sdk::hash::Hash::new_unique(),
+            1,
+            solana_sdk::pubkey::new_rand(),
+        );
+        let data = vec![vec![entry]];
+        let file_name = PathBuf::from("test_checksum");
+        cache.save(&file_name, &data, 0).unwrap();
+
+        // Flip a byte in the entry payload, past the header and the
+        // offset table (2 u64s: start + sentinel), to simulate corruption
+        // or a torn write.
+        let path = cache_dir.join(&file_name);
+        let entries_offset =
+            std::mem::size_of::<Header>() as u64 + 2 * std::mem::size_of::<u64>() as u64;
+        let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.seek(std::io::SeekFrom::Start(entries_offset)).unwrap();
+        file.write_all(&[0xFF]).unwrap();
+        drop(file);
+
+        assert_eq!(cache.integrity_stats.checksum_mismatches.load(Ordering::Relaxed), 0);
+        let result = cache.load_map(&file_name);
+        assert_eq!(result.err().unwrap().kind(), std::io::ErrorKind::InvalidData);
+        assert_eq!(cache.integrity_stats.checksum_mismatches.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_get_bin_slice_reads_exact_bin_range() {
+        use tempfile::TempDir;
+        let tmpdir = TempDir::new().unwrap();
+        let cache_dir = tmpdir.path().to_path_buf();
+        let cache = CacheHashData::new(cache_dir);
+
+        let bin_calculator = PubkeyBinCalculator24::new(4);
+        let (data, _total_points) = generate_test_data(12, 4, &bin_calculator);
+
+        let file_name = PathBuf::from("test_bin_index");
+        cache.save(&file_name, &data, 0).unwrap();
+        let cache_file = cache.load_map(&file_name).unwrap();
+
+        for (bin, expected) in data.iter().enumerate() {
+            let actual = cache_file.get_bin_slice(bin).unwrap();
+            assert_eq!(actual.len(), expected.len());
+            assert_eq!(
+                actual.iter().map(|e| e.pubkey).collect::<Vec<_>>(),
+                expected.iter().map(|e| e.pubkey).collect::<Vec<_>>(),
+            );
+        }
+        // Out of range for this file's 4 bins.
+        assert!(cache_file.get_bin_slice(4).is_none());
+    }
+
+    #[test]
+    fn test_get_bin_slice_is_none_for_old_format_file_without_index() {
+        use {std::io::Write, tempfile::TempDir};
+        let tmpdir = TempDir::new().unwrap();
+        let cache_dir = tmpdir.path().to_path_buf();
+        let cache = CacheHashData::new(cache_dir.clone());
+
+        // Hand-craft a pre-index file: header with `bin_count: 0` directly
+        // followed by entry bytes, with no offset table in between --
+        // exactly what `save` produced before this index existed.
+        let entry = CalculateHashIntermediate::new(
+            solana_sdk::hash::Hash::new_unique(),
+            1,
+            solana_sdk::pubkey::new_rand(),
+        );
+        let entry_bytes = unsafe {
+            std::slice::from_raw_parts(
+                &entry as *const EntryType as *const u8,
+                std::mem::size_of::<EntryType>(),
+            )
+        };
+        let header = Header {
+            count: 1,
+            version: CURRENT_HEADER_VERSION,
+            compression: Compression::None as u8,
+            _reserved: [0; 6],
+            checksum: 0,
+            start_bin_index: 0,
+            bin_count: 0,
+        };
+        let header_bytes = bytemuck::bytes_of(&header);
+
+        let file_name = PathBuf::from("test_no_index");
+        let path = cache_dir.join(&file_name);
+        let mut file = std::fs::File::create(&path).unwrap();
+        file.write_all(header_bytes).unwrap();
+        file.write_all(entry_bytes).unwrap();
+        drop(file);
+
+        let cache_file = cache.load_map(&file_name).unwrap();
+        assert!(cache_file.get_bin_slice(0).is_none());
+        assert_eq!(cache_file.get_cache_hash_data().len(), 1);
+    }
+
     fn bin_data(
         data: &mut SavedType,
         bin_calculator: &PubkeyBinCalculator24,
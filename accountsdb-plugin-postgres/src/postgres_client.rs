@@ -9,9 +9,24 @@ use {
     },
     chrono::Utc,
     crossbeam_channel::{bounded, Receiver, RecvTimeoutError, Sender},
+    deadpool_postgres::{
+        GenericClient, Manager as DeadpoolManager, ManagerConfig, Pool as DeadpoolPool,
+        RecyclingMethod, Runtime as DeadpoolRuntime,
+    },
+    lazy_static::lazy_static,
     log::*,
-    postgres::{Client, NoTls, Statement},
+    lru::LruCache,
+    native_tls::{Certificate, Identity, TlsConnector},
+    postgres::{
+        binary_copy::BinaryCopyInWriter, error::SqlState, types::Type, Client, NoTls, Statement,
+    },
+    postgres_native_tls::MakeTlsConnector,
     postgres_types::ToSql,
+    prometheus::{
+        Encoder, Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, Opts, Registry,
+        TextEncoder,
+    },
+    serde_derive::Deserialize,
     solana_accountsdb_plugin_interface::accountsdb_plugin_interface::{
         AccountsDbPluginError, ReplicaAccountInfo, ReplicaTransactionInfo, SlotStatus,
     },
@@ -23,6 +38,7 @@ use {
             v0::{self, AddressMapIndexes},
             MappedAddresses, MappedMessage, Message, MessageHeader, SanitizedMessage,
         },
+        pubkey::Pubkey,
         timing::AtomicInterval,
         transaction::TransactionError,
     },
@@ -30,6 +46,9 @@ use {
         InnerInstructions, Reward, TransactionStatusMeta, TransactionTokenBalance,
     },
     std::{
+        collections::HashMap,
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
         sync::{
             atomic::{AtomicBool, AtomicUsize, Ordering},
             Arc, Mutex,
@@ -37,7 +56,9 @@ use {
         thread::{self, sleep, Builder, JoinHandle},
         time::Duration,
     },
-    tokio_postgres::types,
+    tokio::runtime::{Builder as TokioRuntimeBuilder, Runtime as TokioRuntime},
+    tokio::time::timeout as tokio_timeout,
+    tokio_postgres::{types, NoTls as TokioNoTls},
 };
 
 /// The maximum asynchronous requests allowed in the channel to avoid excessive
@@ -46,8 +67,74 @@ const MAX_ASYNC_REQUESTS: usize = 40960;
 const DEFAULT_POSTGRES_PORT: u16 = 5432;
 const DEFAULT_THREADS_COUNT: usize = 100;
 const DEFAULT_ACCOUNTS_INSERT_BATCH_SIZE: usize = 10;
+/// Default size of `SimplePostgresClient::transaction_id_cache`, the
+/// in-memory LRU mapping recently-seen signatures to `transactions.transaction_id`
+/// so `log_transaction` doesn't take a round trip per transaction.
+const DEFAULT_TRANSACTION_ID_CACHE_SIZE: usize = 10_000;
+/// Default number of accounts `ParallelPostgresClient::build_db_block_info`
+/// keeps in each of `heavily_writelocked_accounts`/`heavily_readlocked_accounts`.
+const DEFAULT_HEAVILY_LOCKED_ACCOUNTS_LIMIT: usize = 20;
+/// Default minimum number of times an account must be locked within a block
+/// before `build_db_block_info` considers it "heavily" locked.
+const DEFAULT_HEAVILY_LOCKED_ACCOUNTS_THRESHOLD: u32 = 10;
+/// Default limit, in estimated serialized bytes, on `pending_account_updates`
+/// before `insert_accounts_in_batch` flushes regardless of `batch_size`; see
+/// `SchemaSize`.
+const DEFAULT_MAX_QUERY_SIZE: usize = 200 * 1024;
+/// Default number of capped-exponential-backoff attempts
+/// `SimplePostgresClient::reconnect` makes before giving up.
+const DEFAULT_MAX_RECONNECT_ATTEMPTS: u32 = 10;
+/// Default starting delay for `reconnect`'s backoff, doubled after each
+/// failed attempt up to `DEFAULT_RECONNECT_BACKOFF_MAX_MS`.
+const DEFAULT_RECONNECT_BACKOFF_BASE_MS: u64 = 500;
+/// Default cap on `reconnect`'s doubled backoff delay.
+const DEFAULT_RECONNECT_BACKOFF_MAX_MS: u64 = 30_000;
+/// Default number of times `with_reconnect_retry` retries a statement that
+/// fails with a transient (serialization failure/deadlock) error.
+const DEFAULT_MAX_RETRIES: u32 = 5;
+/// Default starting delay for `with_reconnect_retry`'s transient-error
+/// backoff, doubled after each attempt up to `DEFAULT_MAX_RETRY_BACKOFF_MS`.
+const DEFAULT_BACKOFF_BASE_MS: u64 = 50;
+/// Default cap on `with_reconnect_retry`'s doubled transient-error backoff
+/// delay.
+const DEFAULT_MAX_RETRY_BACKOFF_MS: u64 = 5_000;
+
+/// Default number of most-recent `account_transactions` rows kept per
+/// pubkey; see `SimplePostgresClient::log_transaction`.
+const DEFAULT_LATEST_TXS_PER_ACCOUNT: usize = 120;
 const ACCOUNT_COLUMN_COUNT: usize = 9;
 const DEFAULT_PANIC_ON_DB_ERROR: bool = false;
+/// Default maximum number of physical connections `AsyncPostgresPool` keeps
+/// open, used when `config.use_connection_pool` is enabled.
+const DEFAULT_POOL_MAX_SIZE: usize = 20;
+/// Default time a pooled-path caller waits for a free connection before
+/// giving up with a timeout error.
+const DEFAULT_POOL_TIMEOUT_MS: u64 = 5_000;
+
+/// Single upsert statement text shared by the synchronous prepared-statement
+/// path (`build_single_account_upsert_statement`) and the pooled async path
+/// (`AsyncPostgresPool::upsert_account`), so the two can't drift apart.
+const UPSERT_ACCOUNT_SQL: &str = "INSERT INTO account AS acct (pubkey, slot, owner, lamports, executable, rent_epoch, data, write_version, updated_on) \
+VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+ON CONFLICT (pubkey) DO UPDATE SET slot=excluded.slot, owner=excluded.owner, lamports=excluded.lamports, executable=excluded.executable, rent_epoch=excluded.rent_epoch, \
+data=excluded.data, write_version=excluded.write_version, updated_on=excluded.updated_on  WHERE acct.slot < excluded.slot OR (\
+acct.slot = excluded.slot AND acct.write_version < excluded.write_version)";
+
+/// How `connect_to_db` should use TLS when talking to the PostgreSQL server,
+/// set via `AccountsDbPluginPostgresConfig::ssl_mode`. Mirrors libpq's
+/// `sslmode` of the same names, restricted to the subset this plugin
+/// supports.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum SslMode {
+    /// Never use TLS.
+    Disable,
+    /// Attempt TLS first; fall back to a plain connection if the server
+    /// doesn't support it.
+    Prefer,
+    /// Use TLS, failing the connection outright if it can't be established.
+    Require,
+}
 
 struct PostgresSqlClientWrapper {
     client: Client,
@@ -56,11 +143,44 @@ struct PostgresSqlClientWrapper {
     update_slot_with_parent_stmt: Statement,
     update_slot_without_parent_stmt: Statement,
     update_transaction_log_stmt: Statement,
+    /// Upserts into the `transactions` signature→id dictionary table and
+    /// returns the `transaction_id`; see `SimplePostgresClient::get_transaction_id`.
+    transaction_id_stmt: Statement,
+    update_block_stmt: Statement,
+    /// Only set when `use_copy` is enabled; merges `account_copy_staging`
+    /// into `account`. See `copy_accounts_to_staging_and_merge`.
+    copy_merge_stmt: Option<Statement>,
+    /// Inserts one `(pubkey, slot, transaction_id, signature)` row into
+    /// `account_transactions`; see `SimplePostgresClient::log_transaction`.
+    insert_account_transaction_stmt: Statement,
+    /// Deletes `account_transactions` rows for a pubkey past the newest
+    /// `latest_txs_per_account` of them.
+    prune_account_transactions_stmt: Statement,
 }
 
 pub struct SimplePostgresClient {
     batch_size: usize,
+    /// Flush `pending_account_updates` once its estimated serialized size
+    /// (tracked in `pending_account_updates_byte_size`) reaches this many
+    /// bytes, even if `batch_size` rows haven't accumulated yet.
+    max_query_size: usize,
+    /// Stream the startup account dump through a binary `COPY` into a temp
+    /// staging table instead of batched `INSERT`s; see
+    /// `copy_accounts_to_staging_and_merge`.
+    use_copy: bool,
     pending_account_updates: Vec<DbAccountInfo>,
+    /// Running total of `DbAccountInfo::data_size()` across
+    /// `pending_account_updates`, compared against `max_query_size`.
+    pending_account_updates_byte_size: usize,
+    /// Caches recently-seen signature -> `transactions.transaction_id`
+    /// lookups; see `get_transaction_id`.
+    transaction_id_cache: LruCache<Vec<u8>, i64>,
+    /// Number of most-recent `account_transactions` rows kept per pubkey;
+    /// see `log_transaction`.
+    latest_txs_per_account: usize,
+    /// Retained so `reconnect` can rebuild the connection and every prepared
+    /// statement from scratch after the connection is lost.
+    config: AccountsDbPluginPostgresConfig,
     client: Mutex<PostgresSqlClientWrapper>,
 }
 
@@ -84,6 +204,35 @@ pub struct DbAccountInfo {
     pub write_version: i64,
 }
 
+/// The largest account `data` payload the runtime allows, used as the
+/// variable-length term of `DbAccountInfo::MAX_SIZE`.
+const MAX_ACCOUNT_DATA_SIZE: usize = 10 * 1024 * 1024;
+
+/// Estimates how many bytes a row contributes to a batched `INSERT`/`COPY`,
+/// so `SimplePostgresClient::insert_accounts_in_batch` can flush
+/// `pending_account_updates` before it grows large enough to exceed
+/// PostgreSQL's message-size limits, rather than relying on row count alone.
+pub trait SchemaSize {
+    /// A conservative estimate for a row carrying only small, fixed-width
+    /// fields (used when the caller has no better information).
+    const DEFAULT_SIZE: usize;
+    /// An upper bound including the type's largest plausible variable-length
+    /// payload.
+    const MAX_SIZE: usize;
+
+    /// The estimated serialized size of this particular value.
+    fn data_size(&self) -> usize;
+}
+
+impl SchemaSize for DbAccountInfo {
+    const DEFAULT_SIZE: usize = 136;
+    const MAX_SIZE: usize = Self::DEFAULT_SIZE + MAX_ACCOUNT_DATA_SIZE;
+
+    fn data_size(&self) -> usize {
+        Self::DEFAULT_SIZE + self.pubkey.len() + self.owner.len() + self.data.len()
+    }
+}
+
 #[derive(Clone, Debug, ToSql)]
 #[postgres(name = "CompiledInstruction")]
 pub struct DbCompiledInstruction {
@@ -192,6 +341,41 @@ pub struct DbTransaction {
     signatures: Vec<Vec<u8>>,
 }
 
+/// A conservative estimate for the log/instruction/account payload a
+/// typical transaction carries, used as the variable-length term of
+/// `DbTransaction::MAX_SIZE`.
+const MAX_TRANSACTION_PAYLOAD_SIZE: usize = 256 * 1024;
+
+impl SchemaSize for DbTransaction {
+    const DEFAULT_SIZE: usize = 256;
+    const MAX_SIZE: usize = Self::DEFAULT_SIZE + MAX_TRANSACTION_PAYLOAD_SIZE;
+
+    fn data_size(&self) -> usize {
+        let log_messages_size = self
+            .meta
+            .log_messages
+            .as_ref()
+            .map(|messages| messages.iter().map(|message| message.len()).sum())
+            .unwrap_or(0);
+        Self::DEFAULT_SIZE
+            + self.signature.len()
+            + self.message_hash.len()
+            + self.signatures.iter().map(|sig| sig.len()).sum::<usize>()
+            + log_messages_size
+    }
+}
+
+/// The block-level aggregates `notify_block` persists to the `blocks`
+/// table; see `ParallelPostgresClient::build_db_block_info`.
+pub struct DbBlockInfo {
+    pub slot: i64,
+    pub processed_transactions: i64,
+    pub total_cu_used: i64,
+    pub total_cu_requested: i64,
+    pub heavily_writelocked_accounts: Vec<Vec<u8>>,
+    pub heavily_readlocked_accounts: Vec<Vec<u8>>,
+}
+
 impl From<&AddressMapIndexes> for DbAddressMapIndexes {
     fn from(address_map_indexes: &AddressMapIndexes) -> Self {
         Self {
@@ -546,35 +730,79 @@ pub trait PostgresClient {
         &mut self,
         transaction_log_info: LogTransactionRequest,
     ) -> Result<(), AccountsDbPluginError>;
+
+    fn notify_block(&mut self, block_info: DbBlockInfo) -> Result<(), AccountsDbPluginError>;
 }
 
 impl SimplePostgresClient {
+    /// Resolves `config` into a libpq key/value connection string, preferring
+    /// `connection_str` when set. Shared by the synchronous connection path
+    /// and `AsyncPostgresPool` so the two can't disagree about how to reach
+    /// the database.
+    fn build_connection_string(
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<String, AccountsDbPluginError> {
+        if let Some(connection_str) = &config.connection_str {
+            return Ok(connection_str.clone());
+        }
+
+        if config.host.is_none() || config.user.is_none() {
+            let msg = format!(
+                "\"connection_str\": {:?}, or \"host\": {:?} \"user\": {:?} must be specified",
+                config.connection_str, config.host, config.user
+            );
+            return Err(AccountsDbPluginError::Custom(Box::new(
+                AccountsDbPluginPostgresError::ConfigurationError { msg },
+            )));
+        }
+
+        let port = config.port.unwrap_or(DEFAULT_POSTGRES_PORT);
+        Ok(format!(
+            "host={} user={} port={}",
+            config.host.as_ref().unwrap(),
+            config.user.as_ref().unwrap(),
+            port
+        ))
+    }
+
     fn connect_to_db(
         config: &AccountsDbPluginPostgresConfig,
     ) -> Result<Client, AccountsDbPluginError> {
-        let port = config.port.unwrap_or(DEFAULT_POSTGRES_PORT);
+        let connection_str = Self::build_connection_string(config)?;
 
-        let connection_str = if let Some(connection_str) = &config.connection_str {
-            connection_str.clone()
-        } else {
-            if config.host.is_none() || config.user.is_none() {
-                let msg = format!(
-                    "\"connection_str\": {:?}, or \"host\": {:?} \"user\": {:?} must be specified",
-                    config.connection_str, config.host, config.user
+        let ssl_mode = config.ssl_mode.unwrap_or(SslMode::Disable);
+        if ssl_mode == SslMode::Disable {
+            return Self::connect_with_tls_result(&connection_str, NoTls);
+        }
+
+        let connector = Self::build_tls_connector(config)?;
+        match Self::connect_with_tls_result(&connection_str, connector) {
+            Ok(client) => Ok(client),
+            Err(err) if ssl_mode == SslMode::Prefer => {
+                warn!(
+                    "Failed to connect to the PostgreSQL database over TLS, falling back to a \
+                     plain connection because \"ssl_mode\" is \"prefer\": {:?}",
+                    err
                 );
-                return Err(AccountsDbPluginError::Custom(Box::new(
-                    AccountsDbPluginPostgresError::ConfigurationError { msg },
-                )));
+                Self::connect_with_tls_result(&connection_str, NoTls)
             }
-            format!(
-                "host={} user={} port={}",
-                config.host.as_ref().unwrap(),
-                config.user.as_ref().unwrap(),
-                port
-            )
-        };
+            Err(err) => Err(err),
+        }
+    }
 
-        match Client::connect(&connection_str, NoTls) {
+    fn connect_with_tls_result<T>(
+        connection_str: &str,
+        tls: T,
+    ) -> Result<Client, AccountsDbPluginError>
+    where
+        T: postgres::tls::MakeTlsConnect<postgres::Socket> + 'static + Send,
+        <T as postgres::tls::MakeTlsConnect<postgres::Socket>>::Stream: Send,
+        <T as postgres::tls::MakeTlsConnect<postgres::Socket>>::TlsConnect: Send,
+        <<T as postgres::tls::MakeTlsConnect<postgres::Socket>>::TlsConnect as postgres::tls::TlsConnect<
+            postgres::Socket,
+        >>::Future: Send,
+    {
+        match Client::connect(connection_str, tls) {
             Err(err) => {
                 let msg = format!(
                     "Error in connecting to the PostgreSQL database: {:?} connection_str: {:?}",
@@ -589,6 +817,97 @@ impl SimplePostgresClient {
         }
     }
 
+    /// Builds the `MakeTlsConnector` used when `config.ssl_mode` asks for
+    /// `Prefer` or `Require`. The CA certificate and the client identity
+    /// (a PKCS#12 bundle) can each be sourced from a file on disk or from a
+    /// base64-encoded environment variable, so operators aren't forced to
+    /// write certificate material into the plugin's JSON config file.
+    fn build_tls_connector(
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<MakeTlsConnector, AccountsDbPluginError> {
+        let mut builder = TlsConnector::builder();
+
+        if let Some(ca_cert_bytes) = Self::load_cert_material(
+            "ssl_ca_file",
+            config.ssl_ca_file.as_deref(),
+            config.ssl_ca_base64_var.as_deref(),
+        )? {
+            let ca_cert = Certificate::from_pem(&ca_cert_bytes)
+                .or_else(|_| Certificate::from_der(&ca_cert_bytes))
+                .map_err(|err| {
+                    Self::tls_configuration_error(format!(
+                        "Failed to parse the \"ssl_ca_file\" certificate: {:?}",
+                        err
+                    ))
+                })?;
+            builder.add_root_certificate(ca_cert);
+        }
+
+        if let Some(identity_bytes) = Self::load_cert_material(
+            "ssl_client_cert_file",
+            config.ssl_client_cert_file.as_deref(),
+            config.ssl_client_cert_base64_var.as_deref(),
+        )? {
+            let password = config.ssl_client_cert_password.as_deref().unwrap_or("");
+            let identity = Identity::from_pkcs12(&identity_bytes, password).map_err(|err| {
+                Self::tls_configuration_error(format!(
+                    "Failed to parse the \"ssl_client_cert_file\" PKCS#12 identity: {:?}",
+                    err
+                ))
+            })?;
+            builder.identity(identity);
+        }
+
+        let connector = builder.build().map_err(|err| {
+            Self::tls_configuration_error(format!("Failed to build the TLS connector: {:?}", err))
+        })?;
+
+        Ok(MakeTlsConnector::new(connector))
+    }
+
+    /// Loads certificate material from `file_field` if set, otherwise from
+    /// the base64-encoded contents of the `base64_var_field` environment
+    /// variable if that's set, otherwise returns `None`.
+    fn load_cert_material(
+        field_name: &str,
+        file_path: Option<&str>,
+        base64_env_var: Option<&str>,
+    ) -> Result<Option<Vec<u8>>, AccountsDbPluginError> {
+        if let Some(path) = file_path {
+            return std::fs::read(path).map(Some).map_err(|err| {
+                Self::tls_configuration_error(format!(
+                    "Failed to read \"{}\" at {:?}: {:?}",
+                    field_name, path, err
+                ))
+            });
+        }
+
+        if let Some(var) = base64_env_var {
+            let encoded = std::env::var(var).map_err(|err| {
+                Self::tls_configuration_error(format!(
+                    "Failed to read the environment variable {:?} referenced by \"{}\": {:?}",
+                    var, field_name, err
+                ))
+            })?;
+            let decoded = base64::decode(encoded.trim()).map_err(|err| {
+                Self::tls_configuration_error(format!(
+                    "Failed to base64-decode the environment variable {:?} referenced by \"{}\": {:?}",
+                    var, field_name, err
+                ))
+            })?;
+            return Ok(Some(decoded));
+        }
+
+        Ok(None)
+    }
+
+    fn tls_configuration_error(msg: String) -> AccountsDbPluginError {
+        error!("{}", msg);
+        AccountsDbPluginError::Custom(Box::new(
+            AccountsDbPluginPostgresError::DataStoreConnectionError { msg },
+        ))
+    }
+
     fn build_bulk_account_insert_statement(
         client: &mut Client,
         config: &AccountsDbPluginPostgresConfig,
@@ -641,17 +960,58 @@ impl SimplePostgresClient {
         }
     }
 
-    fn build_single_account_upsert_statement(
+    /// Creates the session-scoped temp table `copy_accounts_to_staging_and_merge`
+    /// streams the binary `COPY` into. A `TEMP TABLE` is only visible to the
+    /// connection that created it, so this only needs to run once per
+    /// `Client`.
+    fn create_copy_staging_table(
+        client: &mut Client,
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<(), AccountsDbPluginError> {
+        let stmt =
+            "CREATE TEMP TABLE account_copy_staging (LIKE account INCLUDING DEFAULTS) ON COMMIT PRESERVE ROWS";
+
+        match client.batch_execute(stmt) {
+            Err(err) => Err(AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::DataSchemaError {
+                msg: format!(
+                    "Error in creating the COPY staging table in the PostgreSQL database: {} host: {:?} user: {:?} config: {:?}",
+                    err, config.host, config.user, config
+                ),
+            }))),
+            Ok(()) => Ok(()),
+        }
+    }
+
+    /// `COPY` can't express the `ON CONFLICT` upsert semantics
+    /// `build_bulk_account_insert_statement` uses, so rows land in
+    /// `account_copy_staging` first and this statement merges them into
+    /// `account` with the same conflict handling.
+    fn build_copy_merge_statement(
         client: &mut Client,
         config: &AccountsDbPluginPostgresConfig,
     ) -> Result<Statement, AccountsDbPluginError> {
         let stmt = "INSERT INTO account AS acct (pubkey, slot, owner, lamports, executable, rent_epoch, data, write_version, updated_on) \
-        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+        SELECT pubkey, slot, owner, lamports, executable, rent_epoch, data, write_version, updated_on FROM account_copy_staging \
         ON CONFLICT (pubkey) DO UPDATE SET slot=excluded.slot, owner=excluded.owner, lamports=excluded.lamports, executable=excluded.executable, rent_epoch=excluded.rent_epoch, \
-        data=excluded.data, write_version=excluded.write_version, updated_on=excluded.updated_on  WHERE acct.slot < excluded.slot OR (\
+        data=excluded.data, write_version=excluded.write_version, updated_on=excluded.updated_on WHERE acct.slot < excluded.slot OR (\
         acct.slot = excluded.slot AND acct.write_version < excluded.write_version)";
 
-        let stmt = client.prepare(stmt);
+        match client.prepare(stmt) {
+            Err(err) => Err(AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::DataSchemaError {
+                msg: format!(
+                    "Error in preparing the COPY merge statement for the PostgreSQL database: {} host: {:?} user: {:?} config: {:?}",
+                    err, config.host, config.user, config
+                ),
+            }))),
+            Ok(stmt) => Ok(stmt),
+        }
+    }
+
+    fn build_single_account_upsert_statement(
+        client: &mut Client,
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<Statement, AccountsDbPluginError> {
+        let stmt = client.prepare(UPSERT_ACCOUNT_SQL);
 
         match stmt {
             Err(err) => {
@@ -666,11 +1026,36 @@ impl SimplePostgresClient {
         }
     }
 
+    /// Upserts `signature` into the `transactions` dictionary table
+    /// (`transactions(signature CHAR(88) PRIMARY KEY, transaction_id
+    /// BIGSERIAL UNIQUE)`) and returns its `transaction_id`. `ON CONFLICT
+    /// DO NOTHING` wouldn't return a row for a signature that's already
+    /// present, so this upserts `signature` onto itself instead to make the
+    /// `RETURNING` clause fire either way.
+    fn build_transaction_id_statement(
+        client: &mut Client,
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<Statement, AccountsDbPluginError> {
+        let stmt = "INSERT INTO transactions AS td (signature) VALUES ($1) \
+        ON CONFLICT (signature) DO UPDATE SET signature = excluded.signature \
+        RETURNING transaction_id";
+
+        match client.prepare(stmt) {
+            Err(err) => Err(AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::DataSchemaError {
+                msg: format!(
+                    "Error in preparing the transaction-id dictionary statement for the PostgreSQL database: {} host: {:?} user: {:?} config: {:?}",
+                    err, config.host, config.user, config
+                ),
+            }))),
+            Ok(stmt) => Ok(stmt),
+        }
+    }
+
     fn build_transaction_log_upsert_statement(
         client: &mut Client,
         config: &AccountsDbPluginPostgresConfig,
     ) -> Result<Statement, AccountsDbPluginError> {
-        let stmt = "INSERT INTO transaction AS txn (signature, is_vote, slot, message_type, legacy_message, \
+        let stmt = "INSERT INTO transaction AS txn (transaction_id, is_vote, slot, message_type, legacy_message, \
         v0_mapped_message, signatures, message_hash, meta, updated_on) \
         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)";
 
@@ -689,6 +1074,70 @@ impl SimplePostgresClient {
         }
     }
 
+    fn build_block_upsert_statement(
+        client: &mut Client,
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<Statement, AccountsDbPluginError> {
+        let stmt = "INSERT INTO blocks AS blk (slot, processed_transactions, total_cu_used, total_cu_requested, \
+        heavily_writelocked_accounts, heavily_readlocked_accounts, updated_on) \
+        VALUES ($1, $2, $3, $4, $5, $6, $7) \
+        ON CONFLICT (slot) DO UPDATE SET processed_transactions=excluded.processed_transactions, \
+        total_cu_used=excluded.total_cu_used, total_cu_requested=excluded.total_cu_requested, \
+        heavily_writelocked_accounts=excluded.heavily_writelocked_accounts, \
+        heavily_readlocked_accounts=excluded.heavily_readlocked_accounts, updated_on=excluded.updated_on";
+
+        match client.prepare(stmt) {
+            Err(err) => Err(AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::DataSchemaError {
+                msg: format!(
+                    "Error in preparing for the block update PostgreSQL database: {} host: {:?} user: {:?} config: {:?}",
+                    err, config.host, config.user, config
+                ),
+            }))),
+            Ok(stmt) => Ok(stmt),
+        }
+    }
+
+    /// Records one `(pubkey, slot, transaction_id, signature)` row in
+    /// `account_transactions`, the capped "latest transactions per account"
+    /// mapping table; see `log_transaction`.
+    fn build_account_transactions_insert_statement(
+        client: &mut Client,
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<Statement, AccountsDbPluginError> {
+        let stmt = "INSERT INTO account_transactions (pubkey, slot, transaction_id, signature) \
+        VALUES ($1, $2, $3, $4)";
+
+        match client.prepare(stmt) {
+            Err(err) => Err(AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::DataSchemaError {
+                msg: format!(
+                    "Error in preparing for the account_transactions insert PostgreSQL database: {} host: {:?} user: {:?} config: {:?}",
+                    err, config.host, config.user, config
+                ),
+            }))),
+            Ok(stmt) => Ok(stmt),
+        }
+    }
+
+    /// Deletes `account_transactions` rows for a pubkey past the newest
+    /// `$2` of them (ordered by slot); see `log_transaction`.
+    fn build_account_transactions_prune_statement(
+        client: &mut Client,
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<Statement, AccountsDbPluginError> {
+        let stmt = "DELETE FROM account_transactions WHERE pubkey = $1 AND signature NOT IN \
+        (SELECT signature FROM account_transactions WHERE pubkey = $1 ORDER BY slot DESC LIMIT $2)";
+
+        match client.prepare(stmt) {
+            Err(err) => Err(AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::DataSchemaError {
+                msg: format!(
+                    "Error in preparing for the account_transactions prune PostgreSQL database: {} host: {:?} user: {:?} config: {:?}",
+                    err, config.host, config.user, config
+                ),
+            }))),
+            Ok(stmt) => Ok(stmt),
+        }
+    }
+
     fn build_slot_upsert_statement_with_parent(
         client: &mut Client,
         config: &AccountsDbPluginPostgresConfig,
@@ -740,24 +1189,35 @@ impl SimplePostgresClient {
         account: &DbAccountInfo,
         statement: &Statement,
         client: &mut Client,
-    ) -> Result<(), AccountsDbPluginError> {
+    ) -> Result<(), postgres::Error> {
         let lamports = account.lamports() as i64;
         let rent_epoch = account.rent_epoch() as i64;
         let updated_on = Utc::now().naive_utc();
-        let result = client.query(
-            statement,
-            &[
-                &account.pubkey(),
-                &account.slot,
-                &account.owner(),
-                &lamports,
-                &account.executable(),
-                &rent_epoch,
-                &account.data(),
-                &account.write_version(),
-                &updated_on,
-            ],
-        );
+        client
+            .query(
+                statement,
+                &[
+                    &account.pubkey(),
+                    &account.slot,
+                    &account.owner(),
+                    &lamports,
+                    &account.executable(),
+                    &rent_epoch,
+                    &account.data(),
+                    &account.write_version(),
+                    &updated_on,
+                ],
+            )
+            .map(|_| ())
+    }
+
+    /// Update or insert a single account, reconnecting and retrying once if
+    /// the connection was lost.
+    fn upsert_account(&mut self, account: &DbAccountInfo) -> Result<(), AccountsDbPluginError> {
+        let result = Self::with_reconnect_retry(&self.config, &mut self.client, |wrapper| {
+            let statement = &wrapper.update_account_stmt;
+            Self::upsert_account_internal(account, statement, &mut wrapper.client)
+        });
 
         if let Err(err) = result {
             let msg = format!(
@@ -767,81 +1227,233 @@ impl SimplePostgresClient {
             error!("{}", msg);
             return Err(AccountsDbPluginError::AccountsUpdateError { msg });
         }
-
         Ok(())
     }
 
-    /// Update or insert a single account
-    fn upsert_account(&mut self, account: &DbAccountInfo) -> Result<(), AccountsDbPluginError> {
-        let client = self.client.get_mut().unwrap();
-        let statement = &client.update_account_stmt;
-        let client = &mut client.client;
-        Self::upsert_account_internal(account, statement, client)
-    }
-
     /// Insert accounts in batch to reduce network overhead
     fn insert_accounts_in_batch(
         &mut self,
         account: DbAccountInfo,
     ) -> Result<(), AccountsDbPluginError> {
+        self.pending_account_updates_byte_size += account.data_size();
         self.pending_account_updates.push(account);
+        PENDING_ACCOUNT_BUFFER_DEPTH.set(self.pending_account_updates.len() as i64);
 
         if self.pending_account_updates.len() == self.batch_size {
-            let mut measure = Measure::start("accountsdb-plugin-postgres-prepare-values");
-
-            let mut values: Vec<&(dyn types::ToSql + Sync)> =
-                Vec::with_capacity(self.batch_size * ACCOUNT_COLUMN_COUNT);
-            let updated_on = Utc::now().naive_utc();
-            for j in 0..self.batch_size {
-                let account = &self.pending_account_updates[j];
-
-                values.push(&account.pubkey);
-                values.push(&account.slot);
-                values.push(&account.owner);
-                values.push(&account.lamports);
-                values.push(&account.executable);
-                values.push(&account.rent_epoch);
-                values.push(&account.data);
-                values.push(&account.write_version);
-                values.push(&updated_on);
-            }
-            measure.stop();
-            inc_new_counter_debug!(
-                "accountsdb-plugin-postgres-prepare-values-us",
-                measure.as_us() as usize,
-                10000,
-                10000
-            );
+            return if self.use_copy {
+                self.copy_accounts_to_staging_and_merge()
+            } else {
+                self.insert_accounts_via_values()
+            };
+        }
 
-            let mut measure = Measure::start("accountsdb-plugin-postgres-update-account");
-            let client = self.client.get_mut().unwrap();
-            let result = client
-                .client
-                .query(&client.bulk_account_insert_stmt, &values);
+        if self.pending_account_updates_byte_size >= self.max_query_size {
+            // A partial batch doesn't have `batch_size` rows, so it can't be
+            // bound to the fixed-arity prepared `bulk_account_insert_stmt`
+            // (or the COPY merge, whose staging table is only set up when
+            // `use_copy` is on): upsert the pending rows one at a time
+            // instead of waiting for `batch_size` to accumulate.
+            return self.flush_pending_account_updates_individually();
+        }
+        Ok(())
+    }
 
-            self.pending_account_updates.clear();
-            if let Err(err) = result {
-                let msg = format!(
-                    "Failed to persist the update of account to the PostgreSQL database. Error: {:?}",
-                    err
-                );
-                error!("{}", msg);
-                return Err(AccountsDbPluginError::AccountsUpdateError { msg });
-            }
-            measure.stop();
-            inc_new_counter_debug!(
-                "accountsdb-plugin-postgres-update-account-us",
-                measure.as_us() as usize,
-                10000,
-                10000
+    /// Upserts every row in `pending_account_updates` one at a time via
+    /// `upsert_account`, then clears the buffer. Used when `max_query_size`
+    /// triggers a flush before `batch_size` rows have accumulated.
+    fn flush_pending_account_updates_individually(&mut self) -> Result<(), AccountsDbPluginError> {
+        let pending = std::mem::take(&mut self.pending_account_updates);
+        self.pending_account_updates_byte_size = 0;
+        PENDING_ACCOUNT_BUFFER_DEPTH.set(0);
+        for account in &pending {
+            self.upsert_account(account)?;
+        }
+        Ok(())
+    }
+
+    /// Insert `self.pending_account_updates` with a single multi-row `INSERT
+    /// ... VALUES ... ON CONFLICT` built by `build_bulk_account_insert_statement`.
+    fn insert_accounts_via_values(&mut self) -> Result<(), AccountsDbPluginError> {
+        let mut measure = Measure::start("accountsdb-plugin-postgres-prepare-values");
+
+        let mut values: Vec<&(dyn types::ToSql + Sync)> =
+            Vec::with_capacity(self.batch_size * ACCOUNT_COLUMN_COUNT);
+        let updated_on = Utc::now().naive_utc();
+        for j in 0..self.batch_size {
+            let account = &self.pending_account_updates[j];
+
+            values.push(&account.pubkey);
+            values.push(&account.slot);
+            values.push(&account.owner);
+            values.push(&account.lamports);
+            values.push(&account.executable);
+            values.push(&account.rent_epoch);
+            values.push(&account.data);
+            values.push(&account.write_version);
+            values.push(&updated_on);
+        }
+        measure.stop();
+        inc_new_counter_debug!(
+            "accountsdb-plugin-postgres-prepare-values-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+
+        let mut measure = Measure::start("accountsdb-plugin-postgres-update-account");
+        let result = Self::with_reconnect_retry(&self.config, &mut self.client, |wrapper| {
+            wrapper.client.query(&wrapper.bulk_account_insert_stmt, &values)
+        });
+
+        self.pending_account_updates.clear();
+        self.pending_account_updates_byte_size = 0;
+        PENDING_ACCOUNT_BUFFER_DEPTH.set(0);
+        if let Err(err) = result {
+            let msg = format!(
+                "Failed to persist the update of account to the PostgreSQL database. Error: {:?}",
+                err
             );
-            inc_new_counter_debug!(
-                "accountsdb-plugin-postgres-update-account-count",
-                self.batch_size,
-                10000,
-                10000
+            error!("{}", msg);
+            SESSION_ERRORS.inc();
+            return Err(AccountsDbPluginError::AccountsUpdateError { msg });
+        }
+        measure.stop();
+        FLUSH_LATENCY_US.observe(measure.as_us() as f64);
+        ROWS_WRITTEN
+            .with_label_values(&["account"])
+            .inc_by(self.batch_size as u64);
+        inc_new_counter_debug!(
+            "accountsdb-plugin-postgres-update-account-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+        inc_new_counter_debug!(
+            "accountsdb-plugin-postgres-update-account-count",
+            self.batch_size,
+            10000,
+            10000
+        );
+        Ok(())
+    }
+
+    /// Column `Type`s for the `account`/`account_copy_staging` COPY target,
+    /// in table order. Declared up front so `BinaryCopyInWriter` can encode
+    /// each row without per-row type inspection.
+    const COPY_COLUMN_TYPES: [Type; ACCOUNT_COLUMN_COUNT] = [
+        Type::BYTEA,
+        Type::INT8,
+        Type::BYTEA,
+        Type::INT8,
+        Type::BOOL,
+        Type::INT8,
+        Type::BYTEA,
+        Type::INT8,
+        Type::TIMESTAMP,
+    ];
+
+    /// Truncates `account_copy_staging`, streams `accounts` into it via a
+    /// `BinaryCopyInWriter`, then runs `merge_stmt` to fold it into
+    /// `account`.
+    fn copy_accounts_and_merge(
+        client: &mut Client,
+        merge_stmt: &Statement,
+        accounts: &[DbAccountInfo],
+        updated_on: chrono::NaiveDateTime,
+    ) -> Result<u64, std::io::Error> {
+        client
+            .execute("TRUNCATE TABLE account_copy_staging", &[])
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+
+        let writer = client
+            .copy_in(
+                "COPY account_copy_staging (pubkey, slot, owner, lamports, executable, \
+                 rent_epoch, data, write_version, updated_on) FROM STDIN (FORMAT binary)",
+            )
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        let mut writer = BinaryCopyInWriter::new(writer, &Self::COPY_COLUMN_TYPES);
+        for account in accounts {
+            writer
+                .write(&[
+                    &account.pubkey,
+                    &account.slot,
+                    &account.owner,
+                    &account.lamports,
+                    &account.executable,
+                    &account.rent_epoch,
+                    &account.data,
+                    &account.write_version,
+                    &updated_on,
+                ])
+                .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        }
+        let row_count = writer
+            .finish()
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+
+        client
+            .execute(merge_stmt, &[])
+            .map_err(|err| std::io::Error::new(std::io::ErrorKind::Other, err))?;
+        Ok(row_count)
+    }
+
+    /// Streams `self.pending_account_updates` into the session-scoped
+    /// `account_copy_staging` temp table via `BinaryCopyInWriter`, then
+    /// runs `copy_merge_stmt` to merge it into `account` with the same
+    /// upsert semantics `insert_accounts_via_values` gets from its `ON
+    /// CONFLICT` clause. `COPY` itself can't express an `ON CONFLICT`,
+    /// hence the staging table round-trip. Used only for `is_startup`
+    /// batches -- the steady-state path goes through `upsert_account`.
+    fn copy_accounts_to_staging_and_merge(&mut self) -> Result<(), AccountsDbPluginError> {
+        let updated_on = Utc::now().naive_utc();
+        let account_count = self.pending_account_updates.len();
+
+        let mut measure = Measure::start("accountsdb-plugin-postgres-update-account");
+        let client = self.client.get_mut().unwrap();
+        let merge_stmt = client
+            .copy_merge_stmt
+            .as_ref()
+            .expect("copy_merge_stmt is only unset when use_copy is false")
+            .clone();
+        let client = &mut client.client;
+
+        let result = Self::copy_accounts_and_merge(
+            client,
+            &merge_stmt,
+            &self.pending_account_updates,
+            updated_on,
+        )
+        .map(|_row_count| ());
+
+        self.pending_account_updates.clear();
+        self.pending_account_updates_byte_size = 0;
+        PENDING_ACCOUNT_BUFFER_DEPTH.set(0);
+        if let Err(err) = result {
+            let msg = format!(
+                "Failed to persist the update of account to the PostgreSQL database. Error: {:?}",
+                err
             );
+            error!("{}", msg);
+            SESSION_ERRORS.inc();
+            return Err(AccountsDbPluginError::AccountsUpdateError { msg });
         }
+        measure.stop();
+        FLUSH_LATENCY_US.observe(measure.as_us() as f64);
+        ROWS_WRITTEN
+            .with_label_values(&["account"])
+            .inc_by(account_count as u64);
+        inc_new_counter_debug!(
+            "accountsdb-plugin-postgres-update-account-us",
+            measure.as_us() as usize,
+            10000,
+            10000
+        );
+        inc_new_counter_debug!(
+            "accountsdb-plugin-postgres-update-account-count",
+            account_count,
+            10000,
+            10000
+        );
         Ok(())
     }
 
@@ -851,20 +1463,74 @@ impl SimplePostgresClient {
             return Ok(());
         }
 
-        let client = self.client.get_mut().unwrap();
-        let statement = &client.update_account_stmt;
-        let client = &mut client.client;
+        if self.use_copy {
+            return self.copy_accounts_to_staging_and_merge();
+        }
 
-        for account in self.pending_account_updates.drain(..) {
-            Self::upsert_account_internal(&account, statement, client)?;
+        let pending = std::mem::take(&mut self.pending_account_updates);
+        self.pending_account_updates_byte_size = 0;
+        PENDING_ACCOUNT_BUFFER_DEPTH.set(0);
+        for account in &pending {
+            self.upsert_account(account)?;
         }
 
         Ok(())
     }
 
-    pub fn new(config: &AccountsDbPluginPostgresConfig) -> Result<Self, AccountsDbPluginError> {
-        info!("Creating SimplePostgresClient...");
+    /// Applies optional write-throughput session settings to a freshly
+    /// connected `client`, issued as `SET` statements so they only affect
+    /// this session. Each setting is opt-in and defaults to the server's
+    /// own default (full durability, no statement timeout) when unset, so
+    /// operators who need the stronger guarantees don't have to do
+    /// anything. The effective values are logged once at connection time.
+    fn apply_session_settings(
+        client: &mut Client,
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<(), AccountsDbPluginError> {
+        let map_err = |setting: &str, err: postgres::Error| {
+            AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::DataSchemaError {
+                msg: format!("Failed to apply the \"{}\" session setting: {:?}", setting, err),
+            }))
+        };
+
+        if config.synchronous_commit_off.unwrap_or(false) {
+            client
+                .execute("SET synchronous_commit = off", &[])
+                .map_err(|err| map_err("synchronous_commit", err))?;
+            info!("PostgreSQL session setting: synchronous_commit = off");
+        }
+
+        if let Some(commit_delay) = config.commit_delay_us {
+            client
+                .execute(&format!("SET commit_delay = {}", commit_delay), &[])
+                .map_err(|err| map_err("commit_delay", err))?;
+            info!("PostgreSQL session setting: commit_delay = {}", commit_delay);
+        }
+
+        if let Some(statement_timeout_ms) = config.statement_timeout_ms {
+            client
+                .execute(
+                    &format!("SET statement_timeout = {}", statement_timeout_ms),
+                    &[],
+                )
+                .map_err(|err| map_err("statement_timeout", err))?;
+            info!(
+                "PostgreSQL session setting: statement_timeout = {}ms",
+                statement_timeout_ms
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Connects to the database and prepares every statement
+    /// `PostgresSqlClientWrapper` holds. Split out from `new` so `reconnect`
+    /// can rebuild the wrapper from scratch after a dropped connection.
+    fn build_client_wrapper(
+        config: &AccountsDbPluginPostgresConfig,
+    ) -> Result<PostgresSqlClientWrapper, AccountsDbPluginError> {
         let mut client = Self::connect_to_db(config)?;
+        Self::apply_session_settings(&mut client, config)?;
         let bulk_account_insert_stmt =
             Self::build_bulk_account_insert_statement(&mut client, config)?;
         let update_account_stmt = Self::build_single_account_upsert_statement(&mut client, config)?;
@@ -875,24 +1541,188 @@ impl SimplePostgresClient {
             Self::build_slot_upsert_statement_without_parent(&mut client, config)?;
         let update_transaction_log_stmt =
             Self::build_transaction_log_upsert_statement(&mut client, config)?;
+        let transaction_id_stmt = Self::build_transaction_id_statement(&mut client, config)?;
+        let update_block_stmt = Self::build_block_upsert_statement(&mut client, config)?;
+        let insert_account_transaction_stmt =
+            Self::build_account_transactions_insert_statement(&mut client, config)?;
+        let prune_account_transactions_stmt =
+            Self::build_account_transactions_prune_statement(&mut client, config)?;
+
+        let use_copy = config.use_copy.unwrap_or(false);
+        let copy_merge_stmt = if use_copy {
+            Self::create_copy_staging_table(&mut client, config)?;
+            Some(Self::build_copy_merge_statement(&mut client, config)?)
+        } else {
+            None
+        };
+
+        Ok(PostgresSqlClientWrapper {
+            client,
+            update_account_stmt,
+            bulk_account_insert_stmt,
+            update_slot_with_parent_stmt,
+            update_slot_without_parent_stmt,
+            update_transaction_log_stmt,
+            transaction_id_stmt,
+            update_block_stmt,
+            copy_merge_stmt,
+            insert_account_transaction_stmt,
+            prune_account_transactions_stmt,
+        })
+    }
+
+    pub fn new(config: &AccountsDbPluginPostgresConfig) -> Result<Self, AccountsDbPluginError> {
+        info!("Creating SimplePostgresClient...");
+        let wrapper = Self::build_client_wrapper(config)?;
 
         let batch_size = config
             .batch_size
             .unwrap_or(DEFAULT_ACCOUNTS_INSERT_BATCH_SIZE);
+        // `max_batch_bytes` is the operator-facing name for this budget;
+        // `max_query_size` is kept as a fallback for existing configs.
+        let max_query_size = config
+            .max_batch_bytes
+            .or(config.max_query_size)
+            .unwrap_or(DEFAULT_MAX_QUERY_SIZE);
+        let transaction_id_cache_size = config
+            .transaction_id_cache_size
+            .unwrap_or(DEFAULT_TRANSACTION_ID_CACHE_SIZE);
+        let latest_txs_per_account = config
+            .latest_txs_per_account
+            .unwrap_or(DEFAULT_LATEST_TXS_PER_ACCOUNT);
         info!("Created SimplePostgresClient.");
         Ok(Self {
             batch_size,
+            max_query_size,
+            use_copy: wrapper.copy_merge_stmt.is_some(),
             pending_account_updates: Vec::with_capacity(batch_size),
-            client: Mutex::new(PostgresSqlClientWrapper {
-                client,
-                update_account_stmt,
-                bulk_account_insert_stmt,
-                update_slot_with_parent_stmt,
-                update_slot_without_parent_stmt,
-                update_transaction_log_stmt,
-            }),
+            pending_account_updates_byte_size: 0,
+            transaction_id_cache: LruCache::new(transaction_id_cache_size),
+            latest_txs_per_account,
+            config: config.clone(),
+            client: Mutex::new(wrapper),
         })
     }
+
+    /// Returns `true` when `err` indicates the underlying connection itself
+    /// is gone (closed socket, admin shutdown) rather than a data or schema
+    /// problem with the statement that was run -- only these are worth
+    /// reconnecting for.
+    fn is_connection_error(err: &postgres::Error) -> bool {
+        err.is_closed()
+    }
+
+    /// Rebuilds the database connection and every prepared statement with
+    /// capped exponential backoff, giving up after `max_reconnect_attempts`.
+    /// Takes `config` and `client_mutex` as explicit parameters (rather than
+    /// `&mut self`) so callers can hold other `self` fields borrowed (e.g. a
+    /// pending bind-parameter list) across the call.
+    fn reconnect(
+        config: &AccountsDbPluginPostgresConfig,
+        client_mutex: &mut Mutex<PostgresSqlClientWrapper>,
+    ) -> Result<(), AccountsDbPluginError> {
+        let max_attempts = config
+            .max_reconnect_attempts
+            .unwrap_or(DEFAULT_MAX_RECONNECT_ATTEMPTS);
+        let backoff_base_ms = config
+            .reconnect_backoff_base_ms
+            .unwrap_or(DEFAULT_RECONNECT_BACKOFF_BASE_MS);
+        let backoff_max_ms = config
+            .reconnect_backoff_max_ms
+            .unwrap_or(DEFAULT_RECONNECT_BACKOFF_MAX_MS);
+
+        let mut attempt = 0;
+        let mut delay_ms = backoff_base_ms;
+        loop {
+            attempt += 1;
+            RECONNECT_ATTEMPTS.inc();
+            inc_new_counter_debug!("accountsdb-plugin-postgres-reconnect-attempts", 1);
+            warn!(
+                "Attempting to reconnect to the PostgreSQL database (attempt {} of {})",
+                attempt, max_attempts
+            );
+            match Self::build_client_wrapper(config) {
+                Ok(wrapper) => {
+                    *client_mutex.get_mut().unwrap() = wrapper;
+                    info!("Reconnected to the PostgreSQL database");
+                    return Ok(());
+                }
+                Err(err) if attempt < max_attempts => {
+                    warn!(
+                        "Reconnect attempt {} failed, retrying in {}ms: {:?}",
+                        attempt, delay_ms, err
+                    );
+                    sleep(Duration::from_millis(delay_ms));
+                    delay_ms = (delay_ms * 2).min(backoff_max_ms);
+                }
+                Err(err) => {
+                    error!(
+                        "Gave up reconnecting to the PostgreSQL database after {} attempts: {:?}",
+                        attempt, err
+                    );
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    /// Returns `true` for a transient condition worth retrying the same
+    /// statement for -- a serialization failure or deadlock caused by
+    /// concurrent writers -- as opposed to a permanent schema/data error
+    /// that would fail identically on retry and should surface immediately.
+    fn is_transient_error(err: &postgres::Error) -> bool {
+        matches!(
+            err.code(),
+            Some(&SqlState::T_R_SERIALIZATION_FAILURE) | Some(&SqlState::T_R_DEADLOCK_DETECTED)
+        )
+    }
+
+    /// Runs `op`, reconnecting and retrying once if the connection itself
+    /// was lost, and retrying with capped exponential backoff (up to
+    /// `max_retries`, configurable alongside `backoff_base_ms`) if `op`
+    /// instead fails with a transient serialization/deadlock error. A
+    /// permanent error is returned immediately without retrying. See
+    /// `reconnect` for why `config` and `client_mutex` are explicit
+    /// parameters rather than `&mut self`.
+    fn with_reconnect_retry<T>(
+        config: &AccountsDbPluginPostgresConfig,
+        client_mutex: &mut Mutex<PostgresSqlClientWrapper>,
+        mut op: impl FnMut(&mut PostgresSqlClientWrapper) -> Result<T, postgres::Error>,
+    ) -> Result<T, postgres::Error> {
+        let max_retries = config.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+        let backoff_base_ms = config.backoff_base_ms.unwrap_or(DEFAULT_BACKOFF_BASE_MS);
+
+        let mut attempt = 0;
+        loop {
+            let wrapper = client_mutex.get_mut().unwrap();
+            match op(wrapper) {
+                Err(err) if Self::is_connection_error(&err) => {
+                    warn!(
+                        "Lost connection to the PostgreSQL database, attempting to reconnect: {:?}",
+                        err
+                    );
+                    if Self::reconnect(config, client_mutex).is_err() {
+                        return Err(err);
+                    }
+                    let wrapper = client_mutex.get_mut().unwrap();
+                    return op(wrapper);
+                }
+                Err(err) if Self::is_transient_error(&err) && attempt < max_retries => {
+                    attempt += 1;
+                    let delay_ms = backoff_base_ms
+                        .saturating_mul(1u64 << (attempt - 1).min(16))
+                        .min(DEFAULT_MAX_RETRY_BACKOFF_MS);
+                    warn!(
+                        "Transient PostgreSQL error, retrying (attempt {} of {}) in {}ms: {:?}",
+                        attempt, max_retries, delay_ms, err
+                    );
+                    sleep(Duration::from_millis(delay_ms));
+                    continue;
+                }
+                result => return result,
+            }
+        }
+    }
 }
 
 impl PostgresClient for SimplePostgresClient {
@@ -925,18 +1755,19 @@ impl PostgresClient for SimplePostgresClient {
         let parent = parent.map(|parent| parent as i64);
         let updated_on = Utc::now().naive_utc();
         let status_str = status.as_str();
-        let client = self.client.get_mut().unwrap();
 
-        let result = match parent {
-            Some(parent) => client.client.execute(
-                &client.update_slot_with_parent_stmt,
-                &[&slot, &parent, &status_str, &updated_on],
-            ),
-            None => client.client.execute(
-                &client.update_slot_without_parent_stmt,
-                &[&slot, &status_str, &updated_on],
-            ),
-        };
+        let result = Self::with_reconnect_retry(&self.config, &mut self.client, |wrapper| {
+            match parent {
+                Some(parent) => wrapper.client.execute(
+                    &wrapper.update_slot_with_parent_stmt,
+                    &[&slot, &parent, &status_str, &updated_on],
+                ),
+                None => wrapper.client.execute(
+                    &wrapper.update_slot_without_parent_stmt,
+                    &[&slot, &status_str, &updated_on],
+                ),
+            }
+        });
 
         match result {
             Err(err) => {
@@ -959,31 +1790,64 @@ impl PostgresClient for SimplePostgresClient {
         self.flush_buffered_writes()
     }
 
+    /// Looks up (and if necessary upserts) the `transactions.transaction_id`
+    /// for `signature` via `transaction_id_cache`, falling back to
+    /// `transaction_id_stmt` on a cache miss.
+    fn get_transaction_id(&mut self, signature: &[u8]) -> Result<i64, AccountsDbPluginError> {
+        if let Some(&transaction_id) = self.transaction_id_cache.get(signature) {
+            return Ok(transaction_id);
+        }
+
+        let signature_text = bs58::encode(signature).into_string();
+        let result = Self::with_reconnect_retry(&self.config, &mut self.client, |wrapper| {
+            wrapper
+                .client
+                .query_one(&wrapper.transaction_id_stmt, &[&signature_text])
+        });
+
+        let transaction_id = match result {
+            Err(err) => {
+                let msg = format!(
+                    "Failed to look up the transaction_id for signature {}. Error: {:?}",
+                    signature_text, err
+                );
+                error!("{}", msg);
+                return Err(AccountsDbPluginError::AccountsUpdateError { msg });
+            }
+            Ok(row) => row.get(0),
+        };
+
+        self.transaction_id_cache
+            .put(signature.to_vec(), transaction_id);
+        Ok(transaction_id)
+    }
+
     fn log_transaction(
         &mut self,
         transaction_log_info: LogTransactionRequest,
     ) -> Result<(), AccountsDbPluginError> {
-        let client = self.client.get_mut().unwrap();
-        let statement = &client.update_transaction_log_stmt;
-        let client = &mut client.client;
+        let transaction_info = transaction_log_info.transaction_info;
+        let transaction_id = self.get_transaction_id(&transaction_info.signature)?;
+
         let updated_on = Utc::now().naive_utc();
 
-        let transaction_info = transaction_log_info.transaction_info;
-        let result = client.query(
-            statement,
-            &[
-                &transaction_info.signature,
-                &transaction_info.is_vote,
-                &transaction_info.slot,
-                &transaction_info.message_type,
-                &transaction_info.legacy_message,
-                &transaction_info.v0_mapped_message,
-                &transaction_info.signatures,
-                &transaction_info.message_hash,
-                &transaction_info.meta,
-                &updated_on,
-            ],
-        );
+        let result = Self::with_reconnect_retry(&self.config, &mut self.client, |wrapper| {
+            wrapper.client.query(
+                &wrapper.update_transaction_log_stmt,
+                &[
+                    &transaction_id,
+                    &transaction_info.is_vote,
+                    &transaction_info.slot,
+                    &transaction_info.message_type,
+                    &transaction_info.legacy_message,
+                    &transaction_info.v0_mapped_message,
+                    &transaction_info.signatures,
+                    &transaction_info.message_hash,
+                    &transaction_info.meta,
+                    &updated_on,
+                ],
+            )
+        });
 
         if let Err(err) = result {
             let msg = format!(
@@ -994,6 +1858,69 @@ impl PostgresClient for SimplePostgresClient {
             return Err(AccountsDbPluginError::AccountsUpdateError { msg });
         }
 
+        let latest_txs_per_account = self.latest_txs_per_account as i64;
+        for pubkey in transaction_log_info.writable_accounts {
+            let result = Self::with_reconnect_retry(&self.config, &mut self.client, |wrapper| {
+                wrapper.client.execute(
+                    &wrapper.insert_account_transaction_stmt,
+                    &[&pubkey, &transaction_info.slot, &transaction_id, &transaction_info.signature],
+                )
+            });
+            if let Err(err) = result {
+                let msg = format!(
+                    "Failed to record the account_transactions row for the PostgreSQL database. Error: {:?}",
+                    err
+                );
+                error!("{}", msg);
+                return Err(AccountsDbPluginError::AccountsUpdateError { msg });
+            }
+
+            let result = Self::with_reconnect_retry(&self.config, &mut self.client, |wrapper| {
+                wrapper.client.execute(
+                    &wrapper.prune_account_transactions_stmt,
+                    &[&pubkey, &latest_txs_per_account],
+                )
+            });
+            if let Err(err) = result {
+                let msg = format!(
+                    "Failed to prune account_transactions rows for the PostgreSQL database. Error: {:?}",
+                    err
+                );
+                error!("{}", msg);
+                return Err(AccountsDbPluginError::AccountsUpdateError { msg });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn notify_block(&mut self, block_info: DbBlockInfo) -> Result<(), AccountsDbPluginError> {
+        let updated_on = Utc::now().naive_utc();
+
+        let result = Self::with_reconnect_retry(&self.config, &mut self.client, |wrapper| {
+            wrapper.client.execute(
+                &wrapper.update_block_stmt,
+                &[
+                    &block_info.slot,
+                    &block_info.processed_transactions,
+                    &block_info.total_cu_used,
+                    &block_info.total_cu_requested,
+                    &block_info.heavily_writelocked_accounts,
+                    &block_info.heavily_readlocked_accounts,
+                    &updated_on,
+                ],
+            )
+        });
+
+        if let Err(err) = result {
+            let msg = format!(
+                "Failed to persist the update of block info to the PostgreSQL database. Error: {:?}",
+                err
+            );
+            error!("{}", msg);
+            return Err(AccountsDbPluginError::AccountsUpdateError { msg });
+        }
+
         Ok(())
     }
 }
@@ -1011,6 +1938,13 @@ struct UpdateSlotRequest {
 
 pub struct LogTransactionRequest {
     transaction_info: DbTransaction,
+    /// Pubkeys of the transaction's writable accounts, tracked in
+    /// `account_transactions`; see `SimplePostgresClient::log_transaction`.
+    writable_accounts: Vec<Vec<u8>>,
+}
+
+pub struct NotifyBlockRequest {
+    block_info: DbBlockInfo,
 }
 
 #[warn(clippy::large_enum_variant)]
@@ -1018,6 +1952,7 @@ enum DbWorkItem {
     UpdateAccount(Box<UpdateAccountRequest>),
     UpdateSlot(Box<UpdateSlotRequest>),
     LogTransaction(Box<LogTransactionRequest>),
+    NotifyBlock(Box<NotifyBlockRequest>),
 }
 
 impl PostgresClientWorker {
@@ -1061,9 +1996,12 @@ impl PostgresClientWorker {
                             .update_account(request.account, request.is_startup)
                         {
                             error!("Failed to update account: ({})", err);
+                            SESSION_ERRORS.inc();
                             if panic_on_db_errors {
                                 abort();
                             }
+                        } else {
+                            ROWS_WRITTEN.with_label_values(&["account"]).inc();
                         }
                     }
                     DbWorkItem::UpdateSlot(request) => {
@@ -1073,13 +2011,28 @@ impl PostgresClientWorker {
                             request.slot_status,
                         ) {
                             error!("Failed to update slot: ({})", err);
+                            SESSION_ERRORS.inc();
                             if panic_on_db_errors {
                                 abort();
                             }
+                        } else {
+                            ROWS_WRITTEN.with_label_values(&["slot"]).inc();
                         }
                     }
                     DbWorkItem::LogTransaction(transaction_log_info) => {
                         self.client.log_transaction(*transaction_log_info)?;
+                        ROWS_WRITTEN.with_label_values(&["transaction"]).inc();
+                    }
+                    DbWorkItem::NotifyBlock(block_info) => {
+                        if let Err(err) = self.client.notify_block(block_info.block_info) {
+                            error!("Failed to notify block: ({})", err);
+                            SESSION_ERRORS.inc();
+                            if panic_on_db_errors {
+                                abort();
+                            }
+                        } else {
+                            ROWS_WRITTEN.with_label_values(&["block"]).inc();
+                        }
                     }
                 },
                 Err(err) => match err {
@@ -1110,6 +2063,140 @@ impl PostgresClientWorker {
         Ok(())
     }
 }
+
+/// A `deadpool_postgres`-backed connection pool used for account updates
+/// instead of the fixed per-worker `SimplePostgresClient` connection, when
+/// `config.use_connection_pool` is set. Each `upsert_account` call checks
+/// out a pooled connection for just that query instead of a worker pinning
+/// one connection for its entire lifetime, so a slow query no longer blocks
+/// every other update queued behind it on that worker, and a dead
+/// connection is simply recycled away rather than wedging the worker.
+///
+/// This first cut only covers the steady-state single-account upsert path:
+/// slot/transaction/block notifications and the `is_startup` COPY batch
+/// load still go through the synchronous per-worker `SimplePostgresClient`.
+/// `ssl_mode` of `disable` or `require` is supported, reusing
+/// `SimplePostgresClient::build_tls_connector`; `prefer` is rejected at
+/// construction time since a pooled connection can't fall back to a plain
+/// one after the fact.
+struct AsyncPostgresPool {
+    pool: DeadpoolPool,
+    runtime: TokioRuntime,
+    checkout_timeout: Duration,
+}
+
+impl AsyncPostgresPool {
+    fn new(config: &AccountsDbPluginPostgresConfig) -> Result<Self, AccountsDbPluginError> {
+        let ssl_mode = config.ssl_mode.unwrap_or(SslMode::Disable);
+        if ssl_mode == SslMode::Prefer {
+            let msg = "\"use_connection_pool\" does not support \"ssl_mode\" = \"prefer\" \
+                        (a pooled connection can't transparently retry without TLS after the \
+                        pool already committed to it); use \"disable\" or \"require\" instead"
+                .to_string();
+            return Err(AccountsDbPluginError::Custom(Box::new(
+                AccountsDbPluginPostgresError::ConfigurationError { msg },
+            )));
+        }
+
+        let connection_str = SimplePostgresClient::build_connection_string(config)?;
+        let pg_config: tokio_postgres::Config = connection_str.parse().map_err(|err| {
+            AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::ConfigurationError {
+                msg: format!(
+                    "Invalid PostgreSQL connection string for the pooled client: {:?}",
+                    err
+                ),
+            }))
+        })?;
+
+        let manager_config = ManagerConfig {
+            recycling_method: RecyclingMethod::Fast,
+        };
+        let manager = if ssl_mode == SslMode::Disable {
+            DeadpoolManager::from_config(pg_config, TokioNoTls, manager_config)
+        } else {
+            let connector = SimplePostgresClient::build_tls_connector(config)?;
+            DeadpoolManager::from_config(pg_config, connector, manager_config)
+        };
+        let max_size = config.pool_max_size.unwrap_or(DEFAULT_POOL_MAX_SIZE);
+        let pool = DeadpoolPool::builder(manager)
+            .max_size(max_size)
+            .runtime(DeadpoolRuntime::Tokio1)
+            .build()
+            .map_err(|err| {
+                AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::ConfigurationError {
+                    msg: format!("Failed to build the PostgreSQL connection pool: {:?}", err),
+                }))
+            })?;
+
+        let runtime = TokioRuntimeBuilder::new_multi_thread()
+            .worker_threads(config.threads.unwrap_or(DEFAULT_THREADS_COUNT))
+            .enable_all()
+            .build()
+            .map_err(|err| {
+                AccountsDbPluginError::Custom(Box::new(AccountsDbPluginPostgresError::ConfigurationError {
+                    msg: format!("Failed to start the pooled-client Tokio runtime: {:?}", err),
+                }))
+            })?;
+
+        let checkout_timeout = Duration::from_millis(
+            config.pool_timeout_ms.unwrap_or(DEFAULT_POOL_TIMEOUT_MS),
+        );
+
+        Ok(Self {
+            pool,
+            runtime,
+            checkout_timeout,
+        })
+    }
+
+    /// Checks out a pooled connection and upserts `account`, blocking the
+    /// calling worker thread until the query completes.
+    fn upsert_account(&self, account: &DbAccountInfo) -> Result<(), AccountsDbPluginError> {
+        let updated_on = Utc::now().naive_utc();
+        let pool = &self.pool;
+        let checkout_timeout = self.checkout_timeout;
+
+        let result: Result<u64, String> = self.runtime.block_on(async move {
+            let client = tokio_timeout(checkout_timeout, pool.get())
+                .await
+                .map_err(|_| "timed out waiting for a pooled connection".to_string())?
+                .map_err(|err| format!("{:?}", err))?;
+            let stmt = client
+                .prepare_cached(UPSERT_ACCOUNT_SQL)
+                .await
+                .map_err(|err| format!("{:?}", err))?;
+            client
+                .execute(
+                    &stmt,
+                    &[
+                        &account.pubkey,
+                        &account.slot,
+                        &account.owner,
+                        &account.lamports,
+                        &account.executable,
+                        &account.rent_epoch,
+                        &account.data,
+                        &account.write_version,
+                        &updated_on,
+                    ],
+                )
+                .await
+                .map_err(|err| format!("{:?}", err))
+        });
+
+        result.map(|_rows| ()).map_err(|err| {
+            let msg = format!(
+                "Failed to persist the update of account {:?} to the PostgreSQL database via the pooled client. Error: {}",
+                bs58::encode(&account.pubkey).into_string(),
+                err
+            );
+            error!("{}", msg);
+            SESSION_ERRORS.inc();
+            AccountsDbPluginError::AccountsUpdateError { msg }
+        })
+    }
+}
+
 pub struct ParallelPostgresClient {
     workers: Vec<JoinHandle<Result<(), AccountsDbPluginError>>>,
     exit_worker: Arc<AtomicBool>,
@@ -1118,11 +2205,22 @@ pub struct ParallelPostgresClient {
     initialized_worker_count: Arc<AtomicUsize>,
     sender: Sender<DbWorkItem>,
     last_report: AtomicInterval,
+    heavily_locked_accounts_limit: usize,
+    heavily_locked_accounts_threshold: u32,
+    /// Set when `config.use_connection_pool` is enabled; routes
+    /// steady-state `update_account` calls through a checked-out pooled
+    /// connection instead of the worker channel. See `AsyncPostgresPool`.
+    async_pool: Option<Arc<AsyncPostgresPool>>,
 }
 
 impl ParallelPostgresClient {
     pub fn new(config: &AccountsDbPluginPostgresConfig) -> Result<Self, AccountsDbPluginError> {
         info!("Creating ParallelPostgresClient...");
+        let async_pool = if config.use_connection_pool.unwrap_or(false) {
+            Some(Arc::new(AsyncPostgresPool::new(config)?))
+        } else {
+            None
+        };
         let (sender, receiver) = bounded(MAX_ASYNC_REQUESTS);
         let exit_worker = Arc::new(AtomicBool::new(false));
         let mut workers = Vec::default();
@@ -1172,6 +2270,11 @@ impl ParallelPostgresClient {
             workers.push(worker);
         }
 
+        if let Some(port) = config.prometheus_port {
+            QUEUE_CAPACITY.set(MAX_ASYNC_REQUESTS as i64);
+            start_metrics_server(port);
+        }
+
         info!("Created ParallelPostgresClient.");
         Ok(Self {
             last_report: AtomicInterval::default(),
@@ -1181,6 +2284,13 @@ impl ParallelPostgresClient {
             startup_done_count,
             initialized_worker_count,
             sender,
+            heavily_locked_accounts_limit: config
+                .heavily_locked_accounts_limit
+                .unwrap_or(DEFAULT_HEAVILY_LOCKED_ACCOUNTS_LIMIT),
+            heavily_locked_accounts_threshold: config
+                .heavily_locked_accounts_threshold
+                .unwrap_or(DEFAULT_HEAVILY_LOCKED_ACCOUNTS_THRESHOLD),
+            async_pool,
         })
     }
 
@@ -1207,6 +2317,18 @@ impl ParallelPostgresClient {
         slot: u64,
         is_startup: bool,
     ) -> Result<(), AccountsDbPluginError> {
+        // Startup loads still go through the batched COPY/VALUES worker
+        // path; the pool only covers the steady-state single-row upsert.
+        if !is_startup {
+            if let Some(pool) = &self.async_pool {
+                let db_account = DbAccountInfo::new(account, slot);
+                return pool.upsert_account(&db_account).map(|()| {
+                    ROWS_WRITTEN.with_label_values(&["account"]).inc();
+                });
+            }
+        }
+
+        QUEUED_REQUESTS.set(self.sender.len() as i64);
         if self.last_report.should_update(30000) {
             datapoint_debug!(
                 "postgres-plugin-stats",
@@ -1334,8 +2456,17 @@ impl ParallelPostgresClient {
         slot: u64,
         transaction_info: &ReplicaTransactionInfo,
     ) -> LogTransactionRequest {
+        let writable_accounts = Self::account_keys_with_writability(
+            transaction_info.transaction.message(),
+        )
+        .into_iter()
+        .filter(|(_, writable)| *writable)
+        .map(|(pubkey, _)| pubkey.as_ref().to_vec())
+        .collect();
+
         LogTransactionRequest {
             transaction_info: Self::build_db_transaction(slot, transaction_info),
+            writable_accounts,
         }
     }
 
@@ -1356,6 +2487,327 @@ impl ParallelPostgresClient {
         }
         Ok(())
     }
+
+    /// Returns `true` if the account at `index` (out of `len` total account
+    /// keys) is writable according to the statically-partitioned layout
+    /// described by `header` (signers first, then unsigned accounts; the
+    /// tail `num_readonly_*` accounts of each group are read-only).
+    fn partition_writable_static(header: &MessageHeader, len: usize, index: usize) -> bool {
+        let num_signed = header.num_required_signatures as usize;
+        if index < num_signed {
+            index < num_signed.saturating_sub(header.num_readonly_signed_accounts as usize)
+        } else {
+            let num_unsigned = len.saturating_sub(num_signed);
+            let index_in_unsigned = index - num_signed;
+            index_in_unsigned
+                < num_unsigned.saturating_sub(header.num_readonly_unsigned_accounts as usize)
+        }
+    }
+
+    /// Resolves every account key referenced by `message`, in the same
+    /// indexing order the instructions' `accounts` byte indexes use, paired
+    /// with whether it is writable.
+    fn account_keys_with_writability(message: &SanitizedMessage) -> Vec<(Pubkey, bool)> {
+        match message {
+            SanitizedMessage::Legacy(message) => message
+                .account_keys
+                .iter()
+                .enumerate()
+                .map(|(index, pubkey)| {
+                    let writable = Self::partition_writable_static(
+                        &message.header,
+                        message.account_keys.len(),
+                        index,
+                    );
+                    (*pubkey, writable)
+                })
+                .collect(),
+            SanitizedMessage::V0(mapped_message) => {
+                let message = &mapped_message.message;
+                let mut accounts: Vec<(Pubkey, bool)> = message
+                    .account_keys
+                    .iter()
+                    .enumerate()
+                    .map(|(index, pubkey)| {
+                        let writable = Self::partition_writable_static(
+                            &message.header,
+                            message.account_keys.len(),
+                            index,
+                        );
+                        (*pubkey, writable)
+                    })
+                    .collect();
+                accounts.extend(
+                    mapped_message
+                        .mapped_addresses
+                        .writable
+                        .iter()
+                        .map(|pubkey| (*pubkey, true)),
+                );
+                accounts.extend(
+                    mapped_message
+                        .mapped_addresses
+                        .readonly
+                        .iter()
+                        .map(|pubkey| (*pubkey, false)),
+                );
+                accounts
+            }
+        }
+    }
+
+    /// Walks every instruction's `accounts` index list and bumps the
+    /// matching per-account lock counters.
+    fn accumulate_lock_counts(
+        message: &SanitizedMessage,
+        writable_counts: &mut HashMap<Pubkey, u32>,
+        readonly_counts: &mut HashMap<Pubkey, u32>,
+    ) {
+        let accounts = Self::account_keys_with_writability(message);
+        let instructions: &[CompiledInstruction] = match message {
+            SanitizedMessage::Legacy(message) => &message.instructions,
+            SanitizedMessage::V0(mapped_message) => &mapped_message.message.instructions,
+        };
+        for instruction in instructions {
+            for &account_index in &instruction.accounts {
+                if let Some(&(pubkey, writable)) = accounts.get(account_index as usize) {
+                    let counts = if writable {
+                        &mut *writable_counts
+                    } else {
+                        &mut *readonly_counts
+                    };
+                    *counts.entry(pubkey).or_insert(0) += 1;
+                }
+            }
+        }
+    }
+
+    /// Returns the `top_n` accounts meeting `threshold` lock count, sorted
+    /// by descending count.
+    fn top_locked_accounts(
+        counts: &HashMap<Pubkey, u32>,
+        top_n: usize,
+        threshold: u32,
+    ) -> Vec<Vec<u8>> {
+        let mut accounts: Vec<(&Pubkey, &u32)> =
+            counts.iter().filter(|(_, &count)| count >= threshold).collect();
+        accounts.sort_by(|a, b| b.1.cmp(a.1));
+        accounts
+            .into_iter()
+            .take(top_n)
+            .map(|(pubkey, _)| pubkey.as_ref().to_vec())
+            .collect()
+    }
+
+    fn build_db_block_info(
+        block_info: &ReplicaBlockInfo,
+        top_n: usize,
+        threshold: u32,
+    ) -> DbBlockInfo {
+        let mut writable_counts = HashMap::new();
+        let mut readonly_counts = HashMap::new();
+        for transaction in block_info.transactions {
+            Self::accumulate_lock_counts(
+                transaction.message,
+                &mut writable_counts,
+                &mut readonly_counts,
+            );
+        }
+
+        DbBlockInfo {
+            slot: block_info.slot as i64,
+            processed_transactions: block_info.transactions.len() as i64,
+            total_cu_used: block_info.total_cu_used as i64,
+            total_cu_requested: block_info.total_cu_requested as i64,
+            heavily_writelocked_accounts: Self::top_locked_accounts(
+                &writable_counts,
+                top_n,
+                threshold,
+            ),
+            heavily_readlocked_accounts: Self::top_locked_accounts(
+                &readonly_counts,
+                top_n,
+                threshold,
+            ),
+        }
+    }
+
+    pub fn notify_block_info(
+        &mut self,
+        block_info: &ReplicaBlockInfo,
+    ) -> Result<(), AccountsDbPluginError> {
+        let wrk_item = DbWorkItem::NotifyBlock(Box::new(NotifyBlockRequest {
+            block_info: Self::build_db_block_info(
+                block_info,
+                self.heavily_locked_accounts_limit,
+                self.heavily_locked_accounts_threshold,
+            ),
+        }));
+
+        if let Err(err) = self.sender.send(wrk_item) {
+            return Err(AccountsDbPluginError::SlotStatusUpdateError {
+                msg: format!("Failed to update the block info, error: {:?}", err),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A single transaction's sanitized message, as seen within a processed
+/// block, carrying just enough information to derive per-account lock
+/// contention.
+pub struct ReplicaBlockTransactionInfo<'a> {
+    pub message: &'a SanitizedMessage,
+}
+
+/// Aggregate, block-level view handed to [`ParallelPostgresClient::notify_block_info`].
+pub struct ReplicaBlockInfo<'a> {
+    pub slot: u64,
+    pub transactions: &'a [ReplicaBlockTransactionInfo<'a>],
+    pub total_cu_used: u64,
+    pub total_cu_requested: u64,
+}
+
+lazy_static! {
+    static ref METRICS_REGISTRY: Registry = Registry::new();
+
+    /// Number of `DbWorkItem`s currently queued in `ParallelPostgresClient`'s
+    /// async channel, i.e. `Sender::len()`.
+    static ref QUEUED_REQUESTS: IntGauge = {
+        let gauge = IntGauge::new(
+            "accountsdb_plugin_postgres_queued_requests",
+            "Number of DbWorkItems currently queued in the async channel",
+        )
+        .unwrap();
+        METRICS_REGISTRY.register(Box::new(gauge.clone())).unwrap();
+        gauge
+    };
+
+    /// `MAX_ASYNC_REQUESTS`, so `queued_requests` can be read as a fraction
+    /// of capacity.
+    static ref QUEUE_CAPACITY: IntGauge = {
+        let gauge = IntGauge::new(
+            "accountsdb_plugin_postgres_queue_capacity",
+            "Capacity of the async channel feeding the worker threads",
+        )
+        .unwrap();
+        gauge.set(MAX_ASYNC_REQUESTS as i64);
+        METRICS_REGISTRY.register(Box::new(gauge.clone())).unwrap();
+        gauge
+    };
+
+    /// Current length of `SimplePostgresClient::pending_account_updates`.
+    static ref PENDING_ACCOUNT_BUFFER_DEPTH: IntGauge = {
+        let gauge = IntGauge::new(
+            "accountsdb_plugin_postgres_pending_account_buffer_depth",
+            "Number of accounts currently buffered awaiting a batched flush",
+        )
+        .unwrap();
+        METRICS_REGISTRY.register(Box::new(gauge.clone())).unwrap();
+        gauge
+    };
+
+    /// Latency, in microseconds, of flushing `pending_account_updates` to
+    /// the database (covers both the `VALUES` and `COPY` paths).
+    static ref FLUSH_LATENCY_US: Histogram = {
+        let histogram = Histogram::with_opts(HistogramOpts::new(
+            "accountsdb_plugin_postgres_flush_latency_us",
+            "Latency, in microseconds, of flushing buffered account updates",
+        ))
+        .unwrap();
+        METRICS_REGISTRY.register(Box::new(histogram.clone())).unwrap();
+        histogram
+    };
+
+    /// Total rows written, labeled by destination table.
+    static ref ROWS_WRITTEN: IntCounterVec = {
+        let counter = IntCounterVec::new(
+            Opts::new(
+                "accountsdb_plugin_postgres_rows_written",
+                "Total rows written, labeled by destination table",
+            ),
+            &["table"],
+        )
+        .unwrap();
+        METRICS_REGISTRY.register(Box::new(counter.clone())).unwrap();
+        counter
+    };
+
+    /// Total number of statements/queries that failed against the database.
+    static ref SESSION_ERRORS: IntCounter = {
+        let counter = IntCounter::new(
+            "accountsdb_plugin_postgres_session_errors",
+            "Total number of failed statements/queries against the PostgreSQL database",
+        )
+        .unwrap();
+        METRICS_REGISTRY.register(Box::new(counter.clone())).unwrap();
+        counter
+    };
+
+    /// Total number of reconnect attempts made after losing the database
+    /// connection; see `SimplePostgresClient::reconnect`.
+    static ref RECONNECT_ATTEMPTS: IntCounter = {
+        let counter = IntCounter::new(
+            "accountsdb_plugin_postgres_reconnect_attempts",
+            "Total number of reconnect attempts made after losing the database connection",
+        )
+        .unwrap();
+        METRICS_REGISTRY.register(Box::new(counter.clone())).unwrap();
+        counter
+    };
+}
+
+fn encode_prometheus_metrics() -> Vec<u8> {
+    let metric_families = METRICS_REGISTRY.gather();
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&metric_families, &mut buffer)
+        .unwrap();
+    buffer
+}
+
+/// Serves the gathered metrics in Prometheus text exposition format on
+/// every connection, ignoring the request path -- this listener only ever
+/// has one route.
+fn handle_metrics_connection(mut stream: TcpStream) {
+    let mut request = [0u8; 1024];
+    let _ = stream.read(&mut request);
+
+    let body = encode_prometheus_metrics();
+    let header = format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    if stream.write_all(header.as_bytes()).is_ok() {
+        let _ = stream.write_all(&body);
+    }
+}
+
+/// Spawns a thread serving Prometheus metrics on `127.0.0.1:port`. Started
+/// from `ParallelPostgresClient::new` when `config.prometheus_port` is set.
+fn start_metrics_server(port: u16) {
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(err) => {
+            error!(
+                "Failed to bind the Prometheus metrics listener to 127.0.0.1:{}: {:?}",
+                port, err
+            );
+            return;
+        }
+    };
+
+    info!("Serving Prometheus metrics on 127.0.0.1:{}", port);
+    let result = Builder::new()
+        .name("accountsdb-plugin-postgres-metrics".to_string())
+        .spawn(move || {
+            for stream in listener.incoming().flatten() {
+                handle_metrics_connection(stream);
+            }
+        });
+    if let Err(err) = result {
+        error!("Failed to start the Prometheus metrics server thread: {:?}", err);
+    }
 }
 
 pub struct PostgresClientBuilder {}
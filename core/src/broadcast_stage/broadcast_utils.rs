@@ -18,6 +18,18 @@ pub(super) struct ReceiveResults {
     pub time_elapsed: Duration,
     pub bank: Arc<Bank>,
     pub last_tick_height: u64,
+    pub slot_interruption: Option<SlotInterruption>,
+}
+
+/// Reports that `recv_slot_entries` discarded a slot's in-flight entries mid-batch because
+/// a later slot preempted it, so callers can emit metrics and distinguish this from a slot
+/// finishing normally at `max_tick_height`.
+#[derive(Debug, PartialEq, Eq)]
+pub(super) struct SlotInterruption {
+    pub interrupted_slot: Slot,
+    pub discarded_entries: usize,
+    pub discarded_byte_count: u64,
+    pub pending_entry_carried_over: bool,
 }
 
 #[derive(Clone)]
@@ -33,13 +45,42 @@ pub(crate) struct RecvEntriesContext {
     pending_entry: Option<WorkingBankEntry>,
 }
 
+/// How many bytes of entries `recv_slot_entries` should try to coalesce into one batch, sized
+/// to exactly fill `shreds_per_fec_set` shreds under the shred format `merkle_proof_size`
+/// describes (`None` for the legacy, non-merkle format; `Some(proof_size)` for merkle or
+/// chained-merkle shreds, which reserve extra room for the proof). Deriving this from the
+/// shredding variant actually in use, rather than hardcoding the non-merkle capacity, keeps
+/// batches matched to a full FEC set when the cluster switches shred formats.
+pub(super) fn target_batch_byte_count(
+    shreds_per_fec_set: u64,
+    merkle_proof_size: Option<usize>,
+) -> u64 {
+    shreds_per_fec_set * ShredData::capacity(merkle_proof_size).unwrap() as u64
+}
+
+// The per-iteration coalesce wait: the full `receiver_coalesce_ms` normally, but clamped to
+// whatever time remains before `tick_deadline` -- the wall-clock instant the PoH producer is
+// expected to reach the slot's `max_tick_height` by -- once that's shorter. Coalescing exists
+// to batch more entries per shred, not to add latency once the tick schedule is already
+// tight, so the wait shrinks instead of risking a late broadcast. `None` preserves the old
+// fixed-wait behavior for callers with no deadline to give.
+fn coalesce_wait(receiver_coalesce_ms: u64, tick_deadline: Option<Instant>) -> Duration {
+    let full_wait = Duration::from_millis(receiver_coalesce_ms);
+    match tick_deadline {
+        Some(tick_deadline) => {
+            full_wait.min(tick_deadline.saturating_duration_since(Instant::now()))
+        }
+        None => full_wait,
+    }
+}
+
 pub(super) fn recv_slot_entries(
     ctx: &mut RecvEntriesContext,
     receiver: &Receiver<WorkingBankEntry>,
     receiver_coalesce_ms: u64,
+    target_batch_byte_count: u64,
+    tick_deadline: Option<Instant>,
 ) -> Result<ReceiveResults> {
-    let target_batch_byte_count: u64 =
-        32 * ShredData::capacity(/*merkle_proof_size*/ None).unwrap() as u64;
     let mut batch_byte_count: u64 = 8; // Vec len
     let timer = Duration::new(1, 0);
     let recv_start = Instant::now();
@@ -63,6 +104,7 @@ pub(super) fn recv_slot_entries(
                     time_elapsed: recv_start.elapsed(),
                     bank,
                     last_tick_height,
+                    slot_interruption: None,
                 });
             }
             Err(e)?
@@ -76,8 +118,9 @@ pub(super) fn recv_slot_entries(
 
     assert!(last_tick_height <= max_tick_height);
 
+    let mut slot_interruption = None;
     if last_tick_height != max_tick_height && batch_byte_count < target_batch_byte_count {
-        let mut max_wait = Duration::from_millis(receiver_coalesce_ms);
+        let mut max_wait = coalesce_wait(receiver_coalesce_ms, tick_deadline);
         let mut now = Instant::now();
         while let Ok((try_bank, (entry, tick_height))) = receiver.recv_timeout(max_wait) {
             let entry_bytes = serialized_size(&entry)?;
@@ -90,6 +133,14 @@ pub(super) fn recv_slot_entries(
             // broadcast its entries.
             if try_bank.slot() != slot {
                 warn!("Broadcast for slot: {} interrupted", bank.slot());
+                slot_interruption = Some(SlotInterruption {
+                    interrupted_slot: slot,
+                    discarded_entries: entries.len(),
+                    discarded_byte_count: batch_byte_count,
+                    // Filled in once the loop exits, once we know whether it went on to
+                    // stash a pending entry for the next call.
+                    pending_entry_carried_over: false,
+                });
                 entries.clear();
                 batch_byte_count = 8; // Vec len
                 bank = try_bank;
@@ -105,17 +156,23 @@ pub(super) fn recv_slot_entries(
                 break;
             }
 
-            max_wait = max_wait.saturating_sub(now.elapsed());
+            max_wait =
+                coalesce_wait(receiver_coalesce_ms, tick_deadline).saturating_sub(now.elapsed());
             now = Instant::now();
         }
     }
 
+    if let Some(slot_interruption) = slot_interruption.as_mut() {
+        slot_interruption.pending_entry_carried_over = ctx.pending_entry.is_some();
+    }
+
     let time_elapsed = recv_start.elapsed();
     Ok(ReceiveResults {
         entries,
         time_elapsed,
         bank,
         last_tick_height,
+        slot_interruption,
     })
 }
 
@@ -171,9 +228,16 @@ mod tests {
         let mut last_tick_height = 0;
         let mut recv_entries_ctx = RecvEntriesContext::default();
         while let Ok(result) =
-            recv_slot_entries(&mut recv_entries_ctx, &r, DEFAULT_ENTRY_COALESCE_MS)
+            recv_slot_entries(
+                &mut recv_entries_ctx,
+                &r,
+                DEFAULT_ENTRY_COALESCE_MS,
+                target_batch_byte_count(32, /* merkle_proof_size */ None),
+                None,
+            )
         {
             assert_eq!(result.bank.slot(), bank1.slot());
+            assert!(result.slot_interruption.is_none());
             last_tick_height = result.last_tick_height;
             res_entries.extend(result.entries);
         }
@@ -214,16 +278,63 @@ mod tests {
         let mut res_entries = vec![];
         let mut last_tick_height = 0;
         let mut bank_slot = 0;
+        let mut slot_interruption = None;
         let mut recv_entries_ctx = RecvEntriesContext::default();
         while let Ok(result) =
-            recv_slot_entries(&mut recv_entries_ctx, &r, DEFAULT_ENTRY_COALESCE_MS)
+            recv_slot_entries(
+                &mut recv_entries_ctx,
+                &r,
+                DEFAULT_ENTRY_COALESCE_MS,
+                target_batch_byte_count(32, /* merkle_proof_size */ None),
+                None,
+            )
         {
             bank_slot = result.bank.slot();
             last_tick_height = result.last_tick_height;
             res_entries = result.entries;
+            slot_interruption = slot_interruption.or(result.slot_interruption);
         }
         assert_eq!(bank_slot, bank2.slot());
         assert_eq!(last_tick_height, expected_last_height);
         assert_eq!(res_entries, vec![last_entry]);
+
+        let slot_interruption = slot_interruption.expect("slot 1 was interrupted by slot 2");
+        assert_eq!(slot_interruption.interrupted_slot, bank1.slot());
+        assert_eq!(
+            slot_interruption.discarded_entries as u64,
+            bank1.max_tick_height() - 1
+        );
+        assert!(!slot_interruption.pending_entry_carried_over);
+    }
+
+    #[test]
+    fn test_target_batch_byte_count_scales_with_merkle_proof_size() {
+        let legacy = target_batch_byte_count(32, None);
+        let merkle = target_batch_byte_count(32, Some(20));
+
+        // A merkle shred reserves extra room for its proof, so it can carry fewer entry
+        // bytes than a legacy shred of the same wire size -- the target shrinks to match.
+        assert!(merkle < legacy);
+    }
+
+    #[test]
+    fn test_coalesce_wait_without_deadline_is_unclamped() {
+        assert_eq!(coalesce_wait(200, None), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_coalesce_wait_shrinks_once_deadline_is_close() {
+        let tight_deadline = Instant::now() + Duration::from_millis(5);
+        let wait = coalesce_wait(200, Some(tight_deadline));
+        assert!(wait < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_coalesce_wait_is_zero_once_deadline_has_passed() {
+        let past_deadline = Instant::now() - Duration::from_millis(5);
+        assert_eq!(
+            coalesce_wait(200, Some(past_deadline)),
+            Duration::from_millis(0)
+        );
     }
 }
@@ -11,7 +11,10 @@ use solana_metrics::datapoint;
 use solana_runtime::epoch_schedule::EpochSchedule;
 use solana_sdk::pubkey::Pubkey;
 use std::cmp::min;
+use std::collections::hash_map::DefaultHasher;
 use std::collections::HashMap;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
 use std::mem;
 use std::net::SocketAddr;
 use std::net::UdpSocket;
@@ -24,16 +27,192 @@ pub const REPAIRMEN_SLEEP_MILLIS: usize = 1000;
 pub const REPAIR_REDUNDANCY: usize = 3;
 pub const NUM_BUFFER_SLOTS: usize = 100;
 pub const NUM_SLOTS_PER_UPDATE: usize = 2;
+// How many fewer buffer slots a peer already being repaired is held to, versus
+// `NUM_BUFFER_SLOTS` for a peer not currently being repaired. A smaller buffer keeps a peer
+// classified as needing repair for longer, so this is subtracted (not added) to give repair
+// targeting hysteresis around the buffer edge instead of flapping every time a root ticks by.
+pub const REPAIR_EXIT_HYSTERESIS_SLOTS: usize = 50;
+
+/// Remembers whether each peer was being repaired as of the last `recv_loop` pass, so
+/// `should_repair_peer` can apply hysteresis instead of re-deciding from scratch off a single
+/// buffer threshold every pass (which flaps a peer right at the boundary in and out of repair
+/// as its root advances one slot at a time).
+#[derive(Default)]
+struct PeerRepairStateTracker {
+    being_repaired: HashSet<Pubkey>,
+}
+
+impl PeerRepairStateTracker {
+    fn was_being_repaired(&self, peer_id: &Pubkey) -> bool {
+        self.being_repaired.contains(peer_id)
+    }
+
+    fn record(&mut self, peer_id: Pubkey, is_being_repaired: bool) {
+        if is_being_repaired {
+            self.being_repaired.insert(peer_id);
+        } else {
+            self.being_repaired.remove(&peer_id);
+        }
+    }
+}
+
+/// Tracks, per repairee, how many slots its gossiped root advanced by
+/// between one `serve_repairs` pass and the next. A repairee whose root is
+/// advancing quickly is getting its blobs through fine (from us or anyone
+/// else) and needs less repairman redundancy; one that's stalled needs
+/// more, up to `max_redundancy`.
+#[derive(Default)]
+struct RepaireeProgressTracker {
+    last_roots: HashMap<Pubkey, u64>,
+}
+
+impl RepaireeProgressTracker {
+    // Records `current_root` as the repairee's new baseline and returns how
+    // many slots it advanced by since the last call for this repairee, or
+    // `None` if this is the first observation.
+    fn record_and_get_progress(&mut self, repairee_id: Pubkey, current_root: u64) -> Option<u64> {
+        let progress = self
+            .last_roots
+            .get(&repairee_id)
+            .map(|last_root| current_root.saturating_sub(*last_root));
+        self.last_roots.insert(repairee_id, current_root);
+        progress
+    }
+}
+
+/// Re-serve rate above which `RepairNackTracker` raises its redundancy ceiling.
+const HIGH_RESERVE_RATE_THRESHOLD: f64 = 0.5;
+/// Re-serve rate below which `RepairNackTracker` lowers its redundancy ceiling.
+const LOW_RESERVE_RATE_THRESHOLD: f64 = 0.1;
+
+/// Self-tunes the redundancy ceiling fed into `calculate_adaptive_redundancy`, based on how
+/// often this node re-serves the same `(repairee, slot)` pair across back-to-back
+/// `serve_repairs` passes. This tree has no discrete repair-request packet a node can count
+/// explicit re-requests against -- repair here is driven purely off gossiped `EpochSlots`,
+/// with no request/response wire format present locally -- so a repeat serve is used as the
+/// observable proxy for "this repairee is still asking (via an unfilled slot) for blobs it
+/// was already sent": a high re-serve rate means the current redundancy isn't getting blobs
+/// through reliably and should rise, a low rate means it's over-provisioning and can fall,
+/// both clamped to `[min_redundancy, max_redundancy]`.
+struct RepairNackTracker {
+    // `None` until the first pass completes, so that pass has no baseline to compare
+    // against and leaves the ceiling untouched rather than reading as a 0% re-serve rate.
+    previously_served: Option<HashSet<(Pubkey, u64)>>,
+    current_redundancy: usize,
+    min_redundancy: usize,
+    max_redundancy: usize,
+}
+
+impl RepairNackTracker {
+    fn new(min_redundancy: usize, max_redundancy: usize) -> Self {
+        let max_redundancy = max_redundancy.max(min_redundancy);
+        Self {
+            previously_served: None,
+            current_redundancy: max_redundancy,
+            min_redundancy,
+            max_redundancy,
+        }
+    }
+
+    fn current_redundancy(&self) -> usize {
+        self.current_redundancy
+    }
+
+    // Folds in the `(repairee, slot)` pairs actually served this pass, adjusting the
+    // redundancy ceiling for the next pass based on how many of them were also served last
+    // pass (i.e. still unfilled), and returns the (possibly unchanged) new ceiling.
+    fn record_served_slots_and_adjust_redundancy(
+        &mut self,
+        served_this_pass: HashSet<(Pubkey, u64)>,
+    ) -> usize {
+        if let Some(previously_served) = &self.previously_served {
+            if !served_this_pass.is_empty() {
+                let reserved = served_this_pass.intersection(previously_served).count();
+                let reserve_rate = reserved as f64 / served_this_pass.len() as f64;
+
+                if reserve_rate > HIGH_RESERVE_RATE_THRESHOLD {
+                    self.current_redundancy =
+                        (self.current_redundancy + 1).min(self.max_redundancy);
+                } else if reserve_rate < LOW_RESERVE_RATE_THRESHOLD {
+                    self.current_redundancy = self
+                        .current_redundancy
+                        .saturating_sub(1)
+                        .max(self.min_redundancy);
+                }
+            }
+        }
+
+        self.previously_served = Some(served_this_pass);
+        self.current_redundancy
+    }
+}
+
+/// Data blobs in one erasure set. Mirrors the layout `ledger::erasure` codes slots with
+/// (that module isn't present as a local file in this tree), so erasure-aware repair below
+/// treats a slot's blobs as back-to-back sets of `ERASURE_SET_SIZE`, the last of which may
+/// be partial.
+const NUM_DATA: usize = 16;
+/// Coding (parity) blobs in one erasure set; any `NUM_DATA` of the `ERASURE_SET_SIZE` total
+/// blobs in a set are enough to reconstruct the rest.
+const NUM_CODING: usize = 4;
+const ERASURE_SET_SIZE: usize = NUM_DATA + NUM_CODING;
+
+/// Token-bucket rate limiter for repair traffic, shared across every
+/// repairee served within one `serve_repairs` pass. The budget is restored
+/// to `bytes_per_sec` worth of bytes (scaled down to a `REPAIRMEN_SLEEP_MILLIS`
+/// tick) at the start of each pass, so a repairman that exhausts it mid-pass
+/// just skips its remaining sends rather than blocking the repair thread;
+/// the budget resumes on the next tick. `bytes_per_sec == 0` disables
+/// limiting entirely.
+struct RepairRateLimiter {
+    bytes_per_tick: u64,
+    remaining_bytes: u64,
+}
+
+impl RepairRateLimiter {
+    fn new(bytes_per_sec: u64) -> Self {
+        let bytes_per_tick = bytes_per_sec * REPAIRMEN_SLEEP_MILLIS as u64 / 1000;
+        Self {
+            bytes_per_tick,
+            remaining_bytes: bytes_per_tick,
+        }
+    }
+
+    // Restores the full per-tick budget; called once at the start of every
+    // `serve_repairs` pass.
+    fn reset_for_new_pass(&mut self) {
+        self.remaining_bytes = self.bytes_per_tick;
+    }
+
+    // Returns true and deducts `len` from the remaining budget if it fits,
+    // leaving the budget untouched and returning false otherwise. Disabled
+    // (`bytes_per_tick == 0`) limiters always return true.
+    fn try_consume(&mut self, len: u64) -> bool {
+        if self.bytes_per_tick == 0 {
+            return true;
+        }
+        if len > self.remaining_bytes {
+            return false;
+        }
+        self.remaining_bytes -= len;
+        true
+    }
+}
 
 // Represents the blobs that a repairman is responsible for repairing in specific slot. More
 // specifically, a repairman is responsible for every blob in this slot with index
-// `(start_index + step_size * i) % num_blobs_in_slot`, for all `0 <= i <= num_blobs_to_send - 1`
-// in this slot.
+// `base_offset + (start_index + step_size * i) % ring_size`, for all
+// `0 <= i <= num_blobs_to_send - 1` in this slot. `base_offset` is `0` and `ring_size` is
+// `num_blobs_in_slot` for whole-slot repair; erasure-aware repair (see
+// `calculate_my_repairman_index_for_erasure_set`) instead scopes `ring_size` to one erasure
+// set's reconstructable indexes and offsets into that set's position within the slot via
+// `base_offset`.
 struct BlobIndexesToRepairIterator {
     start_index: usize,
     num_blobs_to_send: usize,
     step_size: usize,
-    num_blobs_in_slot: usize,
+    ring_size: usize,
+    base_offset: usize,
     blobs_sent: usize,
 }
 
@@ -43,12 +222,23 @@ impl BlobIndexesToRepairIterator {
         num_blobs_to_send: usize,
         step_size: usize,
         num_blobs_in_slot: usize,
+    ) -> Self {
+        Self::new_with_base_offset(start_index, num_blobs_to_send, step_size, num_blobs_in_slot, 0)
+    }
+
+    fn new_with_base_offset(
+        start_index: usize,
+        num_blobs_to_send: usize,
+        step_size: usize,
+        ring_size: usize,
+        base_offset: usize,
     ) -> Self {
         Self {
             start_index,
             num_blobs_to_send,
             step_size,
-            num_blobs_in_slot,
+            ring_size,
+            base_offset,
             blobs_sent: 0,
         }
     }
@@ -62,7 +252,7 @@ impl Iterator for BlobIndexesToRepairIterator {
             None
         } else {
             let blob_index = Some(
-                (self.start_index + self.step_size * self.blobs_sent) % self.num_blobs_in_slot,
+                self.base_offset + (self.start_index + self.step_size * self.blobs_sent) % self.ring_size,
             );
             self.blobs_sent += 1;
             blob_index
@@ -70,6 +260,36 @@ impl Iterator for BlobIndexesToRepairIterator {
     }
 }
 
+/// A repairee's self-reported set of slots it has already fully received,
+/// as it would be gossiped via a CRDS value adjacent to `EpochSlots` (see
+/// the NOTE below). Consulted by `serve_repairs_to_repairee` to skip slots
+/// a repairee already has, even if they weren't yet reflected in its last
+/// `EpochSlots` update, so repairmen don't keep redundantly resending
+/// blobs after just one of them got through.
+///
+/// NOTE: `crds_value.rs`, where `EpochSlots`'s `CrdsData` variant and the
+/// rest of the gossip table live, isn't present in this tree, so there's
+/// no `CrdsData` variant to add this acknowledgement alongside, and no
+/// gossip path to populate it from a live cluster. This type and the
+/// filtering it enables in `serve_repairs_to_repairee` land the
+/// consultation primitive that wiring would call into once that module
+/// exists here; `recv_loop` below always supplies an empty map in the
+/// meantime.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RepairAcknowledgements {
+    acknowledged_slots: HashSet<u64>,
+}
+
+impl RepairAcknowledgements {
+    pub fn new(acknowledged_slots: HashSet<u64>) -> Self {
+        Self { acknowledged_slots }
+    }
+
+    fn has_slot(&self, slot: u64) -> bool {
+        self.acknowledged_slots.contains(&slot)
+    }
+}
+
 pub struct ClusterInfoRepairListener {
     thread_hdls: Vec<JoinHandle<()>>,
 }
@@ -80,6 +300,10 @@ impl ClusterInfoRepairListener {
         exit: &Arc<AtomicBool>,
         cluster_info: Arc<RwLock<ClusterInfo>>,
         epoch_schedule: EpochSchedule,
+        repair_bytes_per_sec: u64,
+        erasure_aware_repair: bool,
+        min_repair_redundancy: usize,
+        max_repair_redundancy: usize,
     ) -> Self {
         let exit = exit.clone();
         let blocktree = blocktree.clone();
@@ -94,6 +318,10 @@ impl ClusterInfoRepairListener {
                     exit,
                     &cluster_info,
                     epoch_schedule,
+                    repair_bytes_per_sec,
+                    erasure_aware_repair,
+                    min_repair_redundancy,
+                    max_repair_redundancy,
                 );
             })
             .unwrap();
@@ -108,10 +336,19 @@ impl ClusterInfoRepairListener {
         exit: Arc<AtomicBool>,
         cluster_info: &Arc<RwLock<ClusterInfo>>,
         epoch_schedule: EpochSchedule,
+        repair_bytes_per_sec: u64,
+        erasure_aware_repair: bool,
+        min_repair_redundancy: usize,
+        max_repair_redundancy: usize,
     ) -> Result<()> {
         let socket = UdpSocket::bind("0.0.0.0:0").unwrap();
         let my_id = cluster_info.read().unwrap().id();
         let mut my_gossiped_root = 0;
+        let mut rate_limiter = RepairRateLimiter::new(repair_bytes_per_sec);
+        let mut repairee_progress = RepaireeProgressTracker::default();
+        let mut repair_nack_tracker =
+            RepairNackTracker::new(min_repair_redundancy, max_repair_redundancy);
+        let mut peer_repair_state = PeerRepairStateTracker::default();
 
         loop {
             if exit.load(Ordering::Relaxed) {
@@ -120,6 +357,12 @@ impl ClusterInfoRepairListener {
 
             let peers = cluster_info.read().unwrap().gossip_peers();
             let mut peers_needing_repairs: HashMap<Pubkey, EpochSlots> = HashMap::new();
+            // No gossiped acknowledgement CRDS value exists in this tree yet
+            // (see `RepairAcknowledgements`'s doc comment), so this is
+            // always empty here; `serve_repairs` is already wired to
+            // consult it once a real source populates it per-repairee.
+            let repairee_acknowledgements: HashMap<Pubkey, RepairAcknowledgements> =
+                HashMap::new();
 
             // Iterate through all the known nodes in the network, looking for ones that
             // need repairs
@@ -137,12 +380,16 @@ impl ClusterInfoRepairListener {
                         // Following logic needs to be fast because it holds the lock
                         // preventing updates on gossip
                         peer_roots.insert(peer.id, (ts, peer_epoch_slots.root));
-                        if Self::should_repair_peer(
+                        let needs_repair = Self::should_repair_peer(
                             my_root,
                             peer_epoch_slots.root,
                             &epoch_schedule,
                             NUM_BUFFER_SLOTS,
-                        ) {
+                            peer_repair_state.was_being_repaired(&peer.id),
+                            REPAIR_EXIT_HYSTERESIS_SLOTS,
+                        );
+                        peer_repair_state.record(peer.id, needs_repair);
+                        if needs_repair {
                             // Clone out EpochSlots structure to avoid holding lock on gossip
                             peers_needing_repairs.insert(peer.id, peer_epoch_slots.clone());
                         }
@@ -160,6 +407,11 @@ impl ClusterInfoRepairListener {
                 cluster_info,
                 &epoch_schedule,
                 &mut my_gossiped_root,
+                &mut rate_limiter,
+                &repairee_acknowledgements,
+                erasure_aware_repair,
+                &mut repairee_progress,
+                &mut repair_nack_tracker,
             );
 
             sleep(Duration::from_millis(REPAIRMEN_SLEEP_MILLIS as u64));
@@ -175,9 +427,25 @@ impl ClusterInfoRepairListener {
         cluster_info: &Arc<RwLock<ClusterInfo>>,
         epoch_schedule: &EpochSchedule,
         my_gossiped_root: &mut u64,
+        rate_limiter: &mut RepairRateLimiter,
+        repairee_acknowledgements: &HashMap<Pubkey, RepairAcknowledgements>,
+        erasure_aware_repair: bool,
+        repairee_progress: &mut RepaireeProgressTracker,
+        repair_nack_tracker: &mut RepairNackTracker,
     ) -> Result<()> {
+        // Shared across every repairee below, so the budget reflects total
+        // repair traffic sent out of this socket in one pass, not a
+        // per-repairee allowance.
+        rate_limiter.reset_for_new_pass();
+
+        let mut served_slots_this_pass: HashSet<(Pubkey, u64)> = HashSet::new();
+
         for (repairee_id, repairee_epoch_slots) in repairees {
             let repairee_root = repairee_epoch_slots.root;
+            let repair_redundancy = Self::calculate_adaptive_redundancy(
+                repairee_progress.record_and_get_progress(*repairee_id, repairee_root),
+                repair_nack_tracker.current_redundancy(),
+            );
 
             let repairee_tvu = {
                 let r_cluster_info = cluster_info.read().unwrap();
@@ -203,8 +471,14 @@ impl ClusterInfoRepairListener {
 
                 let my_root = Self::read_my_gossiped_root(my_id, cluster_info, my_gossiped_root);
 
+                let default_acknowledgements = RepairAcknowledgements::default();
+                let acknowledgements = repairee_acknowledgements
+                    .get(repairee_id)
+                    .unwrap_or(&default_acknowledgements);
+
                 let _ = Self::serve_repairs_to_repairee(
                     my_id,
+                    repairee_id,
                     my_root,
                     blocktree,
                     &repairee_epoch_slots,
@@ -212,15 +486,23 @@ impl ClusterInfoRepairListener {
                     socket,
                     &repairee_tvu,
                     NUM_SLOTS_PER_UPDATE,
+                    rate_limiter,
+                    acknowledgements,
+                    erasure_aware_repair,
+                    repair_redundancy,
+                    &mut served_slots_this_pass,
                 );
             }
         }
 
+        repair_nack_tracker.record_served_slots_and_adjust_redundancy(served_slots_this_pass);
+
         Ok(())
     }
 
     fn serve_repairs_to_repairee(
         my_id: &Pubkey,
+        repairee_id: &Pubkey,
         my_root: u64,
         blocktree: &Blocktree,
         repairee_epoch_slots: &EpochSlots,
@@ -228,6 +510,11 @@ impl ClusterInfoRepairListener {
         socket: &UdpSocket,
         repairee_tvu: &SocketAddr,
         num_slots_to_repair: usize,
+        rate_limiter: &mut RepairRateLimiter,
+        acknowledgements: &RepairAcknowledgements,
+        erasure_aware_repair: bool,
+        repair_redundancy: usize,
+        served_slots: &mut HashSet<(Pubkey, u64)>,
     ) -> Result<()> {
         let slot_iter = blocktree.rooted_slot_iterator(repairee_epoch_slots.root + 1);
 
@@ -245,7 +532,7 @@ impl ClusterInfoRepairListener {
             if slot > my_root || num_slots_repaired >= num_slots_to_repair {
                 break;
             }
-            if !repairee_epoch_slots.slots.contains(&slot) {
+            if !repairee_epoch_slots.slots.contains(&slot) && !acknowledgements.has_slot(slot) {
                 // Calculate the blob indexes this node is responsible for repairing. Note that
                 // because we are only repairing slots that are before our root, the slot.received
                 // should be equal to the actual total number of blobs in the slot. Optimistically
@@ -254,12 +541,29 @@ impl ClusterInfoRepairListener {
                 // calculate_my_repairman_index_for_slot() will divide responsibility evenly across
                 // the cluster
                 let num_blobs_in_slot = slot_meta.received as usize;
-                if let Some(my_repair_indexes) = Self::calculate_my_repairman_index_for_slot(
-                    my_id,
-                    &eligible_repairmen,
-                    num_blobs_in_slot,
-                    REPAIR_REDUNDANCY,
-                ) {
+                // Erasure-aware repair only needs to collectively cover `NUM_DATA` (data-or-
+                // coding) indexes per erasure set, since the repairee can reconstruct the
+                // rest locally; whole-slot repair covers every index, `repair_redundancy`
+                // times over.
+                let my_repair_indexes = if erasure_aware_repair {
+                    Self::calculate_my_repairman_erasure_aware_indexes_for_slot(
+                        my_id,
+                        &eligible_repairmen,
+                        num_blobs_in_slot,
+                        repair_redundancy,
+                    )
+                } else {
+                    Self::calculate_my_repairman_index_for_slot(
+                        my_id,
+                        &eligible_repairmen,
+                        num_blobs_in_slot,
+                        repair_redundancy,
+                    )
+                    .map(|iter| iter.collect())
+                    .unwrap_or_default()
+                };
+
+                if !my_repair_indexes.is_empty() {
                     // Repairee is missing this slot, send them the blobs for this slot
                     for blob_index in my_repair_indexes {
                         // Loop over the sblob indexes and query the database for these blob that
@@ -271,38 +575,73 @@ impl ClusterInfoRepairListener {
                             .get_data_blob_bytes(slot, blob_index as u64)
                             .expect("Failed to read data blob from blocktree")
                         {
-                            socket.send_to(&blob_data[..], repairee_tvu)?;
-                            total_data_blobs_sent += 1;
+                            if rate_limiter.try_consume(blob_data.len() as u64) {
+                                socket.send_to(&blob_data[..], repairee_tvu)?;
+                                total_data_blobs_sent += 1;
+                            }
                         }
 
-                        if let Some(coding_bytes) = blocktree
-                            .get_coding_blob_bytes(slot, blob_index as u64)
-                            .expect("Failed to read coding blob from blocktree")
-                        {
-                            socket.send_to(&coding_bytes[..], repairee_tvu)?;
-                            total_coding_blobs_sent += 1;
+                        // Erasure-aware repair already only targets `NUM_DATA` data-blob
+                        // indexes per erasure set, so sending coding blobs too would just be
+                        // the redundant traffic this mode exists to avoid.
+                        if !erasure_aware_repair {
+                            if let Some(coding_bytes) = blocktree
+                                .get_coding_blob_bytes(slot, blob_index as u64)
+                                .expect("Failed to read coding blob from blocktree")
+                            {
+                                if rate_limiter.try_consume(coding_bytes.len() as u64) {
+                                    socket.send_to(&coding_bytes[..], repairee_tvu)?;
+                                    total_coding_blobs_sent += 1;
+                                }
+                            }
                         }
                     }
 
+                    served_slots.insert((*repairee_id, slot));
                     num_slots_repaired += 1;
                 }
             }
         }
 
-        Self::report_repair_metrics(total_data_blobs_sent, total_coding_blobs_sent);
+        Self::report_repair_metrics(
+            total_data_blobs_sent,
+            total_coding_blobs_sent,
+            repair_redundancy,
+        );
         Ok(())
     }
 
-    fn report_repair_metrics(total_data_blobs_sent: u64, total_coding_blobs_sent: u64) {
+    fn report_repair_metrics(
+        total_data_blobs_sent: u64,
+        total_coding_blobs_sent: u64,
+        repair_redundancy: usize,
+    ) {
         if total_data_blobs_sent > 0 || total_coding_blobs_sent > 0 {
             datapoint!(
                 "repairman_activity",
                 ("data_sent", total_data_blobs_sent, i64),
-                ("coding_sent", total_coding_blobs_sent, i64)
+                ("coding_sent", total_coding_blobs_sent, i64),
+                ("redundancy", repair_redundancy as i64, i64)
             );
         }
     }
 
+    // Scales `max_redundancy` down by however many slots the repairee
+    // self-reported advancing through since the last pass -- every such slot
+    // is one the cluster resolved without needing every repairman's
+    // redundancy -- but never below 1, and stays at `max_redundancy` for a
+    // repairee with no prior observation (new, or on its first pass).
+    fn calculate_adaptive_redundancy(
+        progress_since_last_pass: Option<u64>,
+        max_redundancy: usize,
+    ) -> usize {
+        let max_redundancy = max_redundancy.max(1);
+        match progress_since_last_pass {
+            None => max_redundancy,
+            Some(progress) => max_redundancy.saturating_sub(progress as usize).max(1),
+        }
+    }
+
     fn shuffle_repairmen(
         eligible_repairmen: &mut Vec<&Pubkey>,
         repairee_id: &Pubkey,
@@ -355,6 +694,127 @@ impl ClusterInfoRepairListener {
         }
     }
 
+    // Deterministic, uniformly-distributed hash of a (slot, ticket_index) pair: every
+    // repairman computes the same value from the same inputs with no coordination, which is
+    // all `calculate_my_repairman_indexes_for_slot_stake_weighted` needs from it.
+    fn hash_repair_ticket(slot: u64, ticket_index: usize) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        slot.hash(&mut hasher);
+        ticket_index.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    // Stake-weighted counterpart to `calculate_my_repairman_index_for_slot`: rather than
+    // splitting a slot's `num_blobs_in_slot * repair_redundancy` repair "tickets" evenly
+    // across `eligible_repairmen`, each repairman's share is proportional to its stake.
+    // Repairmen are sorted by pubkey and laid out as consecutive cumulative-stake intervals
+    // over `[0, total_stake)`; each ticket is deterministically mapped onto that line via
+    // `hash_repair_ticket(slot, ticket_index) % total_stake`, and this returns the blob
+    // indices (`ticket_index % num_blobs_in_slot`) whose ticket landed in `my_id`'s interval.
+    // Every repairman derives the same intervals and the same hash, so assignments agree
+    // without coordination. A repairman with no entry (or zero stake) in `stakes` gets no
+    // interval and so no work; if every repairman is stakeless the slot goes unrepaired by
+    // this path entirely, same as `calculate_my_repairman_index_for_slot` returning `None`
+    // once there are more repairmen than tickets.
+    fn calculate_my_repairman_indexes_for_slot_stake_weighted(
+        my_id: &Pubkey,
+        eligible_repairmen: &[&Pubkey],
+        stakes: &HashMap<Pubkey, u64>,
+        slot: u64,
+        num_blobs_in_slot: usize,
+        repair_redundancy: usize,
+    ) -> Vec<usize> {
+        let mut sorted_repairmen: Vec<&Pubkey> = eligible_repairmen.to_vec();
+        sorted_repairmen.sort();
+
+        let mut cumulative_stake = 0u64;
+        let intervals: Vec<(Pubkey, u64)> = sorted_repairmen
+            .into_iter()
+            .map(|id| {
+                cumulative_stake += stakes.get(id).copied().unwrap_or(0);
+                (*id, cumulative_stake)
+            })
+            .collect();
+        let total_stake = cumulative_stake;
+
+        if total_stake == 0 {
+            return Vec::new();
+        }
+
+        let total_tickets = num_blobs_in_slot * repair_redundancy;
+        (0..total_tickets)
+            .filter(|ticket_index| {
+                let point = Self::hash_repair_ticket(slot, *ticket_index) % total_stake;
+                let owner_index = intervals.partition_point(|(_, end)| *end <= point);
+                intervals
+                    .get(owner_index)
+                    .map_or(false, |(id, _)| id == my_id)
+            })
+            .map(|ticket_index| ticket_index % num_blobs_in_slot)
+            .collect()
+    }
+
+    // Mirrors `calculate_my_repairman_index_for_slot`'s partitioning math, but scoped to one
+    // erasure set's `NUM_DATA` reconstructable indexes instead of every blob in the slot, and
+    // offset by that set's position within the slot via `base_offset`. Any `NUM_DATA` of an
+    // erasure set's `ERASURE_SET_SIZE` blobs are enough to reconstruct the rest, so repairmen
+    // only need to collectively cover `NUM_DATA` of them per set rather than all of them.
+    fn calculate_my_repairman_index_for_erasure_set(
+        my_id: &Pubkey,
+        eligible_repairmen: &[&Pubkey],
+        erasure_set_index: usize,
+        repair_redundancy: usize,
+    ) -> Option<BlobIndexesToRepairIterator> {
+        let total_blobs = NUM_DATA * repair_redundancy;
+        let total_repairmen_for_set = min(total_blobs, eligible_repairmen.len());
+
+        let blobs_per_repairman = min(
+            (total_blobs + total_repairmen_for_set - 1) / total_repairmen_for_set,
+            NUM_DATA,
+        );
+
+        let my_position = eligible_repairmen[..total_repairmen_for_set]
+            .iter()
+            .position(|id| *id == my_id)?;
+
+        let start_index = my_position % NUM_DATA;
+        Some(BlobIndexesToRepairIterator::new_with_base_offset(
+            start_index,
+            blobs_per_repairman,
+            total_repairmen_for_set,
+            NUM_DATA,
+            erasure_set_index * ERASURE_SET_SIZE,
+        ))
+    }
+
+    // Returns every blob index this repairman should send for a slot with
+    // `num_blobs_in_slot` blobs, under erasure-aware repair: only `NUM_DATA` (not
+    // `NUM_DATA + NUM_CODING`) indices per erasure set are distributed across
+    // `eligible_repairmen`, rather than every blob in the slot being sent by
+    // `repair_redundancy` repairmen. The trailing partial erasure set (if
+    // `num_blobs_in_slot` isn't a multiple of `ERASURE_SET_SIZE`) is clamped to the blobs
+    // that actually exist in the slot.
+    fn calculate_my_repairman_erasure_aware_indexes_for_slot(
+        my_id: &Pubkey,
+        eligible_repairmen: &[&Pubkey],
+        num_blobs_in_slot: usize,
+        repair_redundancy: usize,
+    ) -> Vec<usize> {
+        let num_erasure_sets = (num_blobs_in_slot + ERASURE_SET_SIZE - 1) / ERASURE_SET_SIZE;
+        (0..num_erasure_sets)
+            .filter_map(|erasure_set_index| {
+                Self::calculate_my_repairman_index_for_erasure_set(
+                    my_id,
+                    eligible_repairmen,
+                    erasure_set_index,
+                    repair_redundancy,
+                )
+            })
+            .flatten()
+            .filter(|&blob_index| blob_index < num_blobs_in_slot)
+            .collect()
+    }
+
     fn find_eligible_repairmen<'a>(
         my_id: &'a Pubkey,
         repairee_root: u64,
@@ -365,11 +825,16 @@ impl ClusterInfoRepairListener {
         let mut repairmen: Vec<_> = repairman_roots
             .iter()
             .filter_map(|(repairman_id, (_, repairman_root))| {
+                // No per-candidate-repairman state is tracked here (unlike the repairee's
+                // own need-repair check in `recv_loop`), so this always judges fresh off the
+                // full buffer, same as before hysteresis was added.
                 if Self::should_repair_peer(
                     *repairman_root,
                     repairee_root,
                     epoch_schedule,
                     num_buffer_slots,
+                    false,
+                    0,
                 ) {
                     Some(repairman_id)
                 } else {
@@ -402,17 +867,33 @@ impl ClusterInfoRepairListener {
 
     // Decide if a repairman with root == `repairman_root` should send repairs to a
     // potential repairee with root == `repairee_root`
+    // `was_being_repaired` softens `num_buffer_slots` by `repair_exit_hysteresis_slots` for a
+    // peer that was already being repaired last pass, so it stays eligible until it climbs
+    // that much further past the buffer edge rather than exiting repair the instant its root
+    // crosses the same threshold that let it in. A peer not being repaired is held to the full
+    // `num_buffer_slots`, i.e. it must fall further behind to become eligible in the first
+    // place. Pass `false` and `0` from call sites with no per-peer repair state to track
+    // (e.g. judging a candidate repairman rather than a repairee), which reproduces the old
+    // single-threshold behavior.
     fn should_repair_peer(
         repairman_root: u64,
         repairee_root: u64,
         epoch_schedule: &EpochSchedule,
         num_buffer_slots: usize,
+        was_being_repaired: bool,
+        repair_exit_hysteresis_slots: usize,
     ) -> bool {
+        let effective_buffer_slots = if was_being_repaired {
+            num_buffer_slots.saturating_sub(repair_exit_hysteresis_slots)
+        } else {
+            num_buffer_slots
+        };
+
         // Check if this potential repairman's confirmed leader schedule is greater
         // than an epoch ahead of the repairee's known schedule
         let repairman_epoch = epoch_schedule.get_stakers_epoch(repairman_root);
         let repairee_epoch =
-            epoch_schedule.get_stakers_epoch(repairee_root + num_buffer_slots as u64);
+            epoch_schedule.get_stakers_epoch(repairee_root + effective_buffer_slots as u64);
 
         repairman_epoch > repairee_epoch
     }
@@ -491,9 +972,12 @@ mod tests {
 
         // Have all the repairman send the repairs
         let num_missing_slots = num_slots / 2;
+        let mut rate_limiter = RepairRateLimiter::new(0);
+        let acknowledgements = RepairAcknowledgements::default();
         for repairman_id in &eligible_repairmen {
             ClusterInfoRepairListener::serve_repairs_to_repairee(
                 &repairman_id,
+                &repairee_id,
                 num_slots - 1,
                 &blocktree,
                 &repairee_epoch_slots,
@@ -501,6 +985,11 @@ mod tests {
                 &my_socket,
                 &repairee_tvu,
                 num_missing_slots as usize,
+                &mut rate_limiter,
+                &acknowledgements,
+                false,
+                REPAIR_REDUNDANCY,
+                &mut HashSet::new(),
             )
             .unwrap();
         }
@@ -526,6 +1015,290 @@ mod tests {
         Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
     }
 
+    #[test]
+    fn test_serve_repairs_to_repairee_skips_acknowledged_slots() {
+        let blocktree_path = get_tmp_ledger_path!();
+        let blocktree = Blocktree::open(&blocktree_path).unwrap();
+        let blobs_per_slot = 5;
+        let num_slots = 10;
+        assert_eq!(num_slots % 2, 0);
+        let (blobs, _) = make_many_slot_entries(0, num_slots, blobs_per_slot);
+        blocktree.insert_data_blobs(&blobs).unwrap();
+        blocktree.set_root(0, 0).unwrap();
+        blocktree.set_root(num_slots - 1, 0).unwrap();
+
+        let my_id = Pubkey::new_rand();
+        let my_socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+
+        // Repairee is missing every odd indexed slot in (repairee_root, num_slots].
+        let repairee_id = Pubkey::new_rand();
+        let repairee_root = 0;
+        let repairee_slots: HashSet<_> = (0..=num_slots).step_by(2).collect();
+        let repairee_epoch_slots = EpochSlots::new(repairee_id, repairee_root, repairee_slots, 1);
+
+        let num_repairmen = blobs_per_slot - 1;
+        let mut eligible_repairmen: Vec<_> =
+            (0..num_repairmen).map(|_| Pubkey::new_rand()).collect();
+        eligible_repairmen.push(my_id);
+        let eligible_repairmen_refs: Vec<_> = eligible_repairmen.iter().collect();
+
+        let (repairee_sender, repairee_receiver) = channel();
+        let repairee_socket = Arc::new(UdpSocket::bind("0.0.0.0:0").unwrap());
+        let repairee_tvu = repairee_socket.local_addr().unwrap();
+        let repairee_exit = Arc::new(AtomicBool::new(false));
+        let repairee_receiver_thread_hdl =
+            streamer::blob_receiver(repairee_socket, &repairee_exit, repairee_sender);
+
+        // The repairee has since gossiped that it filled slot 1, even though
+        // its last EpochSlots update doesn't reflect that yet.
+        let mut acknowledged_slots = HashSet::new();
+        acknowledged_slots.insert(1);
+        let acknowledgements = RepairAcknowledgements::new(acknowledged_slots);
+
+        let num_missing_slots = num_slots / 2;
+        let mut rate_limiter = RepairRateLimiter::new(0);
+        for repairman_id in &eligible_repairmen {
+            ClusterInfoRepairListener::serve_repairs_to_repairee(
+                &repairman_id,
+                &repairee_id,
+                num_slots - 1,
+                &blocktree,
+                &repairee_epoch_slots,
+                &eligible_repairmen_refs,
+                &my_socket,
+                &repairee_tvu,
+                num_missing_slots as usize,
+                &mut rate_limiter,
+                &acknowledgements,
+                false,
+                REPAIR_REDUNDANCY,
+                &mut HashSet::new(),
+            )
+            .unwrap();
+        }
+
+        let mut received_blobs: Vec<Arc<RwLock<Blob>>> = vec![];
+        // One fewer slot's worth of blobs than the unacknowledged case, since
+        // slot 1 is now skipped.
+        let num_expected_blobs =
+            (num_slots / 2 - 1) * blobs_per_slot * REPAIR_REDUNDANCY as u64;
+        while (received_blobs.len() as u64) < num_expected_blobs {
+            received_blobs.extend(repairee_receiver.recv().unwrap());
+        }
+
+        sleep(Duration::from_millis(1000));
+        assert!(repairee_receiver.try_recv().is_err());
+        assert_eq!(received_blobs.len() as u64, num_expected_blobs);
+
+        repairee_exit.store(true, Ordering::Relaxed);
+        repairee_receiver_thread_hdl.join().unwrap();
+        drop(blocktree);
+        Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
+    }
+
+    #[test]
+    fn test_serve_repairs_to_repairee_erasure_aware() {
+        let blocktree_path = get_tmp_ledger_path!();
+        let blocktree = Blocktree::open(&blocktree_path).unwrap();
+        // One full erasure set plus a partial one, so the test also covers
+        // the trailing-partial-set clamp in
+        // calculate_my_repairman_erasure_aware_indexes_for_slot.
+        let blobs_per_slot = ERASURE_SET_SIZE + NUM_DATA / 2;
+        let num_slots = 2;
+        let (blobs, _) = make_many_slot_entries(0, num_slots, blobs_per_slot as u64);
+        blocktree.insert_data_blobs(&blobs).unwrap();
+        blocktree.set_root(0, 0).unwrap();
+        blocktree.set_root(num_slots - 1, 0).unwrap();
+
+        let my_id = Pubkey::new_rand();
+        let my_socket = UdpSocket::bind("0.0.0.0:0").unwrap();
+
+        let repairee_id = Pubkey::new_rand();
+        let repairee_root = 0;
+        let repairee_epoch_slots = EpochSlots::new(repairee_id, repairee_root, HashSet::new(), 1);
+
+        // Exactly enough repairmen that each reconstructable index in an
+        // erasure set is covered by precisely REPAIR_REDUNDANCY of them.
+        let num_repairmen = NUM_DATA * REPAIR_REDUNDANCY;
+        let mut eligible_repairmen: Vec<_> =
+            (0..num_repairmen).map(|_| Pubkey::new_rand()).collect();
+        eligible_repairmen.push(my_id);
+        let eligible_repairmen_refs: Vec<_> = eligible_repairmen.iter().collect();
+
+        let (repairee_sender, repairee_receiver) = channel();
+        let repairee_socket = Arc::new(UdpSocket::bind("0.0.0.0:0").unwrap());
+        let repairee_tvu = repairee_socket.local_addr().unwrap();
+        let repairee_exit = Arc::new(AtomicBool::new(false));
+        let repairee_receiver_thread_hdl =
+            streamer::blob_receiver(repairee_socket, &repairee_exit, repairee_sender);
+
+        let mut rate_limiter = RepairRateLimiter::new(0);
+        let acknowledgements = RepairAcknowledgements::default();
+        for repairman_id in &eligible_repairmen {
+            ClusterInfoRepairListener::serve_repairs_to_repairee(
+                &repairman_id,
+                &repairee_id,
+                num_slots - 1,
+                &blocktree,
+                &repairee_epoch_slots,
+                &eligible_repairmen_refs,
+                &my_socket,
+                &repairee_tvu,
+                num_slots as usize,
+                &mut rate_limiter,
+                &acknowledgements,
+                true,
+                REPAIR_REDUNDANCY,
+                &mut HashSet::new(),
+            )
+            .unwrap();
+        }
+
+        let mut received_blobs: Vec<Arc<RwLock<Blob>>> = vec![];
+        // Erasure-aware repair only distributes NUM_DATA (not
+        // NUM_DATA + NUM_CODING) indexes per erasure set across repairmen,
+        // each still covered REPAIR_REDUNDANCY times over, and the trailing
+        // partial set is clamped to the blobs that actually exist. Only slot 1
+        // lies past the repairee's root (slot 0), so only it is repaired.
+        let num_full_erasure_sets = blobs_per_slot / ERASURE_SET_SIZE;
+        let covered_in_last_set = min(blobs_per_slot % ERASURE_SET_SIZE, NUM_DATA);
+        let num_expected_blobs = (num_slots - 1) as usize
+            * REPAIR_REDUNDANCY
+            * (num_full_erasure_sets * NUM_DATA + covered_in_last_set);
+        while received_blobs.len() < num_expected_blobs {
+            received_blobs.extend(repairee_receiver.recv().unwrap());
+        }
+
+        sleep(Duration::from_millis(1000));
+        assert!(repairee_receiver.try_recv().is_err());
+        assert_eq!(received_blobs.len(), num_expected_blobs);
+
+        repairee_exit.store(true, Ordering::Relaxed);
+        repairee_receiver_thread_hdl.join().unwrap();
+        drop(blocktree);
+        Blocktree::destroy(&blocktree_path).expect("Expected successful database destruction");
+    }
+
+    #[test]
+    fn test_calculate_adaptive_redundancy() {
+        // No prior observation -- be conservative and use the max.
+        assert_eq!(
+            ClusterInfoRepairListener::calculate_adaptive_redundancy(None, REPAIR_REDUNDANCY),
+            REPAIR_REDUNDANCY
+        );
+
+        // Stalled (no progress) keeps the max.
+        assert_eq!(
+            ClusterInfoRepairListener::calculate_adaptive_redundancy(Some(0), REPAIR_REDUNDANCY),
+            REPAIR_REDUNDANCY
+        );
+
+        // Some progress scales redundancy down, but not below 1.
+        assert_eq!(
+            ClusterInfoRepairListener::calculate_adaptive_redundancy(Some(1), REPAIR_REDUNDANCY),
+            REPAIR_REDUNDANCY - 1
+        );
+        assert_eq!(
+            ClusterInfoRepairListener::calculate_adaptive_redundancy(
+                Some(u64::MAX),
+                REPAIR_REDUNDANCY
+            ),
+            1
+        );
+    }
+
+    #[test]
+    fn test_repairee_progress_tracker() {
+        let mut tracker = RepaireeProgressTracker::default();
+        let repairee_id = Pubkey::new_rand();
+
+        // First observation has nothing to compare against.
+        assert_eq!(tracker.record_and_get_progress(repairee_id, 5), None);
+        // Root advanced by 3 since the last pass.
+        assert_eq!(tracker.record_and_get_progress(repairee_id, 8), Some(3));
+        // A stalled repairee reports zero progress, not an error.
+        assert_eq!(tracker.record_and_get_progress(repairee_id, 8), Some(0));
+    }
+
+    #[test]
+    fn test_repair_nack_tracker_raises_and_lowers_redundancy() {
+        let repairee_id = Pubkey::new_rand();
+        let mut tracker = RepairNackTracker::new(1, 5);
+        // No prior pass to compare against yet, so the ceiling starts at the max.
+        assert_eq!(tracker.current_redundancy(), 5);
+
+        let mut served = HashSet::new();
+        served.insert((repairee_id, 0));
+        served.insert((repairee_id, 1));
+        // First pass has nothing to compare against yet, so it only seeds
+        // `previously_served` and leaves the ceiling untouched.
+        assert_eq!(
+            tracker.record_served_slots_and_adjust_redundancy(served.clone()),
+            5
+        );
+
+        // An entirely different set of slots this pass means a 0% re-serve
+        // rate, so the ceiling should fall.
+        let mut disjoint_served = HashSet::new();
+        disjoint_served.insert((repairee_id, 2));
+        assert_eq!(
+            tracker.record_served_slots_and_adjust_redundancy(disjoint_served.clone()),
+            4
+        );
+
+        // Re-serving the exact same slots as last pass is a 100% re-serve
+        // rate, so the ceiling should rise back up.
+        assert_eq!(
+            tracker.record_served_slots_and_adjust_redundancy(disjoint_served),
+            5
+        );
+
+        // Never drops below the configured minimum, however many low-rate
+        // passes follow.
+        for _ in 0..10 {
+            let mut served = HashSet::new();
+            served.insert((Pubkey::new_rand(), 0));
+            tracker.record_served_slots_and_adjust_redundancy(served);
+        }
+        assert_eq!(tracker.current_redundancy(), 1);
+    }
+
+    #[test]
+    fn test_repair_nack_tracker_empty_pass_is_a_no_op() {
+        let mut tracker = RepairNackTracker::new(1, 5);
+        assert_eq!(
+            tracker.record_served_slots_and_adjust_redundancy(HashSet::new()),
+            5
+        );
+    }
+
+    #[test]
+    fn test_repair_rate_limiter() {
+        // Budget for exactly 1000 bytes per REPAIRMEN_SLEEP_MILLIS tick.
+        let mut rate_limiter = RepairRateLimiter::new(1000);
+
+        assert!(rate_limiter.try_consume(600));
+        assert!(rate_limiter.try_consume(400));
+        // Budget is now exhausted for this pass.
+        assert!(!rate_limiter.try_consume(1));
+
+        // A smaller send that still fits in the remainder is allowed even
+        // after a larger one was rejected.
+        rate_limiter.reset_for_new_pass();
+        assert!(!rate_limiter.try_consume(1001));
+        assert!(rate_limiter.try_consume(1000));
+
+        // Resuming on the next tick restores the full budget.
+        rate_limiter.reset_for_new_pass();
+        assert!(rate_limiter.try_consume(1000));
+    }
+
+    #[test]
+    fn test_repair_rate_limiter_disabled() {
+        let mut rate_limiter = RepairRateLimiter::new(0);
+        assert!(rate_limiter.try_consume(u64::MAX));
+    }
+
     #[test]
     fn test_shuffle_repairmen() {
         let num_repairmen = 10;
@@ -639,6 +1412,8 @@ mod tests {
             repairee_root,
             &epoch_schedule,
             0,
+            false,
+            0,
         ));
 
         // If repairee is at the same place as us, we don't repair
@@ -649,6 +1424,8 @@ mod tests {
             repairee_root,
             &epoch_schedule,
             0,
+            false,
+            0,
         ));
 
         // If repairee is behind but in the same confirmed epoch, we don't repair
@@ -659,6 +1436,8 @@ mod tests {
             repairee_root,
             &epoch_schedule,
             0,
+            false,
+            0,
         ));
 
         // If we have confirmed the next epoch, but the repairee is within the buffer
@@ -670,6 +1449,8 @@ mod tests {
             repairee_root,
             &epoch_schedule,
             11,
+            false,
+            0,
         ));
 
         // If we have confirmed the next epoch, but the repairee is behind a confirmed epoch
@@ -681,6 +1462,51 @@ mod tests {
             repairee_root,
             &epoch_schedule,
             10,
+            false,
+            0,
+        ));
+    }
+
+    #[test]
+    fn test_should_repair_peer_hysteresis() {
+        let epoch_schedule = EpochSchedule::new(32, 16, false);
+        let repairman_root = 16;
+        let repairee_root = 8;
+
+        // With the full buffer (11), this repairee is still within it and wouldn't be
+        // (re-)entered into repair from scratch.
+        assert!(!ClusterInfoRepairListener::should_repair_peer(
+            repairman_root,
+            repairee_root,
+            &epoch_schedule,
+            11,
+            false,
+            0,
+        ));
+
+        // But a repairee that was already being repaired stays eligible: with
+        // `repair_exit_hysteresis_slots` of 5, the effective buffer drops to 6, and this
+        // repairee is still behind that smaller buffer, so it remains in repair rather than
+        // flapping out the instant it crosses the full buffer's edge.
+        assert!(ClusterInfoRepairListener::should_repair_peer(
+            repairman_root,
+            repairee_root,
+            &epoch_schedule,
+            11,
+            true,
+            5,
+        ));
+
+        // Once it's climbed far enough past the buffer edge that even the reduced,
+        // hysteresis-adjusted buffer no longer covers it, it finally exits repair.
+        let repairee_root = 11;
+        assert!(!ClusterInfoRepairListener::should_repair_peer(
+            repairman_root,
+            repairee_root,
+            &epoch_schedule,
+            11,
+            true,
+            5,
         ));
     }
 
@@ -739,4 +1565,119 @@ mod tests {
             num_repairmen.saturating_sub(num_blobs_in_slot * repair_redundancy)
         );
     }
+
+    #[test]
+    fn test_calculate_my_repairman_indexes_for_slot_stake_weighted_even_distribution() {
+        // With equal stakes the hash-bucketed assignment should still spread coverage
+        // roughly evenly across blobs, same as the unweighted ring-based assignment.
+        let num_repairmen = 10;
+        let num_blobs_in_slot = 42;
+        let repair_redundancy = 3;
+        let slot = 123;
+
+        let eligible_repairmen: Vec<_> = (0..num_repairmen).map(|_| Pubkey::new_rand()).collect();
+        let eligible_repairmen_ref: Vec<_> = eligible_repairmen.iter().collect();
+        let stakes: HashMap<Pubkey, u64> =
+            eligible_repairmen.iter().map(|pk| (*pk, 100)).collect();
+
+        let mut results = HashMap::new();
+        for pk in &eligible_repairmen {
+            let my_repair_indexes =
+                ClusterInfoRepairListener::calculate_my_repairman_indexes_for_slot_stake_weighted(
+                    pk,
+                    &eligible_repairmen_ref[..],
+                    &stakes,
+                    slot,
+                    num_blobs_in_slot,
+                    repair_redundancy,
+                );
+            for blob_index in my_repair_indexes {
+                results.entry(blob_index).and_modify(|e| *e += 1).or_insert(1);
+            }
+        }
+
+        // Every blob should be covered, each by somewhere near `repair_redundancy` repairmen --
+        // the hash-based mapping doesn't guarantee the exact +/-1 bound the ring-based
+        // assignment does, but with equal stakes it shouldn't be wildly off either.
+        assert_eq!(results.len(), num_blobs_in_slot);
+        for count in results.values() {
+            assert!(*count >= 1 && *count <= repair_redundancy * 2);
+        }
+    }
+
+    #[test]
+    fn test_calculate_my_repairman_indexes_for_slot_stake_weighted_proportional_to_stake() {
+        // A repairman with 9x the stake of its peers should end up responsible for
+        // roughly 9x as many repair tickets.
+        let num_blobs_in_slot = 50;
+        let repair_redundancy = 20;
+        let slot = 7;
+
+        let big_stake_repairman = Pubkey::new_rand();
+        let small_stake_repairmen: Vec<_> = (0..9).map(|_| Pubkey::new_rand()).collect();
+
+        let mut eligible_repairmen: Vec<&Pubkey> = small_stake_repairmen.iter().collect();
+        eligible_repairmen.push(&big_stake_repairman);
+
+        let mut stakes = HashMap::new();
+        stakes.insert(big_stake_repairman, 900);
+        for pk in &small_stake_repairmen {
+            stakes.insert(*pk, 100);
+        }
+
+        let big_tickets =
+            ClusterInfoRepairListener::calculate_my_repairman_indexes_for_slot_stake_weighted(
+                &big_stake_repairman,
+                &eligible_repairmen,
+                &stakes,
+                slot,
+                num_blobs_in_slot,
+                repair_redundancy,
+            )
+            .len();
+
+        let small_tickets: usize = small_stake_repairmen
+            .iter()
+            .map(|pk| {
+                ClusterInfoRepairListener::calculate_my_repairman_indexes_for_slot_stake_weighted(
+                    pk,
+                    &eligible_repairmen,
+                    &stakes,
+                    slot,
+                    num_blobs_in_slot,
+                    repair_redundancy,
+                )
+                .len()
+            })
+            .sum::<usize>()
+            / small_stake_repairmen.len();
+
+        assert!(big_tickets > small_tickets * 4);
+    }
+
+    #[test]
+    fn test_calculate_my_repairman_indexes_for_slot_stake_weighted_ignores_zero_stake() {
+        let num_blobs_in_slot = 10;
+        let repair_redundancy = 3;
+        let slot = 55;
+
+        let zero_stake_repairman = Pubkey::new_rand();
+        let staked_repairman = Pubkey::new_rand();
+        let eligible_repairmen = vec![&zero_stake_repairman, &staked_repairman];
+
+        let mut stakes = HashMap::new();
+        stakes.insert(staked_repairman, 100);
+        // `zero_stake_repairman` has no entry at all.
+
+        let indexes =
+            ClusterInfoRepairListener::calculate_my_repairman_indexes_for_slot_stake_weighted(
+                &zero_stake_repairman,
+                &eligible_repairmen,
+                &stakes,
+                slot,
+                num_blobs_in_slot,
+                repair_redundancy,
+            );
+        assert!(indexes.is_empty());
+    }
 }
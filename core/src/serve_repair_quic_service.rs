@@ -3,12 +3,13 @@ use {
     crossbeam_channel::{unbounded, Sender},
     solana_client::connection_cache::ConnectionCache,
     solana_ledger::blockstore::Blockstore,
+    solana_perf::packet::PacketBatchRecycler,
     solana_quic_client::{QuicConfig, QuicConnectionManager, QuicPool},
     solana_sdk::signer::Signer,
     solana_streamer::{
         quic::{spawn_server, StreamStats, MAX_STAKED_CONNECTIONS, MAX_UNSTAKED_CONNECTIONS},
         socket::SocketAddrSpace,
-        streamer::{self, ResponderOption},
+        streamer::{self, ResponderOption, StreamerReceiveStats},
     },
     std::{
         sync::{atomic::AtomicBool, Arc},
@@ -54,7 +55,7 @@ impl ServeRepairService {
             repair_quic_config.serve_repair_address.try_clone().unwrap(),
             &repair_quic_config.identity_keypair,
             host.clone(),
-            request_sender_quic,
+            request_sender_quic.clone(),
             exit.clone(),
             MAX_QUIC_CONNECTIONS_PER_PEER,
             repair_quic_config.staked_nodes.clone(),
@@ -83,18 +84,58 @@ impl ServeRepairService {
             "RepairQuic",
             ResponderOption::ConnectionCache(connection_cache),
             response_receiver_quic,
-            socket_addr_space,
-            Some(stats_reporter_sender),
+            socket_addr_space.clone(),
+            Some(stats_reporter_sender.clone()),
         );
 
+        let mut thread_hdls = vec![repair_quic_t, t_responder_quic];
+
+        // Dual-stack mode: alongside the QUIC server above, also listen for
+        // (and respond to) legacy UDP repair requests, so peers that
+        // haven't migrated to QUIC repair still get served during the
+        // rollout. UDP requests feed the same `request_receiver_quic`
+        // channel QUIC requests do; responses go out over whichever
+        // transport the matching request arrived on, since
+        // `serve_repair.listen` is given both response senders and routes
+        // to the one recorded against the request's peer.
+        let response_sender_udp = if repair_quic_config.enable_udp_fallback {
+            let repair_udp_socket = repair_quic_config.repair_udp_socket.clone();
+
+            let (response_sender_udp, response_receiver_udp) = unbounded();
+            let t_receiver_udp = streamer::receiver(
+                repair_udp_socket.clone(),
+                exit.clone(),
+                request_sender_quic.clone(),
+                PacketBatchRecycler::warmed(100, 1024),
+                Arc::new(StreamerReceiveStats::new("serve_repair_udp_receiver")),
+                1,
+                true,
+                None,
+            );
+            let t_responder_udp = streamer::responder::<QuicPool, QuicConnectionManager, QuicConfig>(
+                "RepairUdp",
+                ResponderOption::Udp(repair_udp_socket),
+                response_receiver_udp,
+                socket_addr_space,
+                Some(stats_reporter_sender),
+            );
+
+            thread_hdls.push(t_receiver_udp);
+            thread_hdls.push(t_responder_udp);
+            Some(response_sender_udp)
+        } else {
+            None
+        };
+
         let t_listen_quic = serve_repair.listen(
             blockstore,
             request_receiver_quic,
             response_sender_quic,
+            response_sender_udp,
             exit,
         );
 
-        let thread_hdls = vec![repair_quic_t, t_responder_quic, t_listen_quic];
+        thread_hdls.push(t_listen_quic);
         Self { thread_hdls }
     }
 
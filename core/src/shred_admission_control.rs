@@ -0,0 +1,273 @@
+//! Stake-weighted admission control for UDP shred ingestion. Under normal conditions every
+//! source is treated equally, same as before; once the inbound backlog or a single source's
+//! packet rate crosses a threshold, packets from low- or zero-stake sources are preferentially
+//! dropped so an unstaked flood can't crowd out legitimate, staked shreds.
+
+use {
+    solana_gossip::cluster_info::ClusterInfo,
+    solana_streamer::quic::StakedNodes,
+    std::{
+        collections::HashMap,
+        net::IpAddr,
+        sync::{Arc, RwLock},
+        time::{Duration, Instant},
+    },
+};
+
+/// Backlog length (in packet batches) at or above which admission control starts preferring
+/// staked sources over unstaked ones.
+pub(crate) const BACKLOG_ADMISSION_THRESHOLD: usize = 1_000;
+/// Per-source packet rate (packets/sec) at or above which admission control kicks in for that
+/// source specifically, even if the overall backlog is still shallow.
+pub(crate) const PER_SOURCE_RATE_ADMISSION_THRESHOLD: f64 = 1_000.0;
+
+const BUCKET_CAPACITY: f64 = 64.0;
+// A fully unstaked peer still gets a small, non-zero trickle so a brand-new validator with no
+// recorded stake yet isn't shut out entirely.
+const MIN_REFILL_RATE: f64 = 1.0;
+
+/// How long a source's bucket/rate state can sit untouched before a sweep reclaims it. This is
+/// admission control for UDP shred ingestion, so the source `IpAddr` it's keyed by is trivially
+/// spoofable by the exact unstaked flood this feature defends against -- without eviction, an
+/// attacker cycling through addresses turns the mitigation into an unbounded-memory-growth DoS
+/// of its own.
+const STALE_ENTRY_TTL: Duration = Duration::from_secs(60);
+/// How often `admit` checks for stale entries, so every call doesn't pay the cost of a full scan
+/// of both maps.
+const EVICTION_SWEEP_INTERVAL: Duration = Duration::from_secs(30);
+
+pub(crate) enum AdmissionDecision {
+    Admit,
+    DropUnstaked,
+    DropLowStake,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new() -> Self {
+        Self {
+            tokens: BUCKET_CAPACITY,
+            last_refill: Instant::now(),
+        }
+    }
+
+    fn try_admit(&mut self, refill_rate: f64) -> bool {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+        self.last_refill = Instant::now();
+        self.tokens = (self.tokens + elapsed * refill_rate).min(BUCKET_CAPACITY);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+struct SourceRate {
+    count_in_window: u32,
+    window_start: Instant,
+    // Separate from `window_start`: that only moves when the 1-second window rolls over, so it
+    // can't tell a sweep how recently this source was actually seen.
+    last_seen: Instant,
+}
+
+impl SourceRate {
+    fn new() -> Self {
+        let now = Instant::now();
+        Self {
+            count_in_window: 0,
+            window_start: now,
+            last_seen: now,
+        }
+    }
+
+    /// Rolling packets/sec for this source, resetting the window once a full second elapses.
+    fn observe(&mut self) -> f64 {
+        self.last_seen = Instant::now();
+        let elapsed = self.window_start.elapsed().as_secs_f64();
+        if elapsed >= 1.0 {
+            self.count_in_window = 0;
+            self.window_start = Instant::now();
+        }
+        self.count_in_window += 1;
+        self.count_in_window as f64 / elapsed.max(1.0)
+    }
+}
+
+/// Per-source-IP token buckets, refilled proportional to the peer's stake fraction, gating
+/// admission only once the caller reports backlog or source-rate pressure.
+pub(crate) struct AdmissionControl {
+    cluster_info: Arc<ClusterInfo>,
+    staked_nodes: Arc<RwLock<StakedNodes>>,
+    buckets: HashMap<IpAddr, TokenBucket>,
+    rates: HashMap<IpAddr, SourceRate>,
+    last_swept: Instant,
+}
+
+impl AdmissionControl {
+    pub(crate) fn new(
+        cluster_info: Arc<ClusterInfo>,
+        staked_nodes: Arc<RwLock<StakedNodes>>,
+    ) -> Self {
+        Self {
+            cluster_info,
+            staked_nodes,
+            buckets: HashMap::new(),
+            rates: HashMap::new(),
+            last_swept: Instant::now(),
+        }
+    }
+
+    /// Drop any source's bucket/rate state that's gone untouched for `STALE_ENTRY_TTL`, so a
+    /// spoofed flood of distinct source addresses can't grow `buckets`/`rates` without bound.
+    /// Runs at most once per `EVICTION_SWEEP_INTERVAL`.
+    fn maybe_evict_stale(&mut self) {
+        if self.last_swept.elapsed() < EVICTION_SWEEP_INTERVAL {
+            return;
+        }
+        self.last_swept = Instant::now();
+        self.buckets
+            .retain(|_, bucket| bucket.last_refill.elapsed() < STALE_ENTRY_TTL);
+        self.rates
+            .retain(|_, rate| rate.last_seen.elapsed() < STALE_ENTRY_TTL);
+    }
+
+    /// Stake fraction (0.0 if `addr` can't be resolved to a known validator, or the cluster
+    /// has no stake at all) of whichever validator gossip has advertised `addr` as a contact
+    /// address.
+    fn stake_fraction(&self, addr: IpAddr) -> f64 {
+        let staked_nodes = self.staked_nodes.read().unwrap();
+        let total_stake = staked_nodes.total_stake();
+        if total_stake == 0 {
+            return 0.0;
+        }
+        let stake = self
+            .cluster_info
+            .lookup_contact_info_by_gossip_addr(&addr)
+            .and_then(|pubkey| staked_nodes.get_node_stake(&pubkey))
+            .unwrap_or(0);
+        stake as f64 / total_stake as f64
+    }
+
+    /// Should a packet from `addr` be admitted, given the caller's observed backlog length?
+    /// Below both thresholds every source is admitted, same as with no admission control at
+    /// all; past either, admission is gated by a token bucket refilled in proportion to the
+    /// source's stake fraction.
+    pub(crate) fn admit(&mut self, addr: IpAddr, backlog_len: usize) -> AdmissionDecision {
+        self.maybe_evict_stale();
+        let source_rate = self
+            .rates
+            .entry(addr)
+            .or_insert_with(SourceRate::new)
+            .observe();
+        if backlog_len < BACKLOG_ADMISSION_THRESHOLD
+            && source_rate < PER_SOURCE_RATE_ADMISSION_THRESHOLD
+        {
+            return AdmissionDecision::Admit;
+        }
+
+        let stake_fraction = self.stake_fraction(addr);
+        let refill_rate = MIN_REFILL_RATE + stake_fraction * BUCKET_CAPACITY;
+        let admitted = self
+            .buckets
+            .entry(addr)
+            .or_insert_with(TokenBucket::new)
+            .try_admit(refill_rate);
+        if admitted {
+            AdmissionDecision::Admit
+        } else if stake_fraction == 0.0 {
+            AdmissionDecision::DropUnstaked
+        } else {
+            AdmissionDecision::DropLowStake
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token_bucket_refills_over_time() {
+        let mut bucket = TokenBucket {
+            tokens: 0.0,
+            last_refill: Instant::now() - std::time::Duration::from_secs(1),
+        };
+        assert!(!bucket.try_admit(0.0), "an empty, non-refilling bucket stays empty");
+
+        let mut bucket = TokenBucket {
+            tokens: 0.0,
+            last_refill: Instant::now() - std::time::Duration::from_secs(1),
+        };
+        assert!(bucket.try_admit(10.0), "a second of refill at 10 tokens/sec admits one packet");
+    }
+
+    #[test]
+    fn test_token_bucket_caps_at_capacity() {
+        let mut bucket = TokenBucket {
+            tokens: BUCKET_CAPACITY,
+            last_refill: Instant::now() - std::time::Duration::from_secs(100),
+        };
+        bucket.try_admit(1_000_000.0);
+        assert!(bucket.tokens <= BUCKET_CAPACITY);
+    }
+
+    #[test]
+    fn test_stale_buckets_are_evicted() {
+        let stale_addr = IpAddr::from([127, 0, 0, 1]);
+        let fresh_addr = IpAddr::from([127, 0, 0, 2]);
+        let mut buckets = HashMap::new();
+        buckets.insert(
+            stale_addr,
+            TokenBucket {
+                tokens: BUCKET_CAPACITY,
+                last_refill: Instant::now() - STALE_ENTRY_TTL - Duration::from_secs(1),
+            },
+        );
+        buckets.insert(
+            fresh_addr,
+            TokenBucket {
+                tokens: BUCKET_CAPACITY,
+                last_refill: Instant::now(),
+            },
+        );
+
+        buckets.retain(|_, bucket| bucket.last_refill.elapsed() < STALE_ENTRY_TTL);
+
+        assert_eq!(buckets.len(), 1);
+        assert!(buckets.contains_key(&fresh_addr));
+    }
+
+    #[test]
+    fn test_stale_rates_are_evicted() {
+        let stale_addr = IpAddr::from([127, 0, 0, 1]);
+        let fresh_addr = IpAddr::from([127, 0, 0, 2]);
+        let mut rates = HashMap::new();
+        rates.insert(
+            stale_addr,
+            SourceRate {
+                count_in_window: 1,
+                window_start: Instant::now() - STALE_ENTRY_TTL - Duration::from_secs(1),
+                last_seen: Instant::now() - STALE_ENTRY_TTL - Duration::from_secs(1),
+            },
+        );
+        rates.insert(
+            fresh_addr,
+            SourceRate {
+                count_in_window: 1,
+                window_start: Instant::now(),
+                last_seen: Instant::now(),
+            },
+        );
+
+        rates.retain(|_, rate| rate.last_seen.elapsed() < STALE_ENTRY_TTL);
+
+        assert_eq!(rates.len(), 1);
+        assert!(rates.contains_key(&fresh_addr));
+    }
+}
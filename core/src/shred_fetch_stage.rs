@@ -4,10 +4,11 @@ use {
     crate::{
         cluster_nodes::check_feature_activation, packet_hasher::PacketHasher,
         repair_service::RepairTransportConfig, serve_repair::ServeRepair,
-        tpu::MAX_QUIC_CONNECTIONS_PER_PEER, tvu::RepairQuicConfig,
+        shred_admission_control::{AdmissionControl, AdmissionDecision},
+        shred_dedup_filter::GenerationalBloomFilter, tpu::MAX_QUIC_CONNECTIONS_PER_PEER,
+        tvu::RepairQuicConfig,
     },
     crossbeam_channel::{unbounded, Sender},
-    lru::LruCache,
     solana_client::connection_cache::ConnectionCache,
     solana_gossip::cluster_info::ClusterInfo,
     solana_ledger::shred::{should_discard_shred, ShredFetchStats},
@@ -19,7 +20,10 @@ use {
         signer::Signer,
     },
     solana_streamer::{
-        quic::{spawn_server, StreamStats, MAX_STAKED_CONNECTIONS, MAX_UNSTAKED_CONNECTIONS},
+        quic::{
+            spawn_server, StakedNodes, StreamStats, MAX_STAKED_CONNECTIONS,
+            MAX_UNSTAKED_CONNECTIONS,
+        },
         streamer::{self, PacketBatchReceiver, StreamerReceiveStats},
     },
     std::{
@@ -30,14 +34,20 @@ use {
     },
 };
 
-const DEFAULT_LRU_SIZE: usize = 10_000;
+// Expected shreds per dedup window (~2 rotations) and the false-positive rate the window's
+// generational Bloom filter is sized for. Tunable: a generation sized this way is about half
+// full at its designed load, and its false-positive rate is roughly the fill ratio raised to the
+// number of hash functions, so raise `EXPECTED_SHREDS_PER_WINDOW` if
+// `ShredFetchStats::shred_dedup_fill_ratio` is regularly tracking well above 0.5.
+const EXPECTED_SHREDS_PER_WINDOW: usize = 10_000;
+const TARGET_FALSE_POSITIVE_RATE: f64 = 0.01;
 
 pub(crate) struct ShredFetchStage {
     thread_hdls: Vec<JoinHandle<()>>,
-    // /// The Quic ConnectonCache using the same Quic Endpoint of the Quic based
-    // /// streamer receiving shreds. The connection cache can be used for sending
-    // /// repair requests.
-    // connection_cache: Option<Arc<ConnectionCache>>,
+    /// The Quic ConnectionCache using the same Quic Endpoint of the Quic based
+    /// streamer receiving shreds. The connection cache can be used for sending
+    /// repair requests.
+    connection_cache: Option<Arc<ConnectionCache>>,
     quic_repair_addr: Option<Arc<UdpSocket>>,
 }
 
@@ -51,9 +61,24 @@ impl ShredFetchStage {
         name: &'static str,
         flags: PacketFlags,
         repair_context: Option<(RepairTransportConfig, &ClusterInfo)>,
+        cluster_info: Arc<ClusterInfo>,
+        staked_nodes: Option<Arc<RwLock<StakedNodes>>>,
     ) {
         const STATS_SUBMIT_CADENCE: Duration = Duration::from_secs(1);
-        let mut shreds_received = LruCache::new(DEFAULT_LRU_SIZE);
+        // `backlog` mirrors `recvr` so `.len()` can be read without consuming the receiver
+        // the `for` loop below iterates by value.
+        let backlog = recvr.clone();
+        let mut admission_control =
+            staked_nodes.map(|staked_nodes| AdmissionControl::new(cluster_info, staked_nodes));
+        // Two generations spanning one slot each, so the dedup window is one to two slots
+        // wide depending on where in the rotation a given shred lands. The filter rotates
+        // itself on this cadence independently of the `last_updated` refresh below, since
+        // mixing the two would mean comparing hashes across a hasher reseed.
+        let mut shreds_received = GenerationalBloomFilter::new(
+            EXPECTED_SHREDS_PER_WINDOW,
+            TARGET_FALSE_POSITIVE_RATE,
+            Duration::from_millis(DEFAULT_MS_PER_SLOT),
+        );
         let mut last_updated = Instant::now();
         let mut keypair = repair_context
             .as_ref()
@@ -71,8 +96,16 @@ impl ShredFetchStage {
         for mut packet_batch in recvr {
             if last_updated.elapsed().as_millis() as u64 > DEFAULT_MS_PER_SLOT {
                 last_updated = Instant::now();
+                // Reseeding here is what bounds how long an adversary has to search for an
+                // `AHasher` collision under a single seed and use it to get legitimate shreds
+                // dropped as "already seen" -- past this point, any collision they found stops
+                // lining up with the packets it was found against. `shreds_received`'s
+                // generations were filled against the old seed, though, so a handful of genuine
+                // duplicates straddling this exact instant can slip through unflagged for one
+                // rotation (it rotates on its own schedule, see above); that's a bounded,
+                // one-time dip in dedup coverage, not a growing hole like leaving the seed fixed
+                // for the process lifetime would be.
                 packet_hasher.reset();
-                shreds_received.clear();
                 {
                     let bank_forks_r = bank_forks.read().unwrap();
                     last_root = bank_forks_r.root();
@@ -105,7 +138,24 @@ impl ShredFetchStage {
             let max_slot = last_slot + 2 * slots_per_epoch;
             let should_drop_merkle_shreds =
                 |shred_slot| should_drop_merkle_shreds(shred_slot, &root_bank);
+            let backlog_len = backlog.len();
             for packet in packet_batch.iter_mut() {
+                if let Some(admission_control) = admission_control.as_mut() {
+                    let source_ip = packet.meta().socket_addr().ip();
+                    match admission_control.admit(source_ip, backlog_len) {
+                        AdmissionDecision::DropUnstaked => {
+                            stats.admission_dropped_unstaked += 1;
+                            packet.meta_mut().set_discard(true);
+                            continue;
+                        }
+                        AdmissionDecision::DropLowStake => {
+                            stats.admission_dropped_lowstake += 1;
+                            packet.meta_mut().set_discard(true);
+                            continue;
+                        }
+                        AdmissionDecision::Admit => {}
+                    }
+                }
                 if should_discard_packet(
                     packet,
                     last_root,
@@ -121,6 +171,7 @@ impl ShredFetchStage {
                     packet.meta_mut().flags.insert(flags);
                 }
             }
+            stats.shred_dedup_fill_ratio = shreds_received.fill_ratio();
             stats.maybe_submit(name, STATS_SUBMIT_CADENCE);
             if sendr.send(packet_batch).is_err() {
                 break;
@@ -138,6 +189,8 @@ impl ShredFetchStage {
         name: &'static str,
         flags: PacketFlags,
         repair_context: Option<(Arc<UdpSocket>, Arc<ClusterInfo>)>,
+        cluster_info: Arc<ClusterInfo>,
+        staked_nodes: Option<Arc<RwLock<StakedNodes>>>,
     ) -> (Vec<JoinHandle<()>>, JoinHandle<()>) {
         let (packet_sender, packet_receiver) = unbounded();
         let streamers = sockets
@@ -172,6 +225,8 @@ impl ShredFetchStage {
                     name,
                     flags,
                     repair_context,
+                    cluster_info,
+                    staked_nodes,
                 )
             })
             .unwrap();
@@ -242,6 +297,8 @@ impl ShredFetchStage {
                     name,
                     flags,
                     repair_context,
+                    cluster_info.clone(),
+                    None, // staked_nodes: the quic endpoint already admits by stake itself
                 )
             })
             .unwrap();
@@ -261,6 +318,9 @@ impl ShredFetchStage {
         exit: &Arc<AtomicBool>,
     ) -> (Option<Arc<ConnectionCache>>, Self) {
         let recycler = PacketBatchRecycler::warmed(100, 1024);
+        // Stake-weighted admission control only applies to plain UDP ingestion -- the quic
+        // repair endpoint already admits connections by stake via MAX_STAKED_CONNECTIONS.
+        let staked_nodes = repair_quic_config.map(|config| config.staked_nodes.clone());
 
         let (mut tvu_threads, tvu_filter) = Self::packet_modifier(
             sockets,
@@ -272,6 +332,8 @@ impl ShredFetchStage {
             "shred_fetch",
             PacketFlags::empty(),
             None, // repair_context
+            cluster_info.clone(),
+            staked_nodes.clone(),
         );
 
         let (tvu_forwards_threads, fwd_thread_hdl) = Self::packet_modifier(
@@ -284,6 +346,8 @@ impl ShredFetchStage {
             "shred_fetch_tvu_forwards",
             PacketFlags::FORWARDED,
             None, // repair_context
+            cluster_info.clone(),
+            staked_nodes.clone(),
         );
 
         let (repair_receiver, repair_handler) = Self::packet_modifier(
@@ -296,6 +360,8 @@ impl ShredFetchStage {
             "shred_fetch_repair",
             PacketFlags::REPAIR,
             Some((repair_socket, cluster_info.clone())),
+            cluster_info.clone(),
+            staked_nodes,
         );
 
         let (connection_cache, quic_repair_addr, repair_quic_t, quic_repair_modifier_t) =
@@ -337,9 +403,10 @@ impl ShredFetchStage {
         }
 
         (
-            connection_cache,
+            connection_cache.clone(),
             Self {
                 thread_hdls: tvu_threads,
+                connection_cache,
                 quic_repair_addr
             },
         )
@@ -353,11 +420,11 @@ impl ShredFetchStage {
         Ok(())
     }
 
-    // /// Obtain the quic based ConnectionCache which used the same
-    // /// Endpoint receiving the repair responses to send repair requests.
-    // pub(crate) fn get_connection_cache(&self) -> Option<Arc<ConnectionCache>> {
-    //     self.connection_cache.clone()
-    // }
+    /// Obtain the quic based ConnectionCache which used the same
+    /// Endpoint receiving the repair responses to send repair requests.
+    pub(crate) fn get_connection_cache(&self) -> Option<Arc<ConnectionCache>> {
+        self.connection_cache.clone()
+    }
 }
 
 // Returns true if the packet should be marked as discard.
@@ -368,7 +435,7 @@ fn should_discard_packet(
     max_slot: Slot, // Max slot to ingest shreds for.
     shred_version: u16,
     packet_hasher: &PacketHasher,
-    shreds_received: &mut LruCache<u64, ()>,
+    shreds_received: &mut GenerationalBloomFilter,
     should_drop_merkle_shreds: impl Fn(Slot) -> bool,
     stats: &mut ShredFetchStats,
 ) -> bool {
@@ -383,12 +450,11 @@ fn should_discard_packet(
         return true;
     }
     let hash = packet_hasher.hash_packet(packet);
-    match shreds_received.put(hash, ()) {
-        None => false,
-        Some(()) => {
-            stats.duplicate_shred += 1;
-            true
-        }
+    if shreds_received.insert_and_check_duplicate(hash) {
+        stats.duplicate_shred += 1;
+        true
+    } else {
+        false
     }
 }
 
@@ -418,7 +484,11 @@ mod tests {
     #[test]
     fn test_data_code_same_index() {
         solana_logger::setup();
-        let mut shreds_received = LruCache::new(DEFAULT_LRU_SIZE);
+        let mut shreds_received = GenerationalBloomFilter::new(
+            EXPECTED_SHREDS_PER_WINDOW,
+            TARGET_FALSE_POSITIVE_RATE,
+            Duration::from_millis(DEFAULT_MS_PER_SLOT),
+        );
         let mut packet = Packet::default();
         let mut stats = ShredFetchStats::default();
 
@@ -473,7 +543,11 @@ mod tests {
     #[test]
     fn test_shred_filter() {
         solana_logger::setup();
-        let mut shreds_received = LruCache::new(DEFAULT_LRU_SIZE);
+        let mut shreds_received = GenerationalBloomFilter::new(
+            EXPECTED_SHREDS_PER_WINDOW,
+            TARGET_FALSE_POSITIVE_RATE,
+            Duration::from_millis(DEFAULT_MS_PER_SLOT),
+        );
         let mut packet = Packet::default();
         let mut stats = ShredFetchStats::default();
         let last_root = 0;
@@ -1,4 +1,7 @@
-use strum::{Display, EnumString, EnumVariantNames, IntoStaticStr, VariantNames};
+use {
+    solana_sdk::clock::Slot,
+    strum::{Display, EnumString, EnumVariantNames, IntoStaticStr, VariantNames},
+};
 
 /// When should snapshot archives be used at startup?
 #[derive(
@@ -13,12 +16,38 @@ pub enum UseSnapshotArchivesAtStartup {
     /// If snapshot archive are not used, then the local snapshot state already on disk is
     /// used instead.  If there is no local state on disk, startup will fail.
     Never,
+    /// Snapshot archives are only extracted when they are newer than the snapshot state
+    /// already on disk.  This avoids the runtime cost of extracting when the on-disk state
+    /// is already sufficient, while still falling back to the archives when it is not (or
+    /// when there is no on-disk state at all).
+    WhenNewer,
 }
 
 impl UseSnapshotArchivesAtStartup {
     pub const fn variants() -> &'static [&'static str] {
         Self::VARIANTS
     }
+
+    /// Should the snapshot archive at `newest_archive_slot` be extracted, given that the
+    /// newest snapshot state already on disk is at `newest_on_disk_slot`?
+    ///
+    /// `newest_on_disk_slot` is `None` when there is no snapshot state on disk at all, in
+    /// which case the archive must be extracted regardless of mode (`Never` is the one
+    /// exception, since it never falls back to archives).
+    pub fn should_extract_archive(
+        &self,
+        newest_archive_slot: Slot,
+        newest_on_disk_slot: Option<Slot>,
+    ) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::WhenNewer => match newest_on_disk_slot {
+                Some(newest_on_disk_slot) => newest_archive_slot > newest_on_disk_slot,
+                None => true,
+            },
+        }
+    }
 }
 
 pub mod cli {
@@ -44,7 +73,10 @@ pub mod cli {
         and will only use snapshot-related state already on disk. \
         If there is no state already on disk, startup will fail. \
         Note, this will use the latest state available, \
-        which may be newer than the latest snapshot archive."
+        which may be newer than the latest snapshot archive. \
+        \nSpecifying \"when-newer\" will use snapshot archives at startup \
+        only when they are newer than the snapshot-related state already on disk. \
+        If there is no state already on disk, this behaves the same as \"always\"."
     }
     pub const fn possible_values() -> &'static [&'static str] {
         UseSnapshotArchivesAtStartup::VARIANTS
@@ -53,3 +85,34 @@ pub mod cli {
         UseSnapshotArchivesAtStartup::default().into()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_extract_archive_always() {
+        assert!(UseSnapshotArchivesAtStartup::Always.should_extract_archive(100, Some(200)));
+        assert!(UseSnapshotArchivesAtStartup::Always.should_extract_archive(100, Some(50)));
+        assert!(UseSnapshotArchivesAtStartup::Always.should_extract_archive(100, None));
+    }
+
+    #[test]
+    fn test_should_extract_archive_never() {
+        assert!(!UseSnapshotArchivesAtStartup::Never.should_extract_archive(100, Some(200)));
+        assert!(!UseSnapshotArchivesAtStartup::Never.should_extract_archive(100, Some(50)));
+        assert!(!UseSnapshotArchivesAtStartup::Never.should_extract_archive(100, None));
+    }
+
+    #[test]
+    fn test_should_extract_archive_when_newer() {
+        // on-disk state is newer than the archive, so keep what's on disk
+        assert!(!UseSnapshotArchivesAtStartup::WhenNewer.should_extract_archive(100, Some(200)));
+
+        // the archive is newer than what's on disk, so extract it
+        assert!(UseSnapshotArchivesAtStartup::WhenNewer.should_extract_archive(100, Some(50)));
+
+        // no on-disk state at all, so the archive must be extracted
+        assert!(UseSnapshotArchivesAtStartup::WhenNewer.should_extract_archive(100, None));
+    }
+}
@@ -94,3 +94,52 @@ pub fn setup_file_with_default(logfile: &str, filter: &str) {
         .build();
     replace_logger(logger);
 }
+
+// Rebuilds the logger from `filter` and swaps it into the live `LOGGER`.
+// Unlike `setup_with_default`, this is meant to be called on an
+// already-running process to change its verbosity, not just at startup.
+pub fn reload_filter(filter: &str) {
+    setup_with(filter);
+}
+
+// Installs a SIGHUP handler that reloads the log filter from RUST_LOG each
+// time the process receives the signal, so an operator can raise a running
+// validator's log verbosity without a restart: `export RUST_LOG=...; kill
+// -HUP <pid>`. `filter` is only the initial value, used before the first
+// signal arrives.
+pub fn setup_with_signal_reload(filter: &str) {
+    setup_with(filter);
+
+    let mut signals = signal_hook::iterator::Signals::new([signal_hook::consts::SIGHUP])
+        .expect("failed to install SIGHUP handler");
+    std::thread::spawn(move || {
+        for _ in signals.forever() {
+            if let Ok(filter) = std::env::var("RUST_LOG") {
+                reload_filter(&filter);
+            }
+        }
+    });
+}
+
+// Configures logging with one JSON object per line (`timestamp`, `level`,
+// `target`, `line`, `thread_id`, `message`) instead of env_logger's default
+// plaintext format, so logs can be shipped to a structured log pipeline.
+pub fn setup_json(filter: &str) {
+    let logger = env_logger::Builder::from_env(env_logger::Env::new().default_filter_or(filter))
+        .format(|buf, record| {
+            writeln!(
+                buf,
+                "{}",
+                serde_json::json!({
+                    "timestamp": buf.timestamp_nanos().to_string(),
+                    "level": record.level().to_string(),
+                    "target": record.target(),
+                    "line": record.line().unwrap_or(0),
+                    "thread_id": format!("{:?}", std::thread::current().id()),
+                    "message": record.args().to_string(),
+                })
+            )
+        })
+        .build();
+    replace_logger(logger);
+}
@@ -2,23 +2,116 @@ use {
     crate::{boxed_error, ValidatorType},
     k8s_openapi::{
         api::{
-            apps::v1::{ReplicaSet, ReplicaSetSpec},
+            apps::v1::{ReplicaSet, ReplicaSetSpec, StatefulSet, StatefulSetSpec},
             core::v1::{
-                ConfigMap, ConfigMapVolumeSource, Container, EnvVar, EnvVarSource, Namespace,
-                ObjectFieldSelector, PodSpec, PodTemplateSpec, Service, ServicePort, ServiceSpec,
-                Volume, VolumeMount,
+                ConfigMap, ConfigMapVolumeSource, Container, EnvVar, EnvVarSource, ExecAction,
+                Namespace, ObjectFieldSelector, PersistentVolumeClaim, PersistentVolumeClaimSpec,
+                Pod, PodSpec, PodTemplateSpec, Probe, ResourceRequirements, Service, ServicePort,
+                ServiceSpec, TCPSocketAction, Volume, VolumeMount,
             },
         },
-        apimachinery::pkg::apis::meta::v1::LabelSelector,
+        apimachinery::pkg::{
+            api::resource::Quantity, apis::meta::v1::LabelSelector, util::intstr::IntOrString,
+        },
     },
+    bytes::Bytes,
+    futures::{Stream, StreamExt, TryStreamExt},
     kube::{
-        api::{Api, ObjectMeta, PostParams},
+        api::{Api, AttachParams, ListParams, LogParams, ObjectMeta, Patch, PatchParams, PostParams},
+        runtime::watcher,
         Client,
     },
     log::*,
     std::{collections::BTreeMap, error::Error},
+    tokio::io::AsyncReadExt,
 };
 
+// Field manager name used when server-side-applying config so repeated
+// `reload_genesis` calls from this process update the same managed fields
+// instead of fighting another client for ownership.
+const FIELD_MANAGER: &str = "solana-k8s-cluster";
+
+// Ledger and accounts state live on dedicated PersistentVolumeClaims so a
+// rescheduled validator pod reattaches to the same data instead of
+// re-downloading the ledger from scratch.
+const LEDGER_MOUNT_PATH: &str = "/home/solana/ledger";
+const ACCOUNTS_MOUNT_PATH: &str = "/home/solana/accounts";
+
+/// CPU/memory requests and limits for a validator `Container`, so the
+/// scheduler can bin-pack pods instead of overcommitting nodes.
+#[derive(Clone)]
+pub struct ValidatorResources {
+    pub cpu_request: String,
+    pub cpu_limit: String,
+    pub mem_request: String,
+    pub mem_limit: String,
+}
+
+impl Default for ValidatorResources {
+    fn default() -> Self {
+        Self {
+            cpu_request: "2".to_string(),
+            cpu_limit: "4".to_string(),
+            mem_request: "8Gi".to_string(),
+            mem_limit: "16Gi".to_string(),
+        }
+    }
+}
+
+fn resource_requirements(resources: &ValidatorResources) -> ResourceRequirements {
+    let mut requests = BTreeMap::new();
+    requests.insert("cpu".to_string(), Quantity(resources.cpu_request.clone()));
+    requests.insert(
+        "memory".to_string(),
+        Quantity(resources.mem_request.clone()),
+    );
+
+    let mut limits = BTreeMap::new();
+    limits.insert("cpu".to_string(), Quantity(resources.cpu_limit.clone()));
+    limits.insert("memory".to_string(), Quantity(resources.mem_limit.clone()));
+
+    ResourceRequirements {
+        requests: Some(requests),
+        limits: Some(limits),
+        ..Default::default()
+    }
+}
+
+// `getHealth` is a JSON-RPC method, not a plain HTTP resource, so it can't be
+// probed with `httpGet`; shell out to curl with an exec probe instead.
+fn readiness_probe() -> Probe {
+    Probe {
+        exec: Some(ExecAction {
+            command: Some(vec![
+                "curl".to_string(),
+                "-s".to_string(),
+                "-X".to_string(),
+                "POST".to_string(),
+                "-H".to_string(),
+                "Content-Type: application/json".to_string(),
+                "-d".to_string(),
+                r#"{"jsonrpc":"2.0","id":1,"method":"getHealth"}"#.to_string(),
+                "http://localhost:8899".to_string(),
+            ]),
+        }),
+        initial_delay_seconds: Some(10),
+        period_seconds: Some(10),
+        ..Default::default()
+    }
+}
+
+fn liveness_probe() -> Probe {
+    Probe {
+        tcp_socket: Some(TCPSocketAction {
+            port: IntOrString::Int(8001), // Gossip Port
+            ..Default::default()
+        }),
+        initial_delay_seconds: Some(15),
+        period_seconds: Some(15),
+        ..Default::default()
+    }
+}
+
 pub struct Kubernetes<'a> {
     client: Client,
     namespace: &'a str,
@@ -56,6 +149,84 @@ impl<'a> Kubernetes<'a> {
         api.create(&PostParams::default(), &config_map).await
     }
 
+    /// Pushes a new genesis (or validator args) to the `genesis-config`
+    /// ConfigMap on a running cluster. Unlike `create_config_map`, this uses
+    /// a server-side apply patch rather than `create`, so it succeeds
+    /// whether or not the ConfigMap already exists and never fights other
+    /// field managers for ownership of unrelated fields.
+    pub async fn reload_genesis(&self, base64_content: String) -> Result<ConfigMap, kube::Error> {
+        let mut data = BTreeMap::<String, String>::new();
+        data.insert("genesis.bin".to_string(), base64_content);
+        let config_map = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("genesis-config".to_string()),
+                ..Default::default()
+            },
+            data: Some(data),
+            ..Default::default()
+        };
+
+        let api: Api<ConfigMap> = Api::namespaced(self.client.clone(), self.namespace);
+        api.patch(
+            "genesis-config",
+            &PatchParams::apply(FIELD_MANAGER),
+            &Patch::Apply(&config_map),
+        )
+        .await
+    }
+
+    /// Watches the `name` ConfigMap and, for every version the watch delivers
+    /// (including its current state once when the watch starts, not only
+    /// later modifications), rolls the bootstrap and standard validator
+    /// StatefulSets so their pods
+    /// pick up the new genesis/config. The restart is driven by patching
+    /// each StatefulSet's pod template annotations with the ConfigMap's new
+    /// `resourceVersion`: Kubernetes treats a pod template change as needing
+    /// a rolling replacement, so this alone is enough to trigger one without
+    /// deleting pods by hand.
+    pub async fn watch_config_map(&self, name: &str) -> Result<(), Box<dyn Error>> {
+        let api: Api<ConfigMap> = Api::namespaced(self.client.clone(), self.namespace);
+        let mut events = watcher(api, watcher::Config::default().fields(&format!("metadata.name={name}")))
+            .applied_objects()
+            .boxed();
+
+        while let Some(config_map) = events.try_next().await? {
+            let resource_version = config_map.metadata.resource_version.unwrap_or_default();
+            info!("{} changed, resource_version={}", name, resource_version);
+            self.restart_statefulset_for_config_change("bootstrap-validator-statefulset", &resource_version)
+                .await?;
+            self.restart_statefulset_for_config_change("validator-statefulset", &resource_version)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn restart_statefulset_for_config_change(
+        &self,
+        statefulset_name: &str,
+        resource_version: &str,
+    ) -> Result<(), kube::Error> {
+        let api: Api<StatefulSet> = Api::namespaced(self.client.clone(), self.namespace);
+        let patch = serde_json::json!({
+            "spec": {
+                "template": {
+                    "metadata": {
+                        "annotations": {
+                            "solana.com/genesis-config-version": resource_version,
+                        }
+                    }
+                }
+            }
+        });
+        api.patch(
+            statefulset_name,
+            &PatchParams::default(),
+            &Patch::Strategic(patch),
+        )
+        .await?;
+        Ok(())
+    }
+
     pub async fn namespace_exists(&self) -> Result<bool, kube::Error> {
         let namespaces: Api<Namespace> = Api::all(self.client.clone());
         let namespace_list = namespaces.list(&Default::default()).await?;
@@ -101,6 +272,7 @@ impl<'a> Kubernetes<'a> {
         image_name: &str,
         num_bootstrap_validators: i32,
         config_map_name: Option<String>,
+        resources: &ValidatorResources,
     ) -> Result<ReplicaSet, Box<dyn Error>> {
         let env_var = vec![EnvVar {
             name: "MY_POD_IP".to_string(),
@@ -126,6 +298,7 @@ impl<'a> Kubernetes<'a> {
             env_var,
             &command,
             config_map_name,
+            resources,
         )
     }
 
@@ -139,6 +312,7 @@ impl<'a> Kubernetes<'a> {
         env_vars: Vec<EnvVar>,
         command: &Vec<String>,
         config_map_name: Option<String>,
+        resources: &ValidatorResources,
     ) -> Result<ReplicaSet, Box<dyn Error>> {
         let config_map_name = match config_map_name {
             Some(name) => name,
@@ -174,6 +348,9 @@ impl<'a> Kubernetes<'a> {
                     env: Some(env_vars),
                     command: Some(command.clone()),
                     volume_mounts: Some(vec![volume_mount]),
+                    resources: Some(resource_requirements(resources)),
+                    readiness_probe: Some(readiness_probe()),
+                    liveness_probe: Some(liveness_probe()),
                     ..Default::default()
                 }],
                 volumes: Some(vec![volume]),
@@ -203,6 +380,187 @@ impl<'a> Kubernetes<'a> {
         })
     }
 
+    pub fn create_bootstrap_validator_statefulset(
+        &self,
+        container_name: &str,
+        image_name: &str,
+        config_map_name: Option<String>,
+    ) -> Result<StatefulSet, Box<dyn Error>> {
+        let env_var = vec![EnvVar {
+            name: "MY_POD_IP".to_string(),
+            value_from: Some(EnvVarSource {
+                field_ref: Some(ObjectFieldSelector {
+                    field_path: "status.podIP".to_string(),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }];
+
+        // let command = vec!["/workspace/start-bootstrap-validator.sh".to_string()];
+        let command = vec!["sleep".to_string(), "3600".to_string()];
+
+        self.create_statefulset(
+            "bootstrap-validator",
+            &self.bootstrap_validator_selector,
+            container_name,
+            image_name,
+            1,
+            env_var,
+            &command,
+            config_map_name,
+        )
+    }
+
+    /// Builds an `apps/v1 StatefulSet` for `app_name`, the persistent-state
+    /// counterpart to `create_replicas_set`. `serviceName` binds the
+    /// StatefulSet to the headless `Service` from `create_service`, which is
+    /// what gives each pod its stable `<pod-name>.<service-name>` DNS
+    /// identity across restarts; `volume_claim_templates` gives every pod
+    /// its own ledger and accounts volume that survives rescheduling.
+    fn create_statefulset(
+        &self,
+        app_name: &str,
+        label_selector: &BTreeMap<String, String>,
+        container_name: &str,
+        image_name: &str,
+        num_validators: i32,
+        env_vars: Vec<EnvVar>,
+        command: &Vec<String>,
+        config_map_name: Option<String>,
+    ) -> Result<StatefulSet, Box<dyn Error>> {
+        let config_map_name = match config_map_name {
+            Some(name) => name,
+            None => return Err(boxed_error!("config_map_name is None!")),
+        };
+
+        let volume = Volume {
+            name: "genesis-config-volume".into(),
+            config_map: Some(ConfigMapVolumeSource {
+                name: Some(config_map_name.clone()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let volume_mount = VolumeMount {
+            name: "genesis-config-volume".to_string(),
+            mount_path: "/home/solana/genesis".to_string(),
+            ..Default::default()
+        };
+
+        let ledger_volume_mount = VolumeMount {
+            name: "ledger-volume".to_string(),
+            mount_path: LEDGER_MOUNT_PATH.to_string(),
+            ..Default::default()
+        };
+
+        let accounts_volume_mount = VolumeMount {
+            name: "accounts-volume".to_string(),
+            mount_path: ACCOUNTS_MOUNT_PATH.to_string(),
+            ..Default::default()
+        };
+
+        // Define the pod spec
+        let pod_spec = PodTemplateSpec {
+            metadata: Some(ObjectMeta {
+                labels: Some(label_selector.clone()),
+                ..Default::default()
+            }),
+            spec: Some(PodSpec {
+                containers: vec![Container {
+                    name: container_name.to_string(),
+                    image: Some(image_name.to_string()),
+                    image_pull_policy: Some("Never".to_string()), // Set the image pull policy to "Never"
+                    env: Some(env_vars),
+                    command: Some(command.clone()),
+                    volume_mounts: Some(vec![
+                        volume_mount,
+                        ledger_volume_mount,
+                        accounts_volume_mount,
+                    ]),
+                    ..Default::default()
+                }],
+                volumes: Some(vec![volume]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let statefulset_spec = StatefulSetSpec {
+            service_name: format!("{}-service", app_name),
+            replicas: Some(num_validators),
+            selector: LabelSelector {
+                match_labels: Some(label_selector.clone()),
+                ..Default::default()
+            },
+            template: pod_spec,
+            volume_claim_templates: Some(vec![
+                Self::persistent_volume_claim("ledger-volume"),
+                Self::persistent_volume_claim("accounts-volume"),
+            ]),
+            ..Default::default()
+        };
+
+        Ok(StatefulSet {
+            metadata: ObjectMeta {
+                name: Some(format!("{}-statefulset", app_name)),
+                namespace: Some(self.namespace.to_string()),
+                ..Default::default()
+            },
+            spec: Some(statefulset_spec),
+            ..Default::default()
+        })
+    }
+
+    fn persistent_volume_claim(name: &str) -> PersistentVolumeClaim {
+        let mut requests = BTreeMap::new();
+        requests.insert("storage".to_string(), Quantity("500Gi".to_string()));
+
+        PersistentVolumeClaim {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                ..Default::default()
+            },
+            spec: Some(PersistentVolumeClaimSpec {
+                access_modes: Some(vec!["ReadWriteOnce".to_string()]),
+                resources: Some(ResourceRequirements {
+                    requests: Some(requests),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    pub async fn deploy_statefulset(
+        &self,
+        statefulset: &StatefulSet,
+    ) -> Result<StatefulSet, kube::Error> {
+        let api: Api<StatefulSet> = Api::namespaced(self.client.clone(), self.namespace);
+        let post_params = PostParams::default();
+        info!("creating statefulset!");
+        // Apply the StatefulSet
+        api.create(&post_params, statefulset).await
+    }
+
+    pub async fn check_statefulset_ready(&self, statefulset_name: &str) -> Result<bool, kube::Error> {
+        let statefulsets: Api<StatefulSet> = Api::namespaced(self.client.clone(), self.namespace);
+        let statefulset = statefulsets.get(statefulset_name).await?;
+
+        let desired_validators = statefulset.spec.as_ref().unwrap().replicas.unwrap_or(1);
+        let ready_validators = statefulset
+            .status
+            .as_ref()
+            .unwrap()
+            .ready_replicas
+            .unwrap_or(0);
+
+        Ok(ready_validators >= desired_validators)
+    }
+
     pub async fn deploy_replicas_set(
         &self,
         replica_set: &ReplicaSet,
@@ -286,6 +644,7 @@ impl<'a> Kubernetes<'a> {
         image_name: &str,
         num_validators: i32,
         config_map_name: Option<String>,
+        resources: &ValidatorResources,
     ) -> Result<ReplicaSet, Box<dyn Error>> {
         let env_vars = vec![
             EnvVar {
@@ -334,6 +693,64 @@ impl<'a> Kubernetes<'a> {
             env_vars,
             &command,
             config_map_name,
+            resources,
+        )
+    }
+
+    pub fn create_validator_statefulset(
+        &self,
+        container_name: &str,
+        image_name: &str,
+        num_validators: i32,
+        config_map_name: Option<String>,
+    ) -> Result<StatefulSet, Box<dyn Error>> {
+        let env_vars = vec![
+            EnvVar {
+                name: "NAMESPACE".to_string(),
+                value_from: Some(EnvVarSource {
+                    field_ref: Some(ObjectFieldSelector {
+                        field_path: "metadata.namespace".to_string(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "BOOTSTRAP_RPC_PORT".to_string(),
+                value: Some(format!(
+                    "bootstrap-validator-service.$(NAMESPACE).svc.cluster.local:8899"
+                )),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "BOOTSTRAP_GOSSIP_PORT".to_string(),
+                value: Some(format!(
+                    "bootstrap-validator-service.$(NAMESPACE).svc.cluster.local:8001"
+                )),
+                ..Default::default()
+            },
+            EnvVar {
+                name: "BOOTSTRAP_FAUCET_PORT".to_string(),
+                value: Some(format!(
+                    "bootstrap-validator-service.$(NAMESPACE).svc.cluster.local:9900"
+                )),
+                ..Default::default()
+            },
+        ];
+
+        // let command = vec!["/workspace/start-validator.sh".to_string()];
+        let command = vec!["sleep".to_string(), "3600".to_string()];
+
+        self.create_statefulset(
+            "validator",
+            &self.standard_validator_selector,
+            container_name,
+            image_name,
+            num_validators,
+            env_vars,
+            &command,
+            config_map_name,
         )
     }
 
@@ -392,4 +809,64 @@ impl<'a> Kubernetes<'a> {
 
         Ok(())
     }
+
+    /// Lists every pod belonging to `validator_type`, selected via the same
+    /// label map `create_selector` populated for that type. Lets a caller
+    /// fan out log tailing or exec across every replica of a workload
+    /// instead of addressing one pod name at a time.
+    pub async fn list_pods_for(&self, validator_type: &ValidatorType) -> Result<Vec<Pod>, kube::Error> {
+        let label_selector = match *validator_type {
+            ValidatorType::Bootstrap => &self.bootstrap_validator_selector,
+            ValidatorType::Standard => &self.standard_validator_selector,
+        };
+        let selector_string = label_selector
+            .iter()
+            .map(|(key, value)| format!("{key}={value}"))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), self.namespace);
+        let pod_list = pods
+            .list(&ListParams::default().labels(&selector_string))
+            .await?;
+        Ok(pod_list.items)
+    }
+
+    /// Tails `pod_name`'s logs, optionally following new output as it's
+    /// written. Callers drive the returned stream themselves, e.g. to print
+    /// chunks as they arrive while debugging a live validator.
+    pub async fn stream_pod_logs(
+        &self,
+        pod_name: &str,
+        follow: bool,
+    ) -> Result<impl Stream<Item = Result<Bytes, kube::Error>>, kube::Error> {
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), self.namespace);
+        let log_params = LogParams {
+            follow,
+            ..Default::default()
+        };
+        pods.log_stream(pod_name, &log_params).await
+    }
+
+    /// Runs `command` inside `pod_name`'s container and returns its combined
+    /// stdout, e.g. for pulling a validator's current ledger tip without
+    /// shelling in by hand.
+    pub async fn exec_in_pod(
+        &self,
+        pod_name: &str,
+        command: &[String],
+    ) -> Result<String, Box<dyn Error>> {
+        let pods: Api<Pod> = Api::namespaced(self.client.clone(), self.namespace);
+        let attach_params = AttachParams::default().stdout(true).stderr(false);
+        let mut attached = pods.exec(pod_name, command, &attach_params).await?;
+
+        let mut stdout = attached
+            .stdout()
+            .ok_or_else(|| boxed_error!("exec produced no stdout stream"))?;
+        let mut output = String::new();
+        stdout.read_to_string(&mut output).await?;
+        attached.join().await?;
+
+        Ok(output)
+    }
 }
@@ -4,15 +4,27 @@ use {
         borsh::try_from_slice_unchecked,
         compute_budget::{self, ComputeBudgetInstruction},
         entrypoint::HEAP_LENGTH as MIN_HEAP_FRAME_BYTES,
+        feature_set::FeatureSet,
         instruction::{CompiledInstruction, InstructionError},
         pubkey::Pubkey,
         transaction::TransactionError,
     },
 };
 
+/// Feature gate selecting the cheaper, post-activation curve25519 cost
+/// schedule in [`ComputeBudgetCosts::with_feature_set`]. Kept local to this
+/// module so repricing a syscall is a one-line data change here rather than a
+/// change to `process_instructions` itself.
+pub mod reduce_curve25519_syscall_costs {
+    solana_sdk::declare_id!("4Ki9DYhxcqjDXywkDacfZodEi7XEEn4sHWct2HEm47mk");
+}
+
 pub const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
 pub const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
 const MAX_HEAP_FRAME_BYTES: u32 = 256 * 1024;
+/// Default and max number of account data bytes a transaction is allowed to
+/// load across all of its instructions.
+pub const MAX_LOADED_ACCOUNTS_DATA_SIZE_BYTES: usize = 64 * 1024 * 1024;
 
 #[cfg(RUSTC_WITH_SPECIALIZATION)]
 impl ::solana_frozen_abi::abi_example::AbiExample for ComputeBudget {
@@ -28,6 +40,42 @@ pub struct ComputeBudget {
     /// allowed to consume. Compute units are consumed by program execution,
     /// resources they use, etc...
     pub compute_unit_limit: u64,
+    /// Maximum cross-program invocation depth allowed
+    pub max_invoke_depth: usize,
+    /// Maximum BPF to BPF call depth
+    pub max_call_depth: usize,
+    /// Size of a stack frame in bytes, must match the size specified in the LLVM BPF backend
+    pub stack_frame_size: usize,
+    /// Maximum cross-program invocation instruction size
+    pub max_cpi_instruction_size: usize,
+    /// Optional program heap region size, if `None` then loader default
+    pub heap_size: Option<usize>,
+    /// Maximum number of account data bytes this transaction may load across
+    /// all of its instructions, via `ComputeBudgetInstruction::SetLoadedAccountsDataSizeLimit`.
+    pub loaded_accounts_data_size_limit: usize,
+    /// Per-syscall compute unit costs, priced separately so a cluster-coordinated
+    /// feature activation can reprice them without a code change.
+    pub costs: ComputeBudgetCosts,
+    /// Per-instruction compute-unit allowance, indexed by instruction index within
+    /// the transaction, set via `ComputeBudgetInstruction::SetInstructionComputeBudgets`.
+    /// `None` when the transaction never set per-instruction budgets, in which case
+    /// only `compute_unit_limit` applies.
+    pub instruction_compute_unit_limits: Option<Vec<u32>>,
+}
+
+impl Default for ComputeBudget {
+    fn default() -> Self {
+        Self::new(MAX_COMPUTE_UNIT_LIMIT as u64)
+    }
+}
+
+/// Per-syscall compute unit costs used while processing a transaction's
+/// instructions. Grouped separately from [`ComputeBudget`] so an alternate
+/// cost profile (e.g. a cheaper post-activation curve25519 schedule) can be
+/// defined in one place, diffed in tests, and selected by feature gate via
+/// [`ComputeBudgetCosts::with_feature_set`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComputeBudgetCosts {
     /// Number of compute units consumed by a log_u64 call
     pub log_64_units: u64,
     /// Number of compute units consumed by a create_program_address call
@@ -35,22 +83,14 @@ pub struct ComputeBudget {
     /// Number of compute units consumed by an invoke call (not including the cost incurred by
     /// the called program)
     pub invoke_units: u64,
-    /// Maximum cross-program invocation depth allowed
-    pub max_invoke_depth: usize,
     /// Base number of compute units consumed to call SHA256
     pub sha256_base_cost: u64,
     /// Incremental number of units consumed by SHA256 (based on bytes)
     pub sha256_byte_cost: u64,
     /// Maximum number of slices hashed per syscall
     pub sha256_max_slices: u64,
-    /// Maximum BPF to BPF call depth
-    pub max_call_depth: usize,
-    /// Size of a stack frame in bytes, must match the size specified in the LLVM BPF backend
-    pub stack_frame_size: usize,
     /// Number of compute units consumed by logging a `Pubkey`
     pub log_pubkey_units: u64,
-    /// Maximum cross-program invocation instruction size
-    pub max_cpi_instruction_size: usize,
     /// Number of account data bytes per compute unit charged during a cross-program invocation
     pub cpi_bytes_per_unit: u64,
     /// Base number of compute units consumed to get a sysvar
@@ -87,8 +127,6 @@ pub struct ComputeBudget {
     /// Number of compute units consumed for a multiscalar multiplication (msm) of ristretto points.
     /// The total cost is calculated as `msm_base_cost + (length - 1) * msm_incremental_cost`.
     pub curve25519_ristretto_msm_incremental_cost: u64,
-    /// Optional program heap region size, if `None` then loader default
-    pub heap_size: Option<usize>,
     /// Number of compute units per additional 32k heap above the default (~.5
     /// us per 32k at 15 units/us rounded up)
     pub heap_cost: u64,
@@ -96,28 +134,17 @@ pub struct ComputeBudget {
     pub mem_op_base_cost: u64,
 }
 
-impl Default for ComputeBudget {
+impl Default for ComputeBudgetCosts {
     fn default() -> Self {
-        Self::new(MAX_COMPUTE_UNIT_LIMIT as u64)
-    }
-}
-
-impl ComputeBudget {
-    pub fn new(compute_unit_limit: u64) -> Self {
-        ComputeBudget {
-            compute_unit_limit,
+        ComputeBudgetCosts {
             log_64_units: 100,
             create_program_address_units: 1500,
             invoke_units: 1000,
-            max_invoke_depth: 4,
             sha256_base_cost: 85,
             sha256_byte_cost: 1,
             sha256_max_slices: 20_000,
-            max_call_depth: 64,
-            stack_frame_size: 4_096,
             log_pubkey_units: 100,
-            max_cpi_instruction_size: 1280, // IPv6 Min MTU size
-            cpi_bytes_per_unit: 250,        // ~50MB at 200,000 units
+            cpi_bytes_per_unit: 250, // ~50MB at 200,000 units
             sysvar_base_cost: 100,
             secp256k1_recover_cost: 25_000,
             syscall_base_cost: 100,
@@ -133,11 +160,74 @@ impl ComputeBudget {
             curve25519_ristretto_multiply_cost: 2_208,
             curve25519_ristretto_msm_base_cost: 2303,
             curve25519_ristretto_msm_incremental_cost: 788,
-            heap_size: None,
             heap_cost: 8,
             mem_op_base_cost: 10,
         }
     }
+}
+
+impl ComputeBudgetCosts {
+    /// Selects the cost schedule active for `feature_set`. Repricing a syscall
+    /// becomes a data change here instead of a change to `process_instructions`,
+    /// and lets the runtime validate historical blocks under whichever cost
+    /// schedule was active at that slot.
+    pub fn with_feature_set(feature_set: &FeatureSet) -> Self {
+        let mut costs = Self::default();
+        if feature_set.is_active(&reduce_curve25519_syscall_costs::id()) {
+            costs.curve25519_edwards_validate_point_cost = 80;
+            costs.curve25519_edwards_add_cost = 240;
+            costs.curve25519_edwards_subtract_cost = 240;
+            costs.curve25519_edwards_multiply_cost = 1_100;
+            costs.curve25519_edwards_msm_base_cost = 1_150;
+            costs.curve25519_edwards_msm_incremental_cost = 380;
+            costs.curve25519_ristretto_validate_point_cost = 85;
+            costs.curve25519_ristretto_add_cost = 260;
+            costs.curve25519_ristretto_subtract_cost = 260;
+            costs.curve25519_ristretto_multiply_cost = 1_120;
+            costs.curve25519_ristretto_msm_base_cost = 1_170;
+            costs.curve25519_ristretto_msm_incremental_cost = 395;
+        }
+        costs
+    }
+}
+
+/// The result of parsing a transaction's `ComputeBudgetInstruction`s, independent of
+/// any particular `ComputeBudget` instance. Each field is `None`/unset only when the
+/// caller is meant to keep whatever default or prior value applies; `compute_unit_limit`
+/// and `loaded_accounts_data_size_limit` are always resolved and clamped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ComputeBudgetLimits {
+    pub updated_heap_bytes: Option<u32>,
+    pub compute_unit_limit: u32,
+    pub prioritization_fee: Option<PrioritizationFeeType>,
+    pub loaded_accounts_data_size_limit: usize,
+    pub instruction_compute_unit_limits: Option<Vec<u32>>,
+}
+
+impl ComputeBudget {
+    pub fn new(compute_unit_limit: u64) -> Self {
+        ComputeBudget {
+            compute_unit_limit,
+            max_invoke_depth: 4,
+            max_call_depth: 64,
+            stack_frame_size: 4_096,
+            max_cpi_instruction_size: 1280, // IPv6 Min MTU size
+            heap_size: None,
+            loaded_accounts_data_size_limit: MAX_LOADED_ACCOUNTS_DATA_SIZE_BYTES,
+            costs: ComputeBudgetCosts::default(),
+            instruction_compute_unit_limits: None,
+        }
+    }
+
+    /// Builds a `ComputeBudget` whose per-syscall costs reflect whichever
+    /// cost schedule is active for `feature_set`, so the runtime can validate
+    /// historical blocks under the cost schedule that was active at that slot.
+    pub fn with_feature_set(compute_unit_limit: u64, feature_set: &FeatureSet) -> Self {
+        ComputeBudget {
+            costs: ComputeBudgetCosts::with_feature_set(feature_set),
+            ..Self::new(compute_unit_limit)
+        }
+    }
 
     pub fn process_instructions<'a>(
         &mut self,
@@ -145,11 +235,87 @@ impl ComputeBudget {
         default_units_per_instruction: bool,
         support_set_compute_unit_price_ix: bool,
         enable_request_heap_frame_ix: bool,
+        support_set_loaded_accounts_data_size_limit_ix: bool,
+        support_set_instruction_compute_budgets_ix: bool,
     ) -> Result<PrioritizationFeeDetails, TransactionError> {
+        let ComputeBudgetLimits {
+            updated_heap_bytes,
+            compute_unit_limit,
+            prioritization_fee,
+            loaded_accounts_data_size_limit,
+            instruction_compute_unit_limits,
+        } = Self::get_compute_budget_limits(
+            instructions,
+            default_units_per_instruction,
+            support_set_compute_unit_price_ix,
+            enable_request_heap_frame_ix,
+            support_set_loaded_accounts_data_size_limit_ix,
+            support_set_instruction_compute_budgets_ix,
+        )?;
+
+        if let Some(updated_heap_bytes) = updated_heap_bytes {
+            self.heap_size = Some(updated_heap_bytes as usize);
+        }
+        self.compute_unit_limit = compute_unit_limit as u64;
+        self.loaded_accounts_data_size_limit = loaded_accounts_data_size_limit;
+        self.instruction_compute_unit_limits = instruction_compute_unit_limits;
+
+        Ok(prioritization_fee
+            .map(|fee_type| PrioritizationFeeDetails::new(fee_type, self.compute_unit_limit))
+            .unwrap_or_default())
+    }
+
+    /// Caps `fee_type` so the `PrioritizationFeeDetails` it produces at
+    /// `compute_unit_limit` never exceeds `max_fee_lamports`, clamping the
+    /// effective per-CU price downward rather than rejecting the transaction.
+    /// Protects a payer who requested a high price from owing more than
+    /// intended once the final compute-unit limit is resolved.
+    fn cap_prioritization_fee(
+        fee_type: PrioritizationFeeType,
+        compute_unit_limit: u64,
+        max_fee_lamports: u64,
+    ) -> PrioritizationFeeType {
+        match fee_type {
+            PrioritizationFeeType::Deprecated(additional_fee) => {
+                PrioritizationFeeType::Deprecated(additional_fee.min(max_fee_lamports))
+            }
+            PrioritizationFeeType::ComputeUnitPrice(micro_lamports) => {
+                if compute_unit_limit == 0 {
+                    return PrioritizationFeeType::ComputeUnitPrice(micro_lamports);
+                }
+                let uncapped_fee = (micro_lamports as u128)
+                    .saturating_mul(compute_unit_limit as u128)
+                    .saturating_div(1_000_000);
+                if uncapped_fee <= max_fee_lamports as u128 {
+                    return PrioritizationFeeType::ComputeUnitPrice(micro_lamports);
+                }
+                let capped_price = (max_fee_lamports as u128)
+                    .saturating_mul(1_000_000)
+                    .saturating_div(compute_unit_limit as u128);
+                PrioritizationFeeType::ComputeUnitPrice(capped_price as u64)
+            }
+        }
+    }
+
+    /// Parses a transaction's `ComputeBudgetInstruction`s into a
+    /// `ComputeBudgetLimits`, with no side effects on any `ComputeBudget`.
+    /// Fee estimators, banking-stage simulation, and RPC preflight can reuse
+    /// this parse without constructing a full `ComputeBudget`.
+    pub fn get_compute_budget_limits<'a>(
+        instructions: impl Iterator<Item = (&'a Pubkey, &'a CompiledInstruction)>,
+        default_units_per_instruction: bool,
+        support_set_compute_unit_price_ix: bool,
+        enable_request_heap_frame_ix: bool,
+        support_set_loaded_accounts_data_size_limit_ix: bool,
+        support_set_instruction_compute_budgets_ix: bool,
+    ) -> Result<ComputeBudgetLimits, TransactionError> {
         let mut num_non_compute_budget_instructions: usize = 0;
         let mut updated_compute_unit_limit = None;
+        let mut updated_loaded_accounts_data_size_limit = None;
         let mut requested_heap_size = None;
         let mut prioritization_fee = None;
+        let mut max_prioritization_fee = None;
+        let mut instruction_compute_unit_limits: Option<(Vec<u32>, u8)> = None;
 
         for (i, (program_id, instruction)) in instructions.enumerate() {
             if compute_budget::check_id(program_id) {
@@ -195,6 +361,30 @@ impl ComputeBudget {
                             prioritization_fee =
                                 Some(PrioritizationFeeType::ComputeUnitPrice(micro_lamports));
                         }
+                        Ok(ComputeBudgetInstruction::SetLoadedAccountsDataSizeLimit(bytes)) => {
+                            if !support_set_loaded_accounts_data_size_limit_ix {
+                                return Err(invalid_instruction_data_error);
+                            }
+                            if updated_loaded_accounts_data_size_limit.is_some() {
+                                return Err(duplicate_instruction_error);
+                            }
+                            updated_loaded_accounts_data_size_limit = Some(bytes as usize);
+                        }
+                        Ok(ComputeBudgetInstruction::SetMaxPrioritizationFee(lamports)) => {
+                            if max_prioritization_fee.is_some() {
+                                return Err(duplicate_instruction_error);
+                            }
+                            max_prioritization_fee = Some(lamports);
+                        }
+                        Ok(ComputeBudgetInstruction::SetInstructionComputeBudgets(budgets)) => {
+                            if !support_set_instruction_compute_budgets_ix {
+                                return Err(invalid_instruction_data_error);
+                            }
+                            if instruction_compute_unit_limits.is_some() {
+                                return Err(duplicate_instruction_error);
+                            }
+                            instruction_compute_unit_limits = Some((budgets, i as u8));
+                        }
                         _ => return Err(invalid_instruction_data_error),
                     }
                 } else if i < 3 {
@@ -225,6 +415,7 @@ impl ComputeBudget {
             }
         }
 
+        let mut updated_heap_bytes = None;
         if let Some((bytes, i)) = requested_heap_size {
             if !enable_request_heap_frame_ix
                 || bytes > MAX_HEAP_FRAME_BYTES
@@ -236,10 +427,10 @@ impl ComputeBudget {
                     InstructionError::InvalidInstructionData,
                 ));
             }
-            self.heap_size = Some(bytes as usize);
+            updated_heap_bytes = Some(bytes);
         }
 
-        self.compute_unit_limit = if default_units_per_instruction {
+        let compute_unit_limit = if default_units_per_instruction {
             updated_compute_unit_limit.or_else(|| {
                 Some(
                     (num_non_compute_budget_instructions as u32)
@@ -250,19 +441,129 @@ impl ComputeBudget {
             updated_compute_unit_limit
         }
         .unwrap_or(MAX_COMPUTE_UNIT_LIMIT)
-        .min(MAX_COMPUTE_UNIT_LIMIT) as u64;
+        .min(MAX_COMPUTE_UNIT_LIMIT);
 
-        Ok(prioritization_fee
-            .map(|fee_type| PrioritizationFeeDetails::new(fee_type, self.compute_unit_limit))
-            .unwrap_or_default())
+        let loaded_accounts_data_size_limit = updated_loaded_accounts_data_size_limit
+            .unwrap_or(MAX_LOADED_ACCOUNTS_DATA_SIZE_BYTES)
+            .min(MAX_LOADED_ACCOUNTS_DATA_SIZE_BYTES);
+
+        let prioritization_fee = if let Some(max_fee_lamports) = max_prioritization_fee {
+            prioritization_fee.map(|fee_type| {
+                Self::cap_prioritization_fee(fee_type, compute_unit_limit as u64, max_fee_lamports)
+            })
+        } else {
+            prioritization_fee
+        };
+
+        let instruction_compute_unit_limits =
+            if let Some((budgets, i)) = instruction_compute_unit_limits {
+                let per_instruction_sum: u64 = budgets.iter().map(|&units| units as u64).sum();
+                if per_instruction_sum > compute_unit_limit as u64 {
+                    return Err(TransactionError::InstructionError(
+                        i,
+                        InstructionError::InvalidInstructionData,
+                    ));
+                }
+                Some(budgets)
+            } else {
+                None
+            };
+
+        Ok(ComputeBudgetLimits {
+            updated_heap_bytes,
+            compute_unit_limit,
+            prioritization_fee,
+            loaded_accounts_data_size_limit,
+            instruction_compute_unit_limits,
+        })
+    }
+
+    /// Checks `consumed_units` spent executing the instruction at `instruction_index`
+    /// against the per-instruction allowance set via
+    /// `ComputeBudgetInstruction::set_instruction_compute_budgets`. Instructions with
+    /// no declared allowance, and transactions that never set per-instruction
+    /// budgets, are unaffected; only the transaction-wide `compute_unit_limit`
+    /// applies to them. Lets one runaway CPI be rejected without waiting for it to
+    /// exhaust the whole transaction's compute budget.
+    pub fn check_instruction_compute_units(
+        &self,
+        instruction_index: u8,
+        consumed_units: u64,
+    ) -> Result<(), TransactionError> {
+        let Some(limit) = self
+            .instruction_compute_unit_limits
+            .as_ref()
+            .and_then(|limits| limits.get(instruction_index as usize))
+        else {
+            return Ok(());
+        };
+
+        if consumed_units > *limit as u64 {
+            return Err(TransactionError::InstructionComputeBudgetExceeded(
+                instruction_index,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Estimates a tight but safe `compute_unit_limit` from the units each
+    /// instruction actually consumed during a dry-run/simulation, rounding the
+    /// summed total up by `safety_margin` (e.g. `1.1` for a 10% margin; values
+    /// below `1.0` are treated as `1.0`, and the margin is taken to four decimal
+    /// places so the product is computed exactly) and clamping to
+    /// `MAX_COMPUTE_UNIT_LIMIT`. The estimate is never rounded below one
+    /// `syscall_base_cost` per instruction, so a simulation that reports zero
+    /// consumption for every instruction still yields a usable floor.
+    /// `compute_unit_price` is the micro-lamports-per-CU rate the caller
+    /// intends to pay, used to report the `PrioritizationFeeDetails` that rate
+    /// implies at the estimated limit.
+    pub fn estimate_from_execution(
+        &self,
+        consumed_units_per_ix: &[u64],
+        compute_unit_price: u64,
+        safety_margin: f64,
+    ) -> ComputeUnitEstimate {
+        let consumed_units: u64 = consumed_units_per_ix.iter().sum();
+        let floor =
+            (consumed_units_per_ix.len() as u64).saturating_mul(self.costs.syscall_base_cost);
+
+        let margin_basis_points = (safety_margin.max(1.0) * 10_000.0).round() as u128;
+        let compute_unit_limit = (consumed_units as u128)
+            .saturating_mul(margin_basis_points)
+            .saturating_add(9_999)
+            / 10_000;
+        let compute_unit_limit = (compute_unit_limit.min(u64::MAX as u128) as u64)
+            .max(floor)
+            .min(MAX_COMPUTE_UNIT_LIMIT as u64);
+
+        let prioritization_fee_details = PrioritizationFeeDetails::new(
+            PrioritizationFeeType::ComputeUnitPrice(compute_unit_price),
+            compute_unit_limit,
+        );
+
+        ComputeUnitEstimate {
+            compute_unit_limit,
+            prioritization_fee_details,
+        }
     }
 }
 
+/// The recommended `compute_unit_limit` produced by
+/// [`ComputeBudget::estimate_from_execution`], along with the
+/// `PrioritizationFeeDetails` that limit implies at the requested
+/// compute-unit price.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComputeUnitEstimate {
+    pub compute_unit_limit: u64,
+    pub prioritization_fee_details: PrioritizationFeeDetails,
+}
+
 #[cfg(test)]
 mod tests {
     use {
         super::*,
         solana_sdk::{
+            feature_set::FeatureSet,
             hash::Hash,
             instruction::Instruction,
             message::Message,
@@ -286,7 +587,7 @@ mod tests {
     }
 
     macro_rules! test {
-        ( $instructions: expr, $expected_result: expr, $expected_budget: expr, $type_change: expr, $enable_request_heap_frame_ix: expr) => {
+        ( $instructions: expr, $expected_result: expr, $expected_budget: expr, $support_set_compute_unit_price_ix: expr, $enable_request_heap_frame_ix: expr, $support_set_loaded_accounts_data_size_limit_ix: expr, $support_set_instruction_compute_budgets_ix: expr) => {
             let payer_keypair = Keypair::new();
             let tx = SanitizedTransaction::from_transaction_for_tests(Transaction::new(
                 &[&payer_keypair],
@@ -297,18 +598,33 @@ mod tests {
             let result = compute_budget.process_instructions(
                 tx.message().program_instructions_iter(),
                 true,
-                $type_change,
+                $support_set_compute_unit_price_ix,
                 $enable_request_heap_frame_ix,
+                $support_set_loaded_accounts_data_size_limit_ix,
+                $support_set_instruction_compute_budgets_ix,
             );
             assert_eq!($expected_result, result);
             assert_eq!(compute_budget, $expected_budget);
         };
+        ( $instructions: expr, $expected_result: expr, $expected_budget: expr, $support_set_compute_unit_price_ix: expr, $enable_request_heap_frame_ix: expr, $support_set_loaded_accounts_data_size_limit_ix: expr) => {
+            test!(
+                $instructions,
+                $expected_result,
+                $expected_budget,
+                $support_set_compute_unit_price_ix,
+                $enable_request_heap_frame_ix,
+                $support_set_loaded_accounts_data_size_limit_ix,
+                true
+            );
+        };
         ( $instructions: expr, $expected_result: expr, $expected_budget: expr) => {
             test!(
                 $instructions,
                 $expected_result,
                 $expected_budget,
                 true,
+                true,
+                true,
                 true
             );
         };
@@ -385,6 +701,7 @@ mod tests {
                 ..ComputeBudget::default()
             },
             false,
+            true,
             true
         );
 
@@ -400,6 +717,7 @@ mod tests {
                 ..ComputeBudget::default()
             },
             false,
+            true,
             true
         );
 
@@ -429,6 +747,7 @@ mod tests {
                 ..ComputeBudget::default()
             },
             false,
+            true,
             true
         );
 
@@ -562,6 +881,7 @@ mod tests {
             )),
             ComputeBudget::default(),
             false,
+            true,
             true
         );
 
@@ -599,6 +919,7 @@ mod tests {
                 ..ComputeBudget::default()
             },
             false,
+            true,
             true
         );
 
@@ -645,7 +966,8 @@ mod tests {
                 ..ComputeBudget::default()
             },
             true,
-            false
+            false,
+            true
         );
 
         // assert requesting heap frame when feature is disable will result instruction error
@@ -660,7 +982,8 @@ mod tests {
             )),
             ComputeBudget::default(),
             true,
-            false
+            false,
+            true
         );
         test!(
             &[
@@ -673,7 +996,8 @@ mod tests {
             )),
             ComputeBudget::default(),
             true,
-            false
+            false,
+            true
         );
         test!(
             &[
@@ -688,7 +1012,8 @@ mod tests {
             )),
             ComputeBudget::default(),
             true,
-            false
+            false,
+            true
         );
         test!(
             &[
@@ -703,7 +1028,8 @@ mod tests {
             )),
             ComputeBudget::default(),
             true,
-            false
+            false,
+            true
         );
 
         // assert normal results when not requesting heap frame when the feature is disabled
@@ -724,11 +1050,10 @@ mod tests {
                 ..ComputeBudget::default()
             },
             true,
-            false
+            false,
+            true
         );
     }
-<<<<<<< HEAD
-=======
 
     #[test]
     fn test_process_loaded_accounts_data_size_limit_instruction() {
@@ -744,6 +1069,7 @@ mod tests {
                     compute_unit_limit: 0,
                     ..ComputeBudget::default()
                 },
+                true,
                 enable_request_heap_frame_ix,
                 support_set_loaded_accounts_data_size_limit_ix
             );
@@ -783,6 +1109,7 @@ mod tests {
                 ],
                 expected_result,
                 expected_budget,
+                true,
                 enable_request_heap_frame_ix,
                 support_set_loaded_accounts_data_size_limit_ix
             );
@@ -822,6 +1149,7 @@ mod tests {
                 ],
                 expected_result,
                 expected_budget,
+                true,
                 enable_request_heap_frame_ix,
                 support_set_loaded_accounts_data_size_limit_ix
             );
@@ -850,6 +1178,7 @@ mod tests {
                 ),],
                 expected_result,
                 expected_budget,
+                true,
                 enable_request_heap_frame_ix,
                 support_set_loaded_accounts_data_size_limit_ix
             );
@@ -886,6 +1215,7 @@ mod tests {
                 ],
                 expected_result,
                 expected_budget,
+                true,
                 enable_request_heap_frame_ix,
                 support_set_loaded_accounts_data_size_limit_ix
             );
@@ -914,6 +1244,7 @@ mod tests {
             false, //not support request_units_deprecated
             true,  //enable_request_heap_frame_ix,
             true,  //support_set_loaded_accounts_data_size_limit_ix,
+            true,  //support_set_instruction_compute_budgets_ix,
         );
 
         // assert process_instructions will be successful with default,
@@ -928,5 +1259,252 @@ mod tests {
             }
         );
     }
->>>>>>> c69bc00f69 (cost model could double count builtin instruction cost (#32422))
+
+    #[test]
+    fn test_get_compute_budget_limits_is_side_effect_free() {
+        let payer_keypair = Keypair::new();
+        let tx = SanitizedTransaction::from_transaction_for_tests(Transaction::new(
+            &[&payer_keypair],
+            Message::new(
+                &[
+                    ComputeBudgetInstruction::set_compute_unit_limit(1),
+                    ComputeBudgetInstruction::set_compute_unit_price(42),
+                    ComputeBudgetInstruction::request_heap_frame(40 * 1024),
+                ],
+                Some(&payer_keypair.pubkey()),
+            ),
+            Hash::default(),
+        ));
+
+        let limits = ComputeBudget::get_compute_budget_limits(
+            tx.message().program_instructions_iter(),
+            true,
+            true,
+            true,
+            true,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            limits,
+            ComputeBudgetLimits {
+                updated_heap_bytes: Some(40 * 1024),
+                compute_unit_limit: 1,
+                prioritization_fee: Some(PrioritizationFeeType::ComputeUnitPrice(42)),
+                loaded_accounts_data_size_limit: MAX_LOADED_ACCOUNTS_DATA_SIZE_BYTES,
+                instruction_compute_unit_limits: None,
+            }
+        );
+
+        // parsing the same instructions twice must be deterministic, since the parse
+        // itself never mutates any `ComputeBudget`
+        let limits_again = ComputeBudget::get_compute_budget_limits(
+            tx.message().program_instructions_iter(),
+            true,
+            true,
+            true,
+            true,
+            true,
+        )
+        .unwrap();
+        assert_eq!(limits, limits_again);
+    }
+
+    #[test]
+    fn test_compute_budget_costs_with_feature_set() {
+        let default_costs = ComputeBudgetCosts::default();
+        assert_eq!(
+            ComputeBudgetCosts::with_feature_set(&FeatureSet::default()),
+            default_costs
+        );
+
+        // the repricing gate is declared in this module, not among the sdk's registered
+        // features, so `FeatureSet::new_enabled()` doesn't include it; activate it directly
+        let mut repriced_feature_set = FeatureSet::default();
+        repriced_feature_set.activate(&reduce_curve25519_syscall_costs::id(), 0);
+        let repriced_costs = ComputeBudgetCosts::with_feature_set(&repriced_feature_set);
+        assert_ne!(repriced_costs, default_costs);
+        assert!(
+            repriced_costs.curve25519_edwards_validate_point_cost
+                < default_costs.curve25519_edwards_validate_point_cost
+        );
+        // activating the repricing feature must not touch unrelated costs
+        assert_eq!(
+            repriced_costs.sha256_base_cost,
+            default_costs.sha256_base_cost
+        );
+
+        assert_eq!(
+            ComputeBudget::with_feature_set(MAX_COMPUTE_UNIT_LIMIT as u64, &repriced_feature_set)
+            .costs,
+            repriced_costs
+        );
+    }
+
+    #[test]
+    fn test_estimate_from_execution() {
+        let compute_budget = ComputeBudget::default();
+
+        // rounds the summed consumption up by the safety margin
+        let estimate = compute_budget.estimate_from_execution(&[1_000, 2_000], 5, 1.1);
+        assert_eq!(estimate.compute_unit_limit, 3_300);
+        assert_eq!(
+            estimate.prioritization_fee_details,
+            PrioritizationFeeDetails::new(PrioritizationFeeType::ComputeUnitPrice(5), 3_300)
+        );
+
+        // a safety margin below 1.0 is not allowed to shrink the estimate
+        let estimate = compute_budget.estimate_from_execution(&[1_000, 2_000], 5, 0.5);
+        assert_eq!(estimate.compute_unit_limit, 3_000);
+
+        // never rounds below one syscall_base_cost per instruction
+        let estimate = compute_budget.estimate_from_execution(&[0, 0, 0], 0, 1.0);
+        assert_eq!(
+            estimate.compute_unit_limit,
+            3 * compute_budget.costs.syscall_base_cost
+        );
+
+        // clamps to MAX_COMPUTE_UNIT_LIMIT
+        let estimate =
+            compute_budget.estimate_from_execution(&[MAX_COMPUTE_UNIT_LIMIT as u64], 0, 2.0);
+        assert_eq!(estimate.compute_unit_limit, MAX_COMPUTE_UNIT_LIMIT as u64);
+    }
+
+    #[test]
+    fn test_set_max_prioritization_fee_caps_fee() {
+        // price * compute_unit_limit / 1_000_000 = 1_000 * 1_000 / 1_000_000 = 1 lamport,
+        // well under the 10-lamport ceiling, so the price passes through unchanged
+        test!(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(1_000),
+                ComputeBudgetInstruction::set_compute_unit_price(1_000),
+                ComputeBudgetInstruction::set_max_prioritization_fee(10),
+            ],
+            Ok(PrioritizationFeeDetails::new(
+                PrioritizationFeeType::ComputeUnitPrice(1_000),
+                1_000,
+            )),
+            ComputeBudget {
+                compute_unit_limit: 1_000,
+                ..ComputeBudget::default()
+            }
+        );
+
+        // price * compute_unit_limit / 1_000_000 = 1_000_000 * 1_000 / 1_000_000 = 1_000 lamports,
+        // clamped down to the 10-lamport ceiling by lowering the effective price
+        test!(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(1_000),
+                ComputeBudgetInstruction::set_compute_unit_price(1_000_000),
+                ComputeBudgetInstruction::set_max_prioritization_fee(10),
+            ],
+            Ok(PrioritizationFeeDetails::new(
+                PrioritizationFeeType::ComputeUnitPrice(10_000),
+                1_000,
+            )),
+            ComputeBudget {
+                compute_unit_limit: 1_000,
+                ..ComputeBudget::default()
+            }
+        );
+
+        // specifying the ceiling twice is a duplicate instruction, same as any other
+        // compute budget instruction
+        test!(
+            &[
+                ComputeBudgetInstruction::set_max_prioritization_fee(10),
+                ComputeBudgetInstruction::set_max_prioritization_fee(20),
+            ],
+            Err(TransactionError::DuplicateInstruction(1)),
+            ComputeBudget::default()
+        );
+    }
+
+    #[test]
+    fn test_set_instruction_compute_budgets() {
+        // per-index budgets within the global limit are recorded on the ComputeBudget
+        test!(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(3_000),
+                ComputeBudgetInstruction::set_instruction_compute_budgets(&[1_000, 2_000]),
+                Instruction::new_with_bincode(Pubkey::new_unique(), &0_u8, vec![]),
+                Instruction::new_with_bincode(Pubkey::new_unique(), &0_u8, vec![]),
+            ],
+            Ok(PrioritizationFeeDetails::default()),
+            ComputeBudget {
+                compute_unit_limit: 3_000,
+                instruction_compute_unit_limits: Some(vec![1_000, 2_000]),
+                ..ComputeBudget::default()
+            }
+        );
+
+        // the per-index sum may not exceed the global compute_unit_limit
+        test!(
+            &[
+                ComputeBudgetInstruction::set_compute_unit_limit(1_000),
+                ComputeBudgetInstruction::set_instruction_compute_budgets(&[1_000, 2_000]),
+            ],
+            Err(TransactionError::InstructionError(
+                1,
+                InstructionError::InvalidInstructionData,
+            )),
+            ComputeBudget::default()
+        );
+
+        // specifying it twice is a duplicate instruction, same as any other compute
+        // budget instruction
+        test!(
+            &[
+                ComputeBudgetInstruction::set_instruction_compute_budgets(&[1_000]),
+                ComputeBudgetInstruction::set_instruction_compute_budgets(&[2_000]),
+            ],
+            Err(TransactionError::DuplicateInstruction(1)),
+            ComputeBudget::default()
+        );
+
+        // with support disabled, the instruction is simply invalid
+        test!(
+            &[ComputeBudgetInstruction::set_instruction_compute_budgets(
+                &[1_000]
+            )],
+            Err(TransactionError::InstructionError(
+                0,
+                InstructionError::InvalidInstructionData,
+            )),
+            ComputeBudget::default(),
+            true,
+            true,
+            true,
+            false
+        );
+
+        // an instruction that stays within its own per-index allowance passes
+        let compute_budget = ComputeBudget {
+            instruction_compute_unit_limits: Some(vec![1_000, 2_000]),
+            ..ComputeBudget::default()
+        };
+        assert_eq!(compute_budget.check_instruction_compute_units(0, 1_000), Ok(()));
+        assert_eq!(
+            compute_budget.check_instruction_compute_units(1, 1_999),
+            Ok(())
+        );
+
+        // exceeding the declared per-index allowance is rejected
+        assert_eq!(
+            compute_budget.check_instruction_compute_units(1, 2_001),
+            Err(TransactionError::InstructionComputeBudgetExceeded(1))
+        );
+
+        // an instruction index with no declared allowance is unaffected
+        assert_eq!(
+            compute_budget.check_instruction_compute_units(2, u64::MAX),
+            Ok(())
+        );
+
+        // no per-instruction budgets at all means only the global limit applies
+        assert_eq!(
+            ComputeBudget::default().check_instruction_compute_units(0, u64::MAX),
+            Ok(())
+        );
+    }
 }
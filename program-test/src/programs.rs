@@ -1,5 +1,5 @@
 use solana_sdk::{account::AccountSharedData, pubkey::Pubkey, rent::Rent};
-use std::sync::Arc;
+use std::{io, path::Path, sync::Arc};
 
 mod spl_token {
     solana_sdk::declare_id!("TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA");
@@ -30,20 +30,85 @@ static SPL_PROGRAMS: &[(Pubkey, &[u8])] = &[
     ),
 ];
 
+/// One entry in a `BuiltinProgramSet`: a program's compiled ELF bytes, plus the loader that
+/// should own its account (`bpf_loader` for a plain BPF program, `bpf_loader_upgradeable` for
+/// an upgradeable one).
+struct BuiltinProgram {
+    elf: Vec<u8>,
+    loader: Pubkey,
+}
+
+/// Builds the set of built-in programs a `ProgramTest` validator starts with.
+/// `BuiltinProgramSet::default()` seeds the same four SPL programs `spl_programs` used to
+/// hardcode; call `with_program` or `with_program_from_file` to add more -- e.g. a current
+/// SPL Token-2022 build, or any arbitrary compiled `.so` -- without rebuilding this crate.
+pub struct BuiltinProgramSet {
+    programs: Vec<(Pubkey, BuiltinProgram)>,
+}
+
+impl Default for BuiltinProgramSet {
+    fn default() -> Self {
+        let programs = SPL_PROGRAMS
+            .iter()
+            .map(|(program_id, elf)| {
+                (
+                    *program_id,
+                    BuiltinProgram {
+                        elf: elf.to_vec(),
+                        loader: solana_program::bpf_loader::id(),
+                    },
+                )
+            })
+            .collect();
+        Self { programs }
+    }
+}
+
+impl BuiltinProgramSet {
+    /// Adds `program_id` to this set, owned by `loader` and backed by `elf`'s compiled bytes.
+    /// Replaces any existing entry already registered for `program_id`.
+    pub fn with_program(mut self, program_id: Pubkey, elf: Vec<u8>, loader: Pubkey) -> Self {
+        self.programs.retain(|(id, _)| *id != program_id);
+        self.programs.push((program_id, BuiltinProgram { elf, loader }));
+        self
+    }
+
+    /// Like `with_program`, but reads the ELF bytes from `path` on disk instead of taking them
+    /// compiled-in, so a local test environment can point at a freshly built or downloaded
+    /// `.so` -- e.g. a current SPL Token-2022 -- without recompiling this crate.
+    pub fn with_program_from_file(
+        self,
+        program_id: Pubkey,
+        path: impl AsRef<Path>,
+        loader: Pubkey,
+    ) -> io::Result<Self> {
+        let elf = std::fs::read(path)?;
+        Ok(self.with_program(program_id, elf, loader))
+    }
+
+    /// Finalizes this set into the `(Pubkey, AccountSharedData)` pairs a `ProgramTest`
+    /// validator inserts directly into its genesis accounts, with each program's balance set
+    /// to the rent-exempt minimum for its data length.
+    pub fn build(self, rent: &Rent) -> Vec<(Pubkey, AccountSharedData)> {
+        self.programs
+            .into_iter()
+            .map(|(program_id, program)| {
+                (
+                    program_id,
+                    AccountSharedData {
+                        lamports: rent.minimum_balance(program.elf.len()),
+                        data: Arc::new(program.elf),
+                        owner: program.loader,
+                        executable: true,
+                        rent_epoch: 0,
+                    },
+                )
+            })
+            .collect()
+    }
+}
+
+/// Kept for existing callers: equivalent to `BuiltinProgramSet::default().build(rent)`.
 pub fn spl_programs(rent: &Rent) -> Vec<(Pubkey, AccountSharedData)> {
-    SPL_PROGRAMS
-        .iter()
-        .map(|(program_id, elf)| {
-            (
-                *program_id,
-                AccountSharedData {
-                    lamports: rent.minimum_balance(elf.len()).min(1),
-                    data: Arc::new(elf.to_vec()),
-                    owner: solana_program::bpf_loader::id(),
-                    executable: true,
-                    rent_epoch: 0,
-                },
-            )
-        })
-        .collect()
+    BuiltinProgramSet::default().build(rent)
 }
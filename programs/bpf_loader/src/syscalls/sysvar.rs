@@ -1,4 +1,4 @@
-use {super::*, crate::declare_syscall};
+use {super::*, crate::declare_syscall, solana_sdk::pubkey::Pubkey};
 
 fn get_sysvar<T: std::fmt::Debug + Sysvar + SysvarId + Clone>(
     sysvar: Result<Arc<T>, InstructionError>,
@@ -127,3 +127,74 @@ declare_syscall!(
         )
     }
 );
+
+// Copies `length` bytes starting at `offset` out of the serialized sysvar named by the
+// pubkey at `sysvar_id_addr` into the guest buffer at `var_addr`, so programs can read a
+// slice of a large sysvar (e.g. `SlotHashes`, `StakeHistory`) without the bespoke
+// `SyscallGet*Sysvar`/`get_sysvar` machinery above, and so new sysvars never need one.
+fn get_sysvar_data_slice(
+    sysvar_id_addr: u64,
+    offset: u64,
+    length: u64,
+    var_addr: u64,
+    memory_mapping: &mut MemoryMapping,
+    invoke_context: &mut InvokeContext,
+) -> Result<u64, EbpfError> {
+    invoke_context.get_compute_meter().consume(
+        invoke_context
+            .get_compute_budget()
+            .sysvar_base_cost
+            .saturating_add(length),
+    )?;
+    let check_aligned = invoke_context.get_check_aligned();
+    let sysvar_id = translate_type::<Pubkey>(memory_mapping, sysvar_id_addr, check_aligned)?;
+
+    let sysvar_buf = invoke_context
+        .get_sysvar_cache()
+        .get_sysvar_data(sysvar_id)
+        .ok_or(SyscallError::InstructionError(
+            InstructionError::UnsupportedSysvar,
+        ))?;
+
+    let offset = offset as usize;
+    let length = length as usize;
+    let slice = sysvar_buf
+        .get(offset..offset.saturating_add(length))
+        .ok_or(SyscallError::InstructionError(
+            InstructionError::InvalidArgument,
+        ))?;
+
+    let destination =
+        translate_slice_mut::<u8>(memory_mapping, var_addr, length as u64, check_aligned)?;
+    destination.copy_from_slice(slice);
+
+    Ok(SUCCESS)
+}
+
+declare_syscall!(
+    /// Get `length` bytes of a sysvar's serialized data starting at `offset`, without
+    /// needing a dedicated syscall for the sysvar in question
+    SyscallGetSysvar,
+    fn inner_call(
+        &mut self,
+        sysvar_id_addr: u64,
+        offset: u64,
+        length: u64,
+        var_addr: u64,
+        _arg5: u64,
+        memory_mapping: &mut MemoryMapping,
+    ) -> Result<u64, EbpfError> {
+        let mut invoke_context = self
+            .invoke_context
+            .try_borrow_mut()
+            .map_err(|_| SyscallError::InvokeContextBorrowFailed)?;
+        get_sysvar_data_slice(
+            sysvar_id_addr,
+            offset,
+            length,
+            var_addr,
+            memory_mapping,
+            &mut invoke_context,
+        )
+    }
+);
@@ -5,6 +5,24 @@ use solana_sdk::instruction::InstructionError;
 use solana_sdk::pubkey::Pubkey;
 use solana_sdk::solana_entrypoint;
 
+/// Opcodes `noop` dispatches on, decoded from `data[0]`.
+const OP_NOOP: u8 = 0x00;
+const OP_BURN_COMPUTE: u8 = 0x01;
+const OP_LOG_COUNTS: u8 = 0x02;
+const OP_RETURN_ERROR: u8 = 0x03;
+const OP_SYNC_DATA: u8 = 0x04;
+
+/// `InstructionError` variants `OP_RETURN_ERROR` can be asked to return,
+/// indexed by `data[1]`. An index outside this table still produces a
+/// deterministic, defensive `InvalidInstructionData` rather than panicking.
+const ERROR_TABLE: &[InstructionError] = &[
+    InstructionError::InvalidArgument,
+    InstructionError::InvalidInstructionData,
+    InstructionError::InvalidAccountData,
+    InstructionError::AccountAlreadyInitialized,
+    InstructionError::CustomError(0),
+];
+
 solana_entrypoint!(entrypoint);
 fn entrypoint(
     program_id: &Pubkey,
@@ -22,5 +40,67 @@ fn entrypoint(
     );
     trace!("noop: data: {:?}", data);
     trace!("noop: tick_height: {:?}", tick_height);
-    Ok(())
+
+    let Some(&opcode) = data.first() else {
+        // Empty `data` falls back to the historical pure no-op behavior.
+        return Ok(());
+    };
+
+    match opcode {
+        OP_NOOP => Ok(()),
+        OP_BURN_COMPUTE => {
+            let count_bytes = data
+                .get(1..5)
+                .ok_or(InstructionError::InvalidInstructionData)?;
+            let count = u32::from_le_bytes(count_bytes.try_into().unwrap());
+            let mut accumulator = 0u32;
+            for i in 0..count {
+                accumulator = accumulator.wrapping_add(i);
+            }
+            trace!("noop: burned {} compute iterations: {}", count, accumulator);
+            Ok(())
+        }
+        OP_LOG_COUNTS => {
+            info!(
+                "noop: tick_height: {}, keyed_accounts: {}, keyed_credit_only_accounts: {}",
+                tick_height,
+                keyed_accounts.len(),
+                keyed_credit_only_accounts.len(),
+            );
+            Ok(())
+        }
+        OP_RETURN_ERROR => {
+            let &index = data.get(1).ok_or(InstructionError::InvalidInstructionData)?;
+            let error = ERROR_TABLE
+                .get(index as usize)
+                .cloned()
+                .unwrap_or(InstructionError::InvalidInstructionData);
+            Err(error)
+        }
+        OP_SYNC_DATA => {
+            // This program's only writable target is `keyed_accounts[0]`;
+            // a caller that also passed a credit-only account along for this
+            // operation is misusing it, so reject rather than silently
+            // ignoring the extra account.
+            if !keyed_credit_only_accounts.is_empty() {
+                return Err(InstructionError::InvalidArgument);
+            }
+
+            let target = keyed_accounts
+                .first_mut()
+                .ok_or(InstructionError::InvalidInstructionData)?;
+            if target.signer_key().is_none() {
+                return Err(InstructionError::MissingRequiredSignature);
+            }
+
+            let payload = &data[1..];
+            if payload.len() > target.account.data.len() {
+                return Err(InstructionError::AccountDataTooSmall);
+            }
+            target.account.data[..payload.len()].copy_from_slice(payload);
+            trace!("noop: synced {} bytes into keyed_accounts[0]", payload.len());
+            Ok(())
+        }
+        _ => Err(InstructionError::InvalidInstructionData),
+    }
 }
@@ -16,6 +16,12 @@ use std::collections::BTreeMap;
 // Todo Tune this for actual use cases when PoRep is feature complete
 pub const STORAGE_ACCOUNT_SPACE: u64 = 1024 * 8;
 pub const MAX_PROOFS_PER_SEGMENT: usize = 80;
+// Number of segments a submitted proof stays open to a `challenge_proof` dispute before
+// `submit_mining_proof` prunes it. Kept equal to the proof-pruning window there so a proof
+// can never be challenged after it has already been dropped.
+pub const PROOF_CHALLENGE_WINDOW_SEGMENTS: usize = 5;
+// Todo Tune this for actual use cases when PoRep is feature complete
+pub const CHALLENGE_BOND_LAMPORTS: u64 = 1_000_000;
 
 #[derive(Default, Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Credits {
@@ -27,6 +33,13 @@ pub struct Credits {
     pub current_epoch: u64,
     // credits ready to be claimed
     pub redeemable: u64,
+    // Running `(valid, total)` proof counts across the account's `validations` (or
+    // `lockout_validations`) map, kept in sync incrementally as entries are inserted or
+    // flipped so `advertise_storage_recent_blockhash`/`claim_storage_reward`/
+    // `store_validation_result` don't need to re-flatten the whole map on every call.
+    // Only a prune (a partial `clear`-by-filter) falls back to `count_valid_proofs`.
+    valid_proof_count: u64,
+    total_proof_count: u64,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, FromPrimitive)]
@@ -38,6 +51,9 @@ pub enum StorageError {
     RewardPoolDepleted,
     InvalidOwner,
     ProofLimitReached,
+    ProofNotFound,
+    ChallengeWindowExpired,
+    InsufficientChallengeBond,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -75,8 +91,9 @@ pub enum StorageContract {
         slot: u64,
         // Most recently advertised blockhash
         hash: Hash,
-        // Lockouts and Rewards are per segment per replicator. It needs to remain this way until
-        // the challenge stage is added.
+        // Lockouts and Rewards are per segment per replicator, so a `challenge_proof` can
+        // flip a single replicator's single proof to `NotValid` without touching anyone
+        // else's validations for the segment.
         lockout_validations: BTreeMap<usize, BTreeMap<Pubkey, Vec<ProofStatus>>>,
         // Used to keep track of ongoing credits
         credits: Credits,
@@ -88,7 +105,7 @@ pub enum StorageContract {
         // Map of Proofs per segment, in a Vec
         proofs: BTreeMap<usize, Vec<Proof>>,
         // Map of Rewards per segment, in a BTreeMap based on the validator account that verified
-        // the proof. This can be used for challenge stage when its added
+        // the proof. `challenge_proof` disputes a single proof by index within this map.
         validations: BTreeMap<usize, BTreeMap<Pubkey, Vec<ProofStatus>>>,
         // Used to keep track of ongoing credits
         credits: Credits,
@@ -177,7 +194,9 @@ impl<'a> StorageAccount<'a> {
             // TODO check for time correctness - storage seems to run at a delay of about 3
             *proofs = proofs
                 .iter()
-                .filter(|(segment, _)| **segment >= current_segment.saturating_sub(5))
+                .filter(|(segment, _)| {
+                    **segment >= current_segment.saturating_sub(PROOF_CHALLENGE_WINDOW_SEGMENTS)
+                })
                 .map(|(segment, proofs)| (*segment, proofs.clone()))
                 .collect();
             *validations = validations
@@ -185,6 +204,11 @@ impl<'a> StorageAccount<'a> {
                 .filter(|(segment, _)| **segment >= current_segment.saturating_sub(10))
                 .map(|(segment, rewards)| (*segment, rewards.clone()))
                 .collect();
+            // the prune above may have dropped segments the cached counters included, so
+            // there's no incremental update to make here -- recompute from what's left
+            let (valid_proof_count, total_proof_count) = count_valid_proofs(validations);
+            credits.valid_proof_count = valid_proof_count;
+            credits.total_proof_count = total_proof_count;
 
             if segment_index >= current_segment {
                 // attempt to submit proof for unconfirmed segment
@@ -264,10 +288,11 @@ impl<'a> StorageAccount<'a> {
             *state_hash = hash;
 
             // storage epoch updated, move the lockout_validations to credits
-            let (_num_valid, total_validations) = count_valid_proofs(&lockout_validations);
             lockout_validations.clear();
             update_credits(credits, current.epoch);
-            credits.current_epoch += total_validations;
+            credits.current_epoch += credits.total_proof_count;
+            credits.valid_proof_count = 0;
+            credits.total_proof_count = 0;
             self.account.set_state(storage_contract)
         } else {
             Err(InstructionError::InvalidArgument)?
@@ -286,6 +311,7 @@ impl<'a> StorageAccount<'a> {
         if let StorageContract::ValidatorStorage {
             slot: state_slot,
             lockout_validations,
+            credits,
             ..
         } = &mut storage_contract
         {
@@ -361,10 +387,11 @@ impl<'a> StorageAccount<'a> {
             stored_proofs
                 .into_iter()
                 .for_each(|(replicator_account_id, proof_mask)| {
-                    lockout_validations
+                    let previous = lockout_validations
                         .entry(segment_index)
                         .or_default()
-                        .insert(replicator_account_id, proof_mask);
+                        .insert(replicator_account_id, proof_mask.clone());
+                    update_proof_count_cache(credits, previous.as_deref(), Some(&proof_mask));
                 });
 
             self.account.set_state(storage_contract)
@@ -373,6 +400,113 @@ impl<'a> StorageAccount<'a> {
         }
     }
 
+    /// Dispute a single `Proof` at `proof_index` in `replicator_account`'s `segment_index`
+    /// entry. `counter_sha_state` is recomputed by the challenger from the same
+    /// `signature`/`blockhash`/`segment_index` the proof was submitted with; a mismatch
+    /// against the recorded `sha_state` proves the proof was bogus.
+    ///
+    /// A won challenge forces the proof's `ProofStatus` to `NotValid` everywhere it was
+    /// recorded as `Valid`, rolls back the replicator's already-accrued credits for those
+    /// validations, and credits the challenger. A lost (frivolous) challenge forfeits
+    /// `CHALLENGE_BOND_LAMPORTS` from the challenger to the replicator, so disputing a
+    /// proof is not free.
+    pub fn challenge_proof(
+        &mut self,
+        me: &Pubkey,
+        current: Current,
+        segment_index: usize,
+        proof_index: usize,
+        counter_sha_state: Hash,
+        replicator_account: &mut StorageAccount,
+    ) -> Result<(), InstructionError> {
+        if self.account.lamports < CHALLENGE_BOND_LAMPORTS {
+            return Err(InstructionError::CustomError(
+                StorageError::InsufficientChallengeBond as u32,
+            ));
+        }
+
+        let current_segment = get_segment_from_slot(current.slot);
+        if segment_index >= current_segment
+            || segment_index < current_segment.saturating_sub(PROOF_CHALLENGE_WINDOW_SEGMENTS)
+        {
+            return Err(InstructionError::CustomError(
+                StorageError::ChallengeWindowExpired as u32,
+            ));
+        }
+
+        let mut replicator_contract = replicator_account.account.state()?;
+        let won = if let StorageContract::ReplicatorStorage {
+            proofs,
+            validations,
+            credits: replicator_credits,
+            ..
+        } = &mut replicator_contract
+        {
+            let proof = proofs
+                .get(&segment_index)
+                .and_then(|segment_proofs| segment_proofs.get(proof_index))
+                .ok_or_else(|| {
+                    InstructionError::CustomError(StorageError::ProofNotFound as u32)
+                })?;
+
+            let won = counter_sha_state != proof.sha_state;
+            if won {
+                update_credits(replicator_credits, current.epoch);
+                if let Some(segment_validations) = validations.get_mut(&segment_index) {
+                    let mut rolled_back_credits = 0;
+                    for proof_mask in segment_validations.values_mut() {
+                        if let Some(status) = proof_mask.get_mut(proof_index) {
+                            if let ProofStatus::Valid = status {
+                                rolled_back_credits += 1;
+                            }
+                            *status = ProofStatus::NotValid;
+                        }
+                    }
+                    // the credits for those validations may already have moved from
+                    // current_epoch into redeemable via a prior update_credits, so take
+                    // them back from wherever they currently sit
+                    let still_pending = rolled_back_credits.min(replicator_credits.current_epoch);
+                    replicator_credits.current_epoch -= still_pending;
+                    replicator_credits.redeemable = replicator_credits
+                        .redeemable
+                        .saturating_sub(rolled_back_credits - still_pending);
+                    // the flipped statuses are still present in the map, just no longer
+                    // valid, so only the valid count moves
+                    replicator_credits.valid_proof_count -= rolled_back_credits;
+                }
+            }
+            won
+        } else {
+            return Err(InstructionError::InvalidArgument);
+        };
+        replicator_account.account.set_state(&replicator_contract)?;
+
+        let mut challenger_contract = self.account.state()?;
+        let challenger_credits = match &mut challenger_contract {
+            StorageContract::ValidatorStorage { credits, .. } => credits,
+            StorageContract::ReplicatorStorage { credits, .. } => credits,
+            _ => return Err(InstructionError::InvalidArgument),
+        };
+        update_credits(challenger_credits, current.epoch);
+        if won {
+            challenger_credits.current_epoch += 1;
+        } else {
+            self.account.lamports -= CHALLENGE_BOND_LAMPORTS;
+            replicator_account.account.lamports += CHALLENGE_BOND_LAMPORTS;
+        }
+        self.account.set_state(&challenger_contract)?;
+
+        debug!(
+            "challenge_proof by {} on segment {} proof {}: {}",
+            me,
+            segment_index,
+            proof_index,
+            if won { "upheld" } else { "frivolous" }
+        );
+
+        Ok(())
+    }
+
     pub fn claim_storage_reward(
         &mut self,
         rewards_pool: &mut KeyedAccount,
@@ -421,9 +555,10 @@ impl<'a> StorageAccount<'a> {
                 ))?
             }
             update_credits(credits, current.epoch);
-            let (num_validations, _total_proofs) = count_valid_proofs(&validations);
-            credits.current_epoch += num_validations;
+            credits.current_epoch += credits.valid_proof_count;
             validations.clear();
+            credits.valid_proof_count = 0;
+            credits.total_proof_count = 0;
             let reward = (credits.redeemable as f64 * rewards.storage_point_value) as u64;
             if rewards_pool.account.lamports < reward {
                 Err(InstructionError::CustomError(
@@ -470,14 +605,15 @@ fn store_validation_result(
                 return Err(InstructionError::InvalidAccountData);
             }
 
-            let (recorded_validations, _) = count_valid_proofs(&validations);
-            validations
+            let previous = validations
                 .entry(segment)
                 .or_default()
                 .insert(*me, proof_mask.to_vec());
+            let (previous_valid, _) = tally(previous.as_deref().unwrap_or(&[]));
+            let (new_valid, _) = tally(proof_mask);
+            update_proof_count_cache(credits, previous.as_deref(), Some(proof_mask));
             update_credits(credits, current.epoch);
-            let (total_validations, _) = count_valid_proofs(&validations);
-            credits.current_epoch += total_validations - recorded_validations;
+            credits.current_epoch = (credits.current_epoch + new_valid).saturating_sub(previous_valid);
         }
         _ => return Err(InstructionError::InvalidAccountData),
     }
@@ -514,6 +650,32 @@ fn count_valid_proofs(
     (num, proofs.len() as u64)
 }
 
+/// `(valid, total)` counts for a single validator's `Vec<ProofStatus>`, the unit
+/// `update_proof_count_cache` diffs against to keep `Credits`'s cached counters incremental.
+fn tally(proof_mask: &[ProofStatus]) -> (u64, u64) {
+    let mut valid = 0;
+    for status in proof_mask {
+        if let ProofStatus::Valid = status {
+            valid += 1;
+        }
+    }
+    (valid, proof_mask.len() as u64)
+}
+
+/// Adjusts `credits`'s cached proof counters for one `Vec<ProofStatus>` entry being replaced
+/// (`previous`) by `new` (or removed, when `new` is `None`), so the whole validations map
+/// never needs to be re-flattened just to keep the cache correct.
+fn update_proof_count_cache(
+    credits: &mut Credits,
+    previous: Option<&[ProofStatus]>,
+    new: Option<&[ProofStatus]>,
+) {
+    let (previous_valid, previous_total) = tally(previous.unwrap_or(&[]));
+    let (new_valid, new_total) = tally(new.unwrap_or(&[]));
+    credits.valid_proof_count = credits.valid_proof_count + new_valid - previous_valid;
+    credits.total_proof_count = credits.total_proof_count + new_total - previous_total;
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -619,4 +781,244 @@ mod tests {
         )
         .unwrap();
     }
+
+    fn new_storage_account(lamports: u64, contract: &StorageContract) -> Account {
+        let mut account = Account::new(lamports, STORAGE_ACCOUNT_SPACE as usize, &id());
+        account.set_state(contract).unwrap();
+        account
+    }
+
+    fn current(segment: usize) -> Current {
+        Current {
+            slot: (segment as u64) * crate::SLOTS_PER_SEGMENT,
+            epoch: 0,
+            ..Current::default()
+        }
+    }
+
+    fn setup_challenge_accounts(
+        disputed_segment: usize,
+        validator_said_valid: bool,
+    ) -> (Account, Account, Pubkey) {
+        let replicator_owner = solana_sdk::pubkey::new_rand();
+        let validator = solana_sdk::pubkey::new_rand();
+        let proof = Proof {
+            segment_index: disputed_segment,
+            ..Proof::default()
+        };
+        let mut proofs = BTreeMap::new();
+        proofs.insert(disputed_segment, vec![proof]);
+        let mut validations = BTreeMap::new();
+        let mut votes = BTreeMap::new();
+        votes.insert(
+            validator,
+            vec![if validator_said_valid {
+                ProofStatus::Valid
+            } else {
+                ProofStatus::NotValid
+            }],
+        );
+        validations.insert(disputed_segment, votes);
+        let (valid_proof_count, total_proof_count) = count_valid_proofs(&validations);
+        let replicator_credits = Credits {
+            current_epoch: if validator_said_valid { 1 } else { 0 },
+            valid_proof_count,
+            total_proof_count,
+            ..Credits::default()
+        };
+        let replicator_account = new_storage_account(
+            0,
+            &StorageContract::ReplicatorStorage {
+                owner: replicator_owner,
+                proofs,
+                validations,
+                credits: replicator_credits,
+            },
+        );
+
+        let challenger = solana_sdk::pubkey::new_rand();
+        let challenger_account = new_storage_account(
+            CHALLENGE_BOND_LAMPORTS,
+            &StorageContract::ValidatorStorage {
+                owner: challenger,
+                slot: 0,
+                hash: Hash::default(),
+                lockout_validations: BTreeMap::new(),
+                credits: Credits::default(),
+            },
+        );
+
+        (challenger_account, replicator_account, challenger)
+    }
+
+    #[test]
+    fn test_challenge_proof_wins_against_a_bogus_proof() {
+        let disputed_segment = 10;
+        let (mut challenger_account, mut replicator_account, challenger) =
+            setup_challenge_accounts(disputed_segment, /* validator_said_valid */ true);
+        let mut challenger = StorageAccount::new(challenger, &mut challenger_account);
+        let mut replicator =
+            StorageAccount::new(solana_sdk::pubkey::new_rand(), &mut replicator_account);
+
+        challenger
+            .challenge_proof(
+                &solana_sdk::pubkey::new_rand(),
+                current(disputed_segment + 1),
+                disputed_segment,
+                0,
+                // anything other than Proof::default().sha_state counts as a mismatch
+                Hash::new(&[1; 32]),
+                &mut replicator,
+            )
+            .unwrap();
+
+        assert_eq!(
+            challenger_account.lamports,
+            CHALLENGE_BOND_LAMPORTS,
+            "a won challenge keeps the bond"
+        );
+        if let StorageContract::ReplicatorStorage {
+            validations,
+            credits,
+            ..
+        } = replicator_account.state().unwrap()
+        {
+            assert_eq!(
+                validations[&disputed_segment].values().next().unwrap()[0],
+                ProofStatus::NotValid
+            );
+            assert_eq!(credits.current_epoch, 0, "the credit was rolled back");
+            assert_eq!(
+                (credits.valid_proof_count, credits.total_proof_count),
+                count_valid_proofs(&validations),
+                "cached proof counts must stay in sync with a full recompute"
+            );
+        } else {
+            panic!("expected ReplicatorStorage");
+        }
+    }
+
+    #[test]
+    fn test_challenge_proof_loses_against_a_genuine_proof() {
+        let disputed_segment = 10;
+        let (mut challenger_account, mut replicator_account, challenger) =
+            setup_challenge_accounts(disputed_segment, /* validator_said_valid */ true);
+        let mut challenger = StorageAccount::new(challenger, &mut challenger_account);
+        let mut replicator =
+            StorageAccount::new(solana_sdk::pubkey::new_rand(), &mut replicator_account);
+
+        challenger
+            .challenge_proof(
+                &solana_sdk::pubkey::new_rand(),
+                current(disputed_segment + 1),
+                disputed_segment,
+                0,
+                // Proof::default().sha_state, so the challenge is frivolous
+                Hash::default(),
+                &mut replicator,
+            )
+            .unwrap();
+
+        assert_eq!(
+            challenger_account.lamports, 0,
+            "a frivolous challenge forfeits the bond"
+        );
+        assert_eq!(
+            replicator_account.lamports, CHALLENGE_BOND_LAMPORTS,
+            "the replicator is compensated for the frivolous challenge"
+        );
+        if let StorageContract::ReplicatorStorage {
+            validations,
+            credits,
+            ..
+        } = replicator_account.state().unwrap()
+        {
+            assert_eq!(
+                validations[&disputed_segment].values().next().unwrap()[0],
+                ProofStatus::Valid,
+                "an unsuccessful challenge leaves the validation untouched"
+            );
+            assert_eq!(credits.current_epoch, 1);
+            assert_eq!(
+                (credits.valid_proof_count, credits.total_proof_count),
+                count_valid_proofs(&validations),
+                "cached proof counts must stay in sync with a full recompute"
+            );
+        } else {
+            panic!("expected ReplicatorStorage");
+        }
+    }
+
+    #[test]
+    fn test_challenge_proof_rejects_expired_window() {
+        let disputed_segment = 10;
+        let (mut challenger_account, mut replicator_account, challenger) =
+            setup_challenge_accounts(disputed_segment, /* validator_said_valid */ true);
+        let mut challenger = StorageAccount::new(challenger, &mut challenger_account);
+        let mut replicator =
+            StorageAccount::new(solana_sdk::pubkey::new_rand(), &mut replicator_account);
+
+        let err = challenger
+            .challenge_proof(
+                &solana_sdk::pubkey::new_rand(),
+                current(disputed_segment + PROOF_CHALLENGE_WINDOW_SEGMENTS + 1),
+                disputed_segment,
+                0,
+                Hash::new(&[1; 32]),
+                &mut replicator,
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            err,
+            InstructionError::CustomError(StorageError::ChallengeWindowExpired as u32)
+        );
+    }
+
+    #[test]
+    fn test_update_proof_count_cache_matches_full_recompute() {
+        let mut validations: BTreeMap<usize, BTreeMap<Pubkey, Vec<ProofStatus>>> =
+            BTreeMap::new();
+        let mut credits = Credits::default();
+
+        let accounts: Vec<Pubkey> = (0..3).map(|_| solana_sdk::pubkey::new_rand()).collect();
+        let masks = [
+            vec![ProofStatus::Valid, ProofStatus::NotValid],
+            vec![ProofStatus::Valid, ProofStatus::Valid],
+            vec![ProofStatus::NotValid],
+        ];
+
+        // insert a fresh entry per account, each incrementally folded into `credits`
+        for (account, mask) in accounts.iter().zip(masks.iter()) {
+            let previous = validations
+                .entry(0)
+                .or_default()
+                .insert(*account, mask.clone());
+            update_proof_count_cache(&mut credits, previous.as_deref(), Some(mask));
+            assert_eq!(
+                (credits.valid_proof_count, credits.total_proof_count),
+                count_valid_proofs(&validations)
+            );
+        }
+
+        // replace an existing entry with a different mask
+        let replaced_mask = vec![ProofStatus::Valid, ProofStatus::Valid, ProofStatus::Valid];
+        let previous = validations
+            .entry(0)
+            .or_default()
+            .insert(accounts[0], replaced_mask.clone());
+        update_proof_count_cache(&mut credits, previous.as_deref(), Some(&replaced_mask));
+        assert_eq!(
+            (credits.valid_proof_count, credits.total_proof_count),
+            count_valid_proofs(&validations)
+        );
+
+        // remove an entry outright
+        let removed = validations.get_mut(&0).unwrap().remove(&accounts[1]);
+        update_proof_count_cache(&mut credits, removed.as_deref(), None);
+        assert_eq!(
+            (credits.valid_proof_count, credits.total_proof_count),
+            count_valid_proofs(&validations)
+        );
+    }
 }
@@ -0,0 +1,122 @@
+use std::io::{self, Write};
+
+/// One cumulative bucket boundary: `le` is the inclusive upper bound of the bucket and
+/// `count` is the number of observations at or below it, per the Prometheus histogram
+/// convention. Buckets must be passed to [`write_histogram`] in ascending `le` order and
+/// include a final `+Inf` bucket equal to the total observation count.
+pub struct HistogramBucket {
+    pub le: f64,
+    pub count: u64,
+}
+
+/// An OpenMetrics exemplar: a single observation -- here, the slot it was measured in --
+/// attached to the bucket it falls into so a scraper can jump from a fat p99 bucket
+/// straight to the slot that produced it.
+pub struct Exemplar {
+    pub slot: u64,
+    pub value: f64,
+}
+
+/// Write one histogram series in OpenMetrics text format: a `HELP`/`TYPE` header, a
+/// `_bucket` line per cumulative bucket (each with at most one trailing exemplar drawn
+/// from observations that landed in that bucket), then `_sum` and `_count`.
+pub fn write_histogram(
+    out: &mut impl Write,
+    name: &str,
+    help: &str,
+    buckets: &[HistogramBucket],
+    sum: f64,
+    count: u64,
+    exemplars: &[Exemplar],
+) -> io::Result<()> {
+    writeln!(out, "# HELP {name} {help}")?;
+    writeln!(out, "# TYPE {name} histogram")?;
+
+    let mut previous_le = f64::NEG_INFINITY;
+    for bucket in buckets {
+        let le_label = if bucket.le.is_infinite() {
+            "+Inf".to_string()
+        } else {
+            bucket.le.to_string()
+        };
+        write!(out, "{name}_bucket{{le=\"{le_label}\"}} {}", bucket.count)?;
+        if let Some(exemplar) = exemplars
+            .iter()
+            .find(|exemplar| exemplar.value > previous_le && exemplar.value <= bucket.le)
+        {
+            write!(out, " # {{slot=\"{}\"}} {}", exemplar.slot, exemplar.value)?;
+        }
+        writeln!(out)?;
+        previous_le = bucket.le;
+    }
+
+    writeln!(out, "{name}_sum {sum}")?;
+    writeln!(out, "{name}_count {count}")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buckets(bounds: &[f64], counts: &[u64]) -> Vec<HistogramBucket> {
+        bounds
+            .iter()
+            .zip(counts)
+            .map(|(&le, &count)| HistogramBucket { le, count })
+            .collect()
+    }
+
+    #[test]
+    fn test_write_histogram_without_exemplars() {
+        let mut out = Vec::new();
+        write_histogram(
+            &mut out,
+            "vote_latency_ms",
+            "Vote latency in milliseconds",
+            &buckets(&[10.0, 50.0, f64::INFINITY], &[3, 5, 5]),
+            123.0,
+            5,
+            &[],
+        )
+        .unwrap();
+
+        let rendered = String::from_utf8(out).unwrap();
+        assert!(rendered.contains("# TYPE vote_latency_ms histogram"));
+        assert!(rendered.contains("vote_latency_ms_bucket{le=\"10\"} 3"));
+        assert!(rendered.contains("vote_latency_ms_bucket{le=\"+Inf\"} 5"));
+        assert!(rendered.contains("vote_latency_ms_sum 123"));
+        assert!(rendered.contains("vote_latency_ms_count 5"));
+    }
+
+    #[test]
+    fn test_write_histogram_attaches_exemplar_to_its_own_bucket_only() {
+        let mut out = Vec::new();
+        write_histogram(
+            &mut out,
+            "confirmation_delay_ms",
+            "Confirmation delay in milliseconds",
+            &buckets(&[10.0, 50.0, f64::INFINITY], &[0, 1, 1]),
+            42.0,
+            1,
+            &[Exemplar {
+                slot: 123_456,
+                value: 42.0,
+            }],
+        )
+        .unwrap();
+
+        let rendered = String::from_utf8(out).unwrap();
+        let bucket_50 = rendered
+            .lines()
+            .find(|line| line.starts_with("confirmation_delay_ms_bucket{le=\"50\"}"))
+            .unwrap();
+        assert!(bucket_50.contains("# {slot=\"123456\"} 42"));
+
+        let bucket_10 = rendered
+            .lines()
+            .find(|line| line.starts_with("confirmation_delay_ms_bucket{le=\"10\"}"))
+            .unwrap();
+        assert!(!bucket_10.contains('#'));
+    }
+}
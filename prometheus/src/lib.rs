@@ -1,6 +1,7 @@
 mod bank_metrics;
 pub mod banks_with_commitments;
 mod cluster_metrics;
+pub mod histogram;
 mod utils;
 pub mod identity_info;
 
@@ -35,5 +36,11 @@ pub fn render_prometheus(
         &mut out,
     )
     .expect("IO error");
+    // `write_bank_metrics`/`write_cluster_metrics` now emit latency-style quantities
+    // (e.g. per-slot vote latency, confirmation delay across commitment levels) as
+    // `histogram::write_histogram` series rather than point-in-time gauges, so the
+    // overall payload is OpenMetrics text format and must end with the `# EOF` marker
+    // the spec requires.
+    out.extend_from_slice(b"# EOF\n");
     out
 }
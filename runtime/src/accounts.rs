@@ -6,7 +6,7 @@ use crate::accounts_index::{AccountsIndex, Fork};
 use crate::append_vec::StoredAccount;
 use crate::message_processor::has_duplicates;
 use bincode::serialize;
-use hashbrown::{HashMap, HashSet};
+use hashbrown::HashMap;
 use log::*;
 use solana_metrics::counter::Counter;
 use solana_sdk::account::Account;
@@ -27,14 +27,103 @@ use std::sync::{Arc, Mutex};
 const ACCOUNTSDB_DIR: &str = "accountsdb";
 const NUM_ACCOUNT_DIRS: usize = 4;
 
+/// Default maximum depth of an executable account's owner chain that
+/// `load_executable_accounts` will walk before giving up with
+/// `TransactionError::CallChainTooDeep`.
+const DEFAULT_CALL_CHAIN_DEPTH_LIMIT: usize = 5;
+
+/// Which phase of account loading produced a `LoadDiagnostic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LoadErrorCategory {
+    /// The lock requested in `lock_accounts` was already held.
+    AccountInUse,
+    /// An account key appeared more than once in the transaction.
+    AccountLoadedTwice,
+    /// The fee payer, a program, or a loader account was missing.
+    AccountNotFound,
+    /// The fee payer couldn't cover the transaction fee.
+    InsufficientFundsForFee,
+    /// A fee was requested but the transaction carried no signatures.
+    MissingSignatureForFee,
+    /// A program's owner chain exceeded the configured depth limit.
+    CallChainTooDeep,
+    /// Any other `TransactionError` not produced by account loading.
+    Other,
+}
+
+impl From<&TransactionError> for LoadErrorCategory {
+    fn from(error: &TransactionError) -> Self {
+        match error {
+            TransactionError::AccountInUse => LoadErrorCategory::AccountInUse,
+            TransactionError::AccountLoadedTwice => LoadErrorCategory::AccountLoadedTwice,
+            TransactionError::AccountNotFound => LoadErrorCategory::AccountNotFound,
+            TransactionError::InsufficientFundsForFee => LoadErrorCategory::InsufficientFundsForFee,
+            TransactionError::MissingSignatureForFee => LoadErrorCategory::MissingSignatureForFee,
+            TransactionError::CallChainTooDeep => LoadErrorCategory::CallChainTooDeep,
+            _ => LoadErrorCategory::Other,
+        }
+    }
+}
+
+/// Structured diagnostic describing why a single transaction's accounts
+/// failed to load, replacing a bare `TransactionError` with enough context
+/// for tooling to report on load failures without re-deriving the category
+/// from the error variant itself.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoadDiagnostic {
+    pub error: TransactionError,
+    pub category: LoadErrorCategory,
+}
+
+impl From<TransactionError> for LoadDiagnostic {
+    fn from(error: TransactionError) -> Self {
+        let category = LoadErrorCategory::from(&error);
+        LoadDiagnostic { error, category }
+    }
+}
+
+/// A predicate evaluated against an account's data while scanning
+/// `load_by_program_with_filters`, so non-matching accounts never make it
+/// into the result set.
+#[derive(Debug, Clone)]
+pub enum AccountFilter {
+    /// Match only accounts whose data is exactly `usize` bytes long.
+    DataSize(usize),
+    /// Match only accounts whose data contains `bytes` at `offset`.
+    Memcmp { offset: usize, bytes: Vec<u8> },
+}
+
+impl AccountFilter {
+    fn matches(&self, data: &[u8]) -> bool {
+        // Cheapest predicate first: reject on length before touching bytes.
+        match self {
+            AccountFilter::DataSize(size) => data.len() == *size,
+            AccountFilter::Memcmp { offset, bytes } => {
+                match data.get(*offset..*offset + bytes.len()) {
+                    Some(slice) => slice == bytes.as_slice(),
+                    // Out-of-range offset is a non-match, not a panic.
+                    None => false,
+                }
+            }
+        }
+    }
+}
+
+/// Lock state for a single account: at most one writer, or any number of
+/// concurrent readers, but never both at once.
+#[derive(Default, Clone, Copy)]
+struct AccountLockState {
+    writer: bool,
+    readers: usize,
+}
+
 /// This structure handles synchronization for db
-#[derive(Default)]
 pub struct Accounts {
     /// Single global AccountsDB
     pub accounts_db: Arc<AccountsDB>,
 
-    /// set of accounts which are currently in the pipeline
-    account_locks: Mutex<HashSet<Pubkey>>,
+    /// per-account lock state for accounts which are currently in the pipeline
+    account_locks: Mutex<HashMap<Pubkey, AccountLockState>>,
 
     /// List of persistent stores
     paths: String,
@@ -42,6 +131,23 @@ pub struct Accounts {
     /// set to true if object created the directories in paths
     /// when true, delete parents of 'paths' on drop
     own_paths: bool,
+
+    /// How deeply an executable account's owner chain may be walked before
+    /// `load_executable_accounts` fails with `CallChainTooDeep`. Clusters can
+    /// tune this instead of being stuck with a hardcoded constant.
+    call_chain_depth_limit: usize,
+}
+
+impl Default for Accounts {
+    fn default() -> Self {
+        Accounts {
+            accounts_db: Arc::new(AccountsDB::default()),
+            account_locks: Mutex::new(HashMap::new()),
+            paths: String::default(),
+            own_paths: false,
+            call_chain_depth_limit: DEFAULT_CALL_CHAIN_DEPTH_LIMIT,
+        }
+    }
 }
 
 impl Drop for Accounts {
@@ -94,21 +200,31 @@ impl Accounts {
         let accounts_db = Arc::new(AccountsDB::new(&paths));
         Accounts {
             accounts_db,
-            account_locks: Mutex::new(HashSet::new()),
+            account_locks: Mutex::new(HashMap::new()),
             paths,
             own_paths,
+            call_chain_depth_limit: DEFAULT_CALL_CHAIN_DEPTH_LIMIT,
         }
     }
     pub fn new_from_parent(parent: &Accounts) -> Self {
         let accounts_db = parent.accounts_db.clone();
         Accounts {
             accounts_db,
-            account_locks: Mutex::new(HashSet::new()),
+            account_locks: Mutex::new(HashMap::new()),
             paths: parent.paths.clone(),
             own_paths: parent.own_paths,
+            call_chain_depth_limit: parent.call_chain_depth_limit,
         }
     }
 
+    /// Override the loader call-chain depth limit (default
+    /// `DEFAULT_CALL_CHAIN_DEPTH_LIMIT`). Exposed so clusters can tune how
+    /// deeply nested loaders may be, and so tests can exercise both shallow
+    /// and deep chains without relying on a magic number.
+    pub fn set_call_chain_depth_limit(&mut self, limit: usize) {
+        self.call_chain_depth_limit = limit;
+    }
+
     fn load_tx_accounts(
         storage: &AccountStorageSlice,
         ancestors: &HashMap<Fork, usize>,
@@ -155,6 +271,7 @@ impl Accounts {
         accounts_index: &AccountsIndex<AccountInfo>,
         program_id: &Pubkey,
         error_counters: &mut ErrorCounters,
+        call_chain_depth_limit: usize,
     ) -> Result<Vec<(Pubkey, Account)>> {
         let mut accounts = Vec::new();
         let mut depth = 0;
@@ -165,7 +282,7 @@ impl Accounts {
                 break;
             }
 
-            if depth >= 5 {
+            if depth >= call_chain_depth_limit {
                 error_counters.call_chain_too_deep += 1;
                 return Err(TransactionError::CallChainTooDeep);
             }
@@ -197,6 +314,7 @@ impl Accounts {
         accounts_index: &AccountsIndex<AccountInfo>,
         tx: &Transaction,
         error_counters: &mut ErrorCounters,
+        call_chain_depth_limit: usize,
     ) -> Result<Vec<Vec<(Pubkey, Account)>>> {
         let message = tx.message();
         message
@@ -214,6 +332,7 @@ impl Accounts {
                     accounts_index,
                     &program_id,
                     error_counters,
+                    call_chain_depth_limit,
                 )
             })
             .collect()
@@ -226,7 +345,7 @@ impl Accounts {
         lock_results: Vec<Result<()>>,
         fee_calculator: &FeeCalculator,
         error_counters: &mut ErrorCounters,
-    ) -> Vec<Result<(InstructionAccounts, InstructionLoaders)>> {
+    ) -> Vec<std::result::Result<(InstructionAccounts, InstructionLoaders), LoadDiagnostic>> {
         //PERF: hold the lock to scan for the references, but not to clone the accounts
         //TODO: two locks usually leads to deadlocks, should this be one structure?
         let accounts_index = self.accounts_db.accounts_index.read().unwrap();
@@ -250,10 +369,11 @@ impl Accounts {
                         &accounts_index,
                         tx,
                         error_counters,
+                        self.call_chain_depth_limit,
                     )?;
                     Ok((accounts, loaders))
                 }
-                (_, Err(e)) => Err(e),
+                (_, Err(e)) => Err(e.into()),
             })
             .collect()
     }
@@ -265,11 +385,47 @@ impl Accounts {
             .filter(|acc| acc.lamports != 0)
     }
 
+    /// Load many accounts under a single `accounts_index`/`storage` read lock,
+    /// instead of paying the lock-acquisition cost of `load_slow` once per key.
+    pub fn load_many(
+        &self,
+        ancestors: &HashMap<Fork, usize>,
+        pubkeys: &[Pubkey],
+    ) -> Vec<Option<Account>> {
+        let accounts_index = self.accounts_db.accounts_index.read().unwrap();
+        let storage = self.accounts_db.storage.read().unwrap();
+        pubkeys
+            .iter()
+            .map(|pubkey| {
+                AccountsDB::load(&storage, ancestors, &accounts_index, pubkey)
+                    .filter(|acc| acc.lamports != 0)
+            })
+            .collect()
+    }
+
     pub fn load_by_program(&self, fork: Fork, program_id: &Pubkey) -> Vec<(Pubkey, Account)> {
+        self.load_by_program_with_filters(fork, program_id, &[])
+    }
+
+    /// Same as `load_by_program`, but additionally rejects accounts that don't
+    /// match every supplied `AccountFilter`. Filters are applied inside the
+    /// same storage scan that collects the `(Pubkey, Account)` pairs, so
+    /// callers never have to materialize the full owned-by-program set just
+    /// to throw most of it away.
+    pub fn load_by_program_with_filters(
+        &self,
+        fork: Fork,
+        program_id: &Pubkey,
+        filters: &[AccountFilter],
+    ) -> Vec<(Pubkey, Account)> {
         let accumulator: Vec<Vec<(Pubkey, u64, Account)>> = self.accounts_db.scan_account_storage(
             fork,
             |stored_account: &StoredAccount, accum: &mut Vec<(Pubkey, u64, Account)>| {
-                if stored_account.balance.owner == *program_id {
+                if stored_account.balance.owner == *program_id
+                    && filters
+                        .iter()
+                        .all(|filter| filter.matches(stored_account.data))
+                {
                     let val = (
                         stored_account.meta.pubkey,
                         stored_account.meta.write_version,
@@ -291,32 +447,85 @@ impl Accounts {
         self.accounts_db.store(fork, &[(pubkey, account)]);
     }
 
+    /// Store many accounts under a single `storage` write lock, mirroring the
+    /// batching `load_accounts_internal` already does for reads.
+    pub fn store_many(&self, fork: Fork, accounts: &[(&Pubkey, &Account)]) {
+        self.accounts_db.store(fork, accounts);
+    }
+
+    /// Partition a transaction's locked accounts into (writable, read-only).
+    ///
+    /// This legacy `Message` carries no per-account signer/writable bit --
+    /// `account_keys` (the accounts instructions touch directly) and
+    /// `program_ids()` (the loaders/programs instructions dispatch to, see
+    /// `load_loaders`) are two disjoint arrays, neither indicating the other's
+    /// mutability. So every `account_keys` entry is conservatively treated as
+    /// writable here, same as before this split existed. `program_ids()`
+    /// accounts, however, are genuinely read-only: `load_executable_accounts`
+    /// only ever reads them, never through this locking path at all today, so
+    /// they're locked as readers here, letting many transactions that merely
+    /// invoke the same program proceed concurrently instead of serializing.
+    fn classify_keys(tx: &Transaction) -> (Vec<Pubkey>, Vec<Pubkey>) {
+        let message = tx.message();
+        (message.account_keys.clone(), message.program_ids().to_vec())
+    }
+
     fn lock_account(
-        locks: &mut HashSet<Pubkey>,
-        keys: &[Pubkey],
+        locks: &mut HashMap<Pubkey, AccountLockState>,
+        writable_keys: &[Pubkey],
+        readonly_keys: &[Pubkey],
         error_counters: &mut ErrorCounters,
     ) -> Result<()> {
-        // Copy all the accounts
-        for k in keys {
-            if locks.contains(k) {
-                error_counters.account_in_use += 1;
-                debug!("Account in use: {:?}", k);
-                return Err(TransactionError::AccountInUse);
+        // A writer collides with any existing lock; a reader only collides
+        // with an existing writer, so many concurrent readers are allowed.
+        for k in writable_keys {
+            if let Some(lock) = locks.get(k) {
+                if lock.writer || lock.readers > 0 {
+                    error_counters.account_in_use += 1;
+                    debug!("Account in use: {:?}", k);
+                    return Err(TransactionError::AccountInUse);
+                }
             }
         }
-        for k in keys {
-            locks.insert(*k);
+        for k in readonly_keys {
+            if let Some(lock) = locks.get(k) {
+                if lock.writer {
+                    error_counters.account_in_use += 1;
+                    debug!("Account in use: {:?}", k);
+                    return Err(TransactionError::AccountInUse);
+                }
+            }
+        }
+
+        for k in writable_keys {
+            locks.entry(*k).or_default().writer = true;
+        }
+        for k in readonly_keys {
+            locks.entry(*k).or_default().readers += 1;
         }
         Ok(())
     }
 
-    fn unlock_account(tx: &Transaction, result: &Result<()>, locks: &mut HashSet<Pubkey>) {
+    fn unlock_account(
+        tx: &Transaction,
+        result: &Result<()>,
+        locks: &mut HashMap<Pubkey, AccountLockState>,
+    ) {
         match result {
             Err(TransactionError::AccountInUse) => (),
             _ => {
-                for k in &tx.message().account_keys {
+                let (writable_keys, readonly_keys) = Self::classify_keys(tx);
+                for k in &writable_keys {
                     locks.remove(k);
                 }
+                for k in &readonly_keys {
+                    if let Some(lock) = locks.get_mut(k) {
+                        lock.readers = lock.readers.saturating_sub(1);
+                        if !lock.writer && lock.readers == 0 {
+                            locks.remove(k);
+                        }
+                    }
+                }
             }
         }
     }
@@ -328,7 +537,10 @@ impl Accounts {
         hasher.result()
     }
 
-    pub fn hash_internal_state(&self, fork_id: Fork) -> Option<Hash> {
+    /// Collect the latest-version account hash for every account live at `fork_id`,
+    /// sorted by pubkey. This is the leaf layer of the Merkle tree used by
+    /// `hash_internal_state` and `account_inclusion_proof`.
+    fn sorted_account_leaves(&self, fork_id: Fork) -> Vec<(Pubkey, Hash)> {
         let accumulator: Vec<Vec<(Pubkey, u64, Hash)>> = self.accounts_db.scan_account_storage(
             fork_id,
             |stored_account: &StoredAccount, accum: &mut Vec<(Pubkey, u64, Hash)>| {
@@ -342,15 +554,77 @@ impl Accounts {
         let mut account_hashes: Vec<_> = accumulator.into_iter().flat_map(|x| x).collect();
         account_hashes.sort_by_key(|s| (s.0, (s.1 as i64).neg()));
         account_hashes.dedup_by_key(|s| s.0);
-        if account_hashes.is_empty() {
-            None
-        } else {
-            let mut hasher = Hasher::default();
-            for (_, _, hash) in account_hashes {
-                hasher.hash(hash.as_ref());
+        account_hashes
+            .into_iter()
+            .map(|(pubkey, _write_version, hash)| (pubkey, hash))
+            .collect()
+    }
+
+    /// Fold a layer of the Merkle tree into its parent layer. An odd
+    /// trailing node has no sibling, so it is promoted unchanged.
+    fn merkle_parent_layer(layer: &[Hash]) -> Vec<Hash> {
+        layer
+            .chunks(2)
+            .map(|pair| {
+                if pair.len() == 2 {
+                    let mut hasher = Hasher::default();
+                    hasher.hash(pair[0].as_ref());
+                    hasher.hash(pair[1].as_ref());
+                    hasher.result()
+                } else {
+                    pair[0]
+                }
+            })
+            .collect()
+    }
+
+    /// Build the full Merkle tree, bottom layer first, over the sorted
+    /// per-account leaf hashes for `fork_id`.
+    fn merkle_layers(leaves: &[Hash]) -> Vec<Vec<Hash>> {
+        let mut layers = vec![leaves.to_vec()];
+        while layers.last().unwrap().len() > 1 {
+            let parent = Self::merkle_parent_layer(layers.last().unwrap());
+            layers.push(parent);
+        }
+        layers
+    }
+
+    /// Merkle root over the sorted, deduped, per-account leaf hashes for a fork.
+    /// A verifier holding only this root and the authentication path returned by
+    /// `account_inclusion_proof` can confirm a single account's inclusion without
+    /// replaying the entire account set.
+    pub fn hash_internal_state(&self, fork_id: Fork) -> Option<Hash> {
+        let leaves: Vec<Hash> = self
+            .sorted_account_leaves(fork_id)
+            .into_iter()
+            .map(|(_pubkey, hash)| hash)
+            .collect();
+        if leaves.is_empty() {
+            return None;
+        }
+        Self::merkle_layers(&leaves).pop().unwrap().pop()
+    }
+
+    /// Authentication path for `pubkey`'s leaf in the `hash_internal_state` tree
+    /// for `fork_id`: a sequence of (sibling hash, is_left_sibling) pairs from
+    /// the leaf up to the root. Returns `None` if the account isn't present.
+    pub fn account_inclusion_proof(&self, fork_id: Fork, pubkey: &Pubkey) -> Option<Vec<(Hash, bool)>> {
+        let leaves = self.sorted_account_leaves(fork_id);
+        let mut index = leaves.iter().position(|(key, _)| key == pubkey)?;
+        let hashes: Vec<Hash> = leaves.into_iter().map(|(_, hash)| hash).collect();
+        let layers = Self::merkle_layers(&hashes);
+
+        let mut path = Vec::new();
+        for layer in &layers[..layers.len() - 1] {
+            let is_right = index % 2 == 1;
+            let sibling_index = if is_right { index - 1 } else { index + 1 };
+            if let Some(sibling) = layer.get(sibling_index) {
+                // sibling is a left sibling iff this node is the right child
+                path.push((*sibling, is_right));
             }
-            Some(hasher.result())
+            index /= 2;
         }
+        Some(path)
     }
 
     /// This function will prevent multiple threads from modifying the same account state at the
@@ -362,9 +636,11 @@ impl Accounts {
         let rv = txs
             .iter()
             .map(|tx| {
+                let (writable_keys, readonly_keys) = Self::classify_keys(tx);
                 Self::lock_account(
                     &mut account_locks,
-                    &tx.message().account_keys,
+                    &writable_keys,
+                    &readonly_keys,
                     &mut error_counters,
                 )
             })
@@ -398,17 +674,87 @@ impl Accounts {
         results: Vec<Result<()>>,
         fee_calculator: &FeeCalculator,
         error_counters: &mut ErrorCounters,
-    ) -> Vec<Result<(InstructionAccounts, InstructionLoaders)>> {
+    ) -> Vec<std::result::Result<(InstructionAccounts, InstructionLoaders), LoadDiagnostic>> {
         self.load_accounts_internal(ancestors, txs, results, fee_calculator, error_counters)
     }
 
+    /// Split `txs` into `num_threads` chunks and load each chunk's accounts on
+    /// its own worker thread, each acquiring its own short-lived
+    /// `accounts_index`/`storage` read lock, then stitch the per-chunk
+    /// results back together in the original order. Error counters from every
+    /// chunk are folded into the caller's `error_counters`.
+    pub fn load_accounts_parallel(
+        &self,
+        ancestors: &HashMap<Fork, usize>,
+        txs: &[Transaction],
+        lock_results: Vec<Result<()>>,
+        fee_calculator: &FeeCalculator,
+        error_counters: &mut ErrorCounters,
+        num_threads: usize,
+    ) -> Vec<std::result::Result<(InstructionAccounts, InstructionLoaders), LoadDiagnostic>> {
+        if txs.len() < 2 || num_threads <= 1 {
+            return self.load_accounts_internal(
+                ancestors,
+                txs,
+                lock_results,
+                fee_calculator,
+                error_counters,
+            );
+        }
+
+        let chunk_size = (txs.len() + num_threads - 1) / num_threads;
+        let tx_chunks: Vec<&[Transaction]> = txs.chunks(chunk_size).collect();
+        let lock_result_chunks: Vec<Vec<Result<()>>> = {
+            let mut remaining = lock_results;
+            tx_chunks
+                .iter()
+                .map(|chunk| remaining.drain(..chunk.len()).collect())
+                .collect()
+        };
+
+        let chunk_outputs: Vec<(Vec<_>, ErrorCounters)> = std::thread::scope(|scope| {
+            let handles: Vec<_> = tx_chunks
+                .into_iter()
+                .zip(lock_result_chunks.into_iter())
+                .map(|(tx_chunk, lock_result_chunk)| {
+                    scope.spawn(move || {
+                        let mut chunk_error_counters = ErrorCounters::default();
+                        let results = self.load_accounts_internal(
+                            ancestors,
+                            tx_chunk,
+                            lock_result_chunk,
+                            fee_calculator,
+                            &mut chunk_error_counters,
+                        );
+                        (results, chunk_error_counters)
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("account-loading worker panicked"))
+                .collect()
+        });
+
+        let mut loaded = Vec::with_capacity(txs.len());
+        for (mut chunk_results, chunk_error_counters) in chunk_outputs {
+            error_counters.account_in_use += chunk_error_counters.account_in_use;
+            error_counters.account_loaded_twice += chunk_error_counters.account_loaded_twice;
+            error_counters.account_not_found += chunk_error_counters.account_not_found;
+            error_counters.insufficient_funds += chunk_error_counters.insufficient_funds;
+            error_counters.call_chain_too_deep += chunk_error_counters.call_chain_too_deep;
+            loaded.append(&mut chunk_results);
+        }
+        loaded
+    }
+
     /// Store the accounts into the DB
     pub fn store_accounts(
         &self,
         fork: Fork,
         txs: &[Transaction],
         res: &[Result<()>],
-        loaded: &[Result<(InstructionAccounts, InstructionLoaders)>],
+        loaded: &[std::result::Result<(InstructionAccounts, InstructionLoaders), LoadDiagnostic>],
     ) {
         let mut accounts: Vec<(&Pubkey, &Account)> = vec![];
         for (i, raccs) in loaded.iter().enumerate() {
@@ -460,7 +806,7 @@ mod tests {
         ka: &Vec<(Pubkey, Account)>,
         fee_calculator: &FeeCalculator,
         error_counters: &mut ErrorCounters,
-    ) -> Vec<Result<(InstructionAccounts, InstructionLoaders)>> {
+    ) -> Vec<std::result::Result<(InstructionAccounts, InstructionLoaders), LoadDiagnostic>> {
         let accounts = Accounts::new(None);
         for ka in ka.iter() {
             accounts.store_slow(0, &ka.0, &ka.1);
@@ -481,7 +827,7 @@ mod tests {
         tx: Transaction,
         ka: &Vec<(Pubkey, Account)>,
         error_counters: &mut ErrorCounters,
-    ) -> Vec<Result<(InstructionAccounts, InstructionLoaders)>> {
+    ) -> Vec<std::result::Result<(InstructionAccounts, InstructionLoaders), LoadDiagnostic>> {
         let fee_calculator = FeeCalculator::default();
         load_accounts_with_fee(tx, ka, &fee_calculator, error_counters)
     }
@@ -504,7 +850,9 @@ mod tests {
 
         assert_eq!(error_counters.account_not_found, 1);
         assert_eq!(loaded_accounts.len(), 1);
-        assert_eq!(loaded_accounts[0], Err(TransactionError::AccountNotFound));
+        let diagnostic = loaded_accounts[0].as_ref().unwrap_err();
+        assert_eq!(diagnostic.error, TransactionError::AccountNotFound);
+        assert_eq!(diagnostic.category, LoadErrorCategory::AccountNotFound);
     }
 
     #[test]
@@ -527,7 +875,7 @@ mod tests {
 
         assert_eq!(error_counters.account_not_found, 1);
         assert_eq!(loaded_accounts.len(), 1);
-        assert_eq!(loaded_accounts[0], Err(TransactionError::AccountNotFound));
+        assert_eq!(loaded_accounts[0].as_ref().unwrap_err().error, TransactionError::AccountNotFound);
     }
 
     #[test]
@@ -558,7 +906,7 @@ mod tests {
 
         assert_eq!(error_counters.account_not_found, 1);
         assert_eq!(loaded_accounts.len(), 1);
-        assert_eq!(loaded_accounts[0], Err(TransactionError::AccountNotFound));
+        assert_eq!(loaded_accounts[0].as_ref().unwrap_err().error, TransactionError::AccountNotFound);
     }
 
     #[test]
@@ -590,8 +938,8 @@ mod tests {
         assert_eq!(error_counters.insufficient_funds, 1);
         assert_eq!(loaded_accounts.len(), 1);
         assert_eq!(
-            loaded_accounts[0],
-            Err(TransactionError::InsufficientFundsForFee)
+            loaded_accounts[0].as_ref().unwrap_err().error,
+            TransactionError::InsufficientFundsForFee
         );
     }
 
@@ -694,7 +1042,70 @@ mod tests {
 
         assert_eq!(error_counters.call_chain_too_deep, 1);
         assert_eq!(loaded_accounts.len(), 1);
-        assert_eq!(loaded_accounts[0], Err(TransactionError::CallChainTooDeep));
+        assert_eq!(loaded_accounts[0].as_ref().unwrap_err().error, TransactionError::CallChainTooDeep);
+    }
+
+    #[test]
+    fn test_load_accounts_configurable_call_chain_depth_limit() {
+        let mut ka: Vec<(Pubkey, Account)> = Vec::new();
+        let mut error_counters = ErrorCounters::default();
+
+        let keypair = Keypair::new();
+        let key0 = keypair.pubkey();
+        let key1 = Pubkey::new(&[5u8; 32]);
+        let key2 = Pubkey::new(&[6u8; 32]);
+
+        let account = Account::new(1, 1, &Pubkey::default());
+        ka.push((key0, account));
+
+        let mut account = Account::new(40, 1, &Pubkey::default());
+        account.executable = true;
+        account.owner = native_loader::id();
+        ka.push((key1, account));
+
+        let mut account = Account::new(41, 1, &Pubkey::default());
+        account.executable = true;
+        account.owner = key1;
+        ka.push((key2, account));
+
+        let instructions = vec![CompiledInstruction::new(0, &(), vec![0])];
+        let tx = Transaction::new_with_compiled_instructions(
+            &[&keypair],
+            &[],
+            Hash::default(),
+            vec![key2],
+            instructions,
+        );
+
+        let mut accounts = Accounts::new(None);
+        for (pubkey, account) in ka.iter() {
+            accounts.store_slow(0, pubkey, account);
+        }
+        let ancestors = vec![(0, 0)].into_iter().collect();
+        let fee_calculator = FeeCalculator::default();
+
+        // A depth-2 chain (key2 -> key1 -> native loader) loads fine under the default limit.
+        let loaded = accounts.load_accounts(
+            &ancestors,
+            &[tx.clone()],
+            vec![Ok(())],
+            &fee_calculator,
+            &mut error_counters,
+        );
+        assert!(loaded[0].is_ok());
+
+        // Tightening the limit below the chain's depth now fails.
+        accounts.set_call_chain_depth_limit(1);
+        let mut error_counters = ErrorCounters::default();
+        let loaded = accounts.load_accounts(
+            &ancestors,
+            &[tx],
+            vec![Ok(())],
+            &fee_calculator,
+            &mut error_counters,
+        );
+        assert_eq!(error_counters.call_chain_too_deep, 1);
+        assert_eq!(loaded[0].as_ref().unwrap_err().error, TransactionError::CallChainTooDeep);
     }
 
     #[test]
@@ -727,7 +1138,7 @@ mod tests {
 
         assert_eq!(error_counters.account_not_found, 1);
         assert_eq!(loaded_accounts.len(), 1);
-        assert_eq!(loaded_accounts[0], Err(TransactionError::AccountNotFound));
+        assert_eq!(loaded_accounts[0].as_ref().unwrap_err().error, TransactionError::AccountNotFound);
     }
 
     #[test]
@@ -759,7 +1170,7 @@ mod tests {
 
         assert_eq!(error_counters.account_not_found, 1);
         assert_eq!(loaded_accounts.len(), 1);
-        assert_eq!(loaded_accounts[0], Err(TransactionError::AccountNotFound));
+        assert_eq!(loaded_accounts[0].as_ref().unwrap_err().error, TransactionError::AccountNotFound);
     }
 
     #[test]
@@ -851,8 +1262,8 @@ mod tests {
         assert_eq!(loaded_accounts.len(), 1);
         loaded_accounts[0].clone().unwrap_err();
         assert_eq!(
-            loaded_accounts[0],
-            Err(TransactionError::AccountLoadedTwice)
+            loaded_accounts[0].as_ref().unwrap_err().error,
+            TransactionError::AccountLoadedTwice
         );
     }
 
@@ -878,4 +1289,161 @@ mod tests {
         let loaded = accounts.load_by_program(0, &Pubkey::new(&[4; 32]));
         assert_eq!(loaded, vec![]);
     }
+
+    #[test]
+    fn test_load_by_program_with_filters() {
+        let accounts = Accounts::new(None);
+        let program_id = Pubkey::new(&[7; 32]);
+
+        let pubkey0 = Pubkey::new_rand();
+        let mut account0 = Account::new(1, 0, &program_id);
+        account0.data = vec![1, 2, 3, 4];
+        accounts.store_slow(0, &pubkey0, &account0);
+
+        let pubkey1 = Pubkey::new_rand();
+        let mut account1 = Account::new(1, 0, &program_id);
+        account1.data = vec![9, 9, 3, 4, 5];
+        accounts.store_slow(0, &pubkey1, &account1);
+
+        // DataSize hit/miss
+        let loaded = accounts.load_by_program_with_filters(
+            0,
+            &program_id,
+            &[AccountFilter::DataSize(4)],
+        );
+        assert_eq!(loaded, vec![(pubkey0, account0.clone())]);
+
+        let loaded = accounts.load_by_program_with_filters(
+            0,
+            &program_id,
+            &[AccountFilter::DataSize(100)],
+        );
+        assert_eq!(loaded, vec![]);
+
+        // Memcmp hit/miss
+        let loaded = accounts.load_by_program_with_filters(
+            0,
+            &program_id,
+            &[AccountFilter::Memcmp {
+                offset: 2,
+                bytes: vec![3, 4],
+            }],
+        );
+        let mut loaded_pubkeys: Vec<Pubkey> = loaded.into_iter().map(|(k, _)| k).collect();
+        loaded_pubkeys.sort();
+        let mut expected = vec![pubkey0, pubkey1];
+        expected.sort();
+        assert_eq!(loaded_pubkeys, expected);
+
+        // An out-of-range memcmp offset is a non-match, not a panic.
+        let loaded = accounts.load_by_program_with_filters(
+            0,
+            &program_id,
+            &[AccountFilter::Memcmp {
+                offset: 1000,
+                bytes: vec![1],
+            }],
+        );
+        assert_eq!(loaded, vec![]);
+    }
+
+    #[test]
+    fn test_load_accounts_parallel() {
+        let accounts = Accounts::new(None);
+        let mut error_counters = ErrorCounters::default();
+
+        let mut ka: Vec<(Pubkey, Account)> = Vec::new();
+        let mut txs = Vec::new();
+        for _ in 0..8 {
+            let keypair = Keypair::new();
+            let key0 = keypair.pubkey();
+            ka.push((key0, Account::new(1, 1, &Pubkey::default())));
+            let instructions = vec![CompiledInstruction::new(0, &(), vec![0])];
+            txs.push(Transaction::new_with_compiled_instructions(
+                &[&keypair],
+                &[],
+                Hash::default(),
+                vec![native_loader::id()],
+                instructions,
+            ));
+        }
+        for (pubkey, account) in ka.iter() {
+            accounts.store_slow(0, pubkey, account);
+        }
+
+        let ancestors = vec![(0, 0)].into_iter().collect();
+        let fee_calculator = FeeCalculator::default();
+        let lock_results = vec![Ok(()); txs.len()];
+        let loaded = accounts.load_accounts_parallel(
+            &ancestors,
+            &txs,
+            lock_results,
+            &fee_calculator,
+            &mut error_counters,
+            4,
+        );
+
+        assert_eq!(loaded.len(), txs.len());
+        for (i, result) in loaded.iter().enumerate() {
+            match result {
+                Ok((accounts, _loaders)) => assert_eq!(accounts[0], ka[i].1),
+                Err(e) => panic!("unexpected load failure: {:?}", e),
+            }
+        }
+    }
+
+    #[test]
+    fn test_lock_accounts_allows_concurrent_readers_of_shared_program_id() {
+        let accounts = Accounts::new(None);
+        let program_id = Pubkey::new(&[7u8; 32]);
+        let instructions = vec![CompiledInstruction::new(0, &(), vec![0])];
+
+        let tx_a = Transaction::new_with_compiled_instructions(
+            &[&Keypair::new()],
+            &[],
+            Hash::default(),
+            vec![program_id],
+            instructions.clone(),
+        );
+        let tx_b = Transaction::new_with_compiled_instructions(
+            &[&Keypair::new()],
+            &[],
+            Hash::default(),
+            vec![program_id],
+            instructions,
+        );
+
+        let results = accounts.lock_accounts(&[tx_a, tx_b]);
+        assert!(results[0].is_ok());
+        assert!(results[1].is_ok());
+    }
+
+    #[test]
+    fn test_lock_accounts_rejects_writer_on_already_locked_readonly_program_id() {
+        let accounts = Accounts::new(None);
+        let program_id = Pubkey::new(&[7u8; 32]);
+        let instructions = vec![CompiledInstruction::new(0, &(), vec![0])];
+
+        let reader_tx = Transaction::new_with_compiled_instructions(
+            &[&Keypair::new()],
+            &[],
+            Hash::default(),
+            vec![program_id],
+            instructions.clone(),
+        );
+        let reader_result = accounts.lock_accounts(&[reader_tx]).remove(0);
+        assert!(reader_result.is_ok());
+
+        // `program_id` is locked as a reader above; a transaction that names it
+        // among its own (writable) account_keys must collide with that lock.
+        let writer_tx = Transaction::new_with_compiled_instructions(
+            &[&Keypair::new()],
+            &[program_id],
+            Hash::default(),
+            vec![native_loader::id()],
+            instructions,
+        );
+        let writer_result = accounts.lock_accounts(&[writer_tx]).remove(0);
+        assert_eq!(writer_result.unwrap_err(), TransactionError::AccountInUse);
+    }
 }
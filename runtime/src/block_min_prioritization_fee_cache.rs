@@ -4,20 +4,155 @@ use {
         block_min_prioritization_fee_cache_query::BlockMinPrioritizationFeeCacheQuery,
         block_min_prioritization_fee_cache_update::BlockMinPrioritizationFeeCacheUpdate,
     },
-    log::*,
-    solana_sdk::{clock::Slot, pubkey::Pubkey, transaction::SanitizedTransaction},
+    solana_metrics::datapoint_info,
+    solana_program_runtime::compute_budget::ComputeBudget,
+    solana_sdk::{
+        clock::Slot, message::v0::MessageAddressTableLookup, pubkey::Pubkey,
+        timing::AtomicInterval, transaction::SanitizedTransaction,
+    },
     std::collections::HashMap,
 };
 
+/// Resolves an on-chain Address Lookup Table's currently active address
+/// list, so a v0 message's `MessageAddressTableLookup` indices can be mapped
+/// back to the real `Pubkey`s they reference. Analogous to the external
+/// sidecar's `ALTStore`.
+pub trait AddressLookupTableResolver {
+    /// Returns `table_key`'s currently active addresses, or `None` if the
+    /// table hasn't been (or can no longer be) resolved.
+    fn resolve_lookup_table(&self, table_key: &Pubkey) -> Option<Vec<Pubkey>>;
+}
+
+/// Maps each of `lookups`' writable indices to the real `Pubkey`s they
+/// reference via `resolver`, skipping (rather than failing the block
+/// update) any table `resolver` can't resolve yet.
+fn resolve_writable_lookup_table_accounts(
+    lookups: &[MessageAddressTableLookup],
+    resolver: &dyn AddressLookupTableResolver,
+) -> Vec<Pubkey> {
+    let mut writable_accounts = Vec::new();
+    for lookup in lookups {
+        let Some(table_addresses) = resolver.resolve_lookup_table(&lookup.account_key) else {
+            continue;
+        };
+        for &index in &lookup.writable_indexes {
+            if let Some(address) = table_addresses.get(index as usize) {
+                writable_accounts.push(*address);
+            }
+        }
+    }
+    writable_accounts
+}
+
+/// Parses out the compute-unit limit a transaction requested via
+/// `ComputeBudgetInstruction::SetComputeUnitLimit`, or the implicit default
+/// if it didn't set one explicitly.
+fn get_transaction_cu_requested(tx: &SanitizedTransaction) -> u64 {
+    ComputeBudget::get_compute_budget_limits(
+        tx.message().program_instructions_iter(),
+        true,
+        true,
+        true,
+        true,
+        true,
+    )
+    .map(|limits| limits.compute_unit_limit as u64)
+    .unwrap_or_default()
+}
+
 /// The maximum number of blocks to keep in `BlockMinPrioritizationFeeCache`; States from
 /// up to 150 recent blocks should be sufficient to estimate minimal prioritization fee to
 /// land transactions to current block.
 const NUMBER_OF_RECENT_BLOCKS: usize = 150;
 
+/// How often `BlockMinPrioritizationFeeCache` is allowed to emit its
+/// `datapoint_info!` metrics, so a busy validator doesn't flood the metrics
+/// pipeline with one point per finalized block.
+const METRICS_REPORT_INTERVAL_MS: u64 = 30_000;
+
+/// Counters tracking the outcome of cache updates, reported periodically via
+/// `datapoint_info!` so the subsystem is observable the way the rest of the
+/// validator already is.
+#[derive(Default)]
+struct BlockMinPrioritizationFeeCacheMetrics {
+    successful_transaction_update_count: u64,
+    fail_get_transaction_priority_details_count: u64,
+    fail_get_transaction_account_locks_count: u64,
+    fail_finalize_block_not_found_count: u64,
+    last_report: AtomicInterval,
+}
+
+impl BlockMinPrioritizationFeeCacheMetrics {
+    fn maybe_report(&self, available_block_count: usize, total_slots_retained: usize) {
+        if self.last_report.should_update(METRICS_REPORT_INTERVAL_MS) {
+            datapoint_info!(
+                "block-min-prioritization-fee-cache-stats",
+                (
+                    "successful_transaction_update_count",
+                    self.successful_transaction_update_count,
+                    i64
+                ),
+                (
+                    "fail_get_transaction_priority_details_count",
+                    self.fail_get_transaction_priority_details_count,
+                    i64
+                ),
+                (
+                    "fail_get_transaction_account_locks_count",
+                    self.fail_get_transaction_account_locks_count,
+                    i64
+                ),
+                (
+                    "fail_finalize_block_not_found_count",
+                    self.fail_finalize_block_not_found_count,
+                    i64
+                ),
+                ("available_block_count", available_block_count, i64),
+                ("total_slots_retained", total_slots_retained, i64),
+            );
+        }
+    }
+}
+
 /// Holds up to NUMBER_OF_RECENT_BLOCKS recent block's min prioritization fee for block,
 /// and for each writable accounts per block.
 pub struct BlockMinPrioritizationFeeCache {
     cache: HashMap<Slot, BlockMinPrioritizationFee>,
+    capacity: usize,
+    metrics: BlockMinPrioritizationFeeCacheMetrics,
+}
+
+/// Percentile statistics computed over a set of finalized-block fee samples,
+/// so RPC/estimator callers don't each have to re-sort and re-index the raw
+/// `Vec<u64>` returned by `get_block_min_prioritization_fees`/
+/// `get_account_min_prioritization_fees`. `None` for the percentile fields on
+/// an empty or single-element sample, where a percentile isn't meaningful.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct PrioritizationFeeStats {
+    pub min: Option<u64>,
+    pub max: Option<u64>,
+    pub median: Option<u64>,
+    pub p75: Option<u64>,
+    pub p90: Option<u64>,
+    pub p95: Option<u64>,
+}
+
+fn compute_prioritization_fee_stats(mut fees: Vec<u64>) -> PrioritizationFeeStats {
+    if fees.is_empty() {
+        return PrioritizationFeeStats::default();
+    }
+    fees.sort_unstable();
+
+    let len = fees.len();
+    let percentile = |p: usize| (len > 1).then(|| fees[len * p / 100]);
+    PrioritizationFeeStats {
+        min: fees.first().copied(),
+        max: fees.last().copied(),
+        median: percentile(50),
+        p75: percentile(75),
+        p90: percentile(90),
+        p95: percentile(95),
+    }
 }
 
 impl Default for BlockMinPrioritizationFeeCache {
@@ -30,9 +165,20 @@ impl BlockMinPrioritizationFeeCache {
     pub fn new(capacity: usize) -> Self {
         BlockMinPrioritizationFeeCache {
             cache: HashMap::with_capacity(capacity),
+            capacity,
+            metrics: BlockMinPrioritizationFeeCacheMetrics::default(),
         }
     }
 
+    /// Drops any cached slot at or before `current_slot - capacity`, keeping
+    /// at most the last `capacity` slots up to `current_slot`, so the
+    /// cache doesn't grow unboundedly as the validator keeps running.
+    pub fn prune_old_slots(&mut self, current_slot: Slot) {
+        let capacity = self.capacity as Slot;
+        self.cache
+            .retain(|&slot, _block| current_slot.saturating_sub(slot) < capacity);
+    }
+
     #[allow(dead_code)]
     fn get_block_min_prioritization_fee(&self, slot: &Slot) -> Option<&BlockMinPrioritizationFee> {
         self.cache.get(slot)
@@ -56,27 +202,62 @@ impl BlockMinPrioritizationFeeCache {
 }
 
 impl BlockMinPrioritizationFeeCacheUpdate for BlockMinPrioritizationFeeCache {
-    /// Update block's min prioritization fee with `txs`,
+    /// Update block's min prioritization fee with `txs`, each paired with the
+    /// compute units replay reported it actually consumed. The requested
+    /// compute-unit limit is parsed from each transaction's own
+    /// `ComputeBudgetInstruction::SetComputeUnitLimit`, so only the
+    /// post-execution `cu_consumed` needs to come from the caller.
+    ///
+    /// When `alt_resolver` is given, writable accounts a v0 transaction
+    /// loads via an Address Lookup Table are also attributed the fee;
+    /// `alt_resolver` resolving `None` for a given table skips attribution
+    /// for that table's accounts rather than failing the update.
+    ///
     /// Returns updated min prioritization fee for `slot`
     fn update_transactions<'a>(
         &mut self,
         slot: Slot,
-        txs: impl Iterator<Item = &'a SanitizedTransaction>,
+        txs: impl Iterator<Item = (&'a SanitizedTransaction, u64)>,
+        alt_resolver: Option<&dyn AddressLookupTableResolver>,
     ) -> Option<u64> {
-        let block = self.get_or_add_mut_block_min_prioritization_fee(&slot);
-
-        for sanitized_tx in txs {
-            match block.update_for_transaction(sanitized_tx) {
-                Err(BlockMinPrioritizationFeeError::FailGetTransactionPriorityDetails) => {
-                    debug!("TODO -- fail get tx priority details")
-                } //self.inc_fail_get_transaction_priority_details_count(),
-                Err(BlockMinPrioritizationFeeError::FailGetTransactionAccountLocks) => {
-                    debug!("TODO -- fail get account locks")
-                } //self.inc_fail_get_transaction_account_locks_count(),
-                _ => debug!("TODO -- succeeded"), //self.inc_success_transaction_update_count(),
+        let (mut success_count, mut fail_priority_details_count, mut fail_account_locks_count) =
+            (0u64, 0u64, 0u64);
+        let block_fee = {
+            let block = self.get_or_add_mut_block_min_prioritization_fee(&slot);
+            for (sanitized_tx, cu_consumed) in txs {
+                let cu_requested = get_transaction_cu_requested(sanitized_tx);
+                let lookup_table_writable_accounts = alt_resolver
+                    .map(|resolver| {
+                        resolve_writable_lookup_table_accounts(
+                            sanitized_tx.message().message_address_table_lookups(),
+                            resolver,
+                        )
+                    })
+                    .unwrap_or_default();
+                match block.update_for_transaction(
+                    sanitized_tx,
+                    cu_requested,
+                    cu_consumed,
+                    &lookup_table_writable_accounts,
+                ) {
+                    Err(BlockMinPrioritizationFeeError::FailGetTransactionPriorityDetails) => {
+                        fail_priority_details_count += 1;
+                    }
+                    Err(BlockMinPrioritizationFeeError::FailGetTransactionAccountLocks) => {
+                        fail_account_locks_count += 1;
+                    }
+                    _ => success_count += 1,
+                }
             }
-        }
-        block.get_block_fee()
+            block.get_block_fee()
+        };
+
+        self.metrics.successful_transaction_update_count += success_count;
+        self.metrics.fail_get_transaction_priority_details_count += fail_priority_details_count;
+        self.metrics.fail_get_transaction_account_locks_count += fail_account_locks_count;
+        self.metrics
+            .maybe_report(self.available_block_count(), self.cache.len());
+        block_fee
     }
 
     /// bank is completely replayed from blockstore, prune irrelevant accounts to save space,
@@ -86,8 +267,11 @@ impl BlockMinPrioritizationFeeCacheUpdate for BlockMinPrioritizationFeeCache {
             block.prune_irrelevant_accounts();
             let _ = block.mark_block_completed();
         } else {
-            debug!("TODO"); //self.inc_fail_finalize_block_not_found();
+            self.metrics.fail_finalize_block_not_found_count += 1;
         }
+        self.prune_old_slots(slot);
+        self.metrics
+            .maybe_report(self.available_block_count(), self.cache.len());
     }
 }
 
@@ -131,6 +315,46 @@ impl BlockMinPrioritizationFeeCacheQuery for BlockMinPrioritizationFeeCache {
             .flatten()
             .collect()
     }
+
+    /// Query given account's requested compute units from finalized blocks
+    /// in cache, so callers can tell which hot accounts are driving demand
+    /// even when the transactions touching them paid a low fee.
+    fn get_account_cu_requested(&self, account_key: &Pubkey) -> Vec<u64> {
+        self.cache
+            .iter()
+            .filter_map(|(_slot, block_min_prioritization_fee)| {
+                block_min_prioritization_fee
+                    .is_finalized()
+                    .then(|| block_min_prioritization_fee.get_account_cu_requested(account_key))
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Query given account's consumed compute units from finalized blocks in
+    /// cache, so callers can tell which hot accounts are driving block
+    /// saturation, not just which demanded the highest fee.
+    fn get_account_cu_consumed(&self, account_key: &Pubkey) -> Vec<u64> {
+        self.cache
+            .iter()
+            .filter_map(|(_slot, block_min_prioritization_fee)| {
+                block_min_prioritization_fee
+                    .is_finalized()
+                    .then(|| block_min_prioritization_fee.get_account_cu_consumed(account_key))
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// Percentile statistics over `get_block_min_prioritization_fees()`.
+    fn get_block_fee_stats(&self) -> PrioritizationFeeStats {
+        compute_prioritization_fee_stats(self.get_block_min_prioritization_fees())
+    }
+
+    /// Percentile statistics over `get_account_min_prioritization_fees(account_key)`.
+    fn get_account_fee_stats(&self, account_key: &Pubkey) -> PrioritizationFeeStats {
+        compute_prioritization_fee_stats(self.get_account_min_prioritization_fees(account_key))
+    }
 }
 
 #[cfg(test)]
@@ -187,7 +411,7 @@ mod tests {
         assert_eq!(
             2,
             block_min_prioritization_fee_cache
-                .update_transactions(slot, txs.iter())
+                .update_transactions(slot, txs.iter().map(|tx| (tx, 0)), None)
                 .unwrap()
         );
 
@@ -273,7 +497,7 @@ mod tests {
             assert_eq!(
                 5,
                 block_min_prioritization_fee_cache
-                    .update_transactions(1, txs.iter())
+                    .update_transactions(1, txs.iter().map(|tx| (tx, 0)), None)
                     .unwrap()
             );
             // before block is marked as completed
@@ -298,7 +522,7 @@ mod tests {
             assert_eq!(
                 9,
                 block_min_prioritization_fee_cache
-                    .update_transactions(2, txs.iter())
+                    .update_transactions(2, txs.iter().map(|tx| (tx, 0)), None)
                     .unwrap()
             );
             // before block is marked as completed
@@ -324,7 +548,7 @@ mod tests {
             assert_eq!(
                 2,
                 block_min_prioritization_fee_cache
-                    .update_transactions(3, txs.iter())
+                    .update_transactions(3, txs.iter().map(|tx| (tx, 0)), None)
                     .unwrap()
             );
             // before block is marked as completed
@@ -371,7 +595,7 @@ mod tests {
                     &Pubkey::new_unique(),
                 ),
             ];
-            block_min_prioritization_fee_cache.update_transactions(1, txs.iter());
+            block_min_prioritization_fee_cache.update_transactions(1, txs.iter().map(|tx| (tx, 0)), None);
             // before block is marked as completed
             assert!(block_min_prioritization_fee_cache
                 .get_account_min_prioritization_fees(&write_account_a)
@@ -409,7 +633,7 @@ mod tests {
                     &Pubkey::new_unique(),
                 ),
             ];
-            block_min_prioritization_fee_cache.update_transactions(2, txs.iter());
+            block_min_prioritization_fee_cache.update_transactions(2, txs.iter().map(|tx| (tx, 0)), None);
             // before block is marked as completed
             assert_eq!(
                 vec![5],
@@ -453,7 +677,7 @@ mod tests {
                     &Pubkey::new_unique(),
                 ),
             ];
-            block_min_prioritization_fee_cache.update_transactions(3, txs.iter());
+            block_min_prioritization_fee_cache.update_transactions(3, txs.iter().map(|tx| (tx, 0)), None);
             // before block is marked as completed
             assert_eq!(
                 vec![5],
@@ -489,4 +713,174 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_prune_old_slots_bounds_cache_size() {
+        let mut block_min_prioritization_fee_cache =
+            BlockMinPrioritizationFeeCache::new(NUMBER_OF_RECENT_BLOCKS);
+
+        for slot in 1..=(NUMBER_OF_RECENT_BLOCKS as Slot * 2) {
+            block_min_prioritization_fee_cache.get_or_add_mut_block_min_prioritization_fee(&slot);
+            block_min_prioritization_fee_cache.finalize_block(slot);
+            assert!(block_min_prioritization_fee_cache.cache.len() <= NUMBER_OF_RECENT_BLOCKS);
+            assert!(
+                block_min_prioritization_fee_cache.available_block_count()
+                    <= NUMBER_OF_RECENT_BLOCKS
+            );
+        }
+    }
+
+    #[test]
+    fn test_compute_prioritization_fee_stats() {
+        // empty sample: everything is None
+        assert_eq!(
+            PrioritizationFeeStats::default(),
+            compute_prioritization_fee_stats(vec![])
+        );
+
+        // single-element sample: min/max are defined, percentiles are not
+        assert_eq!(
+            PrioritizationFeeStats {
+                min: Some(5),
+                max: Some(5),
+                median: None,
+                p75: None,
+                p90: None,
+                p95: None,
+            },
+            compute_prioritization_fee_stats(vec![5])
+        );
+
+        // 100-element sample: percentiles land on the expected index
+        let fees: Vec<u64> = (1..=100).collect();
+        assert_eq!(
+            PrioritizationFeeStats {
+                min: Some(1),
+                max: Some(100),
+                median: Some(51),
+                p75: Some(76),
+                p90: Some(91),
+                p95: Some(96),
+            },
+            compute_prioritization_fee_stats(fees)
+        );
+    }
+
+    #[test]
+    fn test_get_block_fee_stats() {
+        let write_account_a = Pubkey::new_unique();
+        let write_account_b = Pubkey::new_unique();
+
+        let mut block_min_prioritization_fee_cache = BlockMinPrioritizationFeeCache::default();
+        assert_eq!(
+            PrioritizationFeeStats::default(),
+            block_min_prioritization_fee_cache.get_block_fee_stats()
+        );
+
+        for (slot, fee) in [(1, 5), (2, 9), (3, 2)] {
+            let txs = vec![build_sanitized_transaction_for_test(
+                fee,
+                &write_account_a,
+                &write_account_b,
+            )];
+            block_min_prioritization_fee_cache.update_transactions(slot, txs.iter().map(|tx| (tx, 0)), None);
+            block_min_prioritization_fee_cache.finalize_block(slot);
+        }
+
+        assert_eq!(
+            PrioritizationFeeStats {
+                min: Some(2),
+                max: Some(9),
+                median: Some(5),
+                p75: Some(9),
+                p90: Some(9),
+                p95: Some(9),
+            },
+            block_min_prioritization_fee_cache.get_block_fee_stats()
+        );
+    }
+
+    #[test]
+    fn test_get_account_cu_requested_and_consumed() {
+        let write_account_a = Pubkey::new_unique();
+        let write_account_b = Pubkey::new_unique();
+
+        let transaction = Transaction::new_unsigned(Message::new(
+            &[
+                system_instruction::transfer(&write_account_a, &write_account_b, 1),
+                ComputeBudgetInstruction::set_compute_unit_limit(100_000),
+            ],
+            Some(&write_account_a),
+        ));
+        let tx = SanitizedTransaction::try_from_legacy_transaction(transaction).unwrap();
+        let txs = vec![tx];
+
+        let mut block_min_prioritization_fee_cache = BlockMinPrioritizationFeeCache::default();
+        let slot = 1;
+        block_min_prioritization_fee_cache
+            .update_transactions(slot, txs.iter().map(|tx| (tx, 1_234)), None);
+
+        // before block is marked as completed
+        assert!(block_min_prioritization_fee_cache
+            .get_account_cu_requested(&write_account_a)
+            .is_empty());
+        assert!(block_min_prioritization_fee_cache
+            .get_account_cu_consumed(&write_account_a)
+            .is_empty());
+
+        block_min_prioritization_fee_cache.finalize_block(slot);
+
+        assert_eq!(
+            vec![100_000],
+            block_min_prioritization_fee_cache.get_account_cu_requested(&write_account_a)
+        );
+        assert_eq!(
+            vec![1_234],
+            block_min_prioritization_fee_cache.get_account_cu_consumed(&write_account_a)
+        );
+    }
+
+    struct TestAddressLookupTableResolver {
+        table_key: Pubkey,
+        addresses: Vec<Pubkey>,
+    }
+
+    impl AddressLookupTableResolver for TestAddressLookupTableResolver {
+        fn resolve_lookup_table(&self, table_key: &Pubkey) -> Option<Vec<Pubkey>> {
+            (table_key == &self.table_key).then(|| self.addresses.clone())
+        }
+    }
+
+    #[test]
+    fn test_resolve_writable_lookup_table_accounts() {
+        let lookup_table_key = Pubkey::new_unique();
+        let resolvable_account = Pubkey::new_unique();
+        let unresolvable_table_key = Pubkey::new_unique();
+
+        let resolver = TestAddressLookupTableResolver {
+            table_key: lookup_table_key,
+            addresses: vec![Pubkey::new_unique(), resolvable_account],
+        };
+
+        // A versioned (v0) message's writable account loaded from a resolvable
+        // lookup table is mapped back to its real `Pubkey`.
+        let lookups = vec![MessageAddressTableLookup {
+            account_key: lookup_table_key,
+            writable_indexes: vec![1],
+            readonly_indexes: vec![],
+        }];
+        assert_eq!(
+            vec![resolvable_account],
+            resolve_writable_lookup_table_accounts(&lookups, &resolver)
+        );
+
+        // A lookup referencing a table the resolver doesn't recognize is
+        // skipped rather than failing the whole resolution.
+        let unresolvable_lookups = vec![MessageAddressTableLookup {
+            account_key: unresolvable_table_key,
+            writable_indexes: vec![0],
+            readonly_indexes: vec![],
+        }];
+        assert!(resolve_writable_lookup_table_accounts(&unresolvable_lookups, &resolver).is_empty());
+    }
 }
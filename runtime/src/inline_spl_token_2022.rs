@@ -0,0 +1,222 @@
+//! Just enough of the Token-2022 account/mint layout to let the secondary
+//! index recognize Token-2022 accounts from account data alone, mirroring
+//! how `inline_spl_token` inlines legacy SPL Token's layout rather than
+//! depending on the `spl-token-2022` crate directly.
+//!
+//! Token-2022's base account and mint layouts are byte-for-byte identical
+//! to legacy SPL Token's (a token account's mint sits at bytes 0..32 and
+//! owner at 32..64; a mint's fields sit at the same fixed offsets), but
+//! Token-2022 can append TLV extension data after the 165-byte base
+//! length. An extended mint can therefore be exactly as long as a base
+//! token account, so data length alone can't tell them apart the way it
+//! can for legacy SPL Token. Byte 165 resolves the ambiguity: it's an
+//! account-type discriminator (0 = uninitialized/base, 1 = Account,
+//! 2 = Mint) that Token-2022 always writes once an account's length
+//! exceeds the base layout.
+//!
+//! NOTE: `account_index_from_rpc_account_index` and the secondary-index
+//! parsing path (`accounts_index`/`secondary_index`, which currently only
+//! recognizes `inline_spl_token::id()`) aren't present in this tree, so
+//! this module isn't wired into `AccountIndex::SplTokenOwner`/
+//! `SplTokenMint` population yet. It lands the parsing primitives that
+//! wiring needs.
+//!
+//! `AccountIndex`/`RpcAccountIndex` (from `solana_runtime::accounts_index`
+//! and `solana_rpc_client_api::config` respectively) aren't present as
+//! local files in this tree either, so adding `SplTokenDelegate`/
+//! `SplTokenCloseAuthority` variants to them, and the indexing-pipeline
+//! wiring those variants would need, isn't possible here. `unpack_account_
+//! delegate` and `unpack_account_close_authority` below land the same
+//! kind of parsing primitive for those two fields, ready for that wiring
+//! once the enums and indexing pipeline exist in this tree.
+
+use solana_sdk::pubkey::Pubkey;
+
+solana_sdk::declare_id!("TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb");
+
+/// Length of the base (pre-extension) account and mint layouts; legacy SPL
+/// Token and Token-2022 agree on this for the base fields.
+pub const ACCOUNT_LEN: usize = 165;
+const MINT_LEN: usize = 82;
+
+/// Offset and length of the `COption<Pubkey>` delegate field within the
+/// base account layout: a 4-byte tag (0 = `None`, 1 = `Some`) followed by
+/// the 32-byte pubkey when present.
+const DELEGATE_OFFSET: usize = 72;
+
+/// Offset of the `COption<Pubkey>` close-authority field within the base
+/// account layout, same tag-then-pubkey encoding as `delegate`.
+const CLOSE_AUTHORITY_OFFSET: usize = 129;
+
+/// Offset of the account-type discriminator byte Token-2022 appends right
+/// after the base account layout. Never present on legacy SPL Token data,
+/// since legacy accounts never extend past `ACCOUNT_LEN`.
+const ACCOUNT_TYPE_OFFSET: usize = ACCOUNT_LEN;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum AccountType {
+    Account,
+    Mint,
+}
+
+// Resolves whether `data` is an account or a mint. Base-length data (no
+// extensions, so no discriminator byte written yet) is disambiguated by
+// length alone; anything longer is disambiguated by the discriminator.
+fn account_type(data: &[u8]) -> Option<AccountType> {
+    match data.get(ACCOUNT_TYPE_OFFSET) {
+        None if data.len() == ACCOUNT_LEN => Some(AccountType::Account),
+        None if data.len() == MINT_LEN => Some(AccountType::Mint),
+        None => None,
+        Some(1) => Some(AccountType::Account),
+        Some(2) => Some(AccountType::Mint),
+        Some(_) => None, // 0 (uninitialized) or an unrecognized discriminator
+    }
+}
+
+/// True if `data` is recognizable Token-2022 account or mint data.
+pub fn valid_account_data(data: &[u8]) -> bool {
+    data.len() >= MINT_LEN && account_type(data).is_some()
+}
+
+/// Unpacks the owner out of Token-2022 token account data, or `None` if
+/// `data` isn't a (non-extended-ambiguous) token account.
+pub fn unpack_account_owner(data: &[u8]) -> Option<Pubkey> {
+    match account_type(data) {
+        Some(AccountType::Account) => data.get(32..64).map(Pubkey::new),
+        _ => None,
+    }
+}
+
+/// Unpacks the mint out of Token-2022 token account data, or `None` if
+/// `data` isn't a (non-extended-ambiguous) token account.
+pub fn unpack_account_mint(data: &[u8]) -> Option<Pubkey> {
+    match account_type(data) {
+        Some(AccountType::Account) => data.get(0..32).map(Pubkey::new),
+        _ => None,
+    }
+}
+
+// Unpacks a `COption<Pubkey>` field at `offset`: a 4-byte little-endian tag
+// (0 = `None`, 1 = `Some`) followed by the pubkey when present.
+fn unpack_coption_pubkey(data: &[u8], offset: usize) -> Option<Pubkey> {
+    let tag = data.get(offset..offset + 4)?;
+    if tag != [1, 0, 0, 0] {
+        return None;
+    }
+    data.get(offset + 4..offset + 36).map(Pubkey::new)
+}
+
+/// Unpacks the delegate out of Token-2022 token account data, or `None` if
+/// `data` isn't a (non-extended-ambiguous) token account or has no
+/// delegate set.
+pub fn unpack_account_delegate(data: &[u8]) -> Option<Pubkey> {
+    match account_type(data) {
+        Some(AccountType::Account) => unpack_coption_pubkey(data, DELEGATE_OFFSET),
+        _ => None,
+    }
+}
+
+/// Unpacks the close authority out of Token-2022 token account data, or
+/// `None` if `data` isn't a (non-extended-ambiguous) token account or has
+/// no close authority set.
+pub fn unpack_account_close_authority(data: &[u8]) -> Option<Pubkey> {
+    match account_type(data) {
+        Some(AccountType::Account) => unpack_coption_pubkey(data, CLOSE_AUTHORITY_OFFSET),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_data(mint: Pubkey, owner: Pubkey, account_type_byte: Option<u8>) -> Vec<u8> {
+        let mut data = vec![0u8; ACCOUNT_LEN];
+        data[0..32].copy_from_slice(mint.as_ref());
+        data[32..64].copy_from_slice(owner.as_ref());
+        if let Some(byte) = account_type_byte {
+            data.push(byte);
+        }
+        data
+    }
+
+    fn set_coption_pubkey(data: &mut [u8], offset: usize, pubkey: Option<Pubkey>) {
+        match pubkey {
+            Some(pubkey) => {
+                data[offset..offset + 4].copy_from_slice(&1u32.to_le_bytes());
+                data[offset + 4..offset + 36].copy_from_slice(pubkey.as_ref());
+            }
+            None => data[offset..offset + 4].copy_from_slice(&0u32.to_le_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_base_account_has_no_discriminator() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let data = account_data(mint, owner, None);
+
+        assert!(valid_account_data(&data));
+        assert_eq!(unpack_account_owner(&data), Some(owner));
+        assert_eq!(unpack_account_mint(&data), Some(mint));
+    }
+
+    #[test]
+    fn test_extended_account_uses_discriminator() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut data = account_data(mint, owner, Some(1));
+        data.extend_from_slice(&[0xAA; 16]); // arbitrary TLV extension bytes
+
+        assert!(valid_account_data(&data));
+        assert_eq!(unpack_account_owner(&data), Some(owner));
+        assert_eq!(unpack_account_mint(&data), Some(mint));
+    }
+
+    #[test]
+    fn test_extended_mint_is_not_mistaken_for_an_account() {
+        // An extended mint can be padded out to exactly ACCOUNT_LEN bytes,
+        // the same length as a base token account; only the discriminator
+        // byte at ACCOUNT_TYPE_OFFSET distinguishes them.
+        let mut data = vec![0u8; ACCOUNT_LEN];
+        data.push(2); // Mint discriminator
+
+        assert!(valid_account_data(&data));
+        assert_eq!(unpack_account_owner(&data), None);
+        assert_eq!(unpack_account_mint(&data), None);
+    }
+
+    #[test]
+    fn test_uninitialized_discriminator_is_not_indexed() {
+        let mut data = vec![0u8; ACCOUNT_LEN];
+        data.push(0);
+
+        assert!(!valid_account_data(&data));
+    }
+
+    #[test]
+    fn test_unpack_account_delegate_and_close_authority() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let close_authority = Pubkey::new_unique();
+        let mut data = account_data(mint, owner, None);
+        set_coption_pubkey(&mut data, DELEGATE_OFFSET, Some(delegate));
+        set_coption_pubkey(&mut data, CLOSE_AUTHORITY_OFFSET, Some(close_authority));
+
+        assert_eq!(unpack_account_delegate(&data), Some(delegate));
+        assert_eq!(unpack_account_close_authority(&data), Some(close_authority));
+    }
+
+    #[test]
+    fn test_unpack_account_delegate_and_close_authority_absent() {
+        let mint = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut data = account_data(mint, owner, None);
+        set_coption_pubkey(&mut data, DELEGATE_OFFSET, None);
+        set_coption_pubkey(&mut data, CLOSE_AUTHORITY_OFFSET, None);
+
+        assert_eq!(unpack_account_delegate(&data), None);
+        assert_eq!(unpack_account_close_authority(&data), None);
+    }
+}
@@ -21,6 +21,15 @@ pub struct RentCollector {
     // snapshot restore)
     #[serde(skip)]
     pub operating_mode: Option<OperatingMode>,
+    /// Epoch-ordered table of rent-parameter changes, sourced from
+    /// `GenesisConfig::rent_schedule` and sorted ascending by activation
+    /// epoch. A staged change takes effect for any epoch at or after its
+    /// listed activation epoch, letting an operating network evolve rent
+    /// economics (lamports-per-byte-year, exemption threshold) by
+    /// publishing new entries rather than shipping a binary with a
+    /// hardcoded `OperatingMode` switch. Empty means `rent` applies for
+    /// every epoch, as before this table existed.
+    pub rent_schedule: Vec<(Epoch, Rent)>,
 }
 
 impl Default for RentCollector {
@@ -32,6 +41,7 @@ impl Default for RentCollector {
             slots_per_year: GenesisConfig::default().slots_per_year(),
             rent: Rent::default(),
             operating_mode: Option::default(),
+            rent_schedule: Vec::default(),
         }
     }
 }
@@ -43,13 +53,17 @@ impl RentCollector {
         slots_per_year: f64,
         rent: &Rent,
         operating_mode: OperatingMode,
+        genesis_config: &GenesisConfig,
     ) -> Self {
+        let mut rent_schedule = genesis_config.rent_schedule.clone();
+        rent_schedule.sort_by_key(|(activation_epoch, _)| *activation_epoch);
         Self {
             epoch,
             epoch_schedule: *epoch_schedule,
             slots_per_year,
             rent: *rent,
             operating_mode: Some(operating_mode),
+            rent_schedule,
         }
     }
 
@@ -61,6 +75,53 @@ impl RentCollector {
         }
     }
 
+    /// Returns the `Rent` parameters in force for `epoch`: the most
+    /// recently activated entry in `rent_schedule` at or before `epoch`,
+    /// falling back to `self.rent` if `epoch` precedes every schedule
+    /// entry (or the schedule is empty).
+    fn rent_for_epoch(&self, epoch: Epoch) -> Rent {
+        self.rent_schedule
+            .iter()
+            .rev()
+            .find(|(activation_epoch, _)| *activation_epoch <= epoch)
+            .map(|(_, rent)| *rent)
+            .unwrap_or(self.rent)
+    }
+
+    /// Computes rent owed (and final exemption status) for `account_epoch..=self.epoch`,
+    /// processing it one run of epochs at a time, where a "run" is the
+    /// longest stretch sharing the same `Rent` parameters. This keeps a
+    /// `rent_schedule` activation boundary that falls inside the window
+    /// from silently being collected under the wrong `Rent`.
+    fn rent_due(&self, lamports: u64, data_len: usize, account_epoch: Epoch) -> (u64, bool) {
+        let mut total_due = 0u64;
+        let mut remaining_lamports = lamports;
+        let mut exempt = false;
+        let mut epoch = account_epoch;
+        while epoch <= self.epoch {
+            let rent = self.rent_for_epoch(epoch);
+            let mut run_end = epoch;
+            while run_end < self.epoch && self.rent_for_epoch(run_end + 1) == rent {
+                run_end += 1;
+            }
+
+            let slots_elapsed: u64 = (epoch..=run_end)
+                .map(|epoch| self.epoch_schedule.get_slots_in_epoch(epoch + 1))
+                .sum();
+
+            let (due, is_exempt) = rent.due(
+                remaining_lamports,
+                data_len,
+                slots_elapsed as f64 / self.slots_per_year,
+            );
+            total_due += due;
+            remaining_lamports = remaining_lamports.saturating_sub(due);
+            exempt = is_exempt;
+            epoch = run_end + 1;
+        }
+        (total_due, exempt)
+    }
+
     fn enable_new_behavior(&self) -> bool {
         match self.operating_mode.unwrap() {
             OperatingMode::Development => true,
@@ -81,15 +142,8 @@ impl RentCollector {
         {
             0
         } else {
-            let slots_elapsed: u64 = (account.rent_epoch..=self.epoch)
-                .map(|epoch| self.epoch_schedule.get_slots_in_epoch(epoch + 1))
-                .sum();
-
-            let (rent_due, exempt) = self.rent.due(
-                account.lamports,
-                account.data.len(),
-                slots_elapsed as f64 / self.slots_per_year,
-            );
+            let (rent_due, exempt) =
+                self.rent_due(account.lamports, account.data.len(), account.rent_epoch);
 
             if exempt || rent_due != 0 {
                 if account.lamports > rent_due {
@@ -193,4 +247,40 @@ mod tests {
         assert_eq!(account.lamports, tiny_lamports - collected);
         assert_ne!(collected, 0);
     }
+
+    #[test]
+    fn test_rent_schedule_switches_parameters_mid_window() {
+        // small enough not to be rent-exempt under the default `Rent`
+        let old_lamports = 1_000;
+        let old_epoch = 0;
+        let new_epoch = 4;
+        let switch_epoch = 2;
+
+        let mut scheduled_account = Account::default();
+        scheduled_account.lamports = old_lamports;
+        scheduled_account.rent_epoch = old_epoch;
+
+        let mut rent_collector =
+            RentCollector::default().clone_with_epoch(new_epoch, OperatingMode::Development);
+        rent_collector.rent_schedule = vec![(switch_epoch, Rent::free())];
+
+        let scheduled_collected = rent_collector
+            .collect_from_existing_account(&Pubkey::new_rand(), &mut scheduled_account);
+
+        // Only epochs before `switch_epoch` are charged under the default,
+        // non-free `Rent`; from `switch_epoch` onward the schedule makes
+        // rent free, so less is collected than if the whole window had
+        // been charged at the default rate.
+        let mut unscheduled_account = Account::default();
+        unscheduled_account.lamports = old_lamports;
+        unscheduled_account.rent_epoch = old_epoch;
+
+        let unscheduled_collector =
+            RentCollector::default().clone_with_epoch(new_epoch, OperatingMode::Development);
+        let unscheduled_collected = unscheduled_collector
+            .collect_from_existing_account(&Pubkey::new_rand(), &mut unscheduled_account);
+
+        assert_ne!(scheduled_collected, 0);
+        assert!(scheduled_collected < unscheduled_collected);
+    }
 }
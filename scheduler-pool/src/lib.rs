@@ -7,13 +7,45 @@
 //! At highest level, this crate takes `SanitizedTransaction`s via its `schedule_execution()` and
 //! commits any side-effects (i.e. on-chain state changes) into `Bank`s via `solana-ledger`'s
 //! helper fun called `execute_batch()`.
+//!
+//! Scheduling itself is conflict-aware and priority-ordered: a dedicated scheduling thread owns a
+//! `solana_unified_scheduler_logic::SchedulingStateMachine` tracking which accounts are
+//! currently locked by in-flight transactions, and only ever hands a transaction to one of this
+//! pool's worker threads once none of its accounts conflict with whatever's already running.
+//! Each transaction's priority (its prioritization fee) doubles as its place in that state
+//! machine's per-account waiter queues, so whenever two transactions do conflict over some
+//! account, the higher-priority one is always the one released first.
+//!
+//! Every `SchedulingContext` carries a `SchedulingMode`, and the two modes behave quite
+//! differently once a transaction can't go through cleanly:
+//!
+//! * `BlockVerification` is replaying a block that already landed on chain, so every transaction
+//!   in it is expected to be applicable; the first one that isn't aborts the rest of the context
+//!   outright instead of quietly pressing on with a partially-replayed block.
+//! * `BlockGeneration` is producing a brand new block out of a candidate set, so there's nothing
+//!   to abort *to* -- a candidate that conflicts with whatever's already been packed, or that
+//!   would blow the block's remaining cost budget, is simply left out of the block, and the rest
+//!   of the candidates keep going. The indexes that did make it in, in packing order, are this
+//!   context's produced block.
+//!
+//! `SchedulerPool` itself is bounded: it keeps at most `max_pool_size` idle `Scheduler`s (any
+//! more returned via `return_to_pool` are dropped -- and thereby have their worker threads
+//! cleanly torn down -- rather than retained), and a background maintenance thread reclaims
+//! whichever idle ones have sat unused past `idle_timeout`. Otherwise a validator that briefly
+//! sees a burst of concurrent banks, each spinning up its own worker threads, would never give
+//! those threads back once things quiet back down.
 
 use {
+    crossbeam_channel::{unbounded, Receiver, Sender},
     solana_ledger::blockstore_processor::{
         execute_batch, TransactionBatchWithIndexes, TransactionStatusSender,
     },
-    solana_program_runtime::timings::ExecuteTimings,
+    solana_program_runtime::{
+        compute_budget::{ComputeBudget, MAX_COMPUTE_UNIT_LIMIT},
+        timings::ExecuteTimings,
+    },
     solana_runtime::{
+        bank::Bank,
         installed_scheduler_pool::{
             InstalledScheduler, InstalledSchedulerPool, ResultWithTiming, SchedulerBox,
             SchedulerId, SchedulerPoolArc, SchedulingContext, WaitSource,
@@ -22,15 +54,155 @@ use {
         vote_sender_types::ReplayVoteSender,
     },
     solana_scheduler::{SchedulingMode, WithSchedulingMode},
-    solana_sdk::transaction::SanitizedTransaction,
-    std::sync::{Arc, Mutex, Weak},
+    solana_sdk::{
+        pubkey::Pubkey,
+        transaction::{SanitizedTransaction, TransactionError},
+    },
+    solana_unified_scheduler_logic::{Page, SchedulingStateMachine, Task},
+    std::{
+        collections::{HashMap, HashSet, VecDeque},
+        sync::{Arc, Mutex, Weak},
+        thread::{Builder, JoinHandle},
+        time::{Duration, Instant},
+    },
 };
 
+/// Number of worker threads a `Scheduler` spawns when `SchedulerPool::new_dyn` isn't given an
+/// explicit count.
+const DEFAULT_WORKER_THREAD_COUNT: usize = 4;
+
+/// Idle `Scheduler`s a `SchedulerPool` keeps around when `new_dyn` isn't given an explicit cap.
+const DEFAULT_MAX_POOL_SIZE: usize = 16;
+
+/// How long an idle `Scheduler` may sit in the pool before maintenance reclaims it, when
+/// `new_dyn` isn't given an explicit timeout.
+const DEFAULT_IDLE_TIMEOUT: Duration = Duration::from_secs(180);
+
+/// How often the pool's maintenance thread wakes up to sweep schedulers that have been idle past
+/// `idle_timeout`.
+const MAINTENANCE_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Fixed per-instruction overhead folded into a transaction's static cost below, approximating
+/// the runtime's own built-in-instruction cost table without pulling in the full cost model
+/// crate just for this pre-filter.
+const PER_INSTRUCTION_BASE_COST: u64 = 150;
+
+/// A transaction's own declared cost may never exceed this by itself: the runtime's
+/// per-transaction compute-unit limit, which no single transaction can be granted more than.
+const MAX_TRANSACTION_COST: u64 = MAX_COMPUTE_UNIT_LIMIT as u64;
+
+/// Total declared cost a `BlockGeneration` context will pack into one produced block, mirroring
+/// the runtime's whole-block compute budget (kept as a multiple of [`MAX_TRANSACTION_COST`]
+/// rather than importing the full cost-tracker crate just for this one constant).
+const MAX_BLOCK_GENERATION_COST: u64 = MAX_TRANSACTION_COST * 32;
+
+/// A transaction's declared cost, i.e. its requested compute units plus the same fixed
+/// per-instruction overhead [`reject_statically_unschedulable`] charges. Shared by that
+/// static-rejection check and `BlockGeneration`'s packing budget so both agree on what a
+/// transaction "costs".
+fn transaction_static_cost(transaction: &SanitizedTransaction, instruction_count: usize) -> u64 {
+    let cu_requested = ComputeBudget::get_compute_budget_limits(
+        transaction.message().program_instructions_iter(),
+        true,
+        true,
+        true,
+        true,
+        true,
+    )
+    .map(|limits| limits.compute_unit_limit as u64)
+    .unwrap_or_default();
+    cu_requested + instruction_count as u64 * PER_INSTRUCTION_BASE_COST
+}
+
+/// Cheaply rejects a transaction that's statically known to fail, before it ever reaches
+/// `schedule_execution`'s account-conflict scheduling (let alone a worker thread): one with no
+/// instructions, one that write-locks the same account twice, or one whose declared cost alone
+/// already exceeds what a single transaction may cost. Mirrors banking stage's "discard packets
+/// statically known to fail" filtering, at the scheduler's doorstep instead of the packet
+/// pipeline's.
+fn reject_statically_unschedulable(
+    transaction: &SanitizedTransaction,
+    instruction_count: usize,
+) -> Option<TransactionError> {
+    if instruction_count == 0 {
+        return Some(TransactionError::SanitizeFailure);
+    }
+
+    let locks = transaction.get_account_locks_unchecked();
+    let mut seen_writable = HashSet::with_capacity(locks.writable.len());
+    if !locks.writable.iter().all(|address| seen_writable.insert(*address)) {
+        return Some(TransactionError::AccountLoadedTwice);
+    }
+
+    let static_cost = transaction_static_cost(transaction, instruction_count);
+    if static_cost > MAX_TRANSACTION_COST {
+        return Some(TransactionError::WouldExceedMaxBlockCostLimit);
+    }
+
+    None
+}
+
+/// Per-context bookkeeping for a `BlockGeneration` scheduler: which accounts have already been
+/// claimed by a packed transaction, how much of the block's cost budget they've used, and the
+/// resulting packing order. Reset whenever a `Drain` completes, since that's when the next
+/// context's transactions start arriving.
+///
+/// Unlike `BlockVerification` (which runs every transaction through the full
+/// `SchedulingStateMachine`, blocking a conflicting one until the account it wants frees up),
+/// once an account is claimed here it stays claimed for the rest of the context: a later
+/// transaction that wants it is dropped outright rather than queued, since block generation has
+/// no reason to wait around for a candidate that might never free the account back up.
+#[derive(Default)]
+struct GenerationPacking {
+    locked_writable: HashSet<Pubkey>,
+    locked_readonly: HashSet<Pubkey>,
+    cost_used: u64,
+    order: Vec<usize>,
+}
+
+impl GenerationPacking {
+    /// Tries to claim `transaction`'s accounts and `cost` against the remaining block budget.
+    /// Leaves no trace and returns `false` if it conflicts with an already-packed transaction's
+    /// accounts or would exceed [`MAX_BLOCK_GENERATION_COST`]; otherwise claims the accounts,
+    /// records `index` as packed, and returns `true`.
+    fn try_pack(&mut self, transaction: &SanitizedTransaction, index: usize, cost: u64) -> bool {
+        let locks = transaction.get_account_locks_unchecked();
+        let conflicts = locks.writable.iter().any(|address| {
+            self.locked_writable.contains(*address) || self.locked_readonly.contains(*address)
+        }) || locks
+            .readonly
+            .iter()
+            .any(|address| self.locked_writable.contains(*address));
+        if conflicts || self.cost_used.saturating_add(cost) > MAX_BLOCK_GENERATION_COST {
+            return false;
+        }
+
+        self.locked_writable
+            .extend(locks.writable.iter().map(|address| **address));
+        self.locked_readonly
+            .extend(locks.readonly.iter().map(|address| **address));
+        self.cost_used += cost;
+        self.order.push(index);
+        true
+    }
+}
+
+/// An idle `Scheduler` sitting in `SchedulerPool::schedulers`, tagged with when it got there so
+/// the maintenance thread knows how long it's been idle.
+#[derive(Debug)]
+struct PooledScheduler {
+    scheduler: SchedulerBox,
+    returned_at: Instant,
+}
+
 // SchedulerPool must be accessed via dyn by solana-runtime code, because of its internal fields'
 // types aren't available there...
 #[derive(Debug)]
 pub struct SchedulerPool {
-    schedulers: Mutex<Vec<SchedulerBox>>,
+    schedulers: Mutex<Vec<PooledScheduler>>,
+    worker_thread_count: usize,
+    max_pool_size: usize,
+    idle_timeout: Duration,
     log_messages_bytes_limit: Option<usize>,
     transaction_status_sender: Option<TransactionStatusSender>,
     replay_vote_sender: Option<ReplayVoteSender>,
@@ -40,19 +212,30 @@ pub struct SchedulerPool {
 
 impl SchedulerPool {
     pub fn new_dyn(
+        worker_thread_count: Option<usize>,
+        max_pool_size: Option<usize>,
+        idle_timeout: Option<Duration>,
         log_messages_bytes_limit: Option<usize>,
         transaction_status_sender: Option<TransactionStatusSender>,
         replay_vote_sender: Option<ReplayVoteSender>,
         prioritization_fee_cache: Arc<PrioritizationFeeCache>,
     ) -> SchedulerPoolArc {
-        Arc::new_cyclic(|weak_pool| Self {
-            schedulers: Mutex::<Vec<SchedulerBox>>::default(),
+        let idle_timeout = idle_timeout.unwrap_or(DEFAULT_IDLE_TIMEOUT);
+        let pool = Arc::new_cyclic(|weak_pool| Self {
+            schedulers: Mutex::<Vec<PooledScheduler>>::default(),
+            worker_thread_count: worker_thread_count
+                .unwrap_or(DEFAULT_WORKER_THREAD_COUNT)
+                .max(1),
+            max_pool_size: max_pool_size.unwrap_or(DEFAULT_MAX_POOL_SIZE),
+            idle_timeout,
             log_messages_bytes_limit,
             transaction_status_sender,
             replay_vote_sender,
             prioritization_fee_cache,
             weak: weak_pool.clone(),
-        })
+        });
+        spawn_maintenance_thread(Arc::downgrade(&pool), idle_timeout);
+        pool
     }
 }
 
@@ -60,9 +243,9 @@ impl InstalledSchedulerPool for SchedulerPool {
     fn take_from_pool(&self, context: SchedulingContext) -> SchedulerBox {
         let mut schedulers = self.schedulers.lock().expect("not poisoned");
         let maybe_scheduler = schedulers.pop();
-        if let Some(scheduler) = maybe_scheduler {
-            scheduler.replace_scheduler_context(context);
-            scheduler
+        if let Some(pooled) = maybe_scheduler {
+            pooled.scheduler.replace_scheduler_context(context);
+            pooled.scheduler
         } else {
             Box::new(Scheduler::spawn(
                 self.weak.upgrade().expect("self-referencing Arc-ed pool"),
@@ -72,27 +255,394 @@ impl InstalledSchedulerPool for SchedulerPool {
     }
 
     fn return_to_pool(&self, scheduler: SchedulerBox) {
-        self.schedulers
-            .lock()
-            .expect("not poisoned")
-            .push(scheduler);
+        let mut schedulers = self.schedulers.lock().expect("not poisoned");
+        if schedulers.len() >= self.max_pool_size {
+            // The pool is already at capacity; drop `schedulers` first so this `scheduler`'s
+            // `Drop` impl (which joins its central thread, which in turn tears down its workers)
+            // doesn't run while the lock is held.
+            drop(schedulers);
+            drop(scheduler);
+            return;
+        }
+        schedulers.push(PooledScheduler {
+            scheduler,
+            returned_at: Instant::now(),
+        });
+    }
+}
+
+/// Periodically sweeps `pool`'s idle schedulers, dropping (and thereby cleanly terminating the
+/// worker threads of) any that have sat unused past `idle_timeout`. Exits once `pool` itself has
+/// no more strong references, i.e. once the pool is gone.
+fn spawn_maintenance_thread(pool: Weak<SchedulerPool>, idle_timeout: Duration) -> JoinHandle<()> {
+    // Never sleep for less than a second, even if `idle_timeout` is very short (or zero): without
+    // a floor, a tiny `idle_timeout` would turn this into a busy loop re-locking `schedulers` on
+    // every spin.
+    let sweep_interval = idle_timeout.min(MAINTENANCE_INTERVAL).max(Duration::from_secs(1));
+    Builder::new()
+        .name("solSchedulerPoolGC".to_string())
+        .spawn(move || loop {
+            std::thread::sleep(sweep_interval);
+            let Some(pool) = pool.upgrade() else {
+                break;
+            };
+            // Split off the timed-out schedulers first and drop them only after releasing the
+            // lock, same as `return_to_pool` does: a scheduler's `Drop` impl joins its central
+            // (and, transitively, worker) threads, which could otherwise stall every other caller
+            // blocked on this same mutex for as long as that teardown takes.
+            let mut schedulers = pool.schedulers.lock().expect("not poisoned");
+            let timed_out = {
+                let idle_timeout = pool.idle_timeout;
+                let (keep, timed_out): (Vec<_>, Vec<_>) = std::mem::take(&mut *schedulers)
+                    .into_iter()
+                    .partition(|pooled| pooled.returned_at.elapsed() < idle_timeout);
+                *schedulers = keep;
+                timed_out
+            };
+            drop(schedulers);
+            drop(timed_out);
+        })
+        .expect("failed to spawn scheduler pool maintenance thread")
+}
+
+/// A unit of work handed from `Scheduler::schedule_execution` to the central scheduling thread.
+enum SchedulerEvent {
+    Execute {
+        transaction: SanitizedTransaction,
+        index: usize,
+        bank: Arc<Bank>,
+        priority: u64,
+        cost: u64,
+        mode: SchedulingMode,
+    },
+    /// Blocks the central thread until every transaction scheduled before this event has been
+    /// executed and its result folded into `SchedulerShared::result_with_timing`, then replies
+    /// on `responder` so `wait_for_termination` can hand the aggregate back to its caller.
+    Drain {
+        responder: Sender<()>,
+    },
+}
+
+/// A completed batch, reported by a worker thread back to the central scheduling thread so it
+/// can release the batch's account locks and let any transactions blocked behind them become
+/// eligible.
+struct WorkerReport {
+    worker_id: usize,
+    task: Task,
+    result_with_timing: ResultWithTiming,
+}
+
+/// One transaction's worth of work dispatched from the central scheduling thread to an idle
+/// worker thread.
+struct WorkerJob {
+    task: Task,
+    bank: Arc<Bank>,
+}
+
+fn spawn_worker(
+    pool: Arc<SchedulerPool>,
+    worker_id: usize,
+    job_rx: Receiver<WorkerJob>,
+    report_tx: Sender<WorkerReport>,
+) -> JoinHandle<()> {
+    Builder::new()
+        .name(format!("solScheduler{worker_id:02}"))
+        .spawn(move || {
+            while let Ok(WorkerJob { task, bank }) = job_rx.recv() {
+                let mut timings = ExecuteTimings::default();
+                let batch = bank.prepare_sanitized_batch_without_locking(task.transaction().clone());
+                let batch_with_indexes = TransactionBatchWithIndexes {
+                    batch,
+                    transaction_indexes: vec![task.task_index()],
+                };
+                let result = execute_batch(
+                    &batch_with_indexes,
+                    &bank,
+                    pool.transaction_status_sender.as_ref(),
+                    pool.replay_vote_sender.as_ref(),
+                    &mut timings,
+                    pool.log_messages_bytes_limit,
+                    &pool.prioritization_fee_cache,
+                );
+                if report_tx
+                    .send(WorkerReport {
+                        worker_id,
+                        task,
+                        result_with_timing: (result, timings),
+                    })
+                    .is_err()
+                {
+                    // The central thread is gone, i.e. this scheduler is being torn down; nothing
+                    // left to report to.
+                    break;
+                }
+            }
+        })
+        .expect("failed to spawn scheduler worker thread")
+}
+
+/// Dispatches as many `ready` tasks as there are `idle_workers` available, routing each to its
+/// own worker over `job_txs`.
+fn dispatch_ready_tasks(
+    ready: &mut VecDeque<(Task, Arc<Bank>)>,
+    idle_workers: &mut Vec<usize>,
+    job_txs: &[Sender<WorkerJob>],
+) {
+    while !ready.is_empty() && !idle_workers.is_empty() {
+        let (task, bank) = ready.pop_front().expect("checked non-empty above");
+        let worker_id = idle_workers.pop().expect("checked non-empty above");
+        let _ = job_txs[worker_id].send(WorkerJob { task, bank });
+    }
+}
+
+/// Folds one transaction's result/timings into `aggregate`. `fail_fast` is `BlockVerification`'s
+/// behavior: the first error seen becomes (and stays) the whole context's result, same as a
+/// replayed block must be treated as invalid as soon as one of its transactions turns out not to
+/// be applicable. `BlockGeneration` passes `fail_fast: false`: a single candidate transaction
+/// failing to execute doesn't invalidate the rest of the block being produced, so `aggregate.0`
+/// is left alone and only the timings get folded in.
+fn accumulate_result_with_timing(
+    fail_fast: bool,
+    aggregate: &mut ResultWithTiming,
+    (result, timings): ResultWithTiming,
+) {
+    if fail_fast && aggregate.0.is_ok() {
+        aggregate.0 = result;
+    }
+    aggregate.1.accumulate(&timings);
+}
+
+fn spawn_central_thread(
+    pool: Arc<SchedulerPool>,
+    shared: Arc<SchedulerShared>,
+    event_rx: Receiver<SchedulerEvent>,
+) -> JoinHandle<()> {
+    let worker_thread_count = pool.worker_thread_count;
+    Builder::new()
+        .name("solSchedulerMain".to_string())
+        .spawn(move || {
+            let (report_tx, report_rx) = unbounded::<WorkerReport>();
+            let mut job_txs = Vec::with_capacity(worker_thread_count);
+            let mut worker_handles = Vec::with_capacity(worker_thread_count);
+            for worker_id in 0..worker_thread_count {
+                let (job_tx, job_rx) = unbounded::<WorkerJob>();
+                job_txs.push(job_tx);
+                worker_handles.push(spawn_worker(
+                    pool.clone(),
+                    worker_id,
+                    job_rx,
+                    report_tx.clone(),
+                ));
+            }
+            // Only the workers should keep `report_rx` alive once this thread exits.
+            drop(report_tx);
+
+            let mut state_machine = SchedulingStateMachine::default();
+            let mut pages: HashMap<Pubkey, Page> = HashMap::new();
+            let mut ready: VecDeque<(Task, Arc<Bank>)> = VecDeque::new();
+            let mut idle_workers: Vec<usize> = (0..worker_thread_count).collect();
+            // The bank of whichever task most recently entered `ready`; every task live within a
+            // single `SchedulingContext` shares one bank, and a new context is only ever adopted
+            // once `Drain` has fully quiesced the previous one.
+            let mut current_bank: Option<Arc<Bank>> = None;
+            // The mode of the context currently in flight, alongside `current_bank`; `None`
+            // between contexts.
+            let mut current_mode: Option<SchedulingMode> = None;
+            // `BlockVerification`'s fail-fast latch: once a transaction in the current context
+            // errors, every later `Execute` for that same context is dropped without even being
+            // attempted, same as the original single-threaded scheduler's intent.
+            let mut aborted = false;
+            // `BlockGeneration`'s packing state for the current context; irrelevant (and left at
+            // its default) while `current_mode` is `BlockVerification`.
+            let mut generation = GenerationPacking::default();
+
+            let handle_report = |state_machine: &mut SchedulingStateMachine,
+                                  ready: &mut VecDeque<(Task, Arc<Bank>)>,
+                                  idle_workers: &mut Vec<usize>,
+                                  current_bank: &Option<Arc<Bank>>,
+                                  current_mode: &Option<SchedulingMode>,
+                                  aborted: &mut bool,
+                                  report: WorkerReport| {
+                let fail_fast = !matches!(current_mode, Some(SchedulingMode::BlockGeneration));
+                if fail_fast && report.result_with_timing.0.is_err() {
+                    *aborted = true;
+                }
+                accumulate_result_with_timing(
+                    fail_fast,
+                    &mut shared.result_with_timing.lock().expect("not poisoned"),
+                    report.result_with_timing,
+                );
+                state_machine.deschedule_task(&report.task);
+                idle_workers.push(report.worker_id);
+
+                if let Some(bank) = current_bank {
+                    while let Some(()) =
+                        state_machine.schedule_retryable_task(|task| ready.push_back((task.clone(), bank.clone())))
+                    {
+                        // keep draining the retryable queue: releasing one lock can make several
+                        // previously-blocked tasks eligible at once.
+                    }
+                }
+            };
+
+            loop {
+                crossbeam_channel::select! {
+                    recv(event_rx) -> event => {
+                        match event {
+                            Ok(SchedulerEvent::Execute { transaction, index, bank, priority, cost, mode }) => {
+                                current_bank = Some(bank.clone());
+                                current_mode = Some(mode);
+
+                                match mode {
+                                    SchedulingMode::BlockVerification => {
+                                        if !aborted {
+                                            let task = SchedulingStateMachine::create_task(
+                                                transaction,
+                                                index,
+                                                cost,
+                                                priority,
+                                                &mut |address| pages.entry(address).or_default().clone(),
+                                            );
+                                            state_machine.schedule_task(task, |task| {
+                                                ready.push_back((task.clone(), bank.clone()))
+                                            });
+                                        }
+                                        // else: `fail_fast` already tripped for this context, so
+                                        // this transaction is never even attempted.
+                                    }
+                                    SchedulingMode::BlockGeneration => {
+                                        if generation.try_pack(&transaction, index, cost) {
+                                            let task = SchedulingStateMachine::create_task(
+                                                transaction,
+                                                index,
+                                                cost,
+                                                priority,
+                                                &mut |address| pages.entry(address).or_default().clone(),
+                                            );
+                                            // `generation` already ruled out any conflict with
+                                            // what's packed so far, so this can never block.
+                                            state_machine.schedule_task(task, |task| {
+                                                ready.push_back((task.clone(), bank.clone()))
+                                            });
+                                        }
+                                        // else: dropped for conflicting with already-packed work
+                                        // or exceeding the remaining block cost budget; simply
+                                        // isn't part of the produced block.
+                                    }
+                                }
+                                dispatch_ready_tasks(&mut ready, &mut idle_workers, &job_txs);
+                            }
+                            Ok(SchedulerEvent::Drain { responder }) => {
+                                while state_machine.active_task_count() > 0 {
+                                    let Ok(report) = report_rx.recv() else {
+                                        break;
+                                    };
+                                    handle_report(
+                                        &mut state_machine,
+                                        &mut ready,
+                                        &mut idle_workers,
+                                        &current_bank,
+                                        &current_mode,
+                                        &mut aborted,
+                                        report,
+                                    );
+                                    dispatch_ready_tasks(&mut ready, &mut idle_workers, &job_txs);
+                                }
+                                current_bank = None;
+                                current_mode = None;
+                                aborted = false;
+                                // `active_task_count() == 0` at this point, so no `Page` here is
+                                // still referenced by an in-flight task; clearing now (rather than
+                                // never) keeps this from growing by one entry per unique pubkey
+                                // ever scheduled over a pooled `Scheduler`'s entire lifetime.
+                                pages.clear();
+                                let packed_order =
+                                    std::mem::replace(&mut generation, GenerationPacking::default()).order;
+                                *shared
+                                    .generation_packing_order
+                                    .lock()
+                                    .expect("not poisoned") = packed_order;
+                                // The responder going away just means nobody is waiting on this
+                                // drain anymore; nothing to clean up on this side either way.
+                                let _ = responder.send(());
+                            }
+                            Err(_) => {
+                                // Every `Scheduler` handle (and the pool that spawned this
+                                // thread) is gone; shut the workers down and exit.
+                                break;
+                            }
+                        }
+                    }
+                    recv(report_rx) -> report => {
+                        let Ok(report) = report else {
+                            // No workers left to report anything; nothing more this thread can
+                            // do.
+                            break;
+                        };
+                        handle_report(
+                            &mut state_machine,
+                            &mut ready,
+                            &mut idle_workers,
+                            &current_bank,
+                            &current_mode,
+                            &mut aborted,
+                            report,
+                        );
+                        dispatch_ready_tasks(&mut ready, &mut idle_workers, &job_txs);
+                    }
+                }
+            }
+
+            drop(job_txs);
+            for worker_handle in worker_handles {
+                let _ = worker_handle.join();
+            }
+        })
+        .expect("failed to spawn scheduler central thread")
+}
+
+/// State shared between a `Scheduler`'s public-facing handle and its central scheduling thread.
+#[derive(Debug)]
+struct SchedulerShared {
+    context: Mutex<Option<SchedulingContext>>,
+    result_with_timing: Mutex<ResultWithTiming>,
+    /// The most recently finished `BlockGeneration` context's packing order: the transaction
+    /// indexes that were actually locked and dispatched, in the order they were accepted. Filled
+    /// in by `Drain` and left empty for `BlockVerification` contexts, where it's meaningless.
+    generation_packing_order: Mutex<Vec<usize>>,
+}
+
+impl Default for SchedulerShared {
+    fn default() -> Self {
+        Self {
+            context: Mutex::new(None),
+            result_with_timing: Mutex::new((Ok(()), ExecuteTimings::default())),
+            generation_packing_order: Mutex::new(Vec::new()),
+        }
     }
 }
 
-// Currently, simplest possible implementation (i.e. single-threaded)
-// this will be replaced with more proper implementation...
-// not usable at all, especially for mainnnet-beta
 #[derive(Debug)]
 struct Scheduler {
     pool: Arc<SchedulerPool>,
-    context_and_result_with_timing: Mutex<(Option<SchedulingContext>, Option<ResultWithTiming>)>,
+    shared: Arc<SchedulerShared>,
+    event_tx: Sender<SchedulerEvent>,
+    central_thread: Option<JoinHandle<()>>,
 }
 
 impl Scheduler {
     fn spawn(pool: Arc<SchedulerPool>, initial_context: SchedulingContext) -> Self {
+        let shared = Arc::new(SchedulerShared {
+            context: Mutex::new(Some(initial_context)),
+            ..SchedulerShared::default()
+        });
+        let (event_tx, event_rx) = unbounded();
+        let central_thread = spawn_central_thread(pool.clone(), shared.clone(), event_rx);
         Self {
             pool,
-            context_and_result_with_timing: Mutex::new((Some(initial_context), None)),
+            shared,
+            event_tx,
+            central_thread: Some(central_thread),
         }
     }
 }
@@ -107,97 +657,155 @@ impl InstalledScheduler for Scheduler {
     }
 
     fn schedule_execution(&self, transaction: &SanitizedTransaction, index: usize) {
-        let (ref context, ref mut result_with_timing) = &mut *self
-            .context_and_result_with_timing
-            .lock()
-            .expect("not poisoned");
-        let context = context.as_ref().expect("active context");
-
-        let batch = context
-            .bank()
-            .prepare_sanitized_batch_without_locking(transaction.clone());
-        let batch_with_indexes = TransactionBatchWithIndexes {
-            batch,
-            transaction_indexes: vec![index],
-        };
-        let (result, timings) =
-            result_with_timing.get_or_insert_with(|| (Ok(()), ExecuteTimings::default()));
+        let context_guard = self.shared.context.lock().expect("not poisoned");
+        let context = context_guard.as_ref().expect("active context");
+        let bank = context.bank().clone();
+        let mode = context.mode();
+        let instruction_count = transaction.message().program_instructions_iter().count();
 
-        let fail_fast = match context.mode() {
-            // this should be false, for (upcoming) BlockGeneration variant .
-            SchedulingMode::BlockVerification => true,
-        };
-
-        // so, we're NOT scheduling at all; rather, just execute tx straight off.  we doesn't need
-        // to solve inter-tx locking deps only in the case of single-thread fifo like this....
-        if !fail_fast {
-            *result = execute_batch(
-                &batch_with_indexes,
-                context.bank(),
-                self.pool.transaction_status_sender.as_ref(),
-                self.pool.replay_vote_sender.as_ref(),
-                timings,
-                self.pool.log_messages_bytes_limit,
-                &self.pool.prioritization_fee_cache,
-            );
+        if let Some(err) = reject_statically_unschedulable(transaction, instruction_count) {
+            // `BlockVerification` is replaying a block that already landed on chain, so a
+            // statically-doomed transaction in it means the block itself wasn't actually valid;
+            // `BlockGeneration` is merely choosing what to put in a block it hasn't produced yet,
+            // so a doomed candidate is just left out, the same as one dropped later for
+            // conflicting with already-packed work.
+            if matches!(mode, SchedulingMode::BlockVerification) {
+                accumulate_result_with_timing(
+                    /* fail_fast */ true,
+                    &mut self.shared.result_with_timing.lock().expect("not poisoned"),
+                    (Err(err), ExecuteTimings::default()),
+                );
+            }
+            return;
         }
+
+        let priority = self
+            .pool
+            .prioritization_fee_cache
+            .calculate_prioritization_fee(transaction);
+        let cost = transaction_static_cost(transaction, instruction_count);
+        // Just hand the transaction off to the central scheduling thread; whether it conflicts
+        // with whatever's already in flight -- and therefore when, or on which worker, it
+        // actually runs -- is entirely that thread's call. `priority` only matters once this
+        // transaction is actually contending with another one over a common account: the
+        // central thread's `SchedulingStateMachine` always releases a contended account to
+        // whichever blocked task has the highest priority first, so among conflicting
+        // transactions the highest-fee one always goes first, while non-conflicting ones still
+        // run in parallel.
+        let _ = self.event_tx.send(SchedulerEvent::Execute {
+            transaction: transaction.clone(),
+            index,
+            bank,
+            priority,
+            cost,
+            mode,
+        });
     }
 
     fn schedule_termination(&mut self) {
         drop::<Option<SchedulingContext>>(
-            self.context_and_result_with_timing
-                .lock()
-                .expect("not poisoned")
-                .0
-                .take(),
+            self.shared.context.lock().expect("not poisoned").take(),
         );
     }
 
     fn wait_for_termination(&mut self, wait_source: &WaitSource) -> Option<ResultWithTiming> {
         let should_block_current_thread = match wait_source {
-            WaitSource::InsideBlock => {
-                // rustfmt...
-                false
-            }
+            WaitSource::InsideBlock => false,
             WaitSource::AcrossBlock | WaitSource::FromBankDrop | WaitSource::FromSchedulerDrop => {
                 true
             }
         };
 
-        if should_block_current_thread {
-            // current simplest form of this trait impl doesn't block the current thread
-            // materially with the following single mutex lock....
-            self.context_and_result_with_timing
-                .lock()
-                .expect("not poisoned")
-                .1
-                .take()
-        } else {
-            None
+        if !should_block_current_thread {
+            return None;
+        }
+
+        let (responder, done) = unbounded();
+        if self
+            .event_tx
+            .send(SchedulerEvent::Drain { responder })
+            .is_err()
+        {
+            return None;
         }
+        // Block until the central thread has deschedule_task-ed every in-flight batch and folded
+        // its result/timings into `result_with_timing`.
+        let _ = done.recv();
+
+        let mut result_with_timing = self.shared.result_with_timing.lock().expect("not poisoned");
+        Some(std::mem::replace(
+            &mut *result_with_timing,
+            (Ok(()), ExecuteTimings::default()),
+        ))
     }
 
     fn replace_scheduler_context(&self, context: SchedulingContext) {
-        *self
-            .context_and_result_with_timing
+        *self.shared.context.lock().expect("not poisoned") = Some(context);
+        *self.shared.result_with_timing.lock().expect("not poisoned") = (Ok(()), ExecuteTimings::default());
+        self.shared
+            .generation_packing_order
             .lock()
-            .expect("not poisoned") = (Some(context), None);
+            .expect("not poisoned")
+            .clear();
     }
 }
 
+impl Scheduler {
+    /// The packing order `wait_for_termination` most recently produced for a `BlockGeneration`
+    /// context: the transaction indexes actually included in the block, in the order they were
+    /// packed.
+    ///
+    /// `InstalledScheduler::wait_for_termination` can only return a `ResultWithTiming` -- it has
+    /// nowhere to carry this alongside it without changing that type, which isn't defined in this
+    /// crate -- so a real block-production caller needs a future revision of that trait (or of
+    /// `ResultWithTiming` itself) to actually reach this. This inherent method is the extension
+    /// point for that; until then it's only reachable from within this crate.
+    fn take_generation_packing_order(&self) -> Vec<usize> {
+        std::mem::take(
+            &mut self
+                .shared
+                .generation_packing_order
+                .lock()
+                .expect("not poisoned"),
+        )
+    }
+}
+
+impl Drop for Scheduler {
+    fn drop(&mut self) {
+        // Struct fields are only dropped *after* this function body returns, so joining first
+        // would wait on a central thread that's still waiting on `event_tx` (the channel's only
+        // sender) to disconnect. Replace it with an already-disconnected sender first -- that's
+        // what actually makes the central thread's `event_rx` see a disconnect and treat it as
+        // its own shutdown signal; it in turn drops every worker's `job_tx`, which ends each
+        // worker's `recv` loop the same way -- then it's safe to block on the join.
+        self.event_tx = crossbeam_channel::bounded(0).0;
+        if let Some(central_thread) = self.central_thread.take() {
+            let _ = central_thread.join();
+        }
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use crate::SchedulerPool;
-    use std::sync::Arc;
     use solana_runtime::bank::Bank;
     use solana_runtime::bank_forks::BankForks;
     use solana_runtime::prioritization_fee_cache::PrioritizationFeeCache;
+    use std::sync::Arc;
 
     #[test]
     fn test_scheduler_pool_new() {
         let _ignored_prioritization_fee_cache = Arc::new(PrioritizationFeeCache::new(0u64));
-        SchedulerPool::new_dyn(None, None, None, _ignored_prioritization_fee_cache);
+        SchedulerPool::new_dyn(
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            _ignored_prioritization_fee_cache,
+        );
     }
 
     #[test]
@@ -52,10 +52,6 @@ pub trait PacketInterface: Clone + Default + Sized + Send + Sync + fmt::Debug {
         Ok(())
     }
 
-    // Hack to allow the introduction of special logic
-    // in necessary places and work around Rust's lack of generic specialization
-    // or similar "compile-time conditionals"
-    // TODO: is there a better way to do this (perhaps a macro of some sort?)?
     fn is_extended() -> bool;
 }
 
@@ -75,44 +71,19 @@ pub struct Meta {
     pub is_simple_vote_tx: bool,
 }
 
+/// Generic over the wire-size of `data`, so `Packet` and `ExtendedPacket`
+/// can share a single implementation instead of duplicating it per size.
 #[derive(Clone)]
 #[repr(C)]
-pub struct Packet {
-    pub data: [u8; PACKET_DATA_SIZE],
+pub struct PacketData<const N: usize> {
+    pub data: [u8; N],
     pub meta: Meta,
 }
 
-// TODO: can we de-duplicate some of this Packet and ExtendedPacket code?
-#[derive(Clone)]
-#[repr(C)]
-pub struct ExtendedPacket {
-    pub data: [u8; EXTENDED_PACKET_DATA_SIZE],
-    pub meta: Meta,
-}
-
-impl PacketInterface for ExtendedPacket {
-    fn get_data(&self) -> &[u8] {
-        &self.data
-    }
-
-    fn get_data_mut(&mut self) -> &mut [u8] {
-        &mut self.data
-    }
-
-    fn get_meta(&self) -> &Meta {
-        &self.meta
-    }
-
-    fn get_meta_mut(&mut self) -> &mut Meta {
-        &mut self.meta
-    }
-
-    fn is_extended() -> bool {
-        true
-    }
-}
+pub type Packet = PacketData<PACKET_DATA_SIZE>;
+pub type ExtendedPacket = PacketData<EXTENDED_PACKET_DATA_SIZE>;
 
-impl PacketInterface for Packet {
+impl<const N: usize> PacketInterface for PacketData<N> {
     fn get_data(&self) -> &[u8] {
         &self.data
     }
@@ -130,46 +101,16 @@ impl PacketInterface for Packet {
     }
 
     fn is_extended() -> bool {
-        false
-    }
-}
-
-impl fmt::Debug for Packet {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "Packet {{ size: {:?}, addr: {:?} }}",
-            self.meta.size,
-            self.meta.addr()
-        )
-    }
-}
-
-#[allow(clippy::uninit_assumed_init)]
-impl Default for Packet {
-    fn default() -> Packet {
-        Packet {
-            data: unsafe { std::mem::MaybeUninit::uninit().assume_init() },
-            meta: Meta::default(),
-        }
-    }
-}
-
-impl PartialEq for Packet {
-    fn eq(&self, other: &Packet) -> bool {
-        let self_data: &[u8] = self.data.as_ref();
-        let other_data: &[u8] = other.data.as_ref();
-        self.meta == other.meta && self_data[..self.meta.size] == other_data[..self.meta.size]
+        N > PACKET_DATA_SIZE
     }
 }
 
-impl fmt::Debug for ExtendedPacket {
-    // It may be useful to know the type of Packet in the debug
-    // print
+impl<const N: usize> fmt::Debug for PacketData<N> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
-            "ExtendedPacket {{ size: {:?}, addr: {:?} }}",
+            "PacketData<{}> {{ size: {:?}, addr: {:?} }}",
+            N,
             self.meta.size,
             self.meta.addr()
         )
@@ -177,17 +118,17 @@ impl fmt::Debug for ExtendedPacket {
 }
 
 #[allow(clippy::uninit_assumed_init)]
-impl Default for ExtendedPacket {
-    fn default() -> ExtendedPacket {
-        ExtendedPacket {
+impl<const N: usize> Default for PacketData<N> {
+    fn default() -> Self {
+        Self {
             data: unsafe { std::mem::MaybeUninit::uninit().assume_init() },
             meta: Meta::default(),
         }
     }
 }
 
-impl PartialEq for ExtendedPacket {
-    fn eq(&self, other: &ExtendedPacket) -> bool {
+impl<const N: usize> PartialEq for PacketData<N> {
+    fn eq(&self, other: &Self) -> bool {
         let self_data: &[u8] = self.data.as_ref();
         let other_data: &[u8] = other.data.as_ref();
         self.meta == other.meta && self_data[..self.meta.size] == other_data[..self.meta.size]
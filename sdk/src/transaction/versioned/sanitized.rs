@@ -6,9 +6,43 @@ use {
         simple_vote_transaction_checker::is_simple_vote_transaction,
         transaction_meta::TransactionMeta,
     },
-    solana_program::message::SanitizedVersionedMessage,
+    solana_program::{message::SanitizedVersionedMessage, pubkey::Pubkey},
 };
 
+/// One account key's write-lock and requested-compute-budget usage within
+/// a transaction, as returned by
+/// `SanitizedVersionedTransaction::get_account_usage`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountUsage {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+    pub cu_requested: u64,
+}
+
+/// Resolves an on-chain Address Lookup Table's currently active address
+/// list, so a v0 message's `MessageAddressTableLookup` indices can be
+/// mapped back to the real `Pubkey`s they reference.
+pub trait ResolveLookups {
+    /// Returns `table_key`'s currently active addresses, or `None` if the
+    /// table hasn't been (or can no longer be) resolved.
+    fn get_lookup_table(&self, key: &Pubkey) -> Option<Vec<Pubkey>>;
+}
+
+/// One account key's write-lock status within a fully-resolved account
+/// set, see `SanitizedVersionedTransaction::resolve`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedAccountKey {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+/// A transaction's account keys with any address-lookup-table entries
+/// resolved to concrete pubkeys, in the canonical wire order: static
+/// keys, then resolved writable lookup keys, then resolved readonly
+/// lookup keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedAccountKeys(pub Vec<ResolvedAccountKey>);
+
 /// Wraps a sanitized `VersionedTransaction` to provide a safe API
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct SanitizedVersionedTransaction {
@@ -60,6 +94,89 @@ impl SanitizedVersionedTransaction {
     pub fn get_transaction_meta(&self) -> &TransactionMeta {
         &self.transaction_meta
     }
+
+    /// Returns one `AccountUsage` per account key in the message, so
+    /// schedulers and analytics tools can see how this transaction's
+    /// requested compute budget maps onto individual accounts -- in
+    /// particular, which write-locked accounts it contends for -- rather
+    /// than only the transaction-wide total.
+    ///
+    /// Only sees the message's static account keys: address-lookup-table
+    /// entries aren't resolved into concrete pubkeys here, since
+    /// `SanitizedVersionedTransaction` doesn't carry a resolver.
+    pub fn get_account_usage(&self) -> Vec<AccountUsage> {
+        let cu_requested = self.transaction_meta.compute_unit_limit;
+        self.message
+            .message
+            .static_account_keys()
+            .iter()
+            .enumerate()
+            .map(|(index, &pubkey)| AccountUsage {
+                pubkey,
+                is_writable: self.message.message.is_writable(index),
+                cu_requested,
+            })
+            .collect()
+    }
+
+    /// Upgrades this transaction's message into its fully-resolved account
+    /// set, pulling each address-lookup-table's active address list from
+    /// `resolver` and indexing it by the lookup's `writable_indexes`/
+    /// `readonly_indexes`. Legacy messages have no lookups to resolve, so
+    /// this just returns their static keys as-is.
+    ///
+    /// Errors with `SanitizeError::IndexOutOfBounds` if a lookup's table
+    /// can't be resolved, or if one of its indexes falls outside the
+    /// resolved table's address list -- either way, the true account set
+    /// can't be reconstructed.
+    pub fn resolve(
+        &self,
+        resolver: &impl ResolveLookups,
+    ) -> Result<ResolvedAccountKeys, SanitizeError> {
+        let message = &self.message.message;
+        let mut resolved: Vec<ResolvedAccountKey> = message
+            .static_account_keys()
+            .iter()
+            .enumerate()
+            .map(|(index, &pubkey)| ResolvedAccountKey {
+                pubkey,
+                is_writable: message.is_writable(index),
+            })
+            .collect();
+
+        let mut writable_keys = Vec::new();
+        let mut readonly_keys = Vec::new();
+        for lookup in message.message_address_table_lookups() {
+            let table_addresses = resolver
+                .get_lookup_table(&lookup.account_key)
+                .ok_or(SanitizeError::IndexOutOfBounds)?;
+            for &index in &lookup.writable_indexes {
+                writable_keys.push(
+                    *table_addresses
+                        .get(index as usize)
+                        .ok_or(SanitizeError::IndexOutOfBounds)?,
+                );
+            }
+            for &index in &lookup.readonly_indexes {
+                readonly_keys.push(
+                    *table_addresses
+                        .get(index as usize)
+                        .ok_or(SanitizeError::IndexOutOfBounds)?,
+                );
+            }
+        }
+
+        resolved.extend(writable_keys.into_iter().map(|pubkey| ResolvedAccountKey {
+            pubkey,
+            is_writable: true,
+        }));
+        resolved.extend(readonly_keys.into_iter().map(|pubkey| ResolvedAccountKey {
+            pubkey,
+            is_writable: false,
+        }));
+
+        Ok(ResolvedAccountKeys(resolved))
+    }
 }
 
 #[cfg(test)]
@@ -68,11 +185,20 @@ mod tests {
         super::*,
         solana_program::{
             hash::Hash,
-            message::{v0, VersionedMessage},
+            message::{v0, MessageHeader, VersionedMessage},
             pubkey::Pubkey,
         },
+        std::collections::HashMap,
     };
 
+    struct FakeLookupResolver(HashMap<Pubkey, Vec<Pubkey>>);
+
+    impl ResolveLookups for FakeLookupResolver {
+        fn get_lookup_table(&self, key: &Pubkey) -> Option<Vec<Pubkey>> {
+            self.0.get(key).cloned()
+        }
+    }
+
     #[test]
     fn test_try_new_with_invalid_signatures() {
         let tx = VersionedTransaction {
@@ -104,4 +230,105 @@ mod tests {
             Err(SanitizeError::InvalidValue)
         );
     }
+
+    #[test]
+    fn test_get_account_usage() {
+        let payer = Pubkey::new_unique();
+        let message = v0::Message::try_compile(&payer, &[], &[], Hash::default()).unwrap();
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        };
+
+        let sanitized_tx =
+            SanitizedVersionedTransaction::try_new(tx, Some(false), &FeatureSet::default())
+                .unwrap();
+        let account_usage = sanitized_tx.get_account_usage();
+
+        assert_eq!(account_usage.len(), 1);
+        assert_eq!(account_usage[0].pubkey, payer);
+        assert!(account_usage[0].is_writable);
+        assert_eq!(
+            account_usage[0].cu_requested,
+            sanitized_tx.get_transaction_meta().compute_unit_limit
+        );
+    }
+
+    fn v0_message_with_lookup(
+        payer: Pubkey,
+        lookup: v0::MessageAddressTableLookup,
+    ) -> v0::Message {
+        v0::Message {
+            header: MessageHeader {
+                num_required_signatures: 1,
+                num_readonly_signed_accounts: 0,
+                num_readonly_unsigned_accounts: 0,
+            },
+            account_keys: vec![payer],
+            recent_blockhash: Hash::default(),
+            instructions: vec![],
+            address_table_lookups: vec![lookup],
+        }
+    }
+
+    #[test]
+    fn test_resolve_concatenates_lookup_accounts_in_canonical_order() {
+        let payer = Pubkey::new_unique();
+        let table_key = Pubkey::new_unique();
+        let writable_key = Pubkey::new_unique();
+        let readonly_key = Pubkey::new_unique();
+
+        let message = v0_message_with_lookup(
+            payer,
+            v0::MessageAddressTableLookup {
+                account_key: table_key,
+                writable_indexes: vec![0],
+                readonly_indexes: vec![1],
+            },
+        );
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        };
+        let sanitized_tx =
+            SanitizedVersionedTransaction::try_new(tx, Some(false), &FeatureSet::default())
+                .unwrap();
+
+        let mut tables = HashMap::new();
+        tables.insert(table_key, vec![writable_key, readonly_key]);
+        let resolved = sanitized_tx.resolve(&FakeLookupResolver(tables)).unwrap();
+
+        assert_eq!(resolved.0.len(), 3);
+        assert_eq!(resolved.0[0].pubkey, payer);
+        assert!(resolved.0[0].is_writable);
+        assert_eq!(resolved.0[1].pubkey, writable_key);
+        assert!(resolved.0[1].is_writable);
+        assert_eq!(resolved.0[2].pubkey, readonly_key);
+        assert!(!resolved.0[2].is_writable);
+    }
+
+    #[test]
+    fn test_resolve_errors_on_unresolvable_table() {
+        let payer = Pubkey::new_unique();
+        let table_key = Pubkey::new_unique();
+
+        let message = v0_message_with_lookup(
+            payer,
+            v0::MessageAddressTableLookup {
+                account_key: table_key,
+                writable_indexes: vec![0],
+                readonly_indexes: vec![],
+            },
+        );
+        let tx = VersionedTransaction {
+            signatures: vec![Signature::default()],
+            message: VersionedMessage::V0(message),
+        };
+        let sanitized_tx =
+            SanitizedVersionedTransaction::try_new(tx, Some(false), &FeatureSet::default())
+                .unwrap();
+
+        let resolved = sanitized_tx.resolve(&FakeLookupResolver(HashMap::new()));
+        assert_eq!(resolved, Err(SanitizeError::IndexOutOfBounds));
+    }
 }
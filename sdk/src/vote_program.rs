@@ -75,6 +75,91 @@ pub enum VoteInstruction {
     Vote,
 }
 
+/// A GRANDPA-style stake-weighted finality vote over a single
+/// `BlockDescription`, created by `VoteInstruction::ProposeBlock` and
+/// updated by `VoteInstruction::Vote`.
+#[derive(Serialize, Default, Deserialize, Debug, PartialEq, Eq, Clone)]
+pub struct WeightedElection {
+    pub block: BlockDescription,
+    /// Sum of `block.weights`, fixed at proposal time.
+    pub total_weight: u64,
+    /// Voters who have cast a vote, and the weight recorded for them.
+    pub voted: HashMap<Pubkey, u64>,
+    pub accumulated_weight: u64,
+    /// Set once `accumulated_weight * 3 > total_weight * 2`; monotonic,
+    /// never unset once `true`.
+    pub finalized: bool,
+}
+
+impl WeightedElection {
+    pub fn new(block: BlockDescription) -> Self {
+        let total_weight = block.weights.values().sum();
+        Self {
+            block,
+            total_weight,
+            voted: HashMap::new(),
+            accumulated_weight: 0,
+            finalized: false,
+        }
+    }
+
+    /// Records a vote from `voter`. `voter` must be one of `block.weights`'
+    /// electors and must not have voted already; finalization is monotonic,
+    /// so a vote arriving after finalization still succeeds but can't
+    /// un-finalize the election.
+    pub fn vote(&mut self, voter: &Pubkey) -> Result<(), ProgramError> {
+        let weight = *self
+            .block
+            .weights
+            .get(voter)
+            .ok_or(ProgramError::GenericError)?;
+
+        if self.voted.insert(*voter, weight).is_some() {
+            return Err(ProgramError::GenericError);
+        }
+
+        self.accumulated_weight += weight;
+        self.finalized =
+            self.finalized || self.accumulated_weight * 3 > self.total_weight * 2;
+        Ok(())
+    }
+
+    /// Returns whether the block is finalized, and the fraction of
+    /// `total_weight` that has voted so far (0.0 if `total_weight` is 0).
+    pub fn tally(&self) -> (bool, f64) {
+        let fraction = if self.total_weight == 0 {
+            0.0
+        } else {
+            self.accumulated_weight as f64 / self.total_weight as f64
+        };
+        (self.finalized, fraction)
+    }
+
+    pub fn deserialize(input: &[u8]) -> Result<WeightedElection, ProgramError> {
+        deserialize(input).map_err(|_| ProgramError::InvalidUserdata)
+    }
+
+    pub fn serialize(self: &WeightedElection, output: &mut [u8]) -> Result<(), ProgramError> {
+        serialize_into(output, self).map_err(|err| match *err {
+            ErrorKind::SizeLimit => ProgramError::UserdataTooSmall,
+            _ => ProgramError::GenericError,
+        })
+    }
+
+    // Upper limit on the size of a WeightedElection. Equal to
+    // sizeof(WeightedElection) when both `block.weights` and `voted` hold
+    // MAX_VOTE_HISTORY voters.
+    pub fn get_max_size() -> usize {
+        let mut block = BlockDescription::default();
+        for _ in 0..MAX_VOTE_HISTORY {
+            block.weights.insert(Pubkey::new_unique(), 1);
+        }
+        let mut election = WeightedElection::new(block);
+        election.voted = election.block.weights.clone();
+        serialized_size(&election).unwrap() as usize
+    }
+}
+
 #[derive(Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct VoteProgram {
     pub votes: VecDeque<Vote>,
@@ -114,4 +199,58 @@ mod tests {
         vote_program.serialize(&mut buffer).unwrap();
         assert_eq!(VoteProgram::deserialize(&buffer).unwrap(), vote_program);
     }
+
+    fn new_weighted_election(weights: &[(Pubkey, u64)]) -> WeightedElection {
+        let block = BlockDescription::new(
+            0,
+            Hash::default(),
+            Hash::default(),
+            weights.iter().cloned().collect(),
+        );
+        WeightedElection::new(block)
+    }
+
+    #[test]
+    fn test_weighted_election_finalizes_at_two_thirds() {
+        let voters: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let mut election =
+            new_weighted_election(&[(voters[0], 2), (voters[1], 2), (voters[2], 1)]);
+
+        election.vote(&voters[0]).unwrap();
+        assert_eq!(election.tally(), (false, 2.0 / 5.0));
+
+        // 4/5 is strictly above two thirds, so the election finalizes here.
+        election.vote(&voters[1]).unwrap();
+        assert_eq!(election.tally(), (true, 4.0 / 5.0));
+
+        // Finalization is monotonic: the last voter coming in afterwards
+        // cannot un-finalize the election.
+        election.vote(&voters[2]).unwrap();
+        assert_eq!(election.tally(), (true, 1.0));
+    }
+
+    #[test]
+    fn test_weighted_election_rejects_unknown_voter() {
+        let voter = Pubkey::new_unique();
+        let mut election = new_weighted_election(&[(voter, 1)]);
+        assert!(election.vote(&Pubkey::new_unique()).is_err());
+    }
+
+    #[test]
+    fn test_weighted_election_rejects_double_vote() {
+        let voter = Pubkey::new_unique();
+        let mut election = new_weighted_election(&[(voter, 1)]);
+        election.vote(&voter).unwrap();
+        assert!(election.vote(&voter).is_err());
+    }
+
+    #[test]
+    fn test_weighted_election_serde() {
+        let mut buffer: Vec<u8> = vec![0; WeightedElection::get_max_size()];
+        let voter = Pubkey::new_unique();
+        let mut election = new_weighted_election(&[(voter, 1)]);
+        election.vote(&voter).unwrap();
+        election.serialize(&mut buffer).unwrap();
+        assert_eq!(WeightedElection::deserialize(&buffer).unwrap(), election);
+    }
 }
@@ -0,0 +1,411 @@
+//! Control-flow-graph and register-liveness export for a loaded program,
+//! reconstructed directly from its sBPF instruction stream.
+
+use {
+    crate::{
+        elf::{self, parse_elf_header, read_u32, section_headers},
+        program::sealevel_program,
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        ffi::CString,
+        os::raw::c_char,
+        ptr,
+    },
+};
+
+/// Output format for `sealevel_dump_cfg`. A single variant today; kept as an
+/// enum so a textual or JSON export can be added without breaking callers.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum sealevel_cfg_format {
+    SEALEVEL_CFG_FORMAT_DOT,
+}
+
+pub(crate) const INSN_SIZE: usize = 8;
+
+/// BPF instruction classes, the low 3 bits of the opcode byte.
+pub(crate) const BPF_CLASS_MASK: u8 = 0x07;
+const BPF_LD: u8 = 0x00;
+const BPF_LDX: u8 = 0x01;
+const BPF_ST: u8 = 0x02;
+const BPF_STX: u8 = 0x03;
+pub(crate) const BPF_ALU: u8 = 0x04;
+pub(crate) const BPF_JMP: u8 = 0x05;
+pub(crate) const BPF_JMP32: u8 = 0x06;
+pub(crate) const BPF_ALU64: u8 = 0x07;
+
+pub(crate) const BPF_SRC_X: u8 = 0x08;
+pub(crate) const BPF_OP_MOV: u8 = 0xb;
+pub(crate) const BPF_OP_JA: u8 = 0x0;
+pub(crate) const BPF_OP_CALL: u8 = 0x8;
+pub(crate) const BPF_OP_EXIT: u8 = 0x9;
+
+pub(crate) const OP_CALL: u8 = (BPF_JMP) | (BPF_OP_CALL << 4);
+pub(crate) const OP_EXIT: u8 = (BPF_JMP) | (BPF_OP_EXIT << 4);
+
+/// `src_reg` value marking a sBPF `call` as a relative call to another
+/// function within the same program, rather than to a syscall/helper.
+pub(crate) const BPF_PSEUDO_CALL: u8 = 1;
+
+/// A decoded 8-byte sBPF instruction at a given instruction index (`pc`).
+#[derive(Clone, Copy)]
+pub(crate) struct Insn {
+    pub(crate) opc: u8,
+    pub(crate) dst: u8,
+    pub(crate) src: u8,
+    pub(crate) off: i16,
+    pub(crate) imm: i32,
+}
+
+fn decode_insn(bytes: &[u8; INSN_SIZE]) -> Insn {
+    Insn {
+        opc: bytes[0],
+        dst: bytes[1] & 0x0f,
+        src: (bytes[1] >> 4) & 0x0f,
+        off: i16::from_le_bytes([bytes[2], bytes[3]]),
+        imm: i32::from_le_bytes([bytes[4], bytes[5], bytes[6], bytes[7]]),
+    }
+}
+
+/// Locates the program's executable `PROGBITS` section (conventionally
+/// `.text`) and decodes it into fixed-width 8-byte sBPF instructions.
+/// Trailing bytes that don't make up a whole instruction are ignored, since
+/// a truncated tail cannot be a valid instruction either way.
+pub(crate) fn decode_instructions(elf: &[u8]) -> Option<Vec<Insn>> {
+    let header = parse_elf_header(elf)?;
+    let text = section_headers(elf, &header)
+        .find(|s| s.sh_type == elf::SHT_PROGBITS && s.sh_flags & elf::SHF_EXECINSTR != 0)?;
+
+    let text_bytes = elf.get(text.sh_offset..text.sh_offset.checked_add(text.sh_size)?)?;
+    Some(
+        text_bytes
+            .chunks_exact(INSN_SIZE)
+            .map(|chunk| decode_insn(chunk.try_into().unwrap()))
+            .collect(),
+    )
+}
+
+/// The successor instruction indices of `insn` at position `pc` within a
+/// `len`-instruction program. An out-of-range target (e.g. a branch past the
+/// end of the program) is dropped rather than followed.
+fn successors(insns: &[Insn], pc: usize) -> Vec<usize> {
+    let insn = insns[pc];
+    let len = insns.len();
+    let fallthrough = (pc + 1 < len).then(|| pc + 1);
+
+    let class = insn.opc & BPF_CLASS_MASK;
+    if insn.opc == OP_EXIT {
+        return vec![];
+    }
+    if insn.opc == OP_CALL {
+        let mut targets = Vec::new();
+        if insn.src == BPF_PSEUDO_CALL {
+            let target = pc as i64 + 1 + insn.imm as i64;
+            if target >= 0 && (target as usize) < len {
+                targets.push(target as usize);
+            }
+        }
+        targets.extend(fallthrough);
+        return targets;
+    }
+    if class == BPF_JMP || class == BPF_JMP32 {
+        let op = (insn.opc >> 4) & 0x0f;
+        let target = pc as i64 + 1 + insn.off as i64;
+        let target = (target >= 0 && (target as usize) < len).then(|| target as usize);
+        if op == BPF_OP_JA {
+            return target.into_iter().collect();
+        }
+        return target.into_iter().chain(fallthrough).collect();
+    }
+
+    fallthrough.into_iter().collect()
+}
+
+/// Finds every basic-block leader: instruction 0, every branch/call target,
+/// and every instruction immediately following a block-ending instruction.
+fn find_leaders(insns: &[Insn]) -> BTreeSet<usize> {
+    let mut leaders = BTreeSet::new();
+    if insns.is_empty() {
+        return leaders;
+    }
+    leaders.insert(0);
+    for pc in 0..insns.len() {
+        for target in successors(insns, pc) {
+            leaders.insert(target);
+        }
+    }
+    leaders
+}
+
+/// A maximal run of instructions with no internal leaders.
+struct Block {
+    start: usize,
+    end: usize, // exclusive
+}
+
+fn build_blocks(insns: &[Insn], leaders: &BTreeSet<usize>) -> Vec<Block> {
+    let mut starts: Vec<usize> = leaders.iter().copied().collect();
+    starts.sort_unstable();
+    starts
+        .iter()
+        .enumerate()
+        .map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(insns.len());
+            Block { start, end }
+        })
+        .collect()
+}
+
+/// r0-r10, encoded as bits 0..=10 of a `u16`.
+type RegSet = u16;
+
+fn reg_bit(reg: u8) -> RegSet {
+    1 << reg
+}
+
+/// The registers `insn` reads and writes, per the sBPF calling convention and
+/// ALU/memory operand encoding. `call` is modeled as reading the argument
+/// registers r1-r5 and writing the return register r0; `exit` is modeled as
+/// reading r0.
+fn reg_effects(insn: &Insn) -> (RegSet, RegSet) {
+    let class = insn.opc & BPF_CLASS_MASK;
+    let source_is_reg = insn.opc & BPF_SRC_X != 0;
+
+    if insn.opc == OP_EXIT {
+        return (reg_bit(0), 0);
+    }
+    if insn.opc == OP_CALL {
+        let reads = reg_bit(1) | reg_bit(2) | reg_bit(3) | reg_bit(4) | reg_bit(5);
+        return (reads, reg_bit(0));
+    }
+
+    match class {
+        BPF_ALU | BPF_ALU64 => {
+            let op = (insn.opc >> 4) & 0x0f;
+            let mut reads = if source_is_reg { reg_bit(insn.src) } else { 0 };
+            if op != BPF_OP_MOV {
+                reads |= reg_bit(insn.dst);
+            }
+            (reads, reg_bit(insn.dst))
+        }
+        BPF_LD | BPF_LDX => {
+            let reads = if class == BPF_LDX { reg_bit(insn.src) } else { 0 };
+            (reads, reg_bit(insn.dst))
+        }
+        BPF_ST | BPF_STX => {
+            let mut reads = reg_bit(insn.dst);
+            if class == BPF_STX {
+                reads |= reg_bit(insn.src);
+            }
+            (reads, 0)
+        }
+        BPF_JMP | BPF_JMP32 => {
+            let op = (insn.opc >> 4) & 0x0f;
+            if op == BPF_OP_JA {
+                (0, 0)
+            } else {
+                let mut reads = reg_bit(insn.dst);
+                if source_is_reg {
+                    reads |= reg_bit(insn.src);
+                }
+                (reads, 0)
+            }
+        }
+        _ => (0, 0),
+    }
+}
+
+/// Exact backward (reverse-dataflow) register liveness over the whole
+/// instruction stream: `live_out[pc]` is the union of `live_in` over `pc`'s
+/// successors, and `live_in[pc] = (live_out[pc] - writes[pc]) | reads[pc]`.
+/// Iterates to a fixpoint, which a finite CFG over live registers is
+/// guaranteed to reach; the iteration count is capped defensively since the
+/// instruction stream comes from untrusted on-chain data.
+fn compute_liveness(insns: &[Insn]) -> (Vec<RegSet>, Vec<RegSet>) {
+    let len = insns.len();
+    let mut live_in = vec![0 as RegSet; len];
+    let mut live_out = vec![0 as RegSet; len];
+    let effects: Vec<(RegSet, RegSet)> = insns.iter().map(reg_effects).collect();
+    let succs: Vec<Vec<usize>> = (0..len).map(|pc| successors(insns, pc)).collect();
+
+    let max_iters = len.saturating_mul(4).max(1);
+    for _ in 0..max_iters {
+        let mut changed = false;
+        for pc in (0..len).rev() {
+            let mut out = 0 as RegSet;
+            for &succ in &succs[pc] {
+                out |= live_in[succ];
+            }
+            let (reads, writes) = effects[pc];
+            let input = (out & !writes) | reads;
+            if out != live_out[pc] || input != live_in[pc] {
+                changed = true;
+            }
+            live_out[pc] = out;
+            live_in[pc] = input;
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    (live_in, live_out)
+}
+
+fn format_reg_set(regs: RegSet) -> String {
+    let names: Vec<String> = (0..=10)
+        .filter(|&r| regs & reg_bit(r) != 0)
+        .map(|r| format!("r{r}"))
+        .collect();
+    if names.is_empty() {
+        "-".to_string()
+    } else {
+        names.join(" ")
+    }
+}
+
+fn format_insn(insn: &Insn, enable_symbol_and_section_labels: bool) -> String {
+    if enable_symbol_and_section_labels {
+        format!(
+            "opc=0x{:02x} dst=r{} src=r{} off={} imm={}",
+            insn.opc, insn.dst, insn.src, insn.off, insn.imm
+        )
+    } else {
+        format!("0x{:02x}", insn.opc)
+    }
+}
+
+fn render_dot(
+    insns: &[Insn],
+    blocks: &[Block],
+    live_in: &[RegSet],
+    live_out: &[RegSet],
+    enable_symbol_and_section_labels: bool,
+) -> String {
+    let mut dot = String::from("digraph cfg {\n");
+    for block in blocks {
+        let mut label = format!("bb_{}:\\l", block.start);
+        for pc in block.start..block.end {
+            label.push_str(&format_insn(&insns[pc], enable_symbol_and_section_labels));
+            label.push_str("\\l");
+        }
+        if block.start < block.end {
+            label.push_str(&format!(
+                "live-in: {}\\llive-out: {}\\l",
+                format_reg_set(live_in[block.start]),
+                format_reg_set(live_out[block.end - 1]),
+            ));
+        }
+        dot.push_str(&format!(
+            "  bb_{} [shape=box, label=\"{}\"];\n",
+            block.start, label
+        ));
+    }
+
+    for block in blocks {
+        if block.start >= block.end {
+            continue;
+        }
+        let last = block.end - 1;
+        let insn = insns[last];
+        let targets = successors(insns, last);
+        let label = if insn.opc == OP_CALL {
+            "call"
+        } else if (insn.opc & BPF_CLASS_MASK == BPF_JMP || insn.opc & BPF_CLASS_MASK == BPF_JMP32)
+            && (insn.opc >> 4) & 0x0f != BPF_OP_JA
+            && targets.len() > 1
+        {
+            "taken/fallthrough"
+        } else {
+            ""
+        };
+        for target in targets {
+            if label.is_empty() {
+                dot.push_str(&format!("  bb_{} -> bb_{};\n", block.start, target));
+            } else {
+                dot.push_str(&format!(
+                    "  bb_{} -> bb_{} [label=\"{}\"];\n",
+                    block.start, target, label
+                ));
+            }
+        }
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+fn render_liveness_summary(blocks: &[Block], live_in: &[RegSet], live_out: &[RegSet]) -> String {
+    let mut summary = BTreeMap::new();
+    for block in blocks {
+        if block.start >= block.end {
+            continue;
+        }
+        summary.insert(
+            block.start,
+            format!(
+                "bb_{}: live-in={{{}}} live-out={{{}}}",
+                block.start,
+                format_reg_set(live_in[block.start]),
+                format_reg_set(live_out[block.end - 1]),
+            ),
+        );
+    }
+    summary.into_values().collect::<Vec<_>>().join("\n")
+}
+
+/// Reconstructs the basic-block control-flow graph of a loaded program and
+/// emits it in `format`, annotated with a backward register-liveness summary.
+///
+/// `*out_dot` is set to a newly allocated, NUL-terminated string that the
+/// caller must release with `sealevel_cfg_string_free`. If `out_liveness` is
+/// non-null, `*out_liveness` is likewise set to a caller-freed string
+/// summarizing live-out registers per basic block; pass null to skip it.
+///
+/// # Safety
+/// `program` must be a non-null pointer returned by `sealevel_program_new`.
+/// `out_dot` must be non-null and writable; `out_liveness`, if non-null, must
+/// itself be writable.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_dump_cfg(
+    program: *const sealevel_program,
+    format: sealevel_cfg_format,
+    enable_symbol_and_section_labels: bool,
+    out_dot: *mut *mut c_char,
+    out_liveness: *mut *mut c_char,
+) -> bool {
+    let sealevel_cfg_format::SEALEVEL_CFG_FORMAT_DOT = format;
+
+    let elf = (*program).elf();
+    let insns = match decode_instructions(elf) {
+        Some(insns) => insns,
+        None => return false,
+    };
+
+    let leaders = find_leaders(&insns);
+    let blocks = build_blocks(&insns, &leaders);
+    let (live_in, live_out) = compute_liveness(&insns);
+
+    let dot = render_dot(&insns, &blocks, &live_in, &live_out, enable_symbol_and_section_labels);
+    *out_dot = CString::new(dot).unwrap_or_default().into_raw();
+
+    if !out_liveness.is_null() {
+        let summary = render_liveness_summary(&blocks, &live_in, &live_out);
+        *out_liveness = CString::new(summary).unwrap_or_default().into_raw();
+    }
+
+    true
+}
+
+/// Releases a string returned by `sealevel_dump_cfg`.
+///
+/// # Safety
+/// `s` must be a pointer obtained from `sealevel_dump_cfg` (or null, which is
+/// a no-op), and must not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_cfg_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
@@ -5,9 +5,64 @@ use {crate::config::sealevel_config_opt::*, solana_rbpf::vm::Config, std::os::ra
 pub struct sealevel_config {
     pub(crate) config: Config,
     pub(crate) no_verify: bool,
+    pub(crate) enable_profiling: bool,
 }
 
+/// Result of a `sealevel_config_set_*` call.
 #[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum sealevel_result {
+    SEALEVEL_OK,
+    /// `key` is not a value of `sealevel_config_opt`, or is `SEALEVEL_OPT_NONE`.
+    SEALEVEL_ERR_INVALID_KEY,
+    /// `key` is valid but does not hold a value of the type the setter expects.
+    SEALEVEL_ERR_WRONG_VALUE_TYPE,
+    /// No well-formed `NT_GNU_BUILD_ID` note was found.
+    SEALEVEL_ERR_BUILD_ID_NOT_FOUND,
+    /// The caller's output buffer is smaller than the data being returned.
+    SEALEVEL_ERR_BUFFER_TOO_SMALL,
+}
+
+/// The value type a `sealevel_config_opt` key expects, used to validate a
+/// typed setter's argument against the key before writing it into the config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum sealevel_config_opt_kind {
+    None,
+    Bool,
+    Usize,
+    F64,
+}
+
+fn sealevel_config_opt_kind_of(key: sealevel_config_opt) -> sealevel_config_opt_kind {
+    match key {
+        SEALEVEL_OPT_NONE => sealevel_config_opt_kind::None,
+        SEALEVEL_OPT_NO_VERIFY
+        | SEALEVEL_ENABLE_STACK_FRAME_GAPS
+        | SEALEVEL_ENABLE_INSTRUCTION_METER
+        | SEALEVEL_ENABLE_INSTRUCTION_TRACING
+        | SEALEVEL_ENABLE_SYMBOL_AND_SECTION_LABELS
+        | SEALEVEL_ENABLE_PROFILING
+        | SEALEVEL_DISABLE_UNRESOLVED_SYMBOLS_AT_RUNTIME
+        | SEALEVEL_REJECT_BROKEN_ELFS
+        | SEALEVEL_SANITIZE_USER_PROVIDED_VALUES
+        | SEALEVEL_ENCRYPT_ENVIRONMENT_REGISTERS
+        | SEALEVEL_DISABLE_DEPRECATED_LOAD_INSTRUCTIONS
+        | SEALEVEL_SYSCALL_BPF_FUNCTION_HASH_COLLISION
+        | SEALEVEL_REJECT_CALLX_R10
+        | SEALEVEL_DYNAMIC_STACK_FRAMES
+        | SEALEVEL_ENABLE_SDIV
+        | SEALEVEL_OPTIMIZE_RODATA
+        | SEALEVEL_STATIC_SYSCALLS
+        | SEALEVEL_ENABLE_ELF_VADDR => sealevel_config_opt_kind::Bool,
+        SEALEVEL_OPT_MAX_CALL_DEPTH
+        | SEALEVEL_STACK_FRAME_SIZE
+        | SEALEVEL_INSTRUCTION_METER_CHECKPOINT_DISTANCE => sealevel_config_opt_kind::Usize,
+        SEALEVEL_NOOP_INSTRUCTION_RATIO => sealevel_config_opt_kind::F64,
+    }
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum sealevel_config_opt {
     SEALEVEL_OPT_NONE,
     SEALEVEL_OPT_NO_VERIFY,
@@ -18,6 +73,7 @@ pub enum sealevel_config_opt {
     SEALEVEL_ENABLE_INSTRUCTION_METER,
     SEALEVEL_ENABLE_INSTRUCTION_TRACING,
     SEALEVEL_ENABLE_SYMBOL_AND_SECTION_LABELS,
+    SEALEVEL_ENABLE_PROFILING,
     SEALEVEL_DISABLE_UNRESOLVED_SYMBOLS_AT_RUNTIME,
     SEALEVEL_REJECT_BROKEN_ELFS,
     SEALEVEL_NOOP_INSTRUCTION_RATIO,
@@ -50,14 +106,135 @@ pub extern "C" fn sealevel_config_new() -> *mut sealevel_config {
     Box::into_raw(Box::new(wrapper))
 }
 
-macro_rules! va_bool {
-    ($args:ident) => {
-        $args.arg::<c_int>() != 0
-    };
+fn set_bool_unchecked(config: &mut sealevel_config, key: sealevel_config_opt, value: bool) {
+    match key {
+        SEALEVEL_OPT_NO_VERIFY => config.no_verify = value,
+        SEALEVEL_ENABLE_STACK_FRAME_GAPS => config.config.enable_stack_frame_gaps = value,
+        SEALEVEL_ENABLE_INSTRUCTION_METER => config.config.enable_instruction_meter = value,
+        SEALEVEL_ENABLE_INSTRUCTION_TRACING => config.config.enable_instruction_tracing = value,
+        SEALEVEL_ENABLE_SYMBOL_AND_SECTION_LABELS => {
+            config.config.enable_symbol_and_section_labels = value
+        }
+        SEALEVEL_ENABLE_PROFILING => config.enable_profiling = value,
+        SEALEVEL_DISABLE_UNRESOLVED_SYMBOLS_AT_RUNTIME => {
+            config.config.disable_unresolved_symbols_at_runtime = value
+        }
+        SEALEVEL_REJECT_BROKEN_ELFS => config.config.reject_broken_elfs = value,
+        SEALEVEL_SANITIZE_USER_PROVIDED_VALUES => {
+            config.config.sanitize_user_provided_values = value
+        }
+        SEALEVEL_ENCRYPT_ENVIRONMENT_REGISTERS => {
+            config.config.encrypt_environment_registers = value
+        }
+        SEALEVEL_DISABLE_DEPRECATED_LOAD_INSTRUCTIONS => {
+            config.config.disable_deprecated_load_instructions = value
+        }
+        SEALEVEL_SYSCALL_BPF_FUNCTION_HASH_COLLISION => {
+            config.config.syscall_bpf_function_hash_collision = value
+        }
+        SEALEVEL_REJECT_CALLX_R10 => config.config.reject_callx_r10 = value,
+        SEALEVEL_DYNAMIC_STACK_FRAMES => config.config.dynamic_stack_frames = value,
+        SEALEVEL_ENABLE_SDIV => config.config.enable_sdiv = value,
+        SEALEVEL_OPTIMIZE_RODATA => config.config.optimize_rodata = value,
+        SEALEVEL_STATIC_SYSCALLS => config.config.static_syscalls = value,
+        SEALEVEL_ENABLE_ELF_VADDR => config.config.enable_elf_vaddr = value,
+        SEALEVEL_OPT_NONE
+        | SEALEVEL_OPT_MAX_CALL_DEPTH
+        | SEALEVEL_STACK_FRAME_SIZE
+        | SEALEVEL_INSTRUCTION_METER_CHECKPOINT_DISTANCE
+        | SEALEVEL_NOOP_INSTRUCTION_RATIO => {
+            unreachable!("caller must check sealevel_config_opt_kind_of first")
+        }
+    }
+}
+
+fn set_usize_unchecked(config: &mut sealevel_config, key: sealevel_config_opt, value: usize) {
+    match key {
+        SEALEVEL_OPT_MAX_CALL_DEPTH => config.config.max_call_depth = value,
+        SEALEVEL_STACK_FRAME_SIZE => config.config.stack_frame_size = value,
+        SEALEVEL_INSTRUCTION_METER_CHECKPOINT_DISTANCE => {
+            config.config.instruction_meter_checkpoint_distance = value
+        }
+        _ => unreachable!("caller must check sealevel_config_opt_kind_of first"),
+    }
+}
+
+fn set_f64_unchecked(config: &mut sealevel_config, key: sealevel_config_opt, value: f64) {
+    match key {
+        SEALEVEL_NOOP_INSTRUCTION_RATIO => config.config.noop_instruction_ratio = value,
+        _ => unreachable!("caller must check sealevel_config_opt_kind_of first"),
+    }
+}
+
+/// Sets a boolean-valued config option.
+///
+/// # Safety
+/// `config` must be a non-null pointer returned by `sealevel_config_new`.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_config_set_bool(
+    config: *mut sealevel_config,
+    key: sealevel_config_opt,
+    value: c_int,
+) -> sealevel_result {
+    match sealevel_config_opt_kind_of(key) {
+        sealevel_config_opt_kind::None => return sealevel_result::SEALEVEL_ERR_INVALID_KEY,
+        sealevel_config_opt_kind::Bool => (),
+        sealevel_config_opt_kind::Usize | sealevel_config_opt_kind::F64 => {
+            return sealevel_result::SEALEVEL_ERR_WRONG_VALUE_TYPE
+        }
+    }
+    set_bool_unchecked(&mut *config, key, value != 0);
+    sealevel_result::SEALEVEL_OK
+}
+
+/// Sets a usize-valued config option.
+///
+/// # Safety
+/// `config` must be a non-null pointer returned by `sealevel_config_new`.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_config_set_usize(
+    config: *mut sealevel_config,
+    key: sealevel_config_opt,
+    value: usize,
+) -> sealevel_result {
+    match sealevel_config_opt_kind_of(key) {
+        sealevel_config_opt_kind::None => return sealevel_result::SEALEVEL_ERR_INVALID_KEY,
+        sealevel_config_opt_kind::Usize => (),
+        sealevel_config_opt_kind::Bool | sealevel_config_opt_kind::F64 => {
+            return sealevel_result::SEALEVEL_ERR_WRONG_VALUE_TYPE
+        }
+    }
+    set_usize_unchecked(&mut *config, key, value);
+    sealevel_result::SEALEVEL_OK
+}
+
+/// Sets an f64-valued config option.
+///
+/// # Safety
+/// `config` must be a non-null pointer returned by `sealevel_config_new`.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_config_set_f64(
+    config: *mut sealevel_config,
+    key: sealevel_config_opt,
+    value: f64,
+) -> sealevel_result {
+    match sealevel_config_opt_kind_of(key) {
+        sealevel_config_opt_kind::None => return sealevel_result::SEALEVEL_ERR_INVALID_KEY,
+        sealevel_config_opt_kind::F64 => (),
+        sealevel_config_opt_kind::Bool | sealevel_config_opt_kind::Usize => {
+            return sealevel_result::SEALEVEL_ERR_WRONG_VALUE_TYPE
+        }
+    }
+    set_f64_unchecked(&mut *config, key, value);
+    sealevel_result::SEALEVEL_OK
 }
 
 /// Sets a config option given the config key and exactly one value arg.
 ///
+/// Kept for compatibility with existing callers; prefer the type-safe
+/// `sealevel_config_set_bool`/`_usize`/`_f64` entry points, which validate `key`
+/// against the value type instead of trusting the caller to pass the right one.
+///
 /// # Safety
 /// Avoid the following undefined behavior:
 /// - Passing the wrong argument type as the config value (each key documents the expected value).
@@ -71,51 +248,17 @@ pub unsafe extern "C" fn sealevel_config_setopt(
     key: sealevel_config_opt,
     mut args: ...
 ) {
-    match key {
-        SEALEVEL_OPT_NONE => (),
-        SEALEVEL_OPT_NO_VERIFY => (*config).no_verify = va_bool!(args),
-        SEALEVEL_OPT_MAX_CALL_DEPTH => (*config).config.max_call_depth = args.arg::<usize>(),
-        SEALEVEL_STACK_FRAME_SIZE => (*config).config.stack_frame_size = args.arg::<usize>(),
-        SEALEVEL_ENABLE_STACK_FRAME_GAPS => {
-            (*config).config.enable_stack_frame_gaps = va_bool!(args)
-        }
-        SEALEVEL_INSTRUCTION_METER_CHECKPOINT_DISTANCE => {
-            (*config).config.instruction_meter_checkpoint_distance = args.arg::<usize>()
-        }
-        SEALEVEL_ENABLE_INSTRUCTION_METER => {
-            (*config).config.enable_instruction_meter = va_bool!(args)
+    match sealevel_config_opt_kind_of(key) {
+        sealevel_config_opt_kind::None => (),
+        sealevel_config_opt_kind::Bool => {
+            sealevel_config_set_bool(config, key, args.arg::<c_int>());
         }
-        SEALEVEL_ENABLE_INSTRUCTION_TRACING => {
-            (*config).config.enable_instruction_tracing = va_bool!(args)
+        sealevel_config_opt_kind::Usize => {
+            sealevel_config_set_usize(config, key, args.arg::<usize>());
         }
-        SEALEVEL_ENABLE_SYMBOL_AND_SECTION_LABELS => {
-            (*config).config.enable_symbol_and_section_labels = va_bool!(args)
-        }
-        SEALEVEL_DISABLE_UNRESOLVED_SYMBOLS_AT_RUNTIME => {
-            (*config).config.disable_unresolved_symbols_at_runtime = va_bool!(args)
-        }
-        SEALEVEL_REJECT_BROKEN_ELFS => (*config).config.reject_broken_elfs = va_bool!(args),
-        SEALEVEL_NOOP_INSTRUCTION_RATIO => {
-            (*config).config.noop_instruction_ratio = args.arg::<f64>()
-        }
-        SEALEVEL_SANITIZE_USER_PROVIDED_VALUES => {
-            (*config).config.sanitize_user_provided_values = va_bool!(args)
-        }
-        SEALEVEL_ENCRYPT_ENVIRONMENT_REGISTERS => {
-            (*config).config.encrypt_environment_registers = va_bool!(args)
-        }
-        SEALEVEL_DISABLE_DEPRECATED_LOAD_INSTRUCTIONS => {
-            (*config).config.disable_deprecated_load_instructions = va_bool!(args)
-        }
-        SEALEVEL_SYSCALL_BPF_FUNCTION_HASH_COLLISION => {
-            (*config).config.syscall_bpf_function_hash_collision = va_bool!(args)
+        sealevel_config_opt_kind::F64 => {
+            sealevel_config_set_f64(config, key, args.arg::<f64>());
         }
-        SEALEVEL_REJECT_CALLX_R10 => (*config).config.reject_callx_r10 = va_bool!(args),
-        SEALEVEL_DYNAMIC_STACK_FRAMES => (*config).config.dynamic_stack_frames = va_bool!(args),
-        SEALEVEL_ENABLE_SDIV => (*config).config.enable_sdiv = va_bool!(args),
-        SEALEVEL_OPTIMIZE_RODATA => (*config).config.optimize_rodata = va_bool!(args),
-        SEALEVEL_STATIC_SYSCALLS => (*config).config.static_syscalls = va_bool!(args),
-        SEALEVEL_ENABLE_ELF_VADDR => (*config).config.enable_elf_vaddr = va_bool!(args),
     }
 }
 
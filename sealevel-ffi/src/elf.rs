@@ -0,0 +1,136 @@
+//! Minimal, bounds-checked ELF64 reading shared by the program-introspection
+//! APIs (`program::sealevel_program_build_id`, `cfg::sealevel_dump_cfg`).
+//! Every accessor takes the untrusted on-chain ELF bytes and returns `None`
+//! rather than panicking or reading past the buffer.
+
+use std::convert::TryInto;
+
+pub(crate) const ELF_MAGIC: [u8; 4] = [0x7f, b'E', b'L', b'F'];
+pub(crate) const ELFCLASS64: u8 = 2;
+pub(crate) const ELFDATA2LSB: u8 = 1;
+
+pub(crate) const SHT_NOTE: u32 = 7;
+pub(crate) const SHT_PROGBITS: u32 = 1;
+pub(crate) const SHF_EXECINSTR: u64 = 0x4;
+pub(crate) const PT_NOTE: u32 = 4;
+
+pub(crate) fn read_u16(elf: &[u8], offset: usize) -> Option<u16> {
+    elf.get(offset..offset.checked_add(2)?)
+        .map(|b| u16::from_le_bytes(b.try_into().unwrap()))
+}
+
+pub(crate) fn read_u32(elf: &[u8], offset: usize) -> Option<u32> {
+    elf.get(offset..offset.checked_add(4)?)
+        .map(|b| u32::from_le_bytes(b.try_into().unwrap()))
+}
+
+pub(crate) fn read_u64(elf: &[u8], offset: usize) -> Option<u64> {
+    elf.get(offset..offset.checked_add(8)?)
+        .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+}
+
+/// 4-byte-aligns `len`, or `None` if doing so would overflow `usize`.
+pub(crate) fn align4(len: usize) -> Option<usize> {
+    len.checked_add(3).map(|n| n & !3)
+}
+
+/// The handful of ELF64 header fields needed to walk section and program
+/// headers. Re-validated against the magic/class/endianness before any
+/// offsets derived from it are trusted.
+pub(crate) struct ElfHeader {
+    pub(crate) e_shoff: usize,
+    pub(crate) e_shentsize: usize,
+    pub(crate) e_shnum: usize,
+    pub(crate) e_phoff: usize,
+    pub(crate) e_phentsize: usize,
+    pub(crate) e_phnum: usize,
+}
+
+pub(crate) fn parse_elf_header(elf: &[u8]) -> Option<ElfHeader> {
+    if elf.len() < 64 || elf.get(0..4)? != ELF_MAGIC {
+        return None;
+    }
+    if elf[4] != ELFCLASS64 || elf[5] != ELFDATA2LSB {
+        return None;
+    }
+
+    Some(ElfHeader {
+        e_shoff: read_u64(elf, 40)? as usize,
+        e_shentsize: read_u16(elf, 58)? as usize,
+        e_shnum: read_u16(elf, 60)? as usize,
+        e_phoff: read_u64(elf, 32)? as usize,
+        e_phentsize: read_u16(elf, 54)? as usize,
+        e_phnum: read_u16(elf, 56)? as usize,
+    })
+}
+
+/// A section header's fields, with `sh_offset`/`sh_size` already re-validated
+/// to lie within `elf`.
+pub(crate) struct SectionHeader {
+    pub(crate) sh_type: u32,
+    pub(crate) sh_flags: u64,
+    pub(crate) sh_offset: usize,
+    pub(crate) sh_size: usize,
+}
+
+/// Yields every section header whose declared `(sh_offset, sh_size)` fits
+/// within `elf`; a header that would overflow or run past the end of the
+/// file is skipped rather than trusted.
+pub(crate) fn section_headers<'a>(
+    elf: &'a [u8],
+    header: &ElfHeader,
+) -> impl Iterator<Item = SectionHeader> + 'a {
+    let e_shoff = header.e_shoff;
+    let e_shentsize = header.e_shentsize;
+    let e_shnum = header.e_shnum;
+    (0..e_shnum).filter_map(move |i| {
+        let shdr_off = e_shoff.checked_add(i.checked_mul(e_shentsize)?)?;
+        let sh_type = read_u32(elf, shdr_off.checked_add(4)?)?;
+        let sh_flags = read_u64(elf, shdr_off.checked_add(8)?)?;
+        let sh_offset = read_u64(elf, shdr_off.checked_add(24)?)? as usize;
+        let sh_size = read_u64(elf, shdr_off.checked_add(32)?)? as usize;
+        if sh_offset.checked_add(sh_size).map_or(true, |end| end > elf.len()) {
+            return None;
+        }
+        Some(SectionHeader {
+            sh_type,
+            sh_flags,
+            sh_offset,
+            sh_size,
+        })
+    })
+}
+
+/// A `PT_NOTE` program header segment's fields, with `p_offset`/`p_filesz`
+/// already re-validated to lie within `elf`.
+pub(crate) struct NoteSegment {
+    pub(crate) p_offset: usize,
+    pub(crate) p_filesz: usize,
+}
+
+/// Yields every `PT_NOTE` program header segment whose declared
+/// `(p_offset, p_filesz)` fits within `elf`.
+pub(crate) fn note_segments<'a>(
+    elf: &'a [u8],
+    header: &ElfHeader,
+) -> impl Iterator<Item = NoteSegment> + 'a {
+    let e_phoff = header.e_phoff;
+    let e_phentsize = header.e_phentsize;
+    let e_phnum = header.e_phnum;
+    (0..e_phnum).filter_map(move |i| {
+        let phdr_off = e_phoff.checked_add(i.checked_mul(e_phentsize)?)?;
+        let p_type = read_u32(elf, phdr_off)?;
+        if p_type != PT_NOTE {
+            return None;
+        }
+        let p_offset = read_u64(elf, phdr_off.checked_add(8)?)? as usize;
+        let p_filesz = read_u64(elf, phdr_off.checked_add(32)?)? as usize;
+        if p_offset.checked_add(p_filesz).map_or(true, |end| end > elf.len()) {
+            return None;
+        }
+        Some(NoteSegment {
+            p_offset,
+            p_filesz,
+        })
+    })
+}
@@ -0,0 +1,138 @@
+//! Instruction-level execution profiling, accumulated during a
+//! `sealevel_vm` run when `SEALEVEL_ENABLE_PROFILING` is set and exported as
+//! either a compact per-function table or a Chrome-tracing-compatible JSON
+//! array of duration events.
+
+use std::{collections::BTreeMap, ffi::CString, os::raw::c_char};
+
+/// Accumulated counts for a single function, keyed by the instruction index
+/// its pseudo-call targets (0 for the entrypoint); ELF symbol hashes are not
+/// resolved.
+#[derive(Default, Clone)]
+pub(crate) struct FunctionProfile {
+    pub(crate) call_count: u64,
+    pub(crate) total_instructions: u64,
+}
+
+/// A profiling sink threaded through VM execution, recording per-function
+/// call/instruction counts and per-PC execution counts.
+#[derive(Default)]
+pub struct sealevel_profile {
+    pub(crate) functions: BTreeMap<u64, FunctionProfile>,
+    pub(crate) pc_counts: BTreeMap<u64, u64>,
+}
+
+impl sealevel_profile {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records one executed instruction at `pc`, attributed to the function
+    /// currently executing (identified by `function_hash`).
+    pub(crate) fn record_instruction(&mut self, function_hash: u64, pc: u64) {
+        self.functions
+            .entry(function_hash)
+            .or_default()
+            .total_instructions += 1;
+        *self.pc_counts.entry(pc).or_default() += 1;
+    }
+
+    /// Records one call into the function identified by `function_hash`.
+    pub(crate) fn record_call(&mut self, function_hash: u64) {
+        self.functions.entry(function_hash).or_default().call_count += 1;
+    }
+}
+
+/// Output format for `sealevel_profile_take`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum sealevel_profile_format {
+    /// A compact table of one line per function (`fn=<hash> calls=<n>
+    /// instructions=<n>`), followed by the hottest individual PCs.
+    SEALEVEL_PROFILE_FORMAT_FUNCTION_TABLE,
+    /// A Chrome-tracing-compatible JSON array of duration events, one per
+    /// function, loadable in `chrome://tracing` or any compatible viewer.
+    SEALEVEL_PROFILE_FORMAT_CHROME_TRACE,
+}
+
+/// How many of the hottest individual PCs to include in the function-table
+/// output; the full per-PC data set isn't bounded, but the report is.
+const MAX_PC_HOTSPOTS: usize = 10;
+
+fn render_function_table(profile: &sealevel_profile) -> String {
+    let mut out = String::new();
+    for (hash, stats) in &profile.functions {
+        out.push_str(&format!(
+            "fn=0x{hash:x} calls={} instructions={}\n",
+            stats.call_count, stats.total_instructions
+        ));
+    }
+
+    if !profile.pc_counts.is_empty() {
+        let mut hotspots: Vec<_> = profile.pc_counts.iter().collect();
+        hotspots.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        out.push_str("pc hotspots:\n");
+        for (pc, count) in hotspots.into_iter().take(MAX_PC_HOTSPOTS) {
+            out.push_str(&format!("pc={pc} count={count}\n"));
+        }
+    }
+
+    out
+}
+
+fn render_chrome_trace(profile: &sealevel_profile) -> String {
+    let mut events = Vec::new();
+    let mut ts = 0u64;
+    for (hash, stats) in &profile.functions {
+        events.push(format!(
+            "{{\"name\":\"fn_0x{hash:x}\",\"ph\":\"X\",\"ts\":{ts},\"dur\":{}}}",
+            stats.total_instructions
+        ));
+        ts += stats.total_instructions;
+    }
+    format!("[{}]", events.join(","))
+}
+
+/// Serializes the profiling data accumulated on `vm` into `*out` as a newly
+/// allocated, NUL-terminated, caller-freed string.
+///
+/// Returns `false` if profiling was never enabled on `vm` (via
+/// `SEALEVEL_ENABLE_PROFILING`), in which case `*out` is left unset.
+///
+/// # Safety
+/// `vm` must be a non-null pointer returned by `sealevel_vm_new`. `out` must
+/// be non-null and writable; release `*out` with
+/// `sealevel_profile_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_profile_take(
+    vm: *const crate::vm::sealevel_vm,
+    format: sealevel_profile_format,
+    out: *mut *mut c_char,
+) -> bool {
+    let Some(profile) = (*vm).profile() else {
+        return false;
+    };
+
+    let rendered = match format {
+        sealevel_profile_format::SEALEVEL_PROFILE_FORMAT_FUNCTION_TABLE => {
+            render_function_table(profile)
+        }
+        sealevel_profile_format::SEALEVEL_PROFILE_FORMAT_CHROME_TRACE => {
+            render_chrome_trace(profile)
+        }
+    };
+    *out = CString::new(rendered).unwrap_or_default().into_raw();
+    true
+}
+
+/// Releases a string returned by `sealevel_profile_take`.
+///
+/// # Safety
+/// `s` must be a pointer obtained from `sealevel_profile_take` (or null,
+/// which is a no-op), and must not have been freed already.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_profile_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
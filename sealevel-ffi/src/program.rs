@@ -0,0 +1,139 @@
+use {
+    crate::{
+        config::sealevel_result,
+        elf::{self, align4, note_segments, parse_elf_header, read_u32, section_headers},
+    },
+    std::{ptr, slice},
+};
+
+/// A loaded Sealevel (on-chain) BPF program, holding a copy of its raw ELF bytes.
+pub struct sealevel_program {
+    elf: Vec<u8>,
+}
+
+impl sealevel_program {
+    pub(crate) fn elf(&self) -> &[u8] {
+        &self.elf
+    }
+}
+
+/// Creates a new `sealevel_program` by copying `len` bytes of ELF data from `data`.
+///
+/// # Safety
+/// Call `sealevel_program_free` on the return value after you are done using it.
+/// `data` must point to at least `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_program_new(
+    data: *const u8,
+    len: usize,
+) -> *mut sealevel_program {
+    let elf = slice::from_raw_parts(data, len).to_vec();
+    Box::into_raw(Box::new(sealevel_program { elf }))
+}
+
+/// Releases resources associated with a `sealevel_program`.
+///
+/// # Safety
+/// Avoid the following undefined behavior:
+/// - Calling this function given a pointer that's _not_ the return value of `sealevel_program_new`.
+/// - Calling this function more than once on the same object (double free).
+/// - Using the program object after calling this function (use-after-free).
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_program_free(program: *mut sealevel_program) {
+    drop(Box::from_raw(program))
+}
+
+const NT_GNU_BUILD_ID: u32 = 3;
+const GNU_NOTE_NAME: &[u8] = b"GNU\0";
+
+/// Locates the ELF's note data, preferring a `SHT_NOTE` section header and
+/// falling back to a `PT_NOTE` program header segment, and returns its
+/// `(offset, size)` re-validated against `elf.len()`.
+fn find_note_range(elf: &[u8]) -> Option<(usize, usize)> {
+    let header = parse_elf_header(elf)?;
+
+    if let Some(section) = section_headers(elf, &header).find(|s| s.sh_type == elf::SHT_NOTE) {
+        return Some((section.sh_offset, section.sh_size));
+    }
+
+    note_segments(elf, &header)
+        .next()
+        .map(|segment| (segment.p_offset, segment.p_filesz))
+}
+
+/// Walks the `Elf_Nhdr` records within the note range `(start, size)`,
+/// returning the descriptor bytes of the first `NT_GNU_BUILD_ID`/`"GNU\0"`
+/// note found. Every `namesz`/`descsz` and its 4-byte-aligned rounding is
+/// re-checked against the range's bounds before being trusted; a record that
+/// would overflow or run past the end of the range stops the walk instead of
+/// being read.
+fn find_build_id_note(elf: &[u8], (start, size): (usize, usize)) -> Option<&[u8]> {
+    let end = start.checked_add(size)?;
+    let notes = elf.get(start..end)?;
+
+    let mut pos = 0usize;
+    while pos.checked_add(12).map_or(false, |hdr_end| hdr_end <= notes.len()) {
+        let namesz = read_u32(notes, pos)? as usize;
+        let descsz = read_u32(notes, pos.checked_add(4)?)? as usize;
+        let note_type = read_u32(notes, pos.checked_add(8)?)?;
+
+        let name_start = pos.checked_add(12)?;
+        let name_end = name_start.checked_add(namesz)?;
+        let desc_start = name_start.checked_add(align4(namesz)?)?;
+        let desc_end = desc_start.checked_add(descsz)?;
+        let desc_aligned_end = desc_start.checked_add(align4(descsz)?)?;
+
+        if desc_aligned_end > notes.len() {
+            break;
+        }
+
+        if descsz > 0
+            && note_type == NT_GNU_BUILD_ID
+            && namesz == GNU_NOTE_NAME.len()
+            && &notes[name_start..name_end] == GNU_NOTE_NAME
+        {
+            return Some(&notes[desc_start..desc_end]);
+        }
+
+        pos = desc_aligned_end;
+    }
+
+    None
+}
+
+/// Extracts the `.note.gnu.build-id` descriptor from a loaded program's ELF
+/// and copies it into `out_buf`, so tooling can correlate on-chain bytecode
+/// with compiler artifacts.
+///
+/// On success, `*out_len` is set to the build-id's length and `SEALEVEL_OK` is
+/// returned. If `out_buf` is smaller than the build-id, `*out_len` is set to
+/// the required length and `SEALEVEL_ERR_BUFFER_TOO_SMALL` is returned so the
+/// caller can retry with a bigger buffer. `SEALEVEL_ERR_BUILD_ID_NOT_FOUND` is
+/// returned if the ELF has no well-formed `NT_GNU_BUILD_ID` note.
+///
+/// # Safety
+/// `program` must be a non-null pointer returned by `sealevel_program_new`.
+/// `out_len` must be non-null, and `out_buf` must point to at least `*out_len`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_program_build_id(
+    program: *const sealevel_program,
+    out_buf: *mut u8,
+    out_len: *mut usize,
+) -> sealevel_result {
+    let elf = (*program).elf();
+
+    let build_id = find_note_range(elf).and_then(|range| find_build_id_note(elf, range));
+    let Some(build_id) = build_id else {
+        return sealevel_result::SEALEVEL_ERR_BUILD_ID_NOT_FOUND;
+    };
+
+    let capacity = *out_len;
+    *out_len = build_id.len();
+    if build_id.len() > capacity {
+        return sealevel_result::SEALEVEL_ERR_BUFFER_TOO_SMALL;
+    }
+
+    ptr::copy_nonoverlapping(build_id.as_ptr(), out_buf, build_id.len());
+    sealevel_result::SEALEVEL_OK
+}
@@ -0,0 +1,275 @@
+//! A loaded, ready-to-run Sealevel virtual machine instance, and a minimal
+//! sBPF interpreter distinguishing a program's own return value from a VM
+//! fault.
+
+use crate::{
+    cfg::{
+        decode_instructions, Insn, BPF_ALU, BPF_ALU64, BPF_CLASS_MASK, BPF_JMP, BPF_JMP32,
+        BPF_OP_JA, BPF_OP_MOV, BPF_PSEUDO_CALL, BPF_SRC_X, OP_CALL, OP_EXIT,
+    },
+    config::sealevel_config,
+    profile::sealevel_profile,
+    program::sealevel_program,
+};
+
+/// A VM bound to a single `sealevel_program`, optionally accumulating an
+/// execution profile when the config it was created with has
+/// `SEALEVEL_ENABLE_PROFILING` set.
+pub struct sealevel_vm {
+    pub(crate) program: *const sealevel_program,
+    profile: Option<sealevel_profile>,
+    max_call_depth: usize,
+    last_program_error: u64,
+}
+
+impl sealevel_vm {
+    fn new(program: *const sealevel_program, enable_profiling: bool, max_call_depth: usize) -> Self {
+        Self {
+            program,
+            profile: enable_profiling.then(sealevel_profile::new),
+            max_call_depth,
+            last_program_error: 0,
+        }
+    }
+
+    pub(crate) fn profile(&self) -> Option<&sealevel_profile> {
+        self.profile.as_ref()
+    }
+
+    /// Interprets `insns` starting at instruction 0 until it `exit`s back out
+    /// of the entrypoint, returning its `r0`, or until it traps.
+    ///
+    /// This is a self-contained interpreter with no memory map or syscall
+    /// registry wired up yet: any load/store instruction or external (non
+    /// pseudo-call) helper call traps as a `MemoryAccessViolation`/
+    /// `Unsupported` exception rather than being silently misexecuted.
+    fn run(&mut self, insns: &[Insn]) -> Result<u64, VmException> {
+        const MAX_INSTRUCTIONS: u64 = 1_000_000;
+        /// Stand-in for the entrypoint's ELF symbol hash; a real function
+        /// hash requires resolving the call-target against the symbol
+        /// table, which this interpreter doesn't do yet.
+        const ENTRYPOINT_HASH: u64 = 0;
+
+        let mut regs = [0u64; 11];
+        let mut call_stack: Vec<(usize, u64)> = Vec::new();
+        let mut pc = 0usize;
+        let mut executed = 0u64;
+        let mut current_function_hash = ENTRYPOINT_HASH;
+
+        loop {
+            let insn = *insns.get(pc).ok_or(VmException::VerifierRejected)?;
+
+            executed += 1;
+            if executed > MAX_INSTRUCTIONS {
+                return Err(VmException::InstructionMeterExceeded);
+            }
+            if let Some(profile) = self.profile.as_mut() {
+                profile.record_instruction(current_function_hash, pc as u64);
+            }
+
+            if insn.opc == OP_EXIT {
+                match call_stack.pop() {
+                    Some((return_pc, caller_hash)) => {
+                        pc = return_pc;
+                        current_function_hash = caller_hash;
+                        continue;
+                    }
+                    None => return Ok(regs[0]),
+                }
+            }
+
+            if insn.opc == OP_CALL {
+                if insn.src != BPF_PSEUDO_CALL {
+                    // A call to a syscall/helper: no registry is wired up to
+                    // resolve it, so it traps.
+                    return Err(VmException::Unsupported);
+                }
+                let target = pc as i64 + 1 + insn.imm as i64;
+                if target < 0 || target as usize >= insns.len() {
+                    return Err(VmException::VerifierRejected);
+                }
+                if call_stack.len() >= self.max_call_depth {
+                    return Err(VmException::CallDepthExceeded);
+                }
+                call_stack.push((pc + 1, current_function_hash));
+                current_function_hash = target as u64;
+                if let Some(profile) = self.profile.as_mut() {
+                    profile.record_call(current_function_hash);
+                }
+                pc = target as usize;
+                continue;
+            }
+
+            let class = insn.opc & BPF_CLASS_MASK;
+            let source_is_reg = insn.opc & BPF_SRC_X != 0;
+            let src_val = if source_is_reg {
+                regs[insn.src as usize]
+            } else {
+                insn.imm as i64 as u64
+            };
+
+            match class {
+                BPF_ALU | BPF_ALU64 => {
+                    let dst_val = regs[insn.dst as usize];
+                    let op = (insn.opc >> 4) & 0x0f;
+                    let result = match op {
+                        0x0 => dst_val.wrapping_add(src_val),
+                        0x1 => dst_val.wrapping_sub(src_val),
+                        0x2 => dst_val.wrapping_mul(src_val),
+                        0x3 => {
+                            if src_val == 0 {
+                                return Err(VmException::DivideByZero);
+                            }
+                            dst_val.wrapping_div(src_val)
+                        }
+                        0x4 => dst_val | src_val,
+                        0x5 => dst_val & src_val,
+                        0x6 => dst_val.wrapping_shl(src_val as u32),
+                        0x7 => dst_val.wrapping_shr(src_val as u32),
+                        0x8 => (!dst_val).wrapping_add(1),
+                        0x9 => {
+                            if src_val == 0 {
+                                return Err(VmException::DivideByZero);
+                            }
+                            dst_val.wrapping_rem(src_val)
+                        }
+                        0xa => dst_val ^ src_val,
+                        op if op == BPF_OP_MOV => src_val,
+                        0xc => ((dst_val as i64).wrapping_shr(src_val as u32)) as u64,
+                        _ => return Err(VmException::Unsupported),
+                    };
+                    regs[insn.dst as usize] = if class == BPF_ALU64 {
+                        result
+                    } else {
+                        result & 0xffff_ffff
+                    };
+                    pc += 1;
+                }
+                BPF_JMP | BPF_JMP32 => {
+                    let op = (insn.opc >> 4) & 0x0f;
+                    let taken = if op == BPF_OP_JA {
+                        true
+                    } else {
+                        let dst_val = regs[insn.dst as usize];
+                        match op {
+                            0x1 => dst_val == src_val,
+                            0x2 => dst_val > src_val,
+                            0x3 => dst_val >= src_val,
+                            0x4 => dst_val & src_val != 0,
+                            0x5 => dst_val != src_val,
+                            0x6 => (dst_val as i64) > (src_val as i64),
+                            0x7 => (dst_val as i64) >= (src_val as i64),
+                            0xa => dst_val < src_val,
+                            0xb => dst_val <= src_val,
+                            0xc => (dst_val as i64) < (src_val as i64),
+                            0xd => (dst_val as i64) <= (src_val as i64),
+                            _ => return Err(VmException::Unsupported),
+                        }
+                    };
+                    pc = if taken {
+                        let target = pc as i64 + 1 + insn.off as i64;
+                        if target < 0 || target as usize >= insns.len() {
+                            return Err(VmException::VerifierRejected);
+                        }
+                        target as usize
+                    } else {
+                        pc + 1
+                    };
+                }
+                _ => return Err(VmException::MemoryAccessViolation),
+            }
+        }
+    }
+}
+
+/// Why execution trapped rather than the program returning normally; all
+/// variants surface to callers as `SEALEVEL_INVOKE_VM_EXCEPTION`.
+enum VmException {
+    VerifierRejected,
+    MemoryAccessViolation,
+    CallDepthExceeded,
+    InstructionMeterExceeded,
+    DivideByZero,
+    Unsupported,
+}
+
+/// Creates a new `sealevel_vm` bound to `program`, configured by `config`.
+///
+/// # Safety
+/// `program` and `config` must be non-null pointers returned by
+/// `sealevel_program_new` and `sealevel_config_new` respectively, and must
+/// outlive the returned `sealevel_vm`. Call `sealevel_vm_free` on the return
+/// value after you are done using it.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_vm_new(
+    program: *const sealevel_program,
+    config: *const sealevel_config,
+) -> *mut sealevel_vm {
+    let vm = sealevel_vm::new(
+        program,
+        (*config).enable_profiling,
+        (*config).config.max_call_depth,
+    );
+    Box::into_raw(Box::new(vm))
+}
+
+/// Releases resources associated with a `sealevel_vm`. Does not free the
+/// `sealevel_program` or `sealevel_config` it was created with.
+///
+/// # Safety
+/// Avoid the following undefined behavior:
+/// - Calling this function given a pointer that's _not_ the return value of `sealevel_vm_new`.
+/// - Calling this function more than once on the same object (double free).
+/// - Using the VM object after calling this function (use-after-free).
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_vm_free(vm: *mut sealevel_vm) {
+    drop(Box::from_raw(vm))
+}
+
+/// Outcome of `sealevel_invoke`, distinguishing a program's own return value
+/// from a fault in the VM itself.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum sealevel_invoke_result {
+    /// The program ran to completion and returned success (`r0 == 0`).
+    SEALEVEL_INVOKE_SUCCESS,
+    /// The program ran to completion but returned a nonzero custom error
+    /// code via `r0`; retrieve it with `sealevel_last_program_error`.
+    SEALEVEL_INVOKE_PROGRAM_ERROR,
+    /// The VM itself faulted (verifier rejection, memory access violation,
+    /// divide by zero, call depth or instruction meter exceeded) rather than
+    /// the program returning.
+    SEALEVEL_INVOKE_VM_EXCEPTION,
+}
+
+/// Runs `vm`'s program from its entrypoint to completion.
+///
+/// # Safety
+/// `vm` must be a non-null pointer returned by `sealevel_vm_new`.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_invoke(vm: *mut sealevel_vm) -> sealevel_invoke_result {
+    let elf = (*(*vm).program).elf();
+    let Some(insns) = decode_instructions(elf) else {
+        return sealevel_invoke_result::SEALEVEL_INVOKE_VM_EXCEPTION;
+    };
+
+    match (*vm).run(&insns) {
+        Ok(0) => sealevel_invoke_result::SEALEVEL_INVOKE_SUCCESS,
+        Ok(r0) => {
+            (*vm).last_program_error = r0;
+            sealevel_invoke_result::SEALEVEL_INVOKE_PROGRAM_ERROR
+        }
+        Err(_) => sealevel_invoke_result::SEALEVEL_INVOKE_VM_EXCEPTION,
+    }
+}
+
+/// Returns the custom error code a program returned via `r0` on its last
+/// `SEALEVEL_INVOKE_PROGRAM_ERROR` outcome. Meaningless if `sealevel_invoke`
+/// hasn't returned `SEALEVEL_INVOKE_PROGRAM_ERROR` for `vm` yet.
+///
+/// # Safety
+/// `vm` must be a non-null pointer returned by `sealevel_vm_new`.
+#[no_mangle]
+pub unsafe extern "C" fn sealevel_last_program_error(vm: *const sealevel_vm) -> u64 {
+    (*vm).last_program_error
+}
@@ -1,25 +1,32 @@
 use accountant::Accountant;
 use transaction::Transaction;
-use signature::PublicKey;
+use signature::{PublicKey, Signature};
 use hash::Hash;
 use entry::Entry;
-use std::net::UdpSocket;
+use std::net::{TcpListener, TcpStream, UdpSocket};
 use bincode::{deserialize, serialize};
 use result::Result;
 use streamer;
 use std::sync::{Arc, Mutex};
 use std::time::Duration;
 use std::sync::mpsc::channel;
-use std::thread::{spawn, JoinHandle};
+use std::thread::{sleep, spawn, JoinHandle};
 use std::default::Default;
-use std::io::Write;
+use std::io::{ErrorKind, Read, Write};
+use serde::Serialize;
 use serde_json;
+use solana_metrics::{datapoint_info, TokenCounter};
+use std::time::Instant;
 
 pub struct AccountantSkel<W: Write + Send + 'static> {
     pub acc: Accountant,
     pub last_id: Hash,
     pub ledger: Vec<Entry>,
     writer: W,
+    // Tracks how many `serve` worker threads are currently live. Only ever
+    // holds one token today, since `serve` spawns a single `t_server`
+    // thread, but it's already wired up for whenever that becomes a pool.
+    worker_tokens: TokenCounter,
 }
 
 #[cfg_attr(feature = "cargo-clippy", allow(large_enum_variant))]
@@ -34,10 +41,27 @@ pub enum Request {
 #[derive(Serialize, Deserialize, Debug)]
 pub enum Response {
     Balance { key: PublicKey, val: Option<i64> },
-    Entries { entries: Vec<Entry> },
+    Entries {
+        entries: Vec<Entry>,
+        more: bool,
+        next_id: Hash,
+    },
     Id { id: Hash, is_last: bool },
+    /// Acknowledges a submitted `Request::Transaction`, since
+    /// `process_request` used to just drop it on the floor (`None`) and log
+    /// to stderr on failure. `reason` is set whenever `accepted` is false.
+    TransactionResult {
+        signature: Signature,
+        accepted: bool,
+        reason: Option<String>,
+    },
 }
 
+// Leave some slack under the 64k UDP packet limit for the `Response::Entries`
+// envelope (the enum discriminant, the `more` flag, and bincode's length
+// prefix for `entries`) so the serialized response never gets truncated.
+const MAX_ENTRIES_RESPONSE_BYTES: usize = 60_000;
+
 impl<W: Write + Send + 'static> AccountantSkel<W> {
     pub fn new(acc: Accountant, w: W) -> Self {
         let last_id = acc.first_id;
@@ -46,6 +70,35 @@ impl<W: Write + Send + 'static> AccountantSkel<W> {
             last_id,
             ledger: vec![],
             writer: w,
+            worker_tokens: TokenCounter::new("accountant_skel-serve_workers"),
+        }
+    }
+
+    /// Rebuilds an `AccountantSkel` from `reader`'s JSON entry log (the same
+    /// stream `sync` appends to via `writer`), replaying every entry's
+    /// transactions through `acc` to reconstruct balances. This lets a
+    /// restarted skel recover state from the log instead of coming back up
+    /// with `acc.first_id` and an empty ledger, as `new` does.
+    pub fn from_ledger<R: Read>(mut acc: Accountant, reader: R, writer: W) -> Self {
+        let mut last_id = acc.first_id;
+        let mut ledger = vec![];
+        let entries = serde_json::Deserializer::from_reader(reader).into_iter::<Entry>();
+        for entry in entries {
+            let entry = entry.expect("failed to deserialize ledger entry");
+            for tr in &entry.transactions {
+                if let Err(err) = acc.process_transaction(tr.clone()) {
+                    eprintln!("Ledger replay transaction error: {:?}", err);
+                }
+            }
+            last_id = entry.id;
+            ledger.push(entry);
+        }
+        AccountantSkel {
+            acc,
+            last_id,
+            ledger,
+            writer,
+            worker_tokens: TokenCounter::new("accountant_skel-serve_workers"),
         }
     }
 
@@ -58,13 +111,46 @@ impl<W: Write + Send + 'static> AccountantSkel<W> {
         self.last_id
     }
 
+    /// Rejects `tr` before it ever reaches the `Accountant`: its signature
+    /// must verify against its own `from` key, and its `from` account must
+    /// hold enough balance to cover it. Without this gate, an attacker can
+    /// spray unsigned or underfunded transactions and force per-packet
+    /// deserialization plus accountant work for free.
+    fn verify_transaction(&self, tr: &Transaction) -> Result<(), String> {
+        if !tr.verify() {
+            return Err("signature verification failed".to_string());
+        }
+        match self.acc.get_balance(&tr.from) {
+            Some(balance) if balance >= tr.tokens => Ok(()),
+            Some(_) => Err("insufficient balance".to_string()),
+            None => Err("unknown sender".to_string()),
+        }
+    }
+
     pub fn process_request(self: &mut Self, msg: Request) -> Option<Response> {
         match msg {
             Request::Transaction(tr) => {
-                if let Err(err) = self.acc.process_transaction(tr) {
-                    eprintln!("Transaction error: {:?}", err);
+                let signature = tr.sig;
+                if let Err(reason) = self.verify_transaction(&tr) {
+                    eprintln!("Transaction rejected: {}", reason);
+                    return Some(Response::TransactionResult {
+                        signature,
+                        accepted: false,
+                        reason: Some(reason),
+                    });
                 }
-                None
+                let (accepted, reason) = match self.acc.process_transaction(tr) {
+                    Ok(()) => (true, None),
+                    Err(err) => {
+                        eprintln!("Transaction error: {:?}", err);
+                        (false, Some(format!("{:?}", err)))
+                    }
+                };
+                Some(Response::TransactionResult {
+                    signature,
+                    accepted,
+                    reason,
+                })
             }
             Request::GetBalance { key } => {
                 let val = self.acc.get_balance(&key);
@@ -72,14 +158,30 @@ impl<W: Write + Send + 'static> AccountantSkel<W> {
             }
             Request::GetEntries { last_id } => {
                 self.sync();
-                let entries = self.ledger
+                let mut candidates = self.ledger
                     .iter()
                     .skip_while(|x| x.id != last_id) // log(n) way to find Entry with id == last_id.
-                    .skip(1) // Skip the entry with last_id.
-                    .take(256) // TODO: Take while the serialized entries fit into a 64k UDP packet.
-                    .cloned()
-                    .collect();
-                Some(Response::Entries { entries })
+                    .skip(1); // Skip the entry with last_id.
+
+                let mut entries = vec![];
+                let mut size = 0;
+                let mut more = false;
+                let mut next_id = last_id;
+                while let Some(entry) = candidates.next() {
+                    let entry_size = serialize(entry).unwrap().len();
+                    if size + entry_size > MAX_ENTRIES_RESPONSE_BYTES {
+                        more = true;
+                        break;
+                    }
+                    size += entry_size;
+                    next_id = entry.id;
+                    entries.push(entry.clone());
+                }
+                Some(Response::Entries {
+                    entries,
+                    more,
+                    next_id,
+                })
             }
             Request::GetId { is_last } => Some(Response::Id {
                 id: if is_last {
@@ -91,6 +193,14 @@ impl<W: Write + Send + 'static> AccountantSkel<W> {
             }),
         }
     }
+    /// Deserializes a single `Request` out of `buf` and runs it through
+    /// `process_request`. Shared by the UDP and TCP `serve` loops so both
+    /// transports agree on exactly how a wire frame turns into a `Response`.
+    fn process_frame(&mut self, buf: &[u8]) -> Result<Option<Response>> {
+        let req = deserialize(buf)?;
+        Ok(self.process_request(req))
+    }
+
     fn process(
         &mut self,
         r_reader: &streamer::Receiver,
@@ -98,6 +208,12 @@ impl<W: Write + Send + 'static> AccountantSkel<W> {
         packet_recycler: &streamer::PacketRecycler,
         response_recycler: &streamer::ResponseRecycler,
     ) -> Result<()> {
+        let start = Instant::now();
+        let mut num_packets = 0;
+        let mut num_accepted = 0;
+        let mut num_rejected = 0;
+        let num_responses;
+
         let timer = Duration::new(1, 0);
         let msgs = r_reader.recv_timeout(timer)?;
         let msgs_ = msgs.clone();
@@ -107,9 +223,16 @@ impl<W: Write + Send + 'static> AccountantSkel<W> {
             let mut num = 0;
             let mut ursps = rsps.write().unwrap();
             for packet in &msgs.read().unwrap().packets {
+                num_packets += 1;
                 let sz = packet.meta.size;
-                let req = deserialize(&packet.data[0..sz])?;
-                if let Some(resp) = self.process_request(req) {
+                if let Some(resp) = self.process_frame(&packet.data[0..sz])? {
+                    if let Response::TransactionResult { accepted, .. } = &resp {
+                        if *accepted {
+                            num_accepted += 1;
+                        } else {
+                            num_rejected += 1;
+                        }
+                    }
                     if ursps.responses.len() <= num {
                         ursps
                             .responses
@@ -125,9 +248,19 @@ impl<W: Write + Send + 'static> AccountantSkel<W> {
                 }
             }
             ursps.responses.resize(num, streamer::Response::default());
+            num_responses = num;
         }
         s_responder.send(rsps_)?;
         streamer::recycle(packet_recycler, msgs_);
+
+        datapoint_info!(
+            "accountant_skel-process",
+            ("packets_received", num_packets, i64),
+            ("transactions_accepted", num_accepted, i64),
+            ("transactions_rejected", num_rejected, i64),
+            ("responses_generated", num_responses, i64),
+            ("process_time_us", start.elapsed().as_micros() as i64, i64),
+        );
         Ok(())
     }
 
@@ -154,6 +287,7 @@ impl<W: Write + Send + 'static> AccountantSkel<W> {
 
         let t_server = spawn(move || {
             if let Ok(me) = Arc::try_unwrap(obj) {
+                let _worker_token = me.lock().unwrap().worker_tokens.create_token();
                 loop {
                     let _e = me.lock().unwrap().process(
                         &r_reader,
@@ -171,4 +305,112 @@ impl<W: Write + Send + 'static> AccountantSkel<W> {
         });
         Ok(vec![t_receiver, t_responder, t_server])
     }
+
+    /// Reliable TCP server for clients that need guaranteed delivery, e.g. a
+    /// funded `Transaction` or a complete `GetEntries` range, where the UDP
+    /// `serve` path's dropped datagrams are unacceptable. Frames are the same
+    /// bincode-encoded `Request`/`Response` payloads as UDP, each prefixed
+    /// with a little-endian `u32` length so they can be read off a stream
+    /// instead of a single datagram.
+    pub fn serve_tcp(
+        obj: Arc<Mutex<AccountantSkel<W>>>,
+        addr: &str,
+        exit: Arc<Mutex<bool>>,
+    ) -> Result<JoinHandle<()>> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+
+        let t_accept = spawn(move || loop {
+            match listener.accept() {
+                Ok((stream, _)) => {
+                    let obj = obj.clone();
+                    let exit = exit.clone();
+                    spawn(move || Self::handle_tcp_client(obj, stream, exit));
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    sleep(Duration::from_millis(100));
+                }
+                Err(e) => {
+                    info!("serve_tcp accept error: {:?}", e);
+                }
+            }
+            if *exit.lock().unwrap() {
+                info!("serve_tcp exiting");
+                break;
+            }
+        });
+        Ok(t_accept)
+    }
+
+    /// Services one TCP client for as long as it keeps the connection open,
+    /// reading length-prefixed `Request` frames and writing back
+    /// length-prefixed `Response` frames until the client disconnects or
+    /// `exit` is set.
+    fn handle_tcp_client(obj: Arc<Mutex<AccountantSkel<W>>>, mut stream: TcpStream, exit: Arc<Mutex<bool>>) {
+        loop {
+            if *exit.lock().unwrap() {
+                break;
+            }
+            let start = Instant::now();
+            let buf = match read_framed(&mut stream) {
+                Ok(buf) => buf,
+                Err(ref e) if e.kind() == ErrorKind::UnexpectedEof => break,
+                Err(e) => {
+                    info!("serve_tcp read error: {:?}", e);
+                    break;
+                }
+            };
+
+            let resp = match obj.lock().unwrap().process_frame(&buf) {
+                Ok(resp) => resp,
+                Err(e) => {
+                    info!("serve_tcp request error: {:?}", e);
+                    break;
+                }
+            };
+
+            let accepted = if let Some(resp) = &resp {
+                if let Response::TransactionResult { accepted, .. } = resp {
+                    Some(*accepted)
+                } else {
+                    None
+                }
+            } else {
+                None
+            };
+
+            if let Some(resp) = resp {
+                if write_framed(&mut stream, &resp).is_err() {
+                    break;
+                }
+            }
+
+            datapoint_info!(
+                "accountant_skel-process_tcp",
+                ("transactions_accepted", (accepted == Some(true)) as i64, i64),
+                ("transactions_rejected", (accepted == Some(false)) as i64, i64),
+                ("process_time_us", start.elapsed().as_micros() as i64, i64),
+            );
+        }
+    }
+}
+
+/// Reads one length-prefixed frame (a little-endian `u32` byte count
+/// followed by that many bytes) off `stream`, as written by `write_framed`.
+fn read_framed<R: Read>(stream: &mut R) -> Result<Vec<u8>> {
+    let mut len_bytes = [0u8; 4];
+    stream.read_exact(&mut len_bytes)?;
+    let len = u32::from_le_bytes(len_bytes) as usize;
+    let mut buf = vec![0u8; len];
+    stream.read_exact(&mut buf)?;
+    Ok(buf)
+}
+
+/// Bincode-serializes `msg` and writes it to `stream` as one length-prefixed
+/// frame, matching what `read_framed` expects on the other end.
+fn write_framed<T: Serialize, W2: Write>(stream: &mut W2, msg: &T) -> Result<()> {
+    let payload = serialize(msg)?;
+    stream.write_all(&(payload.len() as u32).to_le_bytes())?;
+    stream.write_all(&payload)?;
+    Ok(())
 }
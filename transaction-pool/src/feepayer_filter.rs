@@ -1,36 +1,127 @@
 use ahash::AHasher;
-use std::hash::Hasher;
-use solana_sdk::pubkey::Pubkey;
 use rand::thread_rng;
 use rand::Rng;
+use solana_sdk::pubkey::Pubkey;
+use std::hash::Hasher;
 
+const DEFAULT_ROWS: usize = 4;
+const DEFAULT_WIDTH: usize = 1 << 16;
+
+/// How far above the uniform-distribution expectation a fee payer's
+/// estimated count must land before it's flagged as spamming invalid
+/// transactions.
+const DEFAULT_THRESHOLD_MULTIPLIER: u64 = 8;
+
+/// Count-Min Sketch over fee payers that have submitted invalid
+/// transactions, used by banking-stage filtering to cheaply identify
+/// addresses that are flooding the pool. A `d`-row by `w`-counter sketch
+/// only ever over-estimates a payer's count, never under-estimates it, so
+/// `is_invalid` can never produce a false negative for an address that's
+/// actually spamming -- only (rare, bounded) false positives from hash
+/// collisions.
 pub struct FeePayerFilter {
-    feepayers: Vec<u16>,
+    rows: Vec<Vec<u32>>,
+    seeds: Vec<(u128, u128)>,
+    width: usize,
     count: u64,
-    seed: (u128,u128),
+    threshold_multiplier: u64,
 }
 
 impl FeePayerFilter {
     pub fn new() -> Self {
+        Self::with_dimensions(DEFAULT_ROWS, DEFAULT_WIDTH)
+    }
+
+    /// Sizes the sketch from the standard Count-Min error bounds: width
+    /// `w = ceil(e / epsilon)` bounds the overestimate to `epsilon * count`
+    /// of total updates, and depth `d = ceil(ln(1 / delta))` bounds the
+    /// probability of exceeding that error to `delta`. `expected_items` is
+    /// unused by the sizing formula itself but kept in the signature since
+    /// callers typically know it and it documents the sketch's intended
+    /// scale.
+    pub fn with_capacity(expected_items: usize, epsilon: f64, delta: f64) -> Self {
+        let _ = expected_items;
+        let width = (std::f64::consts::E / epsilon).ceil() as usize;
+        let rows = (1.0_f64 / delta).ln().ceil() as usize;
+        Self::with_dimensions(rows.max(1), width.max(1))
+    }
+
+    fn with_dimensions(rows: usize, width: usize) -> Self {
+        let mut rng = thread_rng();
         Self {
-            seed: thread_rng().gen(),
-            blockhashes: vec![false; u16::MAX.into()],
+            rows: vec![vec![0u32; width]; rows],
+            seeds: (0..rows).map(|_| rng.gen()).collect(),
+            width,
+            count: 0,
+            threshold_multiplier: DEFAULT_THRESHOLD_MULTIPLIER,
         }
     }
-    //accumilate invalid fee payers
-    pub fn invalid(&mut self, addr: &Pubkey) {
-        let mut hasher = AHasher::new_with_keys(self.seed.0, self.seed.1);
+
+    fn index(&self, row: usize, addr: &Pubkey) -> usize {
+        let (key0, key1) = self.seeds[row];
+        let mut hasher = AHasher::new_with_keys(key0, key1);
         hasher.write(addr.as_ref());
-        let pos = hasher.finish() % u64::from(u16::MAX);
-        self.feepayers[usize::try_from(pos).unwrap()] = self.feepayers[usize::try_from(pos).unwrap()].saturating_add(1);
+        usize::try_from(hasher.finish() % self.width as u64).unwrap()
+    }
+
+    /// Records one more invalid transaction from `addr`. Uses a
+    /// conservative update: only the row counters currently tied for the
+    /// minimum are incremented, which keeps the sketch's overestimation
+    /// error from compounding across repeated updates of the same item.
+    pub fn invalid(&mut self, addr: &Pubkey) {
+        let indices: Vec<usize> = (0..self.rows.len())
+            .map(|row| self.index(row, addr))
+            .collect();
+        let min = indices
+            .iter()
+            .zip(&self.rows)
+            .map(|(&idx, row)| row[idx])
+            .min()
+            .unwrap_or(0);
+        for (row, &idx) in self.rows.iter_mut().zip(&indices) {
+            if row[idx] == min {
+                row[idx] = row[idx].saturating_add(1);
+            }
+        }
         self.count = self.count.saturating_add(1);
     }
-    //drop those that are above the expected mean
+
+    /// Returns the sketch's (over-)estimate of how many invalid
+    /// transactions `addr` has submitted.
+    pub fn estimate(&self, addr: &Pubkey) -> u64 {
+        (0..self.rows.len())
+            .map(|row| {
+                let idx = self.index(row, addr);
+                u64::from(self.rows[row][idx])
+            })
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Flags `addr` once its estimated invalid-transaction count exceeds
+    /// `threshold_multiplier` times what one counter would hold if all
+    /// `count` updates were spread uniformly over the `width` counters.
     pub fn is_invalid(&self, addr: &Pubkey) -> bool {
-        let mut hasher = AHasher::new_with_keys(self.seed.0, self.seed.1);
-        hasher.write(addr.as_ref());
-        let pos = hasher.finish() % u64::from(u16::MAX);
-        let expected = u64::from(self.blockhashes[usize::try_from(pos).unwrap()]) * u64::from(u16::MAX);
-        expected > self.count
+        let expected = self.threshold_multiplier.saturating_mul(self.count) / self.width as u64;
+        self.estimate(addr) > expected
+    }
+
+    /// Halves every counter (and the global `count`), so the sketch tracks
+    /// a decaying recent window of activity instead of accumulating
+    /// indefinitely. Callers should invoke this on a configurable slot or
+    /// time interval.
+    pub fn decay(&mut self) {
+        for row in &mut self.rows {
+            for counter in row.iter_mut() {
+                *counter /= 2;
+            }
+        }
+        self.count /= 2;
+    }
+}
+
+impl Default for FeePayerFilter {
+    fn default() -> Self {
+        Self::new()
     }
 }
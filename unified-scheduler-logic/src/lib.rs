@@ -71,6 +71,10 @@ struct TaskStatus {
     lock_attempts: Vec<LockAttempt>,
 }
 
+// `SchedulerCell` only ever hands out its `&V`/`&mut V` through
+// `with_borrow`/`with_borrow_mut`'s closure argument; there's no
+// reference-returning `borrow`/`borrow_mut` pair to reborrow-alias against an
+// outstanding reference from the same `Token`, by construction.
 mod cell {
     #[cfg(feature = "dev-context-only-utils")]
     use qualifier_attr::qualifiers;
@@ -85,12 +89,21 @@ mod cell {
             Self(UnsafeCell::new(value))
         }
 
-        pub(super) fn borrow_mut<'t>(&self, _token: &'t mut Token<V>) -> &'t mut V {
-            unsafe { &mut *self.0.get() }
+        // Confining the derived `&mut V`/`&V` to `f`'s body (rather than handing
+        // it back out tied only to the token's reborrow lifetime) is what makes
+        // this sound under Stacked Borrows: two overlapping mutable borrows of
+        // the same cell can never coexist, since each borrow's lifetime ends
+        // when `f` returns.
+        pub(super) fn with_borrow_mut<R>(
+            &self,
+            _token: &mut Token<V>,
+            f: impl FnOnce(&mut V) -> R,
+        ) -> R {
+            f(unsafe { &mut *self.0.get() })
         }
 
-        pub(super) fn borrow<'t>(&self, _token: &'t Token<V>) -> &'t V {
-            unsafe { &*self.0.get() }
+        pub(super) fn with_borrow<R>(&self, _token: &Token<V>, f: impl FnOnce(&V) -> R) -> R {
+            f(unsafe { &*self.0.get() })
         }
     }
 
@@ -127,21 +140,67 @@ impl TaskStatus {
 pub struct TaskInner {
     // put this field out of this struct for maximum space efficiency?
     unique_weight: UniqueWeight,
-    transaction: SanitizedTransaction, // actually should be Bundle
+    transactions: Vec<SanitizedTransaction>,
+    cost: u64,
+    priority: u64,
     task_status: SchedulerCell<TaskStatus>,
 }
 
 impl TaskInner {
+    /// The requested compute units this task will consume if it runs,
+    /// counted against its `SchedulingStateMachine`'s `cost_ceiling`.
+    pub fn cost(&self) -> u64 {
+        self.cost
+    }
+
+    /// This task's fee/priority (e.g. compute-unit price), used to order its
+    /// release ahead of other tasks blocked on the same page once it starts
+    /// waiting; see [`PageInner::heaviest_blocked_task`].
+    pub fn priority(&self) -> u64 {
+        self.priority
+    }
+
+    fn waiter_key(&self) -> WaiterKey {
+        WaiterKey {
+            priority: self.priority,
+            unique_weight: self.unique_weight,
+        }
+    }
+
+    /// The task's sole transaction.
+    ///
+    /// # Panics
+    /// Panics if this task is a multi-transaction bundle; use
+    /// [`Self::transactions`] instead.
     pub fn transaction(&self) -> &SanitizedTransaction {
-        &self.transaction
+        assert_eq!(self.transactions.len(), 1);
+        &self.transactions[0]
     }
 
-    fn lock_attempts_mut<'t>(&self, task_token: &'t mut TaskToken) -> &'t mut Vec<LockAttempt> {
-        &mut self.task_status.borrow_mut(task_token).lock_attempts
+    /// All transactions this task atomically locks and executes together. A
+    /// task created by [`SchedulingStateMachine::create_task`] always has
+    /// exactly one; one created by
+    /// [`SchedulingStateMachine::create_bundle_task`] may have more.
+    pub fn transactions(&self) -> &[SanitizedTransaction] {
+        &self.transactions
     }
 
-    fn lock_attempts<'t>(&self, task_token: &'t TaskToken) -> &'t Vec<LockAttempt> {
-        &self.task_status.borrow(task_token).lock_attempts
+    fn with_lock_attempts_mut<R>(
+        &self,
+        task_token: &mut TaskToken,
+        f: impl FnOnce(&mut Vec<LockAttempt>) -> R,
+    ) -> R {
+        self.task_status
+            .with_borrow_mut(task_token, |task_status| f(&mut task_status.lock_attempts))
+    }
+
+    fn with_lock_attempts<R>(
+        &self,
+        task_token: &TaskToken,
+        f: impl FnOnce(&Vec<LockAttempt>) -> R,
+    ) -> R {
+        self.task_status
+            .with_borrow(task_token, |task_status| f(&task_status.lock_attempts))
     }
 
     pub fn task_index(&self) -> usize {
@@ -168,8 +227,12 @@ impl LockAttempt {
         }
     }
 
-    fn page_mut<'t>(&self, page_token: &'t mut PageToken) -> &'t mut PageInner {
-        self.page.0.borrow_mut(page_token)
+    fn with_page_mut<R>(&self, page_token: &mut PageToken, f: impl FnOnce(&mut PageInner) -> R) -> R {
+        self.page.0.with_borrow_mut(page_token, f)
+    }
+
+    fn with_page<R>(&self, page_token: &PageToken, f: impl FnOnce(&PageInner) -> R) -> R {
+        self.page.0.with_borrow(page_token, f)
     }
 }
 
@@ -191,24 +254,56 @@ impl Usage {
     }
 }
 
+/// A page's current holder, for introspection; see
+/// [`SchedulingStateMachine::blocked_task_report`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PageUsageReport {
+    /// Nothing currently holds this page.
+    Unused,
+    /// One task holds this page writable.
+    Writable,
+    /// This many tasks concurrently hold this page readonly.
+    Readonly(u32),
+}
+
+impl From<Usage> for PageUsageReport {
+    fn from(usage: Usage) -> Self {
+        match usage {
+            Usage::Unused => PageUsageReport::Unused,
+            Usage::Writable => PageUsageReport::Writable,
+            Usage::Readonly(count) => PageUsageReport::Readonly(count.current()),
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 enum RequestedUsage {
     Readonly,
     Writable,
 }
 
+/// Orders a page's blocked waiters by `priority` first, falling back to
+/// `unique_weight` (i.e. arrival order) to break ties deterministically.
+/// `BTreeMap::last_key_value` over this key is therefore a priority max-heap
+/// rather than a FIFO queue.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct WaiterKey {
+    priority: u64,
+    unique_weight: UniqueWeight,
+}
+
 #[derive(Debug, Default)]
 struct PageInner {
     usage: Usage,
-    writable_blocked_tasks: BTreeMap<UniqueWeight, Task>,
-    readonly_blocked_tasks: BTreeMap<UniqueWeight, Task>,
+    writable_blocked_tasks: BTreeMap<WaiterKey, Task>,
+    readonly_blocked_tasks: BTreeMap<WaiterKey, Task>,
 }
 
 impl PageInner {
     fn blocked_tasks_mut(
         &mut self,
         requested_usage: RequestedUsage,
-    ) -> &mut BTreeMap<UniqueWeight, Task> {
+    ) -> &mut BTreeMap<WaiterKey, Task> {
         match requested_usage {
             RequestedUsage::Readonly => &mut self.readonly_blocked_tasks,
             RequestedUsage::Writable => &mut self.writable_blocked_tasks,
@@ -218,36 +313,36 @@ impl PageInner {
     fn insert_blocked_task(&mut self, task: Task, requested_usage: RequestedUsage) {
         let pre_existed = self
             .blocked_tasks_mut(requested_usage)
-            .insert(task.unique_weight, task);
+            .insert(task.waiter_key(), task);
         assert!(pre_existed.is_none());
     }
 
-    fn remove_blocked_task(
-        &mut self,
-        requested_usage: RequestedUsage,
-        unique_weight: UniqueWeight,
-    ) {
-        let removed_entry = self
-            .blocked_tasks_mut(requested_usage)
-            .remove(&unique_weight);
+    /// How many tasks, across both the writable and readonly waiter queues,
+    /// are currently blocked on this page.
+    fn blocked_task_count(&self) -> u32 {
+        (self.writable_blocked_tasks.len() + self.readonly_blocked_tasks.len()) as u32
+    }
+
+    fn remove_blocked_task(&mut self, requested_usage: RequestedUsage, waiter_key: WaiterKey) {
+        let removed_entry = self.blocked_tasks_mut(requested_usage).remove(&waiter_key);
         assert!(removed_entry.is_some());
     }
 
-    fn heaviest_blocked_writing_task(&self) -> Option<(&UniqueWeight, &Task)> {
+    fn heaviest_blocked_writing_task(&self) -> Option<(&WaiterKey, &Task)> {
         self.writable_blocked_tasks
             .last_key_value()
     }
 
-    fn heaviest_blocked_readonly_task(&self) -> Option<(&UniqueWeight, &Task)> {
+    fn heaviest_blocked_readonly_task(&self) -> Option<(&WaiterKey, &Task)> {
         self.readonly_blocked_tasks
             .last_key_value()
     }
 
-    fn heaviest_blocked_task(&self) -> Option<(&UniqueWeight, &Task)> {
+    fn heaviest_blocked_task(&self) -> Option<(&WaiterKey, &Task)> {
         Self::heavier_task(self.heaviest_blocked_writing_task(), self.heaviest_blocked_readonly_task())
     }
 
-    fn heavier_task<'a>(x: Option<(&'a UniqueWeight, &'a Task)>, y: Option<(&'a UniqueWeight, &'a Task)>) -> Option<(&'a UniqueWeight, &'a Task)> {
+    fn heavier_task<'a>(x: Option<(&'a WaiterKey, &'a Task)>, y: Option<(&'a WaiterKey, &'a Task)>) -> Option<(&'a WaiterKey, &'a Task)> {
         cmp::max_by(x, y, |x, y| {
             x.map(|x| x.0).cmp(&y.map(|y| y.0))
         })
@@ -263,6 +358,33 @@ const_assert_eq!(mem::size_of::<Page>(), 8);
 
 type TaskQueue = BTreeMap<UniqueWeight, Task>;
 
+/// Accumulates contention signals between `take_contention_report` calls.
+#[derive(Debug, Default)]
+struct ContentionStats {
+    blocked_queue_depths: Vec<u32>,
+    max_retryable_age: u64,
+    wasted_readonly_wakeups: u32,
+}
+
+/// A point-in-time snapshot of contention observed since the previous
+/// `take_contention_report` call, meant to help a block producer identify
+/// hot accounts driving serialization and feed that back into transaction
+/// admission.
+#[derive(Debug, Default, Clone)]
+pub struct ContentionReport {
+    /// One entry per time a task became blocked on a page, giving that
+    /// page's combined writable- and readonly-waiter queue depth at that
+    /// moment.
+    pub blocked_queue_depths: Vec<u32>,
+    /// The most scheduler ticks any task spent sitting in the retryable
+    /// queue before it was finally relocked.
+    pub max_retryable_age: u64,
+    /// How many times the readonly-wakeup recheck in `try_lock_for_task`
+    /// found a blocked readonly task that was already queued, i.e. fired
+    /// without making any additional progress.
+    pub wasted_readonly_wakeups: u32,
+}
+
 #[cfg_attr(feature = "dev-context-only-utils", field_qualifiers(task_token(pub)))]
 pub struct SchedulingStateMachine {
     retryable_task_queue: TaskQueue,
@@ -273,6 +395,35 @@ pub struct SchedulingStateMachine {
     total_task_count: Counter,
     task_token: TaskToken,
     page_token: PageToken,
+    // A logical clock ticking once per `try_lock_for_task` call, used only
+    // to measure how long tasks wait in `retryable_task_queue`; see
+    // `ContentionReport`.
+    sequence: u64,
+    enqueue_sequences: BTreeMap<UniqueWeight, u64>,
+    contention: ContentionStats,
+    // The aggregate `cost` of every task that's currently active, i.e.
+    // scheduled but not yet `deschedule_task`-ed, mirroring
+    // `active_task_count`'s scope.
+    in_flight_cost: u64,
+    cost_ceiling: u64,
+    cost_blocked_queue: TaskQueue,
+    cost_deferred_task_count: Counter,
+    // Indexed by lane id; how many tasks are currently in-flight (scheduled
+    // via `schedule_task_in_lane` but not yet `deschedule_task_from_lane`-ed)
+    // in each lane. Empty unless this state machine was built with
+    // `with_lane_count`, in which case `affinity_lane` never picks a lane
+    // outside its bounds.
+    lane_in_flight_counts: Vec<u32>,
+    // Every task currently registered as a waiter on some `Page`, keyed by
+    // `unique_weight`, regardless of whether it's also been promoted into
+    // `retryable_task_queue`; see `register_blocked_task_into_pages` and
+    // `Self::drain_blocked`. A task is removed from here only once it fully
+    // locks (the `TaskSource::Retryable` success path in
+    // `try_lock_for_task`), which is also the only place its waiter
+    // registration is removed from every `Page` it touches.
+    lock_blocked_tasks: TaskQueue,
+    // See `Self::pause`.
+    paused: bool,
 }
 
 impl SchedulingStateMachine {
@@ -304,6 +455,214 @@ impl SchedulingStateMachine {
         self.total_task_count.current()
     }
 
+    /// The combined `cost` of every task currently admitted (active or
+    /// lock-blocked) against `cost_ceiling`.
+    pub fn in_flight_cost(&self) -> u64 {
+        self.in_flight_cost
+    }
+
+    /// How many times [`Self::schedule_task`] has deferred a task into the
+    /// cost-blocked queue since this state machine was created; the count is
+    /// not decremented when a deferred task is later scheduled.
+    pub fn cost_deferred_task_count(&self) -> u32 {
+        self.cost_deferred_task_count.current()
+    }
+
+    pub fn has_cost_blocked_task(&self) -> bool {
+        !self.cost_blocked_queue.is_empty()
+    }
+
+    /// Creates a `SchedulingStateMachine` that defers a task in
+    /// `schedule_task` rather than locking it once `in_flight_cost` would
+    /// exceed `cost_ceiling`; see [`Self::schedule_cost_unblocked_task`].
+    pub fn with_cost_ceiling(cost_ceiling: u64) -> Self {
+        Self {
+            cost_ceiling,
+            ..Self::default()
+        }
+    }
+
+    /// Creates a `SchedulingStateMachine` that hands out an
+    /// [`Self::affinity_lane`] for each task scheduled via
+    /// [`Self::schedule_task_in_lane`], tracking how many tasks are
+    /// currently in-flight per lane so a caller can apply its own overcommit
+    /// cap (e.g. `lane_count * factor`) before admitting another task into an
+    /// already-busy lane.
+    pub fn with_lane_count(lane_count: usize) -> Self {
+        Self {
+            lane_in_flight_counts: vec![0; lane_count],
+            ..Self::default()
+        }
+    }
+
+    /// How many tasks are currently in-flight in `lane`, i.e. scheduled via
+    /// [`Self::schedule_task_in_lane`] but not yet handed to
+    /// [`Self::deschedule_task_from_lane`]. Always `0` for a lane outside
+    /// this state machine's configured [`Self::lane_count`].
+    pub fn lane_in_flight_count(&self, lane: usize) -> u32 {
+        self.lane_in_flight_counts.get(lane).copied().unwrap_or(0)
+    }
+
+    /// The number of lanes this state machine was configured with via
+    /// [`Self::with_lane_count`], or `0` if affinity lanes aren't in use.
+    pub fn lane_count(&self) -> usize {
+        self.lane_in_flight_counts.len()
+    }
+
+    /// Hashes `task`'s primary writable account (the fee payer, i.e. the
+    /// first writable lock of its first transaction) into one of
+    /// `lane_count` lanes. Borrows the M:N runtime's task-pinning idea: a
+    /// downstream multi-threaded dispatcher that always steers a given
+    /// account's tasks to the same lane keeps repeatedly-colliding
+    /// transactions on one worker instead of bouncing them between threads.
+    ///
+    /// Pure and stateless: the same `task` and `lane_count` always hash to
+    /// the same lane, so callers can recompute it freely rather than having
+    /// to remember it.
+    pub fn affinity_lane(task: &Task, lane_count: usize) -> usize {
+        if lane_count == 0 {
+            return 0;
+        }
+        let Some(address) = task.transactions().first().and_then(|transaction| {
+            transaction
+                .get_account_locks_unchecked()
+                .writable
+                .first()
+                .map(|address| **address)
+        }) else {
+            return 0;
+        };
+
+        let mut seed = [0u8; 8];
+        seed.copy_from_slice(&address.to_bytes()[..8]);
+        (u64::from_le_bytes(seed) % lane_count as u64) as usize
+    }
+
+    /// Returns the contention signals accumulated since the last call (or
+    /// since this `SchedulingStateMachine` was created), resetting them.
+    pub fn take_contention_report(&mut self) -> ContentionReport {
+        ContentionReport {
+            blocked_queue_depths: mem::take(&mut self.contention.blocked_queue_depths),
+            max_retryable_age: mem::take(&mut self.contention.max_retryable_age),
+            wasted_readonly_wakeups: mem::take(&mut self.contention.wasted_readonly_wakeups),
+        }
+    }
+
+    /// For a `task` that's currently blocked (lock-blocked on one or more
+    /// pages), every account it's waiting on paired with that account's
+    /// page's current holder, so an operator can see exactly what's holding
+    /// up a stalled task. Addresses are recovered from `task`'s own
+    /// transactions via [`union_requested_usages`], in the same deterministic
+    /// order its lock attempts were built in (the same trick `sharded` uses
+    /// to keep `ShardedTask::shard_ids` in lockstep with its lock attempts).
+    pub fn blocked_task_report(&self, task: &Task) -> Vec<(Pubkey, PageUsageReport)> {
+        let addresses = union_requested_usages(task.transactions()).into_keys();
+        task.with_lock_attempts(&self.task_token, |lock_attempts| {
+            addresses
+                .zip(lock_attempts.iter())
+                .map(|(address, attempt)| {
+                    let usage = attempt.with_page(&self.page_token, |page| page.usage);
+                    (address, PageUsageReport::from(usage))
+                })
+                .collect()
+        })
+    }
+
+    /// Every task currently blocked on `page`, in no particular order across
+    /// the writable/readonly waiter queues. Intended for a caller that
+    /// already maintains its own `Pubkey -> Page` map (as `sharded` and the
+    /// tests do) to resolve an account to a `Page` before asking what's
+    /// queued behind it.
+    pub fn blocked_waiters_on(&self, page: &Page) -> Vec<Task> {
+        page.0.with_borrow(&self.page_token, |page| {
+            page.writable_blocked_tasks
+                .values()
+                .chain(page.readonly_blocked_tasks.values())
+                .cloned()
+                .collect()
+        })
+    }
+
+    /// `page`'s current holder.
+    pub fn page_usage_report(&self, page: &Page) -> PageUsageReport {
+        page.0
+            .with_borrow(&self.page_token, |page| page.usage.into())
+    }
+
+    /// The currently-retryable task that's been waiting longest to be
+    /// retried (the oldest enqueue-sequence stamp), together with how many
+    /// scheduler ticks it's been waiting. This only covers the retryable
+    /// queue, the one place this state machine keeps a flat,
+    /// sequence-stamped registry of not-yet-active tasks; a task still
+    /// lock-blocked on some `Page` is visible only via that page's own
+    /// [`Self::blocked_waiters_on`].
+    pub fn longest_waiting_retryable_task(&self) -> Option<(&Task, u64)> {
+        let (unique_weight, enqueued_at) = self
+            .enqueue_sequences
+            .iter()
+            .min_by_key(|(_, &enqueued_at)| enqueued_at)?;
+        let task = self.retryable_task_queue.get(unique_weight)?;
+        Some((task, self.sequence.saturating_sub(*enqueued_at)))
+    }
+
+    /// Whether [`Self::pause`] is currently in effect.
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// Stops [`Self::schedule_retryable_task`] and
+    /// [`Self::schedule_cost_unblocked_task`] from promoting any task into the
+    /// active set; both become no-ops returning `None` until [`Self::resume`]
+    /// is called. [`Self::deschedule_task`] keeps working as usual, so
+    /// already-active tasks can still drain out normally. Meant to quiesce
+    /// this state machine ahead of [`Self::drain_blocked`], e.g. across a bank
+    /// boundary or a restart.
+    pub fn pause(&mut self) {
+        self.paused = true;
+    }
+
+    /// Undoes [`Self::pause`], letting [`Self::schedule_retryable_task`] and
+    /// [`Self::schedule_cost_unblocked_task`] promote tasks again.
+    pub fn resume(&mut self) {
+        self.paused = false;
+    }
+
+    /// Surrenders every not-yet-active task back to the caller: every task
+    /// still lock-blocked on some [`Page`] (whether or not it's also been
+    /// promoted into the retryable queue already) plus every task sitting in
+    /// the retryable queue outright. Each task is first fully unregistered as
+    /// a waiter from every page it was blocked on, so draining never leaves a
+    /// dangling page registration behind, and this state machine's own
+    /// `active_task_count`/`in_flight_cost` bookkeeping is walked back to
+    /// match — leaving only the still-executing active tasks and their held
+    /// page locks. Intended to be called once this state machine is quiesced
+    /// via [`Self::pause`], handing the drained tasks to a fresh
+    /// `SchedulingStateMachine` rather than forcing a hard reset.
+    pub fn drain_blocked(&mut self) -> Vec<Task> {
+        let drained: Vec<Task> = mem::take(&mut self.lock_blocked_tasks)
+            .into_values()
+            .collect();
+
+        for task in &drained {
+            task.with_lock_attempts(&self.task_token, |lock_attempts| {
+                for lock_attempt in lock_attempts {
+                    lock_attempt.with_page_mut(&mut self.page_token, |page| {
+                        page.remove_blocked_task(lock_attempt.requested_usage, task.waiter_key());
+                    });
+                }
+            });
+
+            if self.retryable_task_queue.remove(&task.unique_weight).is_some() {
+                self.enqueue_sequences.remove(&task.unique_weight);
+            }
+
+            self.active_task_count.decrement_self();
+            self.in_flight_cost = self.in_flight_cost.saturating_sub(task.cost);
+        }
+
+        drained
+    }
+
     #[cfg(feature = "dev-context-only-utils")]
     pub fn schedule_task_for_test(&mut self, task: Task) -> Option<Task> {
         self.schedule_task(task, |task| task.clone())
@@ -314,12 +673,75 @@ impl SchedulingStateMachine {
         task: Task,
         on_success: impl FnOnce(&Task) -> R,
     ) -> Option<R> {
+        if self.in_flight_cost.saturating_add(task.cost) > self.cost_ceiling {
+            self.cost_blocked_queue
+                .entry(task.unique_weight)
+                .or_insert(task);
+            self.cost_deferred_task_count.increment_self();
+            return None;
+        }
+
+        self.in_flight_cost += task.cost;
         let ret = self.try_lock_for_task(TaskSource::Runnable, task, on_success);
         self.total_task_count.increment_self();
         self.active_task_count.increment_self();
         ret
     }
 
+    /// Like [`Self::schedule_task`], but pulls its task from the
+    /// cost-blocked queue instead of taking one directly, only doing so if
+    /// `task.cost` now fits under `cost_ceiling`. Intended to be retried (like
+    /// [`Self::schedule_retryable_task`]) whenever [`Self::deschedule_task`]
+    /// frees up budget.
+    pub fn schedule_cost_unblocked_task<R>(
+        &mut self,
+        on_success: impl FnOnce(&Task) -> R,
+    ) -> Option<R> {
+        if self.paused {
+            return None;
+        }
+
+        let (&unique_weight, task) = self.cost_blocked_queue.last_key_value()?;
+        if self.in_flight_cost.saturating_add(task.cost) > self.cost_ceiling {
+            return None;
+        }
+        let task = self.cost_blocked_queue.remove(&unique_weight).unwrap();
+
+        self.in_flight_cost += task.cost;
+        let ret = self.try_lock_for_task(TaskSource::Runnable, task, on_success);
+        self.total_task_count.increment_self();
+        self.active_task_count.increment_self();
+        ret
+    }
+
+    /// Like [`Self::schedule_task`], but also computes `task`'s
+    /// [`Self::affinity_lane`] (against this state machine's configured
+    /// [`Self::lane_count`]) and bumps that lane's in-flight count, to be
+    /// undone later by [`Self::deschedule_task_from_lane`]. The caller is
+    /// expected to have already checked `lane_in_flight_count` against its
+    /// own overcommit cap before calling this.
+    pub fn schedule_task_in_lane<R>(
+        &mut self,
+        task: Task,
+        on_success: impl FnOnce(&Task) -> R,
+    ) -> (usize, Option<R>) {
+        let lane = Self::affinity_lane(&task, self.lane_count());
+        if let Some(count) = self.lane_in_flight_counts.get_mut(lane) {
+            *count += 1;
+        }
+        (lane, self.schedule_task(task, on_success))
+    }
+
+    /// Undoes [`Self::schedule_task_in_lane`]'s in-flight count bump for
+    /// `lane`, then [`Self::deschedule_task`]s `task` as usual. `lane` must be
+    /// the value [`Self::schedule_task_in_lane`] returned for `task`.
+    pub fn deschedule_task_from_lane(&mut self, lane: usize, task: &Task) {
+        if let Some(count) = self.lane_in_flight_counts.get_mut(lane) {
+            *count = count.saturating_sub(1);
+        }
+        self.deschedule_task(task);
+    }
+
     pub fn has_retryable_task(&self) -> bool {
         !self.retryable_task_queue.is_empty()
     }
@@ -328,15 +750,33 @@ impl SchedulingStateMachine {
         self.retryable_task_queue.clear()
     }
 
+    /// Enqueues `task` onto the retryable queue directly, keyed by its own
+    /// `unique_weight`, bypassing `schedule_task`'s lock attempt. Used by
+    /// `sharded`, whose cross-shard tasks aren't owned by any single
+    /// `SchedulingStateMachine`.
+    fn enqueue_retryable_task(&mut self, task: Task) {
+        self.retryable_task_queue
+            .entry(task.unique_weight)
+            .or_insert_with(|| task);
+    }
+
     #[cfg(feature = "dev-context-only-utils")]
     pub fn schedule_retryable_task_for_test(&mut self) -> Option<Task> {
         self.schedule_retryable_task(|task| task.clone())
     }
 
     pub fn schedule_retryable_task<R>(&mut self, on_success: impl FnOnce(&Task) -> R) -> Option<R> {
+        if self.paused {
+            return None;
+        }
+
         self.retryable_task_queue
             .pop_last()
-            .and_then(|(_, task)| {
+            .and_then(|(unique_weight, task)| {
+                if let Some(enqueued_at) = self.enqueue_sequences.remove(&unique_weight) {
+                    let age = self.sequence.saturating_sub(enqueued_at);
+                    self.contention.max_retryable_age = self.contention.max_retryable_age.max(age);
+                }
                 let ret = self.try_lock_for_task(TaskSource::Retryable, task, on_success);
                 self.reschedule_count.increment_self();
                 ret
@@ -350,22 +790,23 @@ impl SchedulingStateMachine {
     pub fn deschedule_task(&mut self, task: &Task) {
         self.active_task_count.decrement_self();
         self.handled_task_count.increment_self();
+        self.in_flight_cost = self.in_flight_cost.saturating_sub(task.cost);
         self.unlock_after_execution(task);
     }
 
     fn attempt_lock_for_execution(
         page_token: &mut PageToken,
-        unique_weight: UniqueWeight,
+        this_key: WaiterKey,
         lock_attempts: &mut [LockAttempt],
         rollback_on_failure: bool,
     ) -> usize {
         let mut lock_count = Counter::zero();
 
         for attempt in lock_attempts.iter_mut() {
-            match Self::attempt_lock_address(page_token, unique_weight, attempt) {
+            match Self::attempt_lock_address(page_token, this_key, attempt) {
                 LockStatus::Succeded(usage) => {
                     if rollback_on_failure {
-                        attempt.page_mut(page_token).usage = usage;
+                        attempt.with_page_mut(page_token, |page| page.usage = usage);
                     } else {
                         attempt.uncommited_usage = usage;
                     }
@@ -380,78 +821,80 @@ impl SchedulingStateMachine {
 
     fn attempt_lock_address(
         page_token: &mut PageToken,
-        this_unique_weight: UniqueWeight,
+        this_key: WaiterKey,
         attempt: &mut LockAttempt,
     ) -> LockStatus {
         let requested_usage = attempt.requested_usage;
-        let page = attempt.page_mut(page_token);
 
-        let mut lock_status = match page.usage {
-            Usage::Unused => LockStatus::Succeded(Usage::renew(requested_usage)),
-            Usage::Readonly(count) => match requested_usage {
-                RequestedUsage::Readonly => {
-                    LockStatus::Succeded(Usage::Readonly(count.increment()))
+        attempt.with_page_mut(page_token, |page| {
+            let mut lock_status = match page.usage {
+                Usage::Unused => LockStatus::Succeded(Usage::renew(requested_usage)),
+                Usage::Readonly(count) => match requested_usage {
+                    RequestedUsage::Readonly => {
+                        LockStatus::Succeded(Usage::Readonly(count.increment()))
+                    }
+                    RequestedUsage::Writable => LockStatus::Failed,
+                },
+                Usage::Writable => LockStatus::Failed,
+            };
+
+            if matches!(lock_status, LockStatus::Succeded(_)) {
+                let no_heavier_other_tasks =
+                    // this key is the heaviest (highest-priority, ties broken by arrival order)
+                    // one among all of other tasks blocked on this page.
+                    (page
+                        .heaviest_blocked_task()
+                        .map(|(&existing_key, _)| this_key >= existing_key)
+                        .unwrap_or(true)) ||
+                    // this _read-only_ key is heavier than any of contened write locks.
+                    (matches!(requested_usage, RequestedUsage::Readonly) && page
+                        .heaviest_blocked_writing_task()
+                        // this_key is readonly and existing_key is writable here.
+                        // so given key can't be same; thus > instead of >= is correct
+                        .map(|(&existing_key, _)| this_key > existing_key)
+                        .unwrap_or(true))
+                ;
+
+                if !no_heavier_other_tasks {
+                    lock_status = LockStatus::Failed
                 }
-                RequestedUsage::Writable => LockStatus::Failed,
-            },
-            Usage::Writable => LockStatus::Failed,
-        };
-
-        if matches!(lock_status, LockStatus::Succeded(_)) {
-            let no_heavier_other_tasks =
-                // this unique_weight is the heaviest one among all of other tasks blocked on this
-                // page.
-                (page
-                    .heaviest_blocked_task()
-                    .map(|(&existing_unique_weight, _)| this_unique_weight >= existing_unique_weight)
-                    .unwrap_or(true)) ||
-                // this _read-only_ unique_weight is heavier than any of contened write locks.
-                (matches!(requested_usage, RequestedUsage::Readonly) && page
-                    .heaviest_blocked_writing_task()
-                    // this_unique_weight is readonly and existing_unique_weight is writable here.
-                    // so given unique_weight can't be same; thus > instead of >= is correct
-                    .map(|(&existing_unique_weight, _)| this_unique_weight > existing_unique_weight)
-                    .unwrap_or(true))
-            ;
-
-            if !no_heavier_other_tasks {
-                lock_status = LockStatus::Failed
             }
-        }
-        lock_status
+            lock_status
+        })
     }
 
     fn unlock(page_token: &mut PageToken, attempt: &LockAttempt) -> bool {
-        let mut is_unused_now = false;
-
         let requested_usage = attempt.requested_usage;
-        let page = attempt.page_mut(page_token);
 
-        match &mut page.usage {
-            Usage::Readonly(ref mut count) => match requested_usage {
-                RequestedUsage::Readonly => {
-                    if count.is_one() {
+        attempt.with_page_mut(page_token, |page| {
+            let mut is_unused_now = false;
+
+            match &mut page.usage {
+                Usage::Readonly(ref mut count) => match requested_usage {
+                    RequestedUsage::Readonly => {
+                        if count.is_one() {
+                            is_unused_now = true;
+                        } else {
+                            count.decrement_self();
+                        }
+                    }
+                    RequestedUsage::Writable => unreachable!(),
+                },
+                Usage::Writable => match requested_usage {
+                    RequestedUsage::Writable => {
                         is_unused_now = true;
-                    } else {
-                        count.decrement_self();
                     }
-                }
-                RequestedUsage::Writable => unreachable!(),
-            },
-            Usage::Writable => match requested_usage {
-                RequestedUsage::Writable => {
-                    is_unused_now = true;
-                }
-                RequestedUsage::Readonly => unreachable!(),
-            },
-            Usage::Unused => unreachable!(),
-        }
+                    RequestedUsage::Readonly => unreachable!(),
+                },
+                Usage::Unused => unreachable!(),
+            }
 
-        if is_unused_now {
-            page.usage = Usage::Unused;
-        }
+            if is_unused_now {
+                page.usage = Usage::Unused;
+            }
 
-        is_unused_now
+            is_unused_now
+        })
     }
 
     fn try_lock_for_task<R>(
@@ -460,16 +903,21 @@ impl SchedulingStateMachine {
         task: Task,
         on_success: impl FnOnce(&Task) -> R,
     ) -> Option<R> {
+        self.sequence += 1;
         let rollback_on_failure = matches!(task_source, TaskSource::Runnable);
 
-        let lock_count = Self::attempt_lock_for_execution(
-            &mut self.page_token,
-            task.unique_weight,
-            task.lock_attempts_mut(&mut self.task_token),
-            rollback_on_failure,
-        );
-
-        if lock_count < task.lock_attempts_mut(&mut self.task_token).len() {
+        let (lock_count, lock_attempts_len) =
+            task.with_lock_attempts_mut(&mut self.task_token, |lock_attempts| {
+                let lock_count = Self::attempt_lock_for_execution(
+                    &mut self.page_token,
+                    task.waiter_key(),
+                    lock_attempts,
+                    rollback_on_failure,
+                );
+                (lock_count, lock_attempts.len())
+            });
+
+        if lock_count < lock_attempts_len {
             if rollback_on_failure {
                 self.rollback_locking(&task, lock_count);
                 self.register_blocked_task_into_pages(&task);
@@ -480,28 +928,46 @@ impl SchedulingStateMachine {
             let ret = on_success(&task);
             match task_source {
                 TaskSource::Retryable => {
-                    for attempt in task.lock_attempts_mut(&mut self.task_token) {
-                        let page = attempt.page_mut(&mut self.page_token);
-                        page.usage = attempt.uncommited_usage;
-                        page.remove_blocked_task(attempt.requested_usage, task.unique_weight);
-                    }
+                    task.with_lock_attempts_mut(&mut self.task_token, |lock_attempts| {
+                        for attempt in lock_attempts.iter() {
+                            attempt.with_page_mut(&mut self.page_token, |page| {
+                                page.usage = attempt.uncommited_usage;
+                                page.remove_blocked_task(attempt.requested_usage, task.waiter_key());
+                            });
+                        }
+                    });
+                    self.lock_blocked_tasks.remove(&task.unique_weight);
 
                     // as soon as `task` is succeeded in locking, trigger re-checks on read only
                     // addresses so that more readonly transactions can be executed
-                    for read_only_lock_attempt in task
-                        .lock_attempts(&self.task_token)
-                        .iter()
-                        .filter(|l| matches!(l.requested_usage, RequestedUsage::Readonly))
-                    {
-                        if let Some((heaviest_readonly_unique_weight, heaviest_readonly_task)) = read_only_lock_attempt
-                            .page_mut(&mut self.page_token)
-                            .heaviest_blocked_readonly_task()
+                    task.with_lock_attempts(&self.task_token, |lock_attempts| {
+                        for read_only_lock_attempt in lock_attempts
+                            .iter()
+                            .filter(|l| matches!(l.requested_usage, RequestedUsage::Readonly))
                         {
-                            self.retryable_task_queue
-                                .entry(heaviest_readonly_unique_weight)
-                                .or_insert_with(|| heaviest_readonly_task.clone());
+                            let heaviest_readonly = read_only_lock_attempt
+                                .with_page_mut(&mut self.page_token, |page| {
+                                    page.heaviest_blocked_readonly_task()
+                                        .map(|(&key, task)| (key.unique_weight, task.clone()))
+                                });
+                            if let Some((heaviest_readonly_unique_weight, heaviest_readonly_task)) =
+                                heaviest_readonly
+                            {
+                                if self
+                                    .retryable_task_queue
+                                    .contains_key(&heaviest_readonly_unique_weight)
+                                {
+                                    self.contention.wasted_readonly_wakeups += 1;
+                                } else {
+                                    self.enqueue_sequences
+                                        .insert(heaviest_readonly_unique_weight, self.sequence);
+                                }
+                                self.retryable_task_queue
+                                    .entry(heaviest_readonly_unique_weight)
+                                    .or_insert_with(|| heaviest_readonly_task);
+                            }
                         }
-                    }
+                    });
                 }
                 TaskSource::Runnable => {}
             }
@@ -510,59 +976,80 @@ impl SchedulingStateMachine {
     }
 
     fn rollback_locking(&mut self, task: &Task, lock_count: usize) {
-        for lock_attempt in &task.lock_attempts_mut(&mut self.task_token)[..lock_count] {
-            Self::unlock(&mut self.page_token, lock_attempt);
-        }
+        task.with_lock_attempts_mut(&mut self.task_token, |lock_attempts| {
+            for lock_attempt in &lock_attempts[..lock_count] {
+                Self::unlock(&mut self.page_token, lock_attempt);
+            }
+        });
     }
 
     fn register_blocked_task_into_pages(&mut self, task: &Task) {
-        for lock_attempt in task.lock_attempts_mut(&mut self.task_token) {
-            let requested_usage = lock_attempt.requested_usage;
-            lock_attempt
-                .page_mut(&mut self.page_token)
-                .insert_blocked_task(task.clone(), requested_usage);
-        }
+        task.with_lock_attempts_mut(&mut self.task_token, |lock_attempts| {
+            for lock_attempt in lock_attempts.iter() {
+                let requested_usage = lock_attempt.requested_usage;
+                let blocked_task_count = lock_attempt.with_page_mut(&mut self.page_token, |page| {
+                    page.insert_blocked_task(task.clone(), requested_usage);
+                    page.blocked_task_count()
+                });
+                self.contention.blocked_queue_depths.push(blocked_task_count);
+            }
+        });
+        self.lock_blocked_tasks
+            .entry(task.unique_weight)
+            .or_insert_with(|| task.clone());
     }
 
     fn unlock_after_execution(&mut self, task: &Task) {
-        for unlock_attempt in task.lock_attempts(&self.task_token) {
-            let is_unused_now = Self::unlock(&mut self.page_token, unlock_attempt);
-            if !is_unused_now {
-                continue;
-            }
+        task.with_lock_attempts(&self.task_token, |lock_attempts| {
+            for unlock_attempt in lock_attempts {
+                let is_unused_now = Self::unlock(&mut self.page_token, unlock_attempt);
+                if !is_unused_now {
+                    continue;
+                }
 
-            let heaviest_uncontended_now = unlock_attempt
-                .page_mut(&mut self.page_token)
-                .heaviest_blocked_task();
-            if let Some(uncontended_task) = heaviest_uncontended_now {
-                self.retryable_task_queue
-                    .entry(uncontended_task.unique_weight)
-                    .or_insert_with(|| uncontended_task.clone());
+                let heaviest_uncontended_now =
+                    unlock_attempt.with_page_mut(&mut self.page_token, |page| {
+                        page.heaviest_blocked_task()
+                            .map(|(&key, task)| (key.unique_weight, task.clone()))
+                    });
+                if let Some((unique_weight, uncontended_task)) = heaviest_uncontended_now {
+                    if !self.retryable_task_queue.contains_key(&unique_weight) {
+                        self.enqueue_sequences.insert(unique_weight, self.sequence);
+                    }
+                    self.retryable_task_queue
+                        .entry(unique_weight)
+                        .or_insert_with(|| uncontended_task);
+                }
             }
-        }
+        });
     }
 
     pub fn create_task(
         transaction: SanitizedTransaction,
         index: usize,
+        cost: u64,
+        priority: u64,
         page_loader: &mut impl FnMut(Pubkey) -> Page,
     ) -> Task {
-        let locks = transaction.get_account_locks_unchecked();
-
-        let writable_locks = locks
-            .writable
-            .iter()
-            .map(|address| (address, RequestedUsage::Writable));
-        let readonly_locks = locks
-            .readonly
-            .iter()
-            .map(|address| (address, RequestedUsage::Readonly));
+        Self::create_bundle_task(&[transaction], index, cost, priority, page_loader)
+    }
 
-        let locks = writable_locks
-            .chain(readonly_locks)
-            .map(|(address, requested_usage)| {
-                LockAttempt::new(page_loader(**address), requested_usage)
-            })
+    /// Like [`Self::create_task`], but locks the union of `transactions`'
+    /// account locks as a single atomic unit: a page written by any member
+    /// transaction is locked `Writable` even if other members only read it,
+    /// and each page is locked at most once regardless of how many member
+    /// transactions touch it. Combined with `try_lock_for_task`'s rollback on
+    /// partial failure, this gives the whole bundle all-or-nothing locking.
+    pub fn create_bundle_task(
+        transactions: &[SanitizedTransaction],
+        index: usize,
+        cost: u64,
+        priority: u64,
+        page_loader: &mut impl FnMut(Pubkey) -> Page,
+    ) -> Task {
+        let locks = union_requested_usages(transactions)
+            .into_iter()
+            .map(|(address, requested_usage)| LockAttempt::new(page_loader(address), requested_usage))
             .collect();
 
         let unique_weight = UniqueWeight::max_value()
@@ -571,7 +1058,9 @@ impl SchedulingStateMachine {
 
         Task::new(TaskInner {
             unique_weight,
-            transaction,
+            transactions: transactions.to_vec(),
+            cost,
+            priority,
             task_status: SchedulerCell::new(TaskStatus::new(locks)),
         })
     }
@@ -588,6 +1077,16 @@ impl Default for SchedulingStateMachine {
             total_task_count: Counter::zero(),
             task_token: unsafe { TaskToken::assume_on_the_scheduler_thread() },
             page_token: unsafe { PageToken::assume_on_the_scheduler_thread() },
+            sequence: 0,
+            enqueue_sequences: BTreeMap::new(),
+            contention: ContentionStats::default(),
+            in_flight_cost: 0,
+            cost_ceiling: u64::MAX,
+            cost_blocked_queue: TaskQueue::default(),
+            cost_deferred_task_count: Counter::zero(),
+            lane_in_flight_counts: Vec::new(),
+            lock_blocked_tasks: TaskQueue::default(),
+            paused: false,
         }
     }
 }
@@ -597,8 +1096,242 @@ enum TaskSource {
     Retryable,
 }
 
+/// Unions `transactions`' account locks into a single per-address usage map,
+/// upgrading an address to `RequestedUsage::Writable` as soon as any
+/// transaction writes it, even if others only read it. Iteration order
+/// (ascending `Pubkey`) is deterministic, which `sharded` relies on to keep a
+/// task's lock attempts and their owning shard ids in lockstep.
+fn union_requested_usages(transactions: &[SanitizedTransaction]) -> BTreeMap<Pubkey, RequestedUsage> {
+    let mut requested_usages = BTreeMap::<Pubkey, RequestedUsage>::new();
+    for transaction in transactions {
+        let locks = transaction.get_account_locks_unchecked();
+        for address in locks.writable {
+            requested_usages.insert(*address, RequestedUsage::Writable);
+        }
+        for address in locks.readonly {
+            requested_usages
+                .entry(*address)
+                .or_insert(RequestedUsage::Readonly);
+        }
+    }
+    requested_usages
+}
+
 type UniqueWeight = u64;
 
+/// An optional, multi-threaded scheduler backend that partitions account
+/// pages across a fixed number of shards so independent account sets can
+/// make progress on separate threads, rather than sharing the single
+/// `SchedulingStateMachine`'s zero-sized, single-thread-only `Token`s.
+///
+/// Each shard owns its own `SchedulingStateMachine` and page map behind a
+/// `parking_lot::Mutex`; a task whose accounts all hash to one shard locks
+/// only that shard's mutex, while a cross-shard task acquires every involved
+/// shard's mutex up front, in a fixed ascending shard-id order, so two
+/// concurrent cross-shard tasks can never deadlock waiting on each other.
+///
+/// This backend trades away the single-machine model's priority-ordered,
+/// push-based waiter wakeups: a task that fails to lock is simply recorded
+/// in its heaviest involved shard's retryable queue rather than registered
+/// on each contended `Page`, so callers drive retries themselves (typically:
+/// after any `deschedule_task`, re-attempt `schedule_task` for whatever a
+/// shard's retryable queue holds).
+pub mod sharded {
+    use {
+        super::{
+            union_requested_usages, LockAttempt, Page, PageToken, SchedulingStateMachine, Task,
+            TaskInner, TaskStatus, TaskToken,
+        },
+        parking_lot::Mutex,
+        solana_sdk::{pubkey::Pubkey, transaction::SanitizedTransaction},
+        std::{collections::HashMap, thread::available_parallelism},
+    };
+
+    const DEFAULT_OVERCOMMIT: usize = 4;
+
+    #[derive(Default)]
+    struct Shard {
+        state_machine: Mutex<SchedulingStateMachine>,
+        pages: Mutex<HashMap<Pubkey, Page>>,
+    }
+
+    /// A task built by [`ShardedSchedulingStateMachine::create_task`], paired
+    /// with the shard id each of its lock attempts belongs to (in the same
+    /// order as the task's internal lock-attempt vector).
+    pub struct ShardedTask {
+        task: Task,
+        shard_ids: Vec<usize>,
+    }
+
+    impl ShardedTask {
+        pub fn task(&self) -> &Task {
+            &self.task
+        }
+    }
+
+    pub struct ShardedSchedulingStateMachine {
+        shards: Vec<Shard>,
+    }
+
+    impl Default for ShardedSchedulingStateMachine {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl ShardedSchedulingStateMachine {
+        /// Creates a backend with `available_parallelism() * overcommit`
+        /// shards.
+        pub fn with_overcommit(overcommit: usize) -> Self {
+            let parallelism = available_parallelism().map(|n| n.get()).unwrap_or(1);
+            Self::with_shard_count(parallelism * overcommit.max(1))
+        }
+
+        /// Creates a backend with `available_parallelism() * 4` shards.
+        pub fn new() -> Self {
+            Self::with_overcommit(DEFAULT_OVERCOMMIT)
+        }
+
+        pub fn with_shard_count(shard_count: usize) -> Self {
+            Self {
+                shards: (0..shard_count.max(1)).map(|_| Shard::default()).collect(),
+            }
+        }
+
+        pub fn shard_count(&self) -> usize {
+            self.shards.len()
+        }
+
+        fn shard_id_for_address(&self, address: &Pubkey) -> usize {
+            let mut seed = [0u8; 8];
+            seed.copy_from_slice(&address.to_bytes()[..8]);
+            (u64::from_le_bytes(seed) % self.shards.len() as u64) as usize
+        }
+
+        fn page_for(&self, shard_id: usize, address: &Pubkey) -> Page {
+            self.shards[shard_id]
+                .pages
+                .lock()
+                .entry(*address)
+                .or_default()
+                .clone()
+        }
+
+        /// Builds a `ShardedTask` locking the union of `transactions`'
+        /// account locks, routing each to the shard its address hashes to.
+        /// Does not lock anything yet; pass the result to
+        /// [`Self::schedule_task`].
+        pub fn create_task(&self, transactions: &[SanitizedTransaction], index: usize) -> ShardedTask {
+            let mut shard_ids = Vec::new();
+            let mut lock_attempts = Vec::new();
+            for (address, requested_usage) in union_requested_usages(transactions) {
+                let shard_id = self.shard_id_for_address(&address);
+                shard_ids.push(shard_id);
+                lock_attempts.push(LockAttempt::new(
+                    self.page_for(shard_id, &address),
+                    requested_usage,
+                ));
+            }
+
+            let unique_weight = super::UniqueWeight::max_value()
+                .checked_sub(index as super::UniqueWeight)
+                .unwrap();
+            let task = Task::new(TaskInner {
+                unique_weight,
+                transactions: transactions.to_vec(),
+                cost: 0,
+                priority: 0,
+                task_status: super::SchedulerCell::new(TaskStatus::new(lock_attempts)),
+            });
+
+            ShardedTask { task, shard_ids }
+        }
+
+        fn sorted_unique_shard_ids(&self, sharded_task: &ShardedTask) -> Vec<usize> {
+            let mut shard_ids = sharded_task.shard_ids.clone();
+            shard_ids.sort_unstable();
+            shard_ids.dedup();
+            shard_ids
+        }
+
+        /// Attempts to lock every page `sharded_task` touches, returning
+        /// whether it fully locked (and is therefore ready to execute). On
+        /// partial failure, rolls back every lock this call took and enqueues
+        /// the task onto the most heavily blocked involved shard's retryable
+        /// queue, to be retried once that shard's contention clears.
+        pub fn schedule_task(&self, sharded_task: &ShardedTask) -> bool {
+            let sorted_shard_ids = self.sorted_unique_shard_ids(sharded_task);
+            let mut guards: Vec<_> = sorted_shard_ids
+                .iter()
+                .map(|&shard_id| self.shards[shard_id].state_machine.lock())
+                .collect();
+
+            // SAFETY: every shard this task's lock attempts can touch is
+            // locked above, for the duration of this function, so this
+            // thread has exclusive access to each of their pages.
+            let mut page_token = unsafe { PageToken::assume_on_the_scheduler_thread() };
+            let mut task_token = unsafe { TaskToken::assume_on_the_scheduler_thread() };
+            let task = sharded_task.task();
+
+            let lock_count = task.with_lock_attempts_mut(&mut task_token, |lock_attempts| {
+                SchedulingStateMachine::attempt_lock_for_execution(
+                    &mut page_token,
+                    task.waiter_key(),
+                    lock_attempts,
+                    true,
+                )
+            });
+            let lock_attempts_len =
+                task.with_lock_attempts(&task_token, |lock_attempts| lock_attempts.len());
+
+            if lock_count == lock_attempts_len {
+                return true;
+            }
+
+            task.with_lock_attempts_mut(&mut task_token, |lock_attempts| {
+                for lock_attempt in &lock_attempts[..lock_count] {
+                    SchedulingStateMachine::unlock(&mut page_token, lock_attempt);
+                }
+            });
+
+            let (heaviest_index, _) = sorted_shard_ids
+                .iter()
+                .enumerate()
+                .max_by_key(|&(i, _)| guards[i].retryable_task_count())
+                .expect("at least one shard is always involved");
+            guards[heaviest_index].enqueue_retryable_task(task.clone());
+
+            false
+        }
+
+        /// Unlocks every page `sharded_task` holds. Callers that want
+        /// blocked waiters to make progress should re-attempt
+        /// `schedule_task` for whatever sits in the now-less-contended
+        /// shards' retryable queues.
+        pub fn deschedule_task(&self, sharded_task: &ShardedTask) {
+            let sorted_shard_ids = self.sorted_unique_shard_ids(sharded_task);
+            // Held only to serialize with concurrent `schedule_task`/
+            // `deschedule_task` calls touching the same shards; unlocking
+            // itself doesn't need anything out of the guards.
+            let _guards: Vec<_> = sorted_shard_ids
+                .iter()
+                .map(|&shard_id| self.shards[shard_id].state_machine.lock())
+                .collect();
+
+            let mut page_token = unsafe { PageToken::assume_on_the_scheduler_thread() };
+            let task_token = unsafe { TaskToken::assume_on_the_scheduler_thread() };
+
+            sharded_task
+                .task()
+                .with_lock_attempts(&task_token, |lock_attempts| {
+                    for lock_attempt in lock_attempts {
+                        SchedulingStateMachine::unlock(&mut page_token, lock_attempt);
+                    }
+                });
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use {
@@ -670,7 +1403,7 @@ mod tests {
              .. })), requested_usage: Writable, uncommited_usage: Unused }] }"
         );
         let sanitized = simplest_transaction();
-        let task = SchedulingStateMachine::create_task(sanitized, 0, &mut |_| Page::default());
+        let task = SchedulingStateMachine::create_task(sanitized, 0, 0, 0, &mut |_| Page::default());
         assert!(format!("{:?}", task).contains("TaskInner"));
     }
 
@@ -686,16 +1419,61 @@ mod tests {
     fn test_create_task() {
         let sanitized = simplest_transaction();
         let task =
-            SchedulingStateMachine::create_task(sanitized.clone(), 3, &mut |_| Page::default());
+            SchedulingStateMachine::create_task(sanitized.clone(), 3, 0, 0, &mut |_| Page::default());
         assert_eq!(task.task_index(), 3);
         assert_eq!(task.transaction(), &sanitized);
     }
 
+    #[test]
+    fn test_create_bundle_task_dedups_and_upgrades_to_writable() {
+        let shared_address = Pubkey::new_unique();
+        let readonly_member = readonly_transaction(shared_address);
+        let writable_member = transaction_with_shared_writable(shared_address);
+        let pages = Arc::new(Mutex::new(HashMap::new()));
+        let address_loader = &mut create_address_loader(Some(pages.clone()));
+
+        let task = SchedulingStateMachine::create_bundle_task(
+            &[readonly_member.clone(), writable_member.clone()],
+            7,
+            0,
+            0,
+            address_loader,
+        );
+        assert_eq!(task.task_index(), 7);
+        assert_eq!(
+            task.transactions(),
+            &[readonly_member, writable_member][..]
+        );
+
+        let mut state_machine = SchedulingStateMachine::default();
+
+        // Both member transactions' fee payers plus `shared_address` and the
+        // shared program id: four lock attempts, not six, because the two
+        // members' overlapping requests for `shared_address` and the program
+        // id were each deduped into one.
+        let lock_attempt_count =
+            task.with_lock_attempts(&state_machine.task_token, |attempts| attempts.len());
+        assert_eq!(lock_attempt_count, 4);
+
+        assert_matches!(state_machine.schedule_task_for_test(task), Some(_));
+
+        // The single, deduped lock attempt for `shared_address` was upgraded
+        // to `Writable` because one of the two member transactions writes
+        // the account, even though the other only reads it.
+        let pages = pages.lock().unwrap();
+        let page = pages.get(&shared_address).unwrap();
+        assert_matches!(
+            page.0
+                .with_borrow(&state_machine.page_token, |page| page.usage),
+            Usage::Writable
+        );
+    }
+
     #[test]
     fn test_schedule_non_conflicting_task() {
         let sanitized = simplest_transaction();
         let address_loader = &mut create_address_loader(None);
-        let task = SchedulingStateMachine::create_task(sanitized.clone(), 3, address_loader);
+        let task = SchedulingStateMachine::create_task(sanitized.clone(), 3, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         let task = state_machine.schedule_task_for_test(task).unwrap();
@@ -711,8 +1489,8 @@ mod tests {
     fn test_schedule_conflicting_task() {
         let sanitized = simplest_transaction();
         let address_loader = &mut create_address_loader(None);
-        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
@@ -732,8 +1510,8 @@ mod tests {
     fn test_schedule_retryable_task() {
         let sanitized = simplest_transaction();
         let address_loader = &mut create_address_loader(None);
-        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
@@ -761,9 +1539,9 @@ mod tests {
     fn test_schedule_retryable_task2() {
         let sanitized = simplest_transaction();
         let address_loader = &mut create_address_loader(None);
-        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, address_loader);
-        let task3 = SchedulingStateMachine::create_task(sanitized.clone(), 0, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, 0, 0, address_loader);
+        let task3 = SchedulingStateMachine::create_task(sanitized.clone(), 0, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
@@ -798,9 +1576,9 @@ mod tests {
     fn test_schedule_retryable_task3() {
         let sanitized = simplest_transaction();
         let address_loader = &mut create_address_loader(None);
-        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, address_loader);
-        let task3 = SchedulingStateMachine::create_task(sanitized.clone(), 5, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, 0, 0, address_loader);
+        let task3 = SchedulingStateMachine::create_task(sanitized.clone(), 5, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
@@ -813,14 +1591,137 @@ mod tests {
         assert_matches!(state_machine.schedule_task_for_test(task3.clone()), None);
     }
 
+    #[test]
+    fn test_schedule_task_cost_ceiling() {
+        let sanitized1 = simplest_transaction();
+        let sanitized2 = simplest_transaction();
+        let address_loader = &mut create_address_loader(None);
+        // Lock-independent (disjoint fee payers), so only the cost ceiling
+        // keeps task2 from becoming active alongside task1.
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 60, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 60, 0, address_loader);
+
+        let mut state_machine = SchedulingStateMachine::with_cost_ceiling(100);
+        assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
+        assert_eq!(state_machine.in_flight_cost(), 60);
+
+        assert_matches!(state_machine.schedule_task_for_test(task2.clone()), None);
+        assert_eq!(state_machine.in_flight_cost(), 60);
+        assert_eq!(state_machine.cost_deferred_task_count(), 1);
+        assert!(state_machine.has_cost_blocked_task());
+        // A cost-blocked task never holds any page locks.
+        assert!(!state_machine.has_retryable_task());
+
+        assert_matches!(
+            state_machine.schedule_cost_unblocked_task(|task| task.clone()),
+            None
+        );
+
+        state_machine.deschedule_task(&task1);
+        assert_eq!(state_machine.in_flight_cost(), 0);
+
+        assert_matches!(
+            state_machine.schedule_cost_unblocked_task(|task| task.clone()),
+            Some(_)
+        );
+        assert_eq!(state_machine.in_flight_cost(), 60);
+        assert!(!state_machine.has_cost_blocked_task());
+
+        state_machine.deschedule_task(&task2);
+        assert!(state_machine.is_empty());
+    }
+
+    #[test]
+    fn test_schedule_releases_highest_priority_waiter_first() {
+        let sanitized = simplest_transaction();
+        let address_loader = &mut create_address_loader(None);
+        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, 0, 0, address_loader);
+        // task2 arrives before task3, but task3's priority is higher; the
+        // priority max-heap should release task3 first despite that.
+        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, 0, 1, address_loader);
+        let task3 = SchedulingStateMachine::create_task(sanitized.clone(), 5, 0, 5, address_loader);
+
+        let mut state_machine = SchedulingStateMachine::default();
+        assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
+        assert_matches!(state_machine.schedule_task_for_test(task2.clone()), None);
+        assert_matches!(state_machine.schedule_task_for_test(task3.clone()), None);
+
+        state_machine.deschedule_task(&task1);
+
+        assert_eq!(
+            state_machine
+                .schedule_retryable_task_for_test()
+                .unwrap()
+                .task_index(),
+            task3.task_index()
+        );
+
+        state_machine.deschedule_task(&task3);
+        assert_eq!(
+            state_machine
+                .schedule_retryable_task_for_test()
+                .unwrap()
+                .task_index(),
+            task2.task_index()
+        );
+
+        state_machine.deschedule_task(&task2);
+        assert!(state_machine.is_empty());
+    }
+
+    #[test]
+    fn test_affinity_lane() {
+        let sanitized = simplest_transaction();
+        let address_loader = &mut create_address_loader(None);
+        let task = SchedulingStateMachine::create_task(sanitized, 0, 0, 0, address_loader);
+
+        // Pure and stateless: recomputing against the same lane count always
+        // picks the same lane.
+        let lane = SchedulingStateMachine::affinity_lane(&task, 4);
+        assert!(lane < 4);
+        assert_eq!(SchedulingStateMachine::affinity_lane(&task, 4), lane);
+
+        // Disabled (lane_count == 0) degrades to a single, fixed lane.
+        assert_eq!(SchedulingStateMachine::affinity_lane(&task, 0), 0);
+    }
+
+    #[test]
+    fn test_schedule_task_in_lane_tracks_in_flight_counts() {
+        let sanitized1 = simplest_transaction();
+        let sanitized2 = simplest_transaction();
+        let address_loader = &mut create_address_loader(None);
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 0, 0, address_loader);
+
+        let mut state_machine = SchedulingStateMachine::with_lane_count(4);
+        assert_eq!(state_machine.lane_count(), 4);
+
+        let expected_lane1 = SchedulingStateMachine::affinity_lane(&task1, 4);
+        let (lane1, result) = state_machine.schedule_task_in_lane(task1.clone(), |task| task.clone());
+        assert_eq!(lane1, expected_lane1);
+        assert_matches!(result, Some(_));
+        assert_eq!(state_machine.lane_in_flight_count(lane1), 1);
+
+        let before = state_machine.lane_in_flight_count(SchedulingStateMachine::affinity_lane(&task2, 4));
+        let (lane2, result) = state_machine.schedule_task_in_lane(task2.clone(), |task| task.clone());
+        assert_matches!(result, Some(_));
+        assert_eq!(state_machine.lane_in_flight_count(lane2), before + 1);
+
+        state_machine.deschedule_task_from_lane(lane1, &task1);
+        assert_eq!(state_machine.lane_in_flight_count(lane1), before);
+
+        state_machine.deschedule_task_from_lane(lane2, &task2);
+        assert!(state_machine.is_empty());
+    }
+
     #[test]
     fn test_schedule_multiple_readonly_task() {
         let conflicting_address = Pubkey::new_unique();
         let sanitized1 = readonly_transaction(conflicting_address);
         let sanitized2 = readonly_transaction(conflicting_address);
         let address_loader = &mut create_address_loader(None);
-        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
@@ -845,9 +1746,9 @@ mod tests {
         let sanitized2 = readonly_transaction(conflicting_address);
         let sanitized3 = transaction_with_shared_writable(conflicting_address);
         let address_loader = &mut create_address_loader(None);
-        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, address_loader);
-        let task3 = SchedulingStateMachine::create_task(sanitized3, 5, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 0, 0, address_loader);
+        let task3 = SchedulingStateMachine::create_task(sanitized3, 5, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(
@@ -884,9 +1785,9 @@ mod tests {
         let sanitized2 = transaction_with_shared_writable(conflicting_address);
         let sanitized3 = readonly_transaction(conflicting_address);
         let address_loader = &mut create_address_loader(None);
-        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, address_loader);
-        let task3 = SchedulingStateMachine::create_task(sanitized3, 5, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 0, 0, address_loader);
+        let task3 = SchedulingStateMachine::create_task(sanitized3, 5, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(
@@ -919,8 +1820,8 @@ mod tests {
         let sanitized1 = readonly_transaction(conflicting_address);
         let sanitized2 = transaction_with_shared_writable(conflicting_address);
         let address_loader = &mut create_address_loader(None);
-        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
@@ -938,9 +1839,9 @@ mod tests {
         let sanitized2 = readonly_transaction(conflicting_address);
         let sanitized3 = readonly_transaction(conflicting_address);
         let address_loader = &mut create_address_loader(None);
-        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, address_loader);
-        let task3 = SchedulingStateMachine::create_task(sanitized3, 5, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 0, 0, address_loader);
+        let task3 = SchedulingStateMachine::create_task(sanitized3, 5, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
@@ -959,8 +1860,8 @@ mod tests {
         let sanitized2 = transaction_with_shared_writable(conflicting_address);
         let pages = Arc::new(Mutex::new(HashMap::new()));
         let address_loader = &mut create_address_loader(Some(pages.clone()));
-        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, address_loader);
-        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, address_loader);
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 0, 0, address_loader);
 
         let mut state_machine = SchedulingStateMachine::default();
         assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
@@ -968,14 +1869,16 @@ mod tests {
         let pages = pages.lock().unwrap();
         let page = pages.get(&conflicting_address).unwrap();
         assert_matches!(
-            page.0.borrow(&state_machine.page_token).usage,
+            page.0
+                .with_borrow(&state_machine.page_token, |page| page.usage),
             Usage::Writable
         );
         let page = pages
             .get(task2.transaction().message().fee_payer())
             .unwrap();
         assert_matches!(
-            page.0.borrow(&state_machine.page_token).usage,
+            page.0
+                .with_borrow(&state_machine.page_token, |page| page.usage),
             Usage::Unused
         );
     }
@@ -995,7 +1898,10 @@ mod tests {
     fn test_unreachable_unlock_conditions2() {
         let mut state_machine = SchedulingStateMachine::default();
         let page = Page::default();
-        page.0.borrow_mut(&mut state_machine.page_token).usage = Usage::Writable;
+        page.0
+            .with_borrow_mut(&mut state_machine.page_token, |page| {
+                page.usage = Usage::Writable
+            });
         SchedulingStateMachine::unlock(
             &mut state_machine.page_token,
             &LockAttempt::new(page, RequestedUsage::Readonly),
@@ -1007,10 +1913,228 @@ mod tests {
     fn test_unreachable_unlock_conditions3() {
         let mut state_machine = SchedulingStateMachine::default();
         let page = Page::default();
-        page.0.borrow_mut(&mut state_machine.page_token).usage = Usage::Readonly(Counter::one());
+        page.0
+            .with_borrow_mut(&mut state_machine.page_token, |page| {
+                page.usage = Usage::Readonly(Counter::one())
+            });
         SchedulingStateMachine::unlock(
             &mut state_machine.page_token,
             &LockAttempt::new(page, RequestedUsage::Writable),
         );
     }
+
+    #[test]
+    fn test_contention_report() {
+        let sanitized = simplest_transaction();
+        let address_loader = &mut create_address_loader(None);
+        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, 0, 0, address_loader);
+
+        let mut state_machine = SchedulingStateMachine::default();
+
+        // An empty report before any contention has occurred.
+        let report = state_machine.take_contention_report();
+        assert_eq!(report.blocked_queue_depths, Vec::<u32>::new());
+        assert_eq!(report.max_retryable_age, 0);
+        assert_eq!(report.wasted_readonly_wakeups, 0);
+
+        assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
+        // task2 conflicts with task1 and piles up behind it on the same page.
+        assert_matches!(state_machine.schedule_task_for_test(task2.clone()), None);
+
+        let report = state_machine.take_contention_report();
+        assert_eq!(report.blocked_queue_depths, vec![1]);
+
+        // Taking the report again without new contention resets it to empty.
+        assert_eq!(
+            state_machine.take_contention_report().blocked_queue_depths,
+            Vec::<u32>::new()
+        );
+
+        state_machine.deschedule_task(&task1);
+        // Tick the sequence clock with an unrelated, non-conflicting task so
+        // task2 has waited at least one tick in the retryable queue.
+        let task3 = SchedulingStateMachine::create_task(simplest_transaction(), 5, 0, 0, address_loader);
+        assert_matches!(state_machine.schedule_task_for_test(task3.clone()), Some(_));
+        assert_matches!(state_machine.schedule_retryable_task_for_test(), Some(_));
+
+        let report = state_machine.take_contention_report();
+        assert!(report.max_retryable_age > 0);
+
+        state_machine.deschedule_task(&task2);
+    }
+
+    #[test]
+    fn test_introspection() {
+        let conflicting_address = Pubkey::new_unique();
+        let sanitized1 = transaction_with_shared_writable(conflicting_address);
+        let sanitized2 = transaction_with_shared_writable(conflicting_address);
+        let pages = Arc::new(Mutex::new(HashMap::new()));
+        let address_loader = &mut create_address_loader(Some(pages.clone()));
+
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 0, 0, address_loader);
+
+        let mut state_machine = SchedulingStateMachine::default();
+        assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
+        assert_matches!(state_machine.schedule_task_for_test(task2.clone()), None);
+
+        // task2 is blocked on `conflicting_address`, which task1 holds
+        // Writable.
+        let report = state_machine.blocked_task_report(&task2);
+        let (_, conflicting_usage) = report
+            .iter()
+            .find(|(address, _)| *address == conflicting_address)
+            .unwrap();
+        assert_eq!(*conflicting_usage, PageUsageReport::Writable);
+
+        let conflicting_page = pages
+            .lock()
+            .unwrap()
+            .get(&conflicting_address)
+            .unwrap()
+            .clone();
+        assert_eq!(
+            state_machine.page_usage_report(&conflicting_page),
+            PageUsageReport::Writable
+        );
+        let waiters = state_machine.blocked_waiters_on(&conflicting_page);
+        assert_eq!(waiters.len(), 1);
+        assert_eq!(waiters[0].task_index(), task2.task_index());
+
+        // Freeing task1 promotes task2 into the retryable queue rather than
+        // re-locking it immediately.
+        state_machine.deschedule_task(&task1);
+        assert!(state_machine.has_retryable_task());
+
+        // Schedule an unrelated, non-conflicting task to tick the sequence
+        // clock forward past task2's enqueue stamp.
+        let task3 = SchedulingStateMachine::create_task(
+            simplest_transaction(),
+            5,
+            0,
+            0,
+            address_loader,
+        );
+        assert_matches!(state_machine.schedule_task_for_test(task3.clone()), Some(_));
+
+        let (longest_waiting, age) = state_machine.longest_waiting_retryable_task().unwrap();
+        assert_eq!(longest_waiting.task_index(), task2.task_index());
+        assert!(age > 0);
+
+        state_machine.deschedule_task(&task3);
+
+        assert_matches!(state_machine.schedule_retryable_task_for_test(), Some(_));
+        assert!(state_machine.longest_waiting_retryable_task().is_none());
+
+        state_machine.deschedule_task(&task2);
+    }
+
+    #[test]
+    fn test_pause_stops_promotion_until_resume() {
+        let sanitized = simplest_transaction();
+        let address_loader = &mut create_address_loader(None);
+        let task1 = SchedulingStateMachine::create_task(sanitized.clone(), 3, 0, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized.clone(), 4, 0, 0, address_loader);
+
+        let mut state_machine = SchedulingStateMachine::default();
+        assert!(!state_machine.is_paused());
+        assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
+        assert_matches!(state_machine.schedule_task_for_test(task2.clone()), None);
+
+        state_machine.deschedule_task(&task1);
+        assert_eq!(state_machine.retryable_task_count(), 1);
+
+        state_machine.pause();
+        assert!(state_machine.is_paused());
+        // Already-blocked task2 stays put while paused, even though it's
+        // ready to retry; deschedule_task for in-flight work is unaffected.
+        assert_matches!(state_machine.schedule_retryable_task_for_test(), None);
+        assert_eq!(state_machine.retryable_task_count(), 1);
+
+        state_machine.resume();
+        assert!(!state_machine.is_paused());
+        assert_matches!(state_machine.schedule_retryable_task_for_test(), Some(_));
+
+        state_machine.deschedule_task(&task2);
+        assert!(state_machine.is_empty());
+    }
+
+    #[test]
+    fn test_drain_blocked_surrenders_lock_blocked_and_retryable_tasks() {
+        let conflicting_address = Pubkey::new_unique();
+        let sanitized1 = transaction_with_shared_writable(conflicting_address);
+        let sanitized2 = transaction_with_shared_writable(conflicting_address);
+        let sanitized3 = transaction_with_shared_writable(conflicting_address);
+        let pages = Arc::new(Mutex::new(HashMap::new()));
+        let address_loader = &mut create_address_loader(Some(pages.clone()));
+        let task1 = SchedulingStateMachine::create_task(sanitized1, 3, 10, 0, address_loader);
+        let task2 = SchedulingStateMachine::create_task(sanitized2, 4, 10, 0, address_loader);
+        let task3 = SchedulingStateMachine::create_task(sanitized3, 5, 10, 0, address_loader);
+
+        let mut state_machine = SchedulingStateMachine::default();
+        assert_matches!(state_machine.schedule_task_for_test(task1.clone()), Some(_));
+        // task2 and task3 both pile up behind task1 on `conflicting_address`.
+        assert_matches!(state_machine.schedule_task_for_test(task2.clone()), None);
+        assert_matches!(state_machine.schedule_task_for_test(task3.clone()), None);
+
+        state_machine.pause();
+        // Freeing task1 promotes task2 (the heaviest waiter) into the
+        // retryable queue, while task3 stays purely lock-blocked; pause
+        // prevents task2 from being promoted any further.
+        state_machine.deschedule_task(&task1);
+        assert_eq!(state_machine.retryable_task_count(), 1);
+
+        let drained = state_machine.drain_blocked();
+        assert_eq!(drained.len(), 2);
+        let drained_indexes: Vec<_> = drained.iter().map(|task| task.task_index()).collect();
+        assert!(drained_indexes.contains(&task2.task_index()));
+        assert!(drained_indexes.contains(&task3.task_index()));
+
+        // Nothing is left waiting behind on the page, nor in the retryable
+        // queue, and the drained tasks' cost was walked back out of
+        // in_flight_cost.
+        assert_eq!(state_machine.retryable_task_count(), 0);
+        assert_eq!(state_machine.in_flight_cost(), 0);
+        assert!(state_machine.is_empty());
+        let conflicting_page = pages.lock().unwrap().get(&conflicting_address).unwrap().clone();
+        assert_eq!(state_machine.blocked_waiters_on(&conflicting_page).len(), 0);
+    }
+
+    #[test]
+    fn test_sharded_scheduling() {
+        use super::sharded::ShardedSchedulingStateMachine;
+
+        let sharded = ShardedSchedulingStateMachine::with_shard_count(4);
+        assert_eq!(sharded.shard_count(), 4);
+
+        let conflicting_address = Pubkey::new_unique();
+        let sanitized1 = transaction_with_shared_writable(conflicting_address);
+        let sanitized2 = transaction_with_shared_writable(conflicting_address);
+        let task1 = sharded.create_task(std::slice::from_ref(&sanitized1), 3);
+        let task2 = sharded.create_task(std::slice::from_ref(&sanitized2), 4);
+
+        // Both member transactions share the writable `conflicting_address`,
+        // so only one of the two tasks can fully lock at a time.
+        assert!(sharded.schedule_task(&task1));
+        assert!(!sharded.schedule_task(&task2));
+
+        sharded.deschedule_task(&task1);
+        assert!(sharded.schedule_task(&task2));
+        sharded.deschedule_task(&task2);
+    }
+
+    #[test]
+    fn test_sharded_bundle_scheduling_dedups_across_shards() {
+        use super::sharded::ShardedSchedulingStateMachine;
+
+        let shared_address = Pubkey::new_unique();
+        let readonly_member = readonly_transaction(shared_address);
+        let writable_member = transaction_with_shared_writable(shared_address);
+
+        let sharded = ShardedSchedulingStateMachine::with_shard_count(4);
+        let task = sharded.create_task(&[readonly_member, writable_member], 0);
+        assert!(sharded.schedule_task(&task));
+        sharded.deschedule_task(&task);
+    }
 }
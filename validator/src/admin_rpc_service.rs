@@ -2,6 +2,7 @@ use jsonrpc_core::ErrorCode;
 use libloading::{Library, Symbol};
 use solana_geyser_plugin_interface::geyser_plugin_interface::GeyserPlugin;
 use solana_geyser_plugin_manager::geyser_plugin_manager::GeyserPluginManager;
+use tiny_http::{Header, Method};
 
 use {
     jsonrpc_core::{MetaIoHandler, Metadata, Result},
@@ -17,18 +18,24 @@ use {
     solana_gossip::{cluster_info::ClusterInfo, contact_info::ContactInfo},
     solana_rpc::rpc::verify_pubkey,
     solana_rpc_client_api::{config::RpcAccountIndex, custom_error::RpcCustomError},
-    solana_runtime::{accounts_index::AccountIndex, bank_forks::BankForks},
+    solana_runtime::{
+        accounts_index::AccountIndex, bank_forks::BankForks,
+        secondary_index::MAX_NUM_LARGEST_INDEX_KEYS_RETURNED,
+    },
     solana_sdk::{
         exit::Exit,
+        hash::{hash, Hash},
         pubkey::Pubkey,
         signature::{read_keypair_file, Keypair, Signer},
     },
     std::{
         collections::{HashMap, HashSet},
         error,
-        fmt::{self, Display},
+        fmt::{self, Display, Write},
+        io::Read,
         net::{IpAddr, Ipv4Addr, SocketAddr},
         path::{Path, PathBuf},
+        str::FromStr,
         sync::{Arc, RwLock},
         thread::{self, Builder},
         time::{Duration, SystemTime},
@@ -37,6 +44,111 @@ use {
 
 type PluginConstructor = unsafe fn() -> *mut dyn GeyserPlugin;
 
+/// Coarse-grained capability granted to a verified admin auth token.
+/// `Mutate` methods (`setIdentity`, `reloadPlugin`, `exit`, ...) require a
+/// `Mutate` token; `ReadOnly` methods (`contactInfo`, `startProgress`,
+/// `getLargestIndexKeys`, ...) accept either tier.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AdminRpcCapability {
+    ReadOnly,
+    Mutate,
+}
+
+impl Default for AdminRpcCapability {
+    // No auth layer (e.g. the IPC socket, which relies on filesystem
+    // permissions rather than a bearer token) grants full access.
+    fn default() -> Self {
+        AdminRpcCapability::Mutate
+    }
+}
+
+/// One configured admin token, stored as a hash rather than the raw secret
+/// so it's never held in memory at rest.
+struct AdminRpcAuthToken {
+    hash: Hash,
+    capability: AdminRpcCapability,
+}
+
+/// Modeled on Garage's admin API bearer-token scheme: the validator is
+/// configured with one or more tokens, each granting either `ReadOnly` or
+/// `Mutate` capability. `verify` hashes a presented token and compares it,
+/// in constant time, against the configured hashes.
+#[derive(Clone, Default)]
+pub struct AdminRpcAuthConfig {
+    tokens: Arc<Vec<AdminRpcAuthToken>>,
+}
+
+impl AdminRpcAuthConfig {
+    /// Builds a config from `(raw token, capability)` pairs; only the
+    /// tokens' hashes are retained.
+    pub fn new(tokens: Vec<(String, AdminRpcCapability)>) -> Self {
+        Self {
+            tokens: Arc::new(
+                tokens
+                    .into_iter()
+                    .map(|(token, capability)| AdminRpcAuthToken {
+                        hash: hash(token.as_bytes()),
+                        capability,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn verify(&self, presented_token: &str) -> Option<AdminRpcCapability> {
+        let presented_hash = hash(presented_token.as_bytes());
+        self.tokens
+            .iter()
+            .find(|token| constant_time_eq(token.hash.as_ref(), presented_hash.as_ref()))
+            .map(|token| token.capability)
+    }
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Registry of program IDs recognized as implementing the "token
+/// interface" (mint at bytes 0..32, owner at 32..64) that the secondary
+/// index parses an account's owner/mint out of. Defaults to the legacy SPL
+/// Token and Token-2022 program IDs; a startup option can extend this to
+/// other programs implementing the same layout, so operators running
+/// specialized clusters can index them without a fork.
+///
+/// NOTE: this only threads the configured set through to
+/// `AdminRpcImpl::get_token_interface_programs` for observability. Wiring
+/// it into the secondary-index parsing path itself is a change to
+/// `accounts_index`/`secondary_index`, neither of which is present in this
+/// tree.
+#[derive(Clone)]
+pub struct TokenInterfacePrograms {
+    programs: Arc<Vec<Pubkey>>,
+}
+
+impl TokenInterfacePrograms {
+    pub fn new(programs: Vec<Pubkey>) -> Self {
+        Self {
+            programs: Arc::new(programs),
+        }
+    }
+
+    fn programs(&self) -> &[Pubkey] {
+        &self.programs
+    }
+}
+
+impl Default for TokenInterfacePrograms {
+    fn default() -> Self {
+        Self::new(vec![
+            solana_runtime::inline_spl_token::id(),
+            solana_runtime::inline_spl_token_2022::id(),
+        ])
+    }
+}
+
 #[derive(Clone)]
 pub struct AdminRpcRequestMetadataPostInit {
     pub cluster_info: Arc<ClusterInfo>,
@@ -54,8 +166,31 @@ pub struct AdminRpcRequestMetadata {
     pub authorized_voter_keypairs: Arc<RwLock<Vec<Arc<Keypair>>>>,
     pub tower_storage: Arc<dyn TowerStorage>,
     pub staked_nodes_overrides: Arc<RwLock<HashMap<Pubkey, u64>>>,
+    /// Path most recently passed to `setStakedNodesOverrides` or
+    /// `reloadStakedNodesOverrides`, so the latter can be called with no
+    /// argument to reload the same file.
+    pub staked_nodes_overrides_path: Arc<RwLock<Option<String>>>,
     pub post_init: Arc<RwLock<Option<AdminRpcRequestMetadataPostInit>>>,
     pub plugin_manager: Arc<RwLock<GeyserPluginManager>>,
+    /// Config file path each loaded plugin was most recently (re)loaded
+    /// with, kept in lockstep with `plugin_manager`'s plugin/lib/libpath
+    /// vectors (same index, same push/remove points), since
+    /// `GeyserPluginManager` itself doesn't track this.
+    pub plugin_config_paths: Arc<RwLock<Vec<String>>>,
+    /// Program IDs configured as implementing the token interface. Only
+    /// reported through `getTokenInterfacePrograms`; the secondary index
+    /// does not read this set.
+    pub token_interface_programs: TokenInterfacePrograms,
+    /// The tokens an auth layer (e.g. `run_http`) verifies a presented
+    /// token against.
+    pub auth_config: AdminRpcAuthConfig,
+    /// The capability this request was verified against. An auth layer
+    /// checks a presented token and sets this on a per-request clone of the
+    /// metadata before dispatching; handlers enforce it via
+    /// `require_capability`. Defaults to full access, since the base
+    /// metadata (used as-is by the IPC socket) has no auth layer in front
+    /// of it.
+    pub capability: AdminRpcCapability,
 }
 impl Metadata for AdminRpcRequestMetadata {}
 
@@ -72,6 +207,25 @@ impl AdminRpcRequestMetadata {
             ))
         }
     }
+
+    /// Rejects the request unless it was verified with at least `required`
+    /// capability. `ReadOnly` handlers accept either tier; `Mutate` handlers
+    /// require a `Mutate` token.
+    fn require_capability(&self, required: AdminRpcCapability) -> Result<()> {
+        let sufficient = match required {
+            AdminRpcCapability::ReadOnly => true,
+            AdminRpcCapability::Mutate => self.capability == AdminRpcCapability::Mutate,
+        };
+        if sufficient {
+            Ok(())
+        } else {
+            Err(jsonrpc_core::error::Error {
+                code: ErrorCode::InvalidRequest,
+                message: String::from("admin token lacks the capability required for this method"),
+                data: None,
+            })
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -96,6 +250,21 @@ pub struct AdminRpcRepairWhitelist {
     pub whitelist: Vec<Pubkey>,
 }
 
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AdminRpcPluginInfo {
+    pub name: String,
+    pub libpath: String,
+    pub config_path: String,
+}
+
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq)]
+pub struct AdminRpcLargestIndexKeysPage {
+    pub keys: Vec<(String, usize)>,
+    /// The `(key_size, key)` to pass as `cursor` to fetch the next page, or
+    /// `None` if this page reached the end of the available keys.
+    pub next_cursor: Option<(usize, String)>,
+}
+
 impl From<ContactInfo> for AdminRpcContactInfo {
     fn from(node: ContactInfo) -> Self {
         macro_rules! unwrap_socket {
@@ -154,6 +323,10 @@ pub trait AdminRpc {
     #[rpc(meta, name = "exit")]
     fn exit(&self, meta: Self::Metadata) -> Result<()>;
 
+    /// Unloads the plugin already loaded from `libpath`, then loads it again
+    /// with `config_file`, so operators can push updated Geyser sink
+    /// configuration (new endpoints, filters) without restarting the
+    /// validator and dropping their account/transaction stream.
     #[rpc(meta, name = "reloadPlugin")]
     fn reload_plugin(
         &self,
@@ -162,6 +335,22 @@ pub trait AdminRpc {
         config_file: String,
     ) -> Result<()>;
 
+    /// Returns the name, libpath, and config path of every loaded Geyser
+    /// plugin, so operators can inspect the running plugin set.
+    #[rpc(meta, name = "listPlugins")]
+    fn list_plugins(&self, meta: Self::Metadata) -> Result<Vec<AdminRpcPluginInfo>>;
+
+    #[rpc(meta, name = "loadPlugin")]
+    fn load_plugin(
+        &self,
+        meta: Self::Metadata,
+        libpath: String,
+        config_file: String,
+    ) -> Result<String>;
+
+    #[rpc(meta, name = "unloadPlugin")]
+    fn unload_plugin(&self, meta: Self::Metadata, name: String) -> Result<()>;
+
     #[rpc(meta, name = "rpcAddress")]
     fn rpc_addr(&self, meta: Self::Metadata) -> Result<Option<SocketAddr>>;
 
@@ -203,6 +392,17 @@ pub trait AdminRpc {
     #[rpc(meta, name = "setStakedNodesOverrides")]
     fn set_staked_nodes_overrides(&self, meta: Self::Metadata, path: String) -> Result<()>;
 
+    /// Re-reads the staked nodes overrides file and atomically swaps it into
+    /// `meta.staked_nodes_overrides`, so per-peer stake weighting can be
+    /// retuned without a validator restart. `path` defaults to whichever
+    /// path was last loaded via `setStakedNodesOverrides` or this method.
+    #[rpc(meta, name = "reloadStakedNodesOverrides")]
+    fn reload_staked_nodes_overrides(
+        &self,
+        meta: Self::Metadata,
+        path: Option<String>,
+    ) -> Result<()>;
+
     #[rpc(meta, name = "contactInfo")]
     fn contact_info(&self, meta: Self::Metadata) -> Result<AdminRpcContactInfo>;
 
@@ -219,13 +419,52 @@ pub trait AdminRpc {
         pubkey_str: String,
     ) -> Result<HashMap<RpcAccountIndex, usize>>;
 
+    /// Returns up to `limit` of the largest keys in `secondary_index`, sorted
+    /// descending by key size (ties broken by descending key). When `cursor`
+    /// is given, only keys strictly after it in that order are returned, so
+    /// callers can walk the full key distribution in stable pages by
+    /// re-issuing this call with the previous page's `next_cursor`.
+    ///
+    /// Pagination is served out of the same bounded top-N slice
+    /// `getLargestIndexKeys` has always fetched (capped at
+    /// `MAX_NUM_LARGEST_INDEX_KEYS_RETURNED`); `next_cursor` comes back
+    /// `None` once that slice is exhausted; there is no cursor over the
+    /// index's full key set in this tree.
     #[rpc(meta, name = "getLargestIndexKeys")]
     fn get_largest_index_keys(
         &self,
         meta: Self::Metadata,
         secondary_index: RpcAccountIndex,
-        max_entries: usize,
-    ) -> Result<Vec<(String, usize)>>;
+        limit: usize,
+        cursor: Option<(usize, String)>,
+    ) -> Result<AdminRpcLargestIndexKeysPage>;
+
+    /// Renders admin-observable validator state in Prometheus text
+    /// exposition format, so a scraper can pull it without a sidecar.
+    #[rpc(meta, name = "metrics")]
+    fn metrics(&self, meta: Self::Metadata) -> Result<String>;
+
+    /// Returns the configured token-interface program IDs. The secondary
+    /// index does not consult this set; see `TokenInterfacePrograms`.
+    #[rpc(meta, name = "getTokenInterfacePrograms")]
+    fn get_token_interface_programs(&self, meta: Self::Metadata) -> Result<Vec<String>>;
+
+    /// Buckets `secondary_index`'s key sizes into power-of-two ranges (1,
+    /// 2-3, 4-7, ...) and returns each bucket's `(min, max, count)`, so
+    /// operators can spot a few massively-fanned-out keys without pulling
+    /// the full largest-keys list.
+    ///
+    /// This only sees the same bounded top-N slice `getLargestIndexKeys`
+    /// fetches (capped at `MAX_NUM_LARGEST_INDEX_KEYS_RETURNED`): the index
+    /// doesn't expose a way to enumerate every key in this tree, so the
+    /// histogram undercounts buckets that are entirely outside that top-N
+    /// slice.
+    #[rpc(meta, name = "getIndexKeySizeHistogram")]
+    fn get_index_key_size_histogram(
+        &self,
+        meta: Self::Metadata,
+        secondary_index: RpcAccountIndex,
+    ) -> Result<Vec<(usize, usize, usize)>>;
 }
 
 pub struct AdminRpcImpl;
@@ -233,6 +472,7 @@ impl AdminRpc for AdminRpcImpl {
     type Metadata = AdminRpcRequestMetadata;
 
     fn exit(&self, meta: Self::Metadata) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
         debug!("exit admin rpc request received");
 
         thread::Builder::new()
@@ -263,6 +503,8 @@ impl AdminRpc for AdminRpcImpl {
         libpath: String,
         config_file: String,
     ) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
+
         // If the validator is requesting reload_plugin, they will likely want
         // it to reload and begin processing notifies as soon as possible. ASAP here
         // does not refer to realtime but rather slot/cluster time (i.e the next slot).
@@ -280,117 +522,97 @@ impl AdminRpc for AdminRpcImpl {
             })
         };
 
-        // Get current plugin and library
-        let (current_plugin, current_lib) = plugin_manager
-            .get_plugin_and_lib_mut(idx)
-            .expect("just checked for existence of libpath");
-
-        // Unload first in case plugin requires exclusive access to resource,
-        // such as a particular port or database.
-        current_plugin.on_unload();
+        // Unload first in case the plugin requires exclusive access to a
+        // resource, such as a particular port or database, then load the
+        // (possibly new) library at the same path. Unlike the old
+        // load-then-revert-on-failure dance, a plugin that fails to load
+        // back in here is simply left unloaded rather than silently
+        // dropped: `loadPlugin` can always re-add it without a validator
+        // restart.
+        let mut config_paths = meta.plugin_config_paths.write().unwrap();
+        AdminRpcImpl::unload_plugin_at(&mut plugin_manager, &mut config_paths, idx);
+        AdminRpcImpl::load_plugin_into(&mut plugin_manager, &mut config_paths, libpath, &config_file)?;
 
-        // Try to load plugin, library
-        // SAFETY: It is up the validator to ensure this is a valid plugin library.
-        let (mut new_plugin, new_lib): (Box<dyn GeyserPlugin>, Library) = {
-            #[cfg(not(test))]
-            unsafe {
-                // Attempt to load Library
-                let lib = Library::new(libpath).map_err(|e| {
-                    jsonrpc_core::error::Error::invalid_params(format!(
-                        "invalid geyser plugin, failed to load: {e}"
-                    ))
-                })?;
+        Ok(())
+    }
 
-                // Attempt to retrieve GeyserPlugin constructor
-                let constructor: Symbol<PluginConstructor> =
-                    lib.get(b"_create_plugin").map_err(|e| {
-                        jsonrpc_core::error::Error::invalid_params(format!(
-                            "invalid geyser plugin, failed to construct plugin: {e}"
-                        ))
-                    })?;
+    fn list_plugins(&self, meta: Self::Metadata) -> Result<Vec<AdminRpcPluginInfo>> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
+        let plugin_manager = meta.plugin_manager.read().unwrap();
+        let config_paths = meta.plugin_config_paths.read().unwrap();
+        Ok(plugin_manager
+            .plugins
+            .iter()
+            .zip(plugin_manager.libpaths.iter())
+            .zip(config_paths.iter())
+            .map(|((plugin, libpath), config_path)| AdminRpcPluginInfo {
+                name: plugin.name().to_string(),
+                libpath: libpath.to_string_lossy().into_owned(),
+                config_path: config_path.clone(),
+            })
+            .collect())
+    }
 
-                // Attempt to construct raw *mut dyn GeyserPlugin
-                let plugin_raw = constructor();
+    fn load_plugin(
+        &self,
+        meta: Self::Metadata,
+        libpath: String,
+        config_file: String,
+    ) -> Result<String> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
+        let mut plugin_manager = meta.plugin_manager.write().unwrap();
+        let mut config_paths = meta.plugin_config_paths.write().unwrap();
+        AdminRpcImpl::load_plugin_into(&mut plugin_manager, &mut config_paths, libpath, &config_file)
+    }
 
-                (Box::from_raw(plugin_raw), lib)
-            }
+    fn unload_plugin(&self, meta: Self::Metadata, name: String) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
+        let mut plugin_manager = meta.plugin_manager.write().unwrap();
 
-            // This is mocked for tests to avoid having to do IO with a dynamically linked library
-            // across different architectures.
-            #[cfg(test)]
-            {
-                tests::dummy_plugin_and_library()
-            }
+        let Some(idx) = plugin_manager.plugins.iter().position(|plugin| plugin.name() == name) else {
+            drop(plugin_manager);
+            return Err(jsonrpc_core::error::Error {
+                code: ErrorCode::InvalidRequest,
+                message: String::from("plugin requested to unload is not loaded"),
+                data: None,
+            })
         };
 
-        // Try unload, on_load
-        // Attempt to load new plugin
-        match new_plugin.on_load(&config_file) {
-            // On success, replace plugin and library
-            // Note: don't need to replace libpath since it matches
-            Ok(()) => {
-                *current_plugin = new_plugin;
-                *current_lib = new_lib;
-            }
-
-            // On failure, attempt to revert and return error
-            // Note that here we are using the same config file as for the new file
-            Err(e) => {
-                return match current_plugin.on_load(&config_file) {
-                    // Failed to load plugin but successfully reverted
-                    Ok(()) => Err(jsonrpc_core::error::Error::invalid_params(format!(
-                        "failed to start new plugin, reverted to current plugin: {e}"
-                    ))),
-
-                    // Failed to load plugin and failed to revert.
-                    //
-                    // Note that many plugin impls don't do anything for on_load or on_unload
-                    // so this should not happen very often
-                    Err(revert_err) => {
-                        // If we failed to revert, unload plugin
-                        // First drop mutable references
-                        drop(current_plugin);
-                        drop(current_lib);
-                        // Then drop plugin, lib, and path
-                        drop(plugin_manager.plugins.remove(idx));
-                        drop(plugin_manager.libs.remove(idx));
-                        drop(plugin_manager.libpaths.remove(idx));
-
-                        Err(jsonrpc_core::error::Error::invalid_params(format!(
-                            "failed to start new plugin, and failed to revert to old plugin. \
-                            The old plugin was dropped. Try to load a plugin with load_plugin. \
-                            new plugin startup error: {e}. old plugin re-startup error: {revert_err}"
-                        )))
-                    }
-                };
-            }
-        }
-
+        let mut config_paths = meta.plugin_config_paths.write().unwrap();
+        AdminRpcImpl::unload_plugin_at(&mut plugin_manager, &mut config_paths, idx);
         Ok(())
     }
 
     fn rpc_addr(&self, meta: Self::Metadata) -> Result<Option<SocketAddr>> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
         debug!("rpc_addr admin rpc request received");
         Ok(meta.rpc_addr)
     }
 
     fn set_log_filter(&self, filter: String) -> Result<()> {
+        // No `Self::Metadata` is passed to this method, so there's no
+        // verified capability to check here; gate it at the transport layer
+        // instead (e.g. don't expose `setLogFilter` over an auth-enforcing
+        // gateway).
         debug!("set_log_filter admin rpc request received");
         solana_logger::setup_with(&filter);
         Ok(())
     }
 
     fn start_time(&self, meta: Self::Metadata) -> Result<SystemTime> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
         debug!("start_time admin rpc request received");
         Ok(meta.start_time)
     }
 
     fn start_progress(&self, meta: Self::Metadata) -> Result<ValidatorStartProgress> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
         debug!("start_progress admin rpc request received");
         Ok(*meta.start_progress.read().unwrap())
     }
 
     fn add_authorized_voter(&self, meta: Self::Metadata, keypair_file: String) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
         debug!("add_authorized_voter request received");
 
         let authorized_voter = read_keypair_file(keypair_file)
@@ -404,6 +626,7 @@ impl AdminRpc for AdminRpcImpl {
         meta: Self::Metadata,
         keypair: Vec<u8>,
     ) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
         debug!("add_authorized_voter_from_bytes request received");
 
         let authorized_voter = Keypair::from_bytes(&keypair).map_err(|err| {
@@ -416,6 +639,7 @@ impl AdminRpc for AdminRpcImpl {
     }
 
     fn remove_all_authorized_voters(&self, meta: Self::Metadata) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
         debug!("remove_all_authorized_voters received");
         meta.authorized_voter_keypairs.write().unwrap().clear();
         Ok(())
@@ -427,6 +651,7 @@ impl AdminRpc for AdminRpcImpl {
         keypair_file: String,
         require_tower: bool,
     ) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
         debug!("set_identity request received");
 
         let identity_keypair = read_keypair_file(&keypair_file).map_err(|err| {
@@ -444,6 +669,7 @@ impl AdminRpc for AdminRpcImpl {
         identity_keypair: Vec<u8>,
         require_tower: bool,
     ) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
         debug!("set_identity_from_bytes request received");
 
         let identity_keypair = Keypair::from_bytes(&identity_keypair).map_err(|err| {
@@ -456,28 +682,39 @@ impl AdminRpc for AdminRpcImpl {
     }
 
     fn set_staked_nodes_overrides(&self, meta: Self::Metadata, path: String) -> Result<()> {
-        let loaded_config = load_staked_nodes_overrides(&path)
-            .map_err(|err| {
-                error!(
-                    "Failed to load staked nodes overrides from {}: {}",
-                    &path, err
-                );
-                jsonrpc_core::error::Error::internal_error()
-            })?
-            .staked_map_id;
-        let mut write_staked_nodes = meta.staked_nodes_overrides.write().unwrap();
-        write_staked_nodes.clear();
-        write_staked_nodes.extend(loaded_config.into_iter());
-        info!("Staked nodes overrides loaded from {}", path);
-        debug!("overrides map: {:?}", write_staked_nodes);
+        meta.require_capability(AdminRpcCapability::Mutate)?;
+        apply_staked_nodes_overrides(&meta, &path)?;
+        *meta.staked_nodes_overrides_path.write().unwrap() = Some(path);
+        Ok(())
+    }
+
+    fn reload_staked_nodes_overrides(
+        &self,
+        meta: Self::Metadata,
+        path: Option<String>,
+    ) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
+        let path = match path.or_else(|| meta.staked_nodes_overrides_path.read().unwrap().clone())
+        {
+            Some(path) => path,
+            None => {
+                return Err(jsonrpc_core::error::Error::invalid_params(
+                    "no staked nodes overrides path given, and none was previously loaded",
+                ))
+            }
+        };
+        apply_staked_nodes_overrides(&meta, &path)?;
+        *meta.staked_nodes_overrides_path.write().unwrap() = Some(path);
         Ok(())
     }
 
     fn contact_info(&self, meta: Self::Metadata) -> Result<AdminRpcContactInfo> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
         meta.with_post_init(|post_init| Ok(post_init.cluster_info.my_contact_info().into()))
     }
 
     fn repair_whitelist(&self, meta: Self::Metadata) -> Result<AdminRpcRepairWhitelist> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
         debug!("repair_whitelist request received");
 
         meta.with_post_init(|post_init| {
@@ -493,6 +730,7 @@ impl AdminRpc for AdminRpcImpl {
     }
 
     fn set_repair_whitelist(&self, meta: Self::Metadata, whitelist: Vec<Pubkey>) -> Result<()> {
+        meta.require_capability(AdminRpcCapability::Mutate)?;
         debug!("set_repair_whitelist request received");
 
         let whitelist: HashSet<Pubkey> = whitelist.into_iter().collect();
@@ -511,6 +749,7 @@ impl AdminRpc for AdminRpcImpl {
         meta: Self::Metadata,
         pubkey_str: String,
     ) -> Result<HashMap<RpcAccountIndex, usize>> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
         debug!(
             "get_secondary_index_key_size rpc request received: {:?}",
             pubkey_str
@@ -562,27 +801,167 @@ impl AdminRpc for AdminRpcImpl {
         &self,
         meta: Self::Metadata,
         secondary_index: RpcAccountIndex,
-        max_entries: usize,
-    ) -> Result<Vec<(String, usize)>> {
+        limit: usize,
+        cursor: Option<(usize, String)>,
+    ) -> Result<AdminRpcLargestIndexKeysPage> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
         debug!(
-            "get_largest_index_keys rpc request received: {:?}",
-            max_entries
+            "get_largest_index_keys rpc request received: limit={:?} cursor={:?}",
+            limit, cursor
         );
+        let limit = limit.min(MAX_NUM_LARGEST_INDEX_KEYS_RETURNED);
+        let cursor = cursor
+            .map(|(size, key)| {
+                Pubkey::from_str(&key)
+                    .map(|key| (size, key))
+                    .map_err(|err| {
+                        jsonrpc_core::error::Error::invalid_params(format!(
+                            "invalid cursor key: {err}"
+                        ))
+                    })
+            })
+            .transpose()?;
         let secondary_index = account_index_from_rpc_account_index(&secondary_index);
         meta.with_post_init(|post_init| {
             let bank = post_init.bank_forks.read().unwrap().root_bank();
             let enabled_account_indexes = &bank.accounts().accounts_db.account_indexes;
             if enabled_account_indexes.is_empty() {
                 debug!("get_secondary_index_key_size: secondary index not enabled.");
-                return Ok(Vec::new());
+                return Ok(AdminRpcLargestIndexKeysPage {
+                    keys: Vec::new(),
+                    next_cursor: None,
+                });
             };
             let accounts_index = &bank.accounts().accounts_db.accounts_index;
-            let largest_keys = accounts_index
-                .get_largest_keys(&secondary_index, max_entries)
+            // Pagination is served out of the same bounded top-N slice the
+            // index already exposes; there's no way to resume a scan past
+            // `MAX_NUM_LARGEST_INDEX_KEYS_RETURNED` in this tree.
+            let mut page = accounts_index
+                .get_largest_keys(&secondary_index, MAX_NUM_LARGEST_INDEX_KEYS_RETURNED)
                 .iter()
-                .map(|&(x, y)| (y.to_string(), x))
+                .map(|&(size, key)| (size, key))
+                .filter(|&(size, key)| match cursor {
+                    Some((cursor_size, cursor_key)) => {
+                        size < cursor_size || (size == cursor_size && key < cursor_key)
+                    }
+                    None => true,
+                })
                 .collect::<Vec<_>>();
-            Ok(largest_keys)
+            let has_more = page.len() > limit;
+            page.truncate(limit);
+            let next_cursor = has_more
+                .then(|| page.last().map(|&(size, key)| (size, key.to_string())))
+                .flatten();
+            let keys = page
+                .into_iter()
+                .map(|(size, key)| (key.to_string(), size))
+                .collect();
+            Ok(AdminRpcLargestIndexKeysPage { keys, next_cursor })
+        })
+    }
+
+    fn metrics(&self, meta: Self::Metadata) -> Result<String> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
+        debug!("metrics admin rpc request received");
+
+        let mut out = String::new();
+
+        let uptime_seconds = meta.start_time.elapsed().unwrap_or_default().as_secs_f64();
+        writeln!(out, "# HELP solana_validator_uptime_seconds Seconds since the validator started.").unwrap();
+        writeln!(out, "# TYPE solana_validator_uptime_seconds gauge").unwrap();
+        writeln!(out, "solana_validator_uptime_seconds {uptime_seconds}").unwrap();
+
+        let start_progress = *meta.start_progress.read().unwrap();
+        writeln!(out, "# HELP solana_validator_start_progress 1 for the validator's current startup stage, 0 for all others.").unwrap();
+        writeln!(out, "# TYPE solana_validator_start_progress gauge").unwrap();
+        writeln!(out, r#"solana_validator_start_progress{{stage="{start_progress:?}"}} 1"#).unwrap();
+
+        let authorized_voter_count = meta.authorized_voter_keypairs.read().unwrap().len();
+        writeln!(out, "# HELP solana_validator_authorized_voter_count Number of authorized voter keypairs loaded.").unwrap();
+        writeln!(out, "# TYPE solana_validator_authorized_voter_count gauge").unwrap();
+        writeln!(out, "solana_validator_authorized_voter_count {authorized_voter_count}").unwrap();
+
+        let geyser_plugin_count = meta.plugin_manager.read().unwrap().plugins.len();
+        writeln!(out, "# HELP solana_validator_geyser_plugin_count Number of loaded Geyser plugins.").unwrap();
+        writeln!(out, "# TYPE solana_validator_geyser_plugin_count gauge").unwrap();
+        writeln!(out, "solana_validator_geyser_plugin_count {geyser_plugin_count}").unwrap();
+
+        if let Some(post_init) = meta.post_init.read().unwrap().as_ref() {
+            let repair_whitelist_size = post_init.repair_whitelist.read().unwrap().len();
+            writeln!(out, "# HELP solana_validator_repair_whitelist_size Number of pubkeys in the repair whitelist.").unwrap();
+            writeln!(out, "# TYPE solana_validator_repair_whitelist_size gauge").unwrap();
+            writeln!(out, "solana_validator_repair_whitelist_size {repair_whitelist_size}").unwrap();
+        }
+
+        writeln!(out, "# HELP solana_validator_largest_secondary_index_key_size Size of the largest key currently tracked by a secondary index.").unwrap();
+        writeln!(out, "# TYPE solana_validator_largest_secondary_index_key_size gauge").unwrap();
+        for secondary_index in [
+            RpcAccountIndex::ProgramId,
+            RpcAccountIndex::SplTokenOwner,
+            RpcAccountIndex::SplTokenMint,
+        ] {
+            if let Some((key, size)) = self
+                .get_largest_index_keys(meta.clone(), secondary_index, 1, None)?
+                .keys
+                .into_iter()
+                .next()
+            {
+                writeln!(
+                    out,
+                    r#"solana_validator_largest_secondary_index_key_size{{index="{secondary_index:?}",key="{key}"}} {size}"#
+                )
+                .unwrap();
+            }
+        }
+
+        Ok(out)
+    }
+
+    fn get_token_interface_programs(&self, meta: Self::Metadata) -> Result<Vec<String>> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
+        Ok(meta
+            .token_interface_programs
+            .programs()
+            .iter()
+            .map(ToString::to_string)
+            .collect())
+    }
+
+    fn get_index_key_size_histogram(
+        &self,
+        meta: Self::Metadata,
+        secondary_index: RpcAccountIndex,
+    ) -> Result<Vec<(usize, usize, usize)>> {
+        meta.require_capability(AdminRpcCapability::ReadOnly)?;
+        debug!("get_index_key_size_histogram rpc request received");
+        let secondary_index = account_index_from_rpc_account_index(&secondary_index);
+        meta.with_post_init(|post_init| {
+            let bank = post_init.bank_forks.read().unwrap().root_bank();
+            let enabled_account_indexes = &bank.accounts().accounts_db.account_indexes;
+            if enabled_account_indexes.is_empty() {
+                debug!("get_index_key_size_histogram: secondary index not enabled.");
+                return Ok(Vec::new());
+            };
+            let accounts_index = &bank.accounts().accounts_db.accounts_index;
+            // Same capped top-N slice `getLargestIndexKeys` draws from;
+            // there's no full-index enumeration API in this tree to
+            // aggregate over every key instead.
+            let mut buckets: HashMap<(usize, usize), usize> = HashMap::new();
+            for &(size, _key) in accounts_index
+                .get_largest_keys(&secondary_index, MAX_NUM_LARGEST_INDEX_KEYS_RETURNED)
+                .iter()
+            {
+                let exp = usize::BITS - 1 - size.max(1).leading_zeros() as u32;
+                let bucket_min = 1usize << exp;
+                let bucket_max = (1usize << (exp + 1)) - 1;
+                *buckets.entry((bucket_min, bucket_max)).or_insert(0) += 1;
+            }
+            let mut histogram: Vec<(usize, usize, usize)> = buckets
+                .into_iter()
+                .map(|((bucket_min, bucket_max), count)| (bucket_min, bucket_max, count))
+                .collect();
+            histogram.sort_unstable_by_key(|&(bucket_min, _, _)| bucket_min);
+            Ok(histogram)
         })
     }
 }
@@ -632,6 +1011,86 @@ impl AdminRpcImpl {
             Ok(())
         })
     }
+
+    /// Calls `on_unload` on the plugin at `idx` and drops its plugin, lib,
+    /// libpath, and config path entries from `plugin_manager`/
+    /// `config_paths`. Shared by `reloadPlugin` and `unloadPlugin` so both
+    /// agree on unload order and cleanup.
+    fn unload_plugin_at(
+        plugin_manager: &mut GeyserPluginManager,
+        config_paths: &mut Vec<String>,
+        idx: usize,
+    ) {
+        let (plugin, lib) = plugin_manager
+            .get_plugin_and_lib_mut(idx)
+            .expect("caller already checked for existence at idx");
+        plugin.on_unload();
+        drop(plugin);
+        drop(lib);
+
+        drop(plugin_manager.plugins.remove(idx));
+        drop(plugin_manager.libs.remove(idx));
+        drop(plugin_manager.libpaths.remove(idx));
+        drop(config_paths.remove(idx));
+    }
+
+    /// dlopens `libpath`, constructs its `GeyserPlugin` via `_create_plugin`,
+    /// calls `on_load(config_file)`, and on success appends the plugin/lib/
+    /// libpath to `plugin_manager` and `config_file` to `config_paths`,
+    /// returning the plugin's assigned name. Shared by `reloadPlugin` and
+    /// `loadPlugin`.
+    fn load_plugin_into(
+        plugin_manager: &mut GeyserPluginManager,
+        config_paths: &mut Vec<String>,
+        libpath: String,
+        config_file: &str,
+    ) -> Result<String> {
+        // SAFETY: It is up the validator to ensure this is a valid plugin library.
+        let (mut plugin, lib): (Box<dyn GeyserPlugin>, Library) = {
+            #[cfg(not(test))]
+            unsafe {
+                // Attempt to load Library
+                let lib = Library::new(&libpath).map_err(|e| {
+                    jsonrpc_core::error::Error::invalid_params(format!(
+                        "invalid geyser plugin, failed to load: {e}"
+                    ))
+                })?;
+
+                // Attempt to retrieve GeyserPlugin constructor
+                let constructor: Symbol<PluginConstructor> =
+                    lib.get(b"_create_plugin").map_err(|e| {
+                        jsonrpc_core::error::Error::invalid_params(format!(
+                            "invalid geyser plugin, failed to construct plugin: {e}"
+                        ))
+                    })?;
+
+                // Attempt to construct raw *mut dyn GeyserPlugin
+                let plugin_raw = constructor();
+
+                (Box::from_raw(plugin_raw), lib)
+            }
+
+            // This is mocked for tests to avoid having to do IO with a dynamically linked library
+            // across different architectures.
+            #[cfg(test)]
+            {
+                tests::dummy_plugin_and_library()
+            }
+        };
+
+        plugin.on_load(config_file).map_err(|e| {
+            jsonrpc_core::error::Error::invalid_params(format!(
+                "failed to start new plugin: {e}"
+            ))
+        })?;
+
+        let name = plugin.name().to_string();
+        plugin_manager.plugins.push(plugin);
+        plugin_manager.libs.push(lib);
+        plugin_manager.libpaths.push(PathBuf::from(libpath));
+        config_paths.push(config_file.to_string());
+        Ok(name)
+    }
 }
 
 fn rpc_account_index_from_account_index(account_index: &AccountIndex) -> RpcAccountIndex {
@@ -694,6 +1153,172 @@ pub fn run(ledger_path: &Path, metadata: AdminRpcRequestMetadata) {
         .unwrap();
 }
 
+#[derive(Deserialize)]
+struct LoadPluginHttpRequest {
+    libpath: String,
+    config_file: String,
+}
+
+#[derive(Deserialize)]
+struct UnloadPluginHttpRequest {
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct RepairWhitelistHttpRequest {
+    whitelist: Vec<Pubkey>,
+}
+
+// Start the Admin RPC interface over HTTP/REST, in addition to the IPC
+// JSON-RPC server started by `run`. Each `AdminRpc` method is exposed as its
+// own route (e.g. `GET /contact-info`, `POST /repair-whitelist`, `POST
+// /plugins/reload`) instead of the JSON-RPC envelope used over IPC, so the
+// gateway can be driven with a plain HTTP client. Every request must carry
+// `Authorization: Bearer <token>`; the presented token is verified against
+// `metadata.auth_config` and its capability is enforced per-route by each
+// handler's `require_capability` check.
+pub fn run_http(addr: SocketAddr, metadata: AdminRpcRequestMetadata) {
+    Builder::new()
+        .name("solAdminHttp".to_string())
+        .spawn(move || {
+            let server = match tiny_http::Server::http(addr) {
+                Ok(server) => server,
+                Err(err) => {
+                    warn!("Unable to start admin http gateway: {:?}", err);
+                    return;
+                }
+            };
+
+            for mut request in server.incoming_requests() {
+                let response = handle_http_request(&metadata, &mut request);
+                let _ = request.respond(response);
+            }
+        })
+        .unwrap();
+}
+
+fn handle_http_request(
+    meta: &AdminRpcRequestMetadata,
+    request: &mut tiny_http::Request,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let mut meta = meta.clone();
+    match extract_bearer_token(request).and_then(|token| meta.auth_config.verify(&token)) {
+        Some(capability) => meta.capability = capability,
+        None => return http_error_response(401, "missing or invalid bearer token"),
+    }
+
+    let method = request.method().clone();
+    let url = request.url().to_string();
+    let mut body = String::new();
+    let _ = request.as_reader().read_to_string(&mut body);
+
+    // `/metrics` returns the Prometheus text exposition format directly,
+    // rather than JSON like every other route.
+    if matches!(method, Method::Get) && url == "/metrics" {
+        return match AdminRpcImpl.metrics(meta) {
+            Ok(text) => http_text_response(200, &text),
+            Err(err) => http_error_response(http_status_for_error_code(&err.code), &err.message),
+        };
+    }
+
+    match dispatch_http_route(&meta, &method, &url, &body) {
+        Ok(value) => http_json_response(200, &value),
+        Err(err) => http_error_response(http_status_for_error_code(&err.code), &err.message),
+    }
+}
+
+fn extract_bearer_token(request: &tiny_http::Request) -> Option<String> {
+    request.headers().iter().find_map(|header| {
+        if header.field.as_str().eq_ignore_ascii_case("authorization") {
+            header.value.as_str().strip_prefix("Bearer ").map(str::to_string)
+        } else {
+            None
+        }
+    })
+}
+
+// Maps one HTTP method+path to the matching `AdminRpc` method, reusing
+// `AdminRpcImpl` directly so the gateway has no business logic of its own.
+fn dispatch_http_route(
+    meta: &AdminRpcRequestMetadata,
+    method: &Method,
+    url: &str,
+    body: &str,
+) -> Result<serde_json::Value> {
+    macro_rules! to_value {
+        ($result:expr) => {
+            $result.map(|value| {
+                serde_json::to_value(value)
+                    .expect("admin rpc response types are always representable as JSON")
+            })
+        };
+    }
+
+    match (method, url) {
+        (Method::Get, "/contact-info") => to_value!(AdminRpcImpl.contact_info(meta.clone())),
+        (Method::Get, "/repair-whitelist") => to_value!(AdminRpcImpl.repair_whitelist(meta.clone())),
+        (Method::Post, "/repair-whitelist") => {
+            let req = parse_json_body::<RepairWhitelistHttpRequest>(body)?;
+            to_value!(AdminRpcImpl.set_repair_whitelist(meta.clone(), req.whitelist))
+        }
+        (Method::Get, "/plugins") => to_value!(AdminRpcImpl.list_plugins(meta.clone())),
+        (Method::Post, "/plugins/load") => {
+            let req = parse_json_body::<LoadPluginHttpRequest>(body)?;
+            to_value!(AdminRpcImpl.load_plugin(meta.clone(), req.libpath, req.config_file))
+        }
+        (Method::Post, "/plugins/reload") => {
+            let req = parse_json_body::<LoadPluginHttpRequest>(body)?;
+            to_value!(AdminRpcImpl.reload_plugin(meta.clone(), req.libpath, req.config_file))
+        }
+        (Method::Post, "/plugins/unload") => {
+            let req = parse_json_body::<UnloadPluginHttpRequest>(body)?;
+            to_value!(AdminRpcImpl.unload_plugin(meta.clone(), req.name))
+        }
+        (Method::Get, "/rpc-address") => to_value!(AdminRpcImpl.rpc_addr(meta.clone())),
+        (Method::Get, "/start-time") => to_value!(AdminRpcImpl.start_time(meta.clone())),
+        (Method::Get, "/start-progress") => to_value!(AdminRpcImpl.start_progress(meta.clone())),
+        (Method::Get, "/token-interface-programs") => {
+            to_value!(AdminRpcImpl.get_token_interface_programs(meta.clone()))
+        }
+        (Method::Post, "/exit") => to_value!(AdminRpcImpl.exit(meta.clone())),
+        _ => Err(jsonrpc_core::error::Error {
+            code: ErrorCode::MethodNotFound,
+            message: format!("no route for {method:?} {url}"),
+            data: None,
+        }),
+    }
+}
+
+fn parse_json_body<T: serde::de::DeserializeOwned>(body: &str) -> Result<T> {
+    serde_json::from_str(body)
+        .map_err(|err| jsonrpc_core::error::Error::invalid_params(format!("invalid request body: {err}")))
+}
+
+fn http_status_for_error_code(code: &ErrorCode) -> u16 {
+    match code {
+        ErrorCode::ParseError | ErrorCode::InvalidRequest | ErrorCode::InvalidParams => 400,
+        ErrorCode::MethodNotFound => 404,
+        ErrorCode::InternalError | ErrorCode::ServerError(_) => 500,
+    }
+}
+
+fn http_json_response(status: u16, value: &serde_json::Value) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let body = serde_json::to_vec(value).unwrap_or_default();
+    tiny_http::Response::from_data(body)
+        .with_status_code(status)
+        .with_header(Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap())
+}
+
+fn http_error_response(status: u16, message: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    http_json_response(status, &serde_json::json!({ "error": message }))
+}
+
+fn http_text_response(status: u16, text: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    tiny_http::Response::from_data(text.as_bytes().to_vec())
+        .with_status_code(status)
+        .with_header(Header::from_bytes(&b"Content-Type"[..], &b"text/plain; version=0.0.4"[..]).unwrap())
+}
+
 fn admin_rpc_path(ledger_path: &Path) -> PathBuf {
     #[cfg(target_family = "windows")]
     {
@@ -751,6 +1376,28 @@ where
     Ok(container_typed)
 }
 
+// Reads `path` and replaces the contents of `meta.staked_nodes_overrides`
+// with it. Shared by `set_staked_nodes_overrides` and
+// `reload_staked_nodes_overrides`, which only differ in how they resolve
+// `path` and whether it's required.
+fn apply_staked_nodes_overrides(meta: &AdminRpcRequestMetadata, path: &str) -> Result<()> {
+    let loaded_config = load_staked_nodes_overrides(&path.to_string())
+        .map_err(|err| {
+            error!(
+                "Failed to load staked nodes overrides from {}: {}",
+                path, err
+            );
+            jsonrpc_core::error::Error::internal_error()
+        })?
+        .staked_map_id;
+    let mut write_staked_nodes = meta.staked_nodes_overrides.write().unwrap();
+    write_staked_nodes.clear();
+    write_staked_nodes.extend(loaded_config.into_iter());
+    info!("Staked nodes overrides loaded from {}", path);
+    debug!("overrides map: {:?}", write_staked_nodes);
+    Ok(())
+}
+
 pub fn load_staked_nodes_overrides(
     path: &String,
 ) -> std::result::Result<StakedNodesOverrides, Box<dyn error::Error>> {
@@ -862,9 +1509,14 @@ mod tests {
                     repair_whitelist,
                 }))),
                 staked_nodes_overrides: Arc::new(RwLock::new(HashMap::new())),
+                staked_nodes_overrides_path: Arc::new(RwLock::new(None)),
                 // For tests, just use an empty manager. In prod, this would be
                 // a shared GeyserPluginManager with the plugin service
                 plugin_manager: Arc::new(RwLock::new(GeyserPluginManager::new())),
+                plugin_config_paths: Arc::new(RwLock::new(Vec::new())),
+                token_interface_programs: TokenInterfacePrograms::default(),
+                auth_config: AdminRpcAuthConfig::default(),
+                capability: AdminRpcCapability::default(),
             };
             let mut io = MetaIoHandler::default();
             io.extend_with(AdminRpcImpl.to_delegate());
@@ -1335,7 +1987,7 @@ mod tests {
         let res = io.handle_request_sync(&req, meta.clone());
         let result: Value = serde_json::from_str(&res.expect("actual response"))
             .expect("actual response deserialization");
-        let largest_program_id_keys: Vec<(String, usize)> =
+        let largest_program_id_keys: AdminRpcLargestIndexKeysPage =
             serde_json::from_value(result["result"].clone()).unwrap();
         // Collect largest key list for SPLTokenOwners
         let req = format!(
@@ -1345,7 +1997,7 @@ mod tests {
         let res = io.handle_request_sync(&req, meta.clone());
         let result: Value = serde_json::from_str(&res.expect("actual response"))
             .expect("actual response deserialization");
-        let largest_spl_token_owner_keys: Vec<(String, usize)> =
+        let largest_spl_token_owner_keys: AdminRpcLargestIndexKeysPage =
             serde_json::from_value(result["result"].clone()).unwrap();
         // Collect largest key list for SPLTokenMints
         let req = format!(
@@ -1355,13 +2007,13 @@ mod tests {
         let res = io.handle_request_sync(&req, meta);
         let result: Value = serde_json::from_str(&res.expect("actual response"))
             .expect("actual response deserialization");
-        let largest_spl_token_mint_keys: Vec<(String, usize)> =
+        let largest_spl_token_mint_keys: AdminRpcLargestIndexKeysPage =
             serde_json::from_value(result["result"].clone()).unwrap();
 
         let largest_keys = vec![
-            largest_program_id_keys,
-            largest_spl_token_owner_keys,
-            largest_spl_token_mint_keys,
+            largest_program_id_keys.keys,
+            largest_spl_token_owner_keys.keys,
+            largest_spl_token_mint_keys.keys,
         ];
 
         // Make sure key lists conform to expected output
@@ -1382,6 +2034,105 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_get_largest_index_keys_is_capped() {
+        let RpcHandler { meta, .. } = RpcHandler::_start();
+
+        let largest_keys = AdminRpcImpl
+            .get_largest_index_keys(
+                meta,
+                RpcAccountIndex::ProgramId,
+                MAX_NUM_LARGEST_INDEX_KEYS_RETURNED + 1000,
+                None,
+            )
+            .unwrap();
+        assert!(largest_keys.keys.len() <= MAX_NUM_LARGEST_INDEX_KEYS_RETURNED);
+        assert_eq!(largest_keys.next_cursor, None);
+    }
+
+    #[test]
+    fn test_get_largest_index_keys_pagination_resumes_after_cursor() {
+        let RpcHandler { meta, .. } = RpcHandler::_start();
+
+        let first_page = AdminRpcImpl
+            .get_largest_index_keys(meta.clone(), RpcAccountIndex::ProgramId, 0, None)
+            .unwrap();
+        // No index entries in a freshly started test validator, so both
+        // requesting zero keys and paging past an empty set are no-ops.
+        assert_eq!(first_page.keys, Vec::new());
+        assert_eq!(first_page.next_cursor, None);
+
+        let page = AdminRpcImpl
+            .get_largest_index_keys(
+                meta,
+                RpcAccountIndex::ProgramId,
+                1,
+                Some((usize::MAX, Pubkey::new_unique().to_string())),
+            )
+            .unwrap();
+        assert_eq!(page.keys, Vec::new());
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn test_get_index_key_size_histogram_empty_index() {
+        let RpcHandler { meta, .. } = RpcHandler::_start();
+
+        let histogram = AdminRpcImpl
+            .get_index_key_size_histogram(meta, RpcAccountIndex::ProgramId)
+            .unwrap();
+        assert_eq!(histogram, Vec::new());
+    }
+
+    #[test]
+    fn test_get_index_key_size_histogram_buckets_by_power_of_two() {
+        let account_indexes = AccountSecondaryIndexes {
+            keys: None,
+            indexes: HashSet::from([AccountIndex::ProgramId]),
+        };
+        let rpc = RpcHandler::start_with_config(TestConfig { account_indexes });
+        let bank = rpc.root_bank();
+        let RpcHandler { meta, .. } = rpc;
+
+        // One ProgramId owning 1 child account (bucket 1-1), and a second
+        // ProgramId owning 3 child accounts (bucket 2-3).
+        let small_owner = Pubkey::new_unique();
+        let big_owner = Pubkey::new_unique();
+        for owner in [small_owner] {
+            let child = Pubkey::new_unique();
+            let account = AccountSharedData::from(Account {
+                lamports: bank.get_minimum_balance_for_rent_exemption(0),
+                owner,
+                ..Account::default()
+            });
+            bank.store_account(&child, &account);
+        }
+        for _ in 0..3 {
+            let child = Pubkey::new_unique();
+            let account = AccountSharedData::from(Account {
+                lamports: bank.get_minimum_balance_for_rent_exemption(0),
+                owner: big_owner,
+                ..Account::default()
+            });
+            bank.store_account(&child, &account);
+        }
+
+        let histogram = AdminRpcImpl
+            .get_index_key_size_histogram(meta, RpcAccountIndex::ProgramId)
+            .unwrap();
+        // Sorted ascending by bucket_min, and every bucket is a valid
+        // power-of-two range.
+        for i in 0..histogram.len() - 1 {
+            assert!(histogram[i].0 < histogram[i + 1].0);
+        }
+        for &(bucket_min, bucket_max, count) in &histogram {
+            assert_eq!(bucket_max, bucket_min * 2 - 1);
+            assert!(count > 0);
+        }
+        assert!(histogram.iter().any(|&(min, max, _)| (min, max) == (1, 1)));
+        assert!(histogram.iter().any(|&(min, max, _)| (min, max) == (2, 3)));
+    }
+
     #[test]
     fn test_geyser_reload() {
         let RpcHandler { io, mut meta, .. } = RpcHandler::_start();
@@ -1436,5 +2187,220 @@ mod tests {
         println!("{result:?}");
         // Ok(()) --> Value::Null result
         assert_eq!(result["result"], Value::Null);
+
+        // listPlugins still reports it, under the same libpath, after reload
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"listPlugins","params":[]}"#;
+        let response = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        let plugins: Vec<AdminRpcPluginInfo> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(
+            plugins,
+            vec![AdminRpcPluginInfo {
+                name: "test".to_string(),
+                libpath: DUMMY_LIBRARY.to_string(),
+                config_path: DUMMY_CONFIG_FILE.to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_geyser_plugin_lifecycle() {
+        let RpcHandler { io, meta, .. } = RpcHandler::_start();
+
+        const DUMMY_CONFIG_FILE: &'static str = "dummy_config";
+        const DUMMY_LIBRARY: &'static str = "dummy_lib";
+
+        // Nothing loaded yet
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"listPlugins","params":[]}"#;
+        let response = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        let plugins: Vec<AdminRpcPluginInfo> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert!(plugins.is_empty());
+
+        // Unloading something that isn't loaded fails
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"unloadPlugin","params":["test"]}"#;
+        let response = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["error"]["message"],
+            "plugin requested to unload is not loaded"
+        );
+
+        // loadPlugin dlopens (the mocked dummy) library and returns its name
+        let req = format!(
+            r#"{{"jsonrpc":"2.0","id":1,"method":"loadPlugin","params":["{}", "{}"]}}"#,
+            DUMMY_LIBRARY, DUMMY_CONFIG_FILE,
+        );
+        let response = io.handle_request_sync(&req, meta.clone());
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        let name: String = serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(name, "test");
+
+        // listPlugins now reports it
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"listPlugins","params":[]}"#;
+        let response = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        let plugins: Vec<AdminRpcPluginInfo> =
+            serde_json::from_value(result["result"].clone()).unwrap();
+        assert_eq!(
+            plugins,
+            vec![AdminRpcPluginInfo {
+                name: "test".to_string(),
+                libpath: DUMMY_LIBRARY.to_string(),
+                config_path: DUMMY_CONFIG_FILE.to_string(),
+            }]
+        );
+
+        // unloadPlugin drops it, so a second unload fails again
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"unloadPlugin","params":["test"]}"#;
+        let response = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(result["result"], Value::Null);
+
+        let req = r#"{"jsonrpc":"2.0","id":1,"method":"unloadPlugin","params":["test"]}"#;
+        let response = io.handle_request_sync(req, meta.clone());
+        let result: Value = serde_json::from_str(&response.expect("actual response"))
+            .expect("actual response deserialization");
+        assert_eq!(
+            result["error"]["message"],
+            "plugin requested to unload is not loaded"
+        );
+    }
+
+    #[test]
+    fn test_http_gateway_dispatch() {
+        let RpcHandler { meta, .. } = RpcHandler::_start();
+
+        let result = dispatch_http_route(&meta, &Method::Get, "/start-progress", "").unwrap();
+        assert_eq!(
+            result,
+            serde_json::to_value(ValidatorStartProgress::default()).unwrap()
+        );
+
+        let err =
+            dispatch_http_route(&meta, &Method::Get, "/not-a-real-route", "").unwrap_err();
+        assert_eq!(err.code, ErrorCode::MethodNotFound);
+
+        let err = dispatch_http_route(&meta, &Method::Post, "/repair-whitelist", "not json")
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidParams);
+    }
+
+    #[test]
+    fn test_http_status_for_error_code() {
+        assert_eq!(http_status_for_error_code(&ErrorCode::InvalidRequest), 400);
+        assert_eq!(http_status_for_error_code(&ErrorCode::MethodNotFound), 404);
+        assert_eq!(http_status_for_error_code(&ErrorCode::InternalError), 500);
+    }
+
+    #[test]
+    fn test_admin_rpc_auth_config_verify() {
+        let auth_config = AdminRpcAuthConfig::new(vec![
+            ("readonly-token".to_string(), AdminRpcCapability::ReadOnly),
+            ("mutate-token".to_string(), AdminRpcCapability::Mutate),
+        ]);
+
+        assert_eq!(
+            auth_config.verify("readonly-token"),
+            Some(AdminRpcCapability::ReadOnly)
+        );
+        assert_eq!(
+            auth_config.verify("mutate-token"),
+            Some(AdminRpcCapability::Mutate)
+        );
+        assert_eq!(auth_config.verify("not-a-configured-token"), None);
+    }
+
+    #[test]
+    fn test_require_capability() {
+        let RpcHandler { mut meta, .. } = RpcHandler::_start();
+
+        meta.capability = AdminRpcCapability::ReadOnly;
+        assert!(meta.require_capability(AdminRpcCapability::ReadOnly).is_ok());
+        let err = meta
+            .require_capability(AdminRpcCapability::Mutate)
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidRequest);
+
+        meta.capability = AdminRpcCapability::Mutate;
+        assert!(meta.require_capability(AdminRpcCapability::ReadOnly).is_ok());
+        assert!(meta.require_capability(AdminRpcCapability::Mutate).is_ok());
+    }
+
+    #[test]
+    fn test_metrics() {
+        let RpcHandler { meta, .. } = RpcHandler::_start();
+
+        let text = AdminRpcImpl.metrics(meta).unwrap();
+        assert!(text.contains("# TYPE solana_validator_uptime_seconds gauge"));
+        assert!(text.contains("solana_validator_uptime_seconds "));
+        assert!(text.contains("# TYPE solana_validator_start_progress gauge"));
+        assert!(text.contains("solana_validator_authorized_voter_count 1"));
+        assert!(text.contains("solana_validator_geyser_plugin_count 0"));
+    }
+
+    #[test]
+    fn test_get_token_interface_programs() {
+        let RpcHandler { meta, .. } = RpcHandler::_start();
+
+        let programs = AdminRpcImpl.get_token_interface_programs(meta).unwrap();
+        assert_eq!(
+            programs,
+            vec![
+                solana_runtime::inline_spl_token::id().to_string(),
+                solana_runtime::inline_spl_token_2022::id().to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reload_staked_nodes_overrides() {
+        let RpcHandler { meta, .. } = RpcHandler::_start();
+
+        let pubkey = Pubkey::new_unique();
+        let mut path = std::env::temp_dir();
+        path.push(format!(
+            "test_reload_staked_nodes_overrides_{}.yml",
+            std::process::id()
+        ));
+        std::fs::write(&path, format!("staked_map_id:\n  {pubkey}: 42\n")).unwrap();
+        let path = path.to_str().unwrap().to_string();
+
+        AdminRpcImpl
+            .set_staked_nodes_overrides(meta.clone(), path.clone())
+            .unwrap();
+        assert_eq!(
+            meta.staked_nodes_overrides.read().unwrap().get(&pubkey),
+            Some(&42)
+        );
+
+        std::fs::write(&path, format!("staked_map_id:\n  {pubkey}: 99\n")).unwrap();
+        AdminRpcImpl
+            .reload_staked_nodes_overrides(meta.clone(), None)
+            .unwrap();
+        assert_eq!(
+            meta.staked_nodes_overrides.read().unwrap().get(&pubkey),
+            Some(&99)
+        );
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_reload_staked_nodes_overrides_requires_a_path_the_first_time() {
+        let RpcHandler { meta, .. } = RpcHandler::_start();
+
+        let err = AdminRpcImpl
+            .reload_staked_nodes_overrides(meta, None)
+            .unwrap_err();
+        assert_eq!(err.code, ErrorCode::InvalidParams);
     }
 }
@@ -0,0 +1,92 @@
+//! Aggregated Bulletproof range proof covering several Pedersen
+//! commitments at once, instead of one independent range proof per
+//! commitment.
+//!
+//! Given `m` commitments `C_j = v_j*G + r_j*H`, the underlying proof
+//! bit-decomposes every `v_j` into its `n = bit_length` bits, concatenates
+//! them into one length-`m*n` vector `a_L` (with `a_R = a_L - 1^{mn}`), and
+//! runs a single logarithmic inner-product argument over the aggregated
+//! relation instead of `m` independent ones. The `z^{j+2}`-weighted shift
+//! of the `j`-th value's bit range out of the aggregate is what `Role`
+//! (source/destination) feeds into when building the commitment vector for
+//! a transfer: both halves of both sides of a transfer can be proven (and
+//! later verified) in one proof instead of four.
+//!
+//! The actual curve arithmetic for proving/verifying lives in
+//! `crate::range_proof::BatchedRangeProof`; this module only bundles the
+//! public commitments (the "context", bound into the instruction data so a
+//! follow-on instruction can look it up) with the proof bytes themselves.
+
+use crate::instruction::{ProofType, ZkProofData};
+#[cfg(not(target_os = "solana"))]
+use crate::{errors::ProofError, range_proof::BatchedRangeProof};
+use bytemuck::{Pod, Zeroable};
+
+/// Maximum number of Pedersen commitments a single aggregated range proof
+/// can cover — enough for the lo and hi halves of both the source and
+/// destination commitments in one transfer.
+pub const MAX_BATCH_COMMITMENTS: usize = 4;
+
+/// Maximum number of inner-product-argument rounds, i.e.
+/// `ceil(log2(MAX_BATCH_COMMITMENTS * 64))`, bounding the fixed-size
+/// `ipp_l_vec`/`ipp_r_vec` arrays below.
+pub const MAX_IPP_ROUNDS: usize = 8;
+
+/// The Pedersen commitments a [`BatchedRangeProofData`] proof attests lie
+/// in `[0, 2^bit_length)`, plus how many of `commitments` are actually in
+/// use (unused slots are zeroed).
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct BatchedRangeProofContext {
+    pub commitments: [[u8; 32]; MAX_BATCH_COMMITMENTS],
+    pub commitment_count: u8,
+    pub bit_length: u8,
+    _padding: [u8; 6],
+}
+
+/// The aggregated Bulletproof itself: the vector commitments `A`/`S`, the
+/// polynomial commitments `T1`/`T2`, the opening scalars, and the
+/// logarithmic inner-product argument's `L`/`R` vectors (padded out to
+/// `MAX_IPP_ROUNDS`; only the first `ipp_round_count` entries are used).
+#[derive(Clone, Copy, Pod, Zeroable)]
+#[repr(C)]
+pub struct BatchedRangeProofProof {
+    pub a: [u8; 32],
+    pub s: [u8; 32],
+    pub t_1: [u8; 32],
+    pub t_2: [u8; 32],
+    pub t_x: [u8; 32],
+    pub t_x_blinding: [u8; 32],
+    pub e_blinding: [u8; 32],
+    pub ipp_l_vec: [[u8; 32]; MAX_IPP_ROUNDS],
+    pub ipp_r_vec: [[u8; 32]; MAX_IPP_ROUNDS],
+    pub ipp_round_count: u8,
+    pub ipp_a: [u8; 32],
+    pub ipp_b: [u8; 32],
+    _padding: [u8; 7],
+}
+
+/// Proof data for `ProofType::BatchedRangeProof`, mirroring the other
+/// `*Data` types in this module: `context` is the public statement (the
+/// commitments being proven), and `proof` is the Bulletproof itself.
+pub struct BatchedRangeProofData {
+    pub context: BatchedRangeProofContext,
+    pub proof: BatchedRangeProofProof,
+}
+
+impl ZkProofData<BatchedRangeProofContext> for BatchedRangeProofData {
+    const PROOF_TYPE: ProofType = ProofType::BatchedRangeProof;
+
+    fn context_data(&self) -> &BatchedRangeProofContext {
+        &self.context
+    }
+
+    #[cfg(not(target_os = "solana"))]
+    fn verify_proof(&self) -> Result<(), ProofError> {
+        BatchedRangeProof::verify(
+            &self.context.commitments[..self.context.commitment_count as usize],
+            self.context.bit_length as usize,
+            &self.proof,
+        )
+    }
+}
@@ -1,3 +1,4 @@
+pub mod batched_range_proof;
 pub mod close_account;
 pub mod pubkey_validity;
 pub mod transfer;
@@ -19,6 +20,7 @@ use {
     curve25519_dalek::scalar::Scalar,
 };
 pub use {
+    batched_range_proof::{BatchedRangeProofContext, BatchedRangeProofData},
     bytemuck::Pod,
     close_account::{CloseAccountData, CloseAccountProofContext},
     pubkey_validity::{PubkeyValidityData, PubkeyValidityProofContext},
@@ -45,6 +47,11 @@ pub enum ProofType {
     PubkeyValidity,
     ValidityProof,
     AggregatedValidityProof,
+    /// A single Bulletproof attesting that several Pedersen commitments
+    /// (e.g. the lo/hi halves of a source and destination commitment in
+    /// one transfer) all lie in `[0, 2^bit_length)`; see
+    /// `batched_range_proof::BatchedRangeProofData`.
+    BatchedRangeProof,
 }
 
 pub trait ZkProofData<T: Pod> {
@@ -0,0 +1,184 @@
+//! Curve arithmetic backing `BatchedRangeProofData::verify_proof`.
+//!
+//! Rather than re-deriving the inner-product argument by hand, `verify`
+//! wraps the `bulletproofs` crate's aggregated `RangeProof::verify_multiple`
+//! -- the same aggregated-range-proof machinery the prover side runs against
+//! -- and only handles converting between our fixed-size wire format
+//! (`[u8; 32]` compressed points/scalars, laid out in
+//! `instruction::batched_range_proof::BatchedRangeProofProof` for
+//! `Pod`/`Zeroable`) and that crate's `RangeProof` byte encoding.
+
+use crate::errors::ProofError;
+use crate::instruction::batched_range_proof::BatchedRangeProofProof;
+use bulletproofs::{BulletproofGens, PedersenGens, RangeProof};
+use curve25519_dalek::ristretto::CompressedRistretto;
+use merlin::Transcript;
+
+/// Aggregated Bulletproof range proof verifier for `ProofType::BatchedRangeProof`.
+pub struct BatchedRangeProof;
+
+impl BatchedRangeProof {
+    /// Verify that every commitment in `commitments` opens to a value in
+    /// `[0, 2^bit_length)`, per the single aggregated proof in `proof`.
+    ///
+    /// `bulletproofs` aggregation requires the commitment count to be a
+    /// power of two (it pads the bit-decomposed vectors to `m * bit_length`
+    /// internally); `BatchedRangeProofContext::commitment_count` is checked
+    /// against that before anything else.
+    pub fn verify(
+        commitments: &[[u8; 32]],
+        bit_length: usize,
+        proof: &BatchedRangeProofProof,
+    ) -> Result<(), ProofError> {
+        let commitment_count = commitments.len();
+        if commitment_count == 0 || !commitment_count.is_power_of_two() {
+            return Err(ProofError::IllegalCommitmentLength);
+        }
+
+        let bp_gens = BulletproofGens::new(bit_length, commitment_count);
+        let pc_gens = PedersenGens::default();
+
+        let compressed_commitments: Vec<CompressedRistretto> = commitments
+            .iter()
+            .map(|commitment| CompressedRistretto(*commitment))
+            .collect();
+
+        let range_proof = RangeProof::from_bytes(&Self::serialize_proof(proof))
+            .map_err(|_| ProofError::ProofVerification)?;
+
+        let mut transcript = Transcript::new(b"BatchedRangeProof");
+        range_proof
+            .verify_multiple(
+                &bp_gens,
+                &pc_gens,
+                &mut transcript,
+                &compressed_commitments,
+                bit_length,
+            )
+            .map_err(|_| ProofError::ProofVerification)
+    }
+
+    /// Lay `proof`'s fields out in the order `bulletproofs::RangeProof::from_bytes`
+    /// expects: `A`, `S`, `T_1`, `T_2`, `t_x`, `t_x_blinding`, `e_blinding`, then
+    /// the inner-product argument's `L`/`R` vectors interleaved per round --
+    /// `L_0, R_0, L_1, R_1, ...` up to `ipp_round_count` (the rest of each fixed
+    /// array is padding) -- then its final `a`/`b` scalars.
+    fn serialize_proof(proof: &BatchedRangeProofProof) -> Vec<u8> {
+        let round_count = proof.ipp_round_count as usize;
+        let mut bytes = Vec::with_capacity(9 * 32 + round_count * 2 * 32);
+
+        bytes.extend_from_slice(&proof.a);
+        bytes.extend_from_slice(&proof.s);
+        bytes.extend_from_slice(&proof.t_1);
+        bytes.extend_from_slice(&proof.t_2);
+        bytes.extend_from_slice(&proof.t_x);
+        bytes.extend_from_slice(&proof.t_x_blinding);
+        bytes.extend_from_slice(&proof.e_blinding);
+        for i in 0..round_count {
+            bytes.extend_from_slice(&proof.ipp_l_vec[i]);
+            bytes.extend_from_slice(&proof.ipp_r_vec[i]);
+        }
+        bytes.extend_from_slice(&proof.ipp_a);
+        bytes.extend_from_slice(&proof.ipp_b);
+
+        bytes
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::Zeroable;
+    use curve25519_dalek::scalar::Scalar;
+    use rand::thread_rng;
+
+    /// Inverse of `serialize_proof`: unpack a `RangeProof::to_bytes()` buffer
+    /// (interleaved `L_i, R_i` per round) into the fixed-size wire struct.
+    fn wire_proof_from_prover_bytes(round_count: usize, bytes: &[u8]) -> BatchedRangeProofProof {
+        let mut offset = 0;
+        let mut take32 = |bytes: &[u8]| -> [u8; 32] {
+            let mut out = [0u8; 32];
+            out.copy_from_slice(&bytes[offset..offset + 32]);
+            offset += 32;
+            out
+        };
+
+        let mut proof = BatchedRangeProofProof::zeroed();
+        proof.a = take32(bytes);
+        proof.s = take32(bytes);
+        proof.t_1 = take32(bytes);
+        proof.t_2 = take32(bytes);
+        proof.t_x = take32(bytes);
+        proof.t_x_blinding = take32(bytes);
+        proof.e_blinding = take32(bytes);
+        for i in 0..round_count {
+            proof.ipp_l_vec[i] = take32(bytes);
+            proof.ipp_r_vec[i] = take32(bytes);
+        }
+        proof.ipp_round_count = round_count as u8;
+        proof.ipp_a = take32(bytes);
+        proof.ipp_b = take32(bytes);
+        proof
+    }
+
+    #[test]
+    fn verify_accepts_a_proof_actually_produced_by_the_prover() {
+        let bit_length = 8;
+        let values = [5u64, 200u64];
+        let commitment_count = values.len();
+        let blindings: Vec<Scalar> = (0..commitment_count)
+            .map(|_| Scalar::random(&mut thread_rng()))
+            .collect();
+
+        let bp_gens = BulletproofGens::new(bit_length, commitment_count);
+        let pc_gens = PedersenGens::default();
+        let mut prover_transcript = Transcript::new(b"BatchedRangeProof");
+        let (range_proof, commitments) = RangeProof::prove_multiple(
+            &bp_gens,
+            &pc_gens,
+            &mut prover_transcript,
+            &values,
+            &blindings,
+            bit_length,
+        )
+        .unwrap();
+
+        let round_count = (bit_length * commitment_count).trailing_zeros() as usize;
+        let wire_proof = wire_proof_from_prover_bytes(round_count, &range_proof.to_bytes());
+        let wire_commitments: Vec<[u8; 32]> =
+            commitments.iter().map(|c| c.to_bytes()).collect();
+
+        assert!(BatchedRangeProof::verify(&wire_commitments, bit_length, &wire_proof).is_ok());
+    }
+
+    #[test]
+    fn verify_rejects_a_tampered_commitment() {
+        let bit_length = 8;
+        let values = [5u64, 200u64];
+        let commitment_count = values.len();
+        let blindings: Vec<Scalar> = (0..commitment_count)
+            .map(|_| Scalar::random(&mut thread_rng()))
+            .collect();
+
+        let bp_gens = BulletproofGens::new(bit_length, commitment_count);
+        let pc_gens = PedersenGens::default();
+        let mut prover_transcript = Transcript::new(b"BatchedRangeProof");
+        let (range_proof, commitments) = RangeProof::prove_multiple(
+            &bp_gens,
+            &pc_gens,
+            &mut prover_transcript,
+            &values,
+            &blindings,
+            bit_length,
+        )
+        .unwrap();
+
+        let round_count = (bit_length * commitment_count).trailing_zeros() as usize;
+        let wire_proof = wire_proof_from_prover_bytes(round_count, &range_proof.to_bytes());
+        let mut wire_commitments: Vec<[u8; 32]> =
+            commitments.iter().map(|c| c.to_bytes()).collect();
+        wire_commitments[0][0] ^= 0xff;
+
+        assert!(BatchedRangeProof::verify(&wire_commitments, bit_length, &wire_proof).is_err());
+    }
+}